@@ -1,15 +1,24 @@
 use adw::prelude::*;
 use adw::subclass::prelude::*;
+use gettextrs::gettext;
 use gtk::gio;
+use std::cell::RefCell;
+use std::path::PathBuf;
 
 use crate::config;
+use crate::convert::{self, OutputFormat};
+use crate::ui::shortcuts;
 use crate::ui::window::LuminaWindow;
 
 mod imp {
     use super::*;
 
     #[derive(Debug, Default)]
-    pub struct LuminaApplication;
+    pub struct LuminaApplication {
+        /// Files given on the command line of the primary instance, opened
+        /// on activation
+        pub pending_files: RefCell<Vec<gio::File>>,
+    }
 
     #[glib::object_subclass]
     impl ObjectSubclass for LuminaApplication {
@@ -21,10 +30,85 @@ mod imp {
     impl ObjectImpl for LuminaApplication {}
 
     impl ApplicationImpl for LuminaApplication {
+        /// `--convert-to` converts the given files and exits before any
+        /// window is created.
+        fn handle_local_options(&self, options: &glib::VariantDict) -> glib::ExitCode {
+            let Some(name) = options.lookup::<String>("convert-to").ok().flatten() else {
+                return self.forward_files(options);
+            };
+            let Some(output) = OutputFormat::from_name(&name) else {
+                eprintln!("{}", gettext("Unknown output format: {}").replace("{}", &name));
+                return glib::ExitCode::FAILURE;
+            };
+
+            let inputs: Vec<PathBuf> = options
+                .lookup::<Vec<PathBuf>>(glib::OPTION_REMAINING)
+                .ok()
+                .flatten()
+                .unwrap_or_default();
+            let output_dir = options.lookup::<PathBuf>("output-dir").ok().flatten();
+
+            if convert::convert_files(&inputs, output, output_dir.as_deref()) {
+                glib::ExitCode::SUCCESS
+            } else {
+                glib::ExitCode::FAILURE
+            }
+        }
+
         fn activate(&self) {
+            let files = self.pending_files.take();
+            if !files.is_empty() {
+                self.open(&files, "");
+                return;
+            }
+            // Without a file to open, start by picking a template
             let app = self.obj();
             let window = LuminaWindow::new(&app.upcast_ref());
             window.present();
+            WidgetExt::activate_action(&window, "win.new-from-template", None)
+                .expect("new-from-template action");
+        }
+
+        /// Every file gets a window of its own, also when sent from another
+        /// instance.
+        fn open(&self, files: &[gio::File], _hint: &str) {
+            let app = self.obj();
+            for path in files.iter().filter_map(|file| file.path()) {
+                let window = LuminaWindow::new(app.upcast_ref());
+                window.open_file(&path);
+                window.present();
+            }
+        }
+    }
+
+    impl LuminaApplication {
+        /// Files given without `--convert-to` are opened by the primary
+        /// instance: handed over right away when that runs elsewhere, or
+        /// kept for activation when this is it.
+        fn forward_files(&self, options: &glib::VariantDict) -> glib::ExitCode {
+            let files: Vec<gio::File> = options
+                .lookup::<Vec<PathBuf>>(glib::OPTION_REMAINING)
+                .ok()
+                .flatten()
+                .unwrap_or_default()
+                .iter()
+                .map(gio::File::for_path)
+                .collect();
+            if files.is_empty() {
+                return self.parent_handle_local_options(options);
+            }
+
+            let app = self.obj();
+            if let Err(e) = app.register(gio::Cancellable::NONE) {
+                eprintln!("{}", e);
+                return glib::ExitCode::FAILURE;
+            }
+            if app.is_remote() {
+                app.open(&files, "");
+                return glib::ExitCode::SUCCESS;
+            }
+            *self.pending_files.borrow_mut() = files;
+            self.parent_handle_local_options(options)
         }
     }
 
@@ -42,14 +126,42 @@ impl LuminaApplication {
     pub fn new() -> Self {
         let app: Self = glib::Object::builder()
             .property("application-id", config::APP_ID)
-            .property("flags", gio::ApplicationFlags::FLAGS_NONE)
+            .property("flags", gio::ApplicationFlags::HANDLES_OPEN)
             .build();
 
+        app.setup_options();
         app.setup_actions();
         app.setup_accels();
         app
     }
 
+    fn setup_options(&self) {
+        self.add_main_option(
+            "convert-to",
+            glib::Char::from(b'c'),
+            glib::OptionFlags::NONE,
+            glib::OptionArg::String,
+            &gettext("Convert the given presentations or folders to pdf or png and exit"),
+            Some("FORMAT"),
+        );
+        self.add_main_option(
+            "output-dir",
+            glib::Char::from(b'o'),
+            glib::OptionFlags::NONE,
+            glib::OptionArg::Filename,
+            &gettext("Write converted files to this folder instead of next to the originals"),
+            Some("DIR"),
+        );
+        self.add_main_option(
+            glib::OPTION_REMAINING,
+            glib::Char::from(0),
+            glib::OptionFlags::NONE,
+            glib::OptionArg::FilenameArray,
+            "",
+            Some("FILES…"),
+        );
+    }
+
     fn setup_actions(&self) {
         let about_action = gio::ActionEntry::builder("about")
             .activate(|app: &Self, _, _| {
@@ -57,22 +169,43 @@ impl LuminaApplication {
             })
             .build();
 
+        let new_window_action = gio::ActionEntry::builder("new-window")
+            .activate(|app: &Self, _, _| {
+                let window = LuminaWindow::new(app.upcast_ref());
+                window.present();
+            })
+            .build();
+
+        let preferences_action = gio::ActionEntry::builder("preferences")
+            .activate(|app: &Self, _, _| {
+                if let Some(win) = app.active_window() {
+                    crate::ui::preferences::show_preferences_dialog(&win);
+                }
+            })
+            .build();
+
         let quit_action = gio::ActionEntry::builder("quit")
             .activate(|app: &Self, _, _| {
-                app.quit();
+                // Windows with edits that were not saved ask first, and the
+                // application ends with the last window
+                for window in app.windows() {
+                    window.close();
+                }
             })
             .build();
 
-        self.add_action_entries([about_action, quit_action]);
+        self.add_action_entries([
+            about_action,
+            new_window_action,
+            preferences_action,
+            quit_action,
+        ]);
     }
 
     fn setup_accels(&self) {
-        self.set_accels_for_action("win.new-presentation", &["<Control>n"]);
-        self.set_accels_for_action("win.open", &["<Control>o"]);
-        self.set_accels_for_action("win.save", &["<Control>s"]);
-        self.set_accels_for_action("win.save-as", &["<Control><Shift>s"]);
-        self.set_accels_for_action("win.export-pdf", &["<Control><Shift>e"]);
-        self.set_accels_for_action("app.quit", &["<Control>q"]);
+        for (action, accels) in shortcuts::ACTION_ACCELS {
+            self.set_accels_for_action(action, accels);
+        }
     }
 
     fn show_about_dialog(&self) {
@@ -0,0 +1,126 @@
+use gettextrs::gettext;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::format;
+use crate::render::{image_render, pdf_export, raster};
+
+/// Pixels per point of converted PNG slides
+const PNG_SCALE: f64 = 2.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    /// One PDF per presentation
+    Pdf,
+    /// One PNG per slide, numbered from 1
+    Png,
+}
+
+impl OutputFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "pdf" => Some(OutputFormat::Pdf),
+            "png" => Some(OutputFormat::Png),
+            _ => None,
+        }
+    }
+}
+
+/// Convert every presentation in `inputs` without opening a window.
+/// Directories contribute the presentations directly inside them. Files
+/// are written next to their source, or into `output_dir` when given. A
+/// file that fails is reported and skipped. Returns whether all succeeded.
+pub fn convert_files(inputs: &[PathBuf], output: OutputFormat, output_dir: Option<&Path>) -> bool {
+    let files = collect_files(inputs);
+    if files.is_empty() {
+        eprintln!("{}", gettext("No presentations to convert"));
+        return false;
+    }
+
+    if let Some(dir) = output_dir {
+        if let Err(e) = std::fs::create_dir_all(dir) {
+            eprintln!("{}: {}", dir.display(), e);
+            return false;
+        }
+    }
+
+    let mut failed = 0;
+    for (i, path) in files.iter().enumerate() {
+        println!("[{}/{}] {}", i + 1, files.len(), path.display());
+        if let Err(e) = convert_file(path, output, output_dir) {
+            eprintln!("{}: {}", path.display(), e);
+            failed += 1;
+        }
+    }
+
+    println!(
+        "{}",
+        gettext("Converted {converted}, failed {failed}")
+            .replace("{converted}", &(files.len() - failed).to_string())
+            .replace("{failed}", &failed.to_string())
+    );
+    failed == 0
+}
+
+fn collect_files(inputs: &[PathBuf]) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for input in inputs {
+        if !input.is_dir() {
+            files.push(input.clone());
+            continue;
+        }
+        match std::fs::read_dir(input) {
+            Ok(entries) => {
+                let mut found: Vec<PathBuf> = entries
+                    .flatten()
+                    .map(|entry| entry.path())
+                    .filter(|path| path.is_file() && format::is_presentation(path))
+                    .collect();
+                found.sort();
+                files.extend(found);
+            }
+            Err(e) => eprintln!("{}: {}", input.display(), e),
+        }
+    }
+    files
+}
+
+fn convert_file(path: &Path, output: OutputFormat, output_dir: Option<&Path>) -> io::Result<()> {
+    let (doc, warnings) = format::load_document(path)?;
+    for warning in &warnings {
+        eprintln!("{}: {}", path.display(), warning);
+    }
+
+    let dir = output_dir
+        .or_else(|| path.parent())
+        .unwrap_or_else(|| Path::new("."));
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "presentation".to_string());
+
+    match output {
+        OutputFormat::Pdf => {
+            let slides: Vec<usize> = (0..doc.slides.len()).collect();
+            pdf_export::export_pdf(&doc, &slides, false, &dir.join(format!("{}.pdf", stem)))?;
+            for font in pdf_export::substituted_fonts(&doc) {
+                eprintln!(
+                    "{}: {}",
+                    path.display(),
+                    gettext("font {family} is not installed, embedded {substitute} instead")
+                        .replace("{family}", &font.family)
+                        .replace("{substitute}", &font.substitute)
+                );
+            }
+            Ok(())
+        }
+        OutputFormat::Png => {
+            for (i, slide) in doc.slides.iter().enumerate() {
+                image_render::log_undecodable_images(i, slide);
+                let png = raster::render_slide_png(slide, &doc.slide_size, PNG_SCALE)?;
+                std::fs::write(dir.join(format!("{}-{}.png", stem, i + 1)), png)?;
+            }
+            Ok(())
+        }
+    }
+}
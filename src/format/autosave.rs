@@ -0,0 +1,28 @@
+use std::path::{Path, PathBuf};
+
+/// File the autosave of the presentation at `path` is written to, next to
+/// it: `talk.odp` is autosaved as `talk.odp.autosave`.
+pub fn autosave_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".autosave");
+    path.with_file_name(name)
+}
+
+/// The autosave of `path` when it was written after the file was last
+/// saved, i.e. it holds changes that were never saved.
+pub fn newer_autosave(path: &Path) -> Option<PathBuf> {
+    let autosave = autosave_path(path);
+    let saved = std::fs::metadata(path).and_then(|m| m.modified()).ok()?;
+    let autosaved = std::fs::metadata(&autosave).and_then(|m| m.modified()).ok()?;
+    (autosaved > saved).then_some(autosave)
+}
+
+/// Delete the autosave of `path`, once its changes are saved or discarded.
+pub fn remove_autosave(path: &Path) {
+    let autosave = autosave_path(path);
+    if let Err(e) = std::fs::remove_file(&autosave) {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            eprintln!("Could not remove {}: {}", autosave.display(), e);
+        }
+    }
+}
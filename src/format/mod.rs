@@ -1,2 +1,29 @@
+pub mod autosave;
 pub mod odp;
 pub mod pptx;
+pub mod warning;
+
+#[cfg(test)]
+pub(crate) mod testing;
+
+use std::io;
+use std::path::Path;
+
+use crate::model::document::Document;
+use warning::ParseWarning;
+
+/// Load an ODP or PPTX file, picked by the file extension.
+pub fn load_document(path: &Path) -> io::Result<(Document, Vec<ParseWarning>)> {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case("pptx") => pptx::reader::load_document(path),
+        _ => odp::reader::load_document(path),
+    }
+}
+
+/// Whether `path` has the extension of a presentation format that can be
+/// loaded.
+pub fn is_presentation(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|ext| ["odp", "fodp", "pptx"].iter().any(|e| ext.eq_ignore_ascii_case(e)))
+}
@@ -10,9 +10,14 @@ pub const NS_XLINK: &str = "http://www.w3.org/1999/xlink";
 pub const NS_META: &str = "urn:oasis:names:tc:opendocument:xmlns:meta:1.0";
 pub const NS_DC: &str = "http://purl.org/dc/elements/1.1/";
 pub const NS_MANIFEST: &str = "urn:oasis:names:tc:opendocument:xmlns:manifest:1.0";
+/// Lumina's own attributes for settings ODF has no equivalent for
+pub const NS_LUMINA: &str = "https://github.com/srueegger/lumina/xmlns/odp";
 
 pub const ODP_MIMETYPE: &str = "application/vnd.oasis.opendocument.presentation";
 
+/// EMU (English Metric Units) per centimeter
+pub const EMU_PER_CM: f64 = 360000.0;
+
 /// Convert points to centimeters (ODP uses cm)
 pub fn pt_to_cm(pt: f64) -> f64 {
     pt / 28.3465
@@ -1,39 +1,121 @@
+use base64::Engine;
 use quick_xml::events::Event;
 use quick_xml::Reader;
-use std::collections::HashMap;
-use std::io::{self, Read};
+use std::collections::{HashMap, HashSet};
+use std::io::{self, Cursor, Read};
 use std::path::Path;
+use uuid::Uuid;
 use zip::ZipArchive;
 
+use crate::format::warning::ParseWarning;
 use crate::model::document::Document;
 use crate::model::element::SlideElement;
-use crate::model::geometry::{Rect, Size};
-use crate::model::image::ImageElement;
-use crate::model::shape::{ShapeElement, ShapeType};
-use crate::model::style::{Color, FillStyle, FontStyle, StrokeStyle};
-use crate::model::text::{TextAlignment, TextElement, TextParagraph, TextRun};
+use crate::model::geometry::{Insets, Point, Rect, Size, SlideSizePreset};
+use crate::model::image::{ImageCrop, ImageData, ImageElement, ImageFilters, ScaleMode};
+use crate::model::shape::{LineMarker, ShapeElement, ShapeType, PRESET_CORNER_RATIO};
+use crate::model::slide::{Background, Gradient, Slide};
+use crate::model::style::{Color, FillStyle, FontStyle, LineCap, LineJoin, StrokeStyle};
+use crate::model::text::{
+    ListStyle, TextAlignment, TextElement, TextOutline, TextParagraph, TextRun, TextShadow,
+    VerticalAlignment,
+};
 
 use super::constants::*;
 
-pub fn load_document(path: &Path) -> io::Result<Document> {
-    let file = std::fs::File::open(path)?;
-    let mut archive = ZipArchive::new(file)
-        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+/// Load a document together with the XML errors that cut parsing short.
+/// Both zipped ODP and flat XML (.fodp) documents are read.
+pub fn load_document(path: &Path) -> io::Result<(Document, Vec<ParseWarning>)> {
+    let data = std::fs::read(path)?;
 
-    // Read content.xml
-    let content_xml = read_zip_entry(&mut archive, "content.xml")?;
-
-    // Read styles.xml for page layout
-    let styles_xml = read_zip_entry(&mut archive, "styles.xml").unwrap_or_default();
+    // Parts in the order they are parsed, styles (with the masters) first
+    let (parts, mut package) = if data.starts_with(b"PK") {
+        let mut archive = ZipArchive::new(Cursor::new(data))
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let content_xml = read_zip_entry(&mut archive, "content.xml")?;
+        // styles.xml holds the page layout and master pages
+        let styles_xml = read_zip_entry(&mut archive, "styles.xml").unwrap_or_default();
+        let meta_xml = read_zip_entry(&mut archive, "meta.xml").unwrap_or_default();
+        let parts = vec![
+            ("styles.xml".to_string(), styles_xml),
+            ("content.xml".to_string(), content_xml),
+            ("meta.xml".to_string(), meta_xml),
+        ];
+        (parts, Package::Zip(archive))
+    } else {
+        // A flat document has styles and content inline in one XML file
+        let xml = String::from_utf8(data)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let part = path
+            .file_name()
+            .map_or_else(String::new, |n| n.to_string_lossy().into_owned());
+        (vec![(part, xml)], Package::Flat)
+    };
 
     // Parse slide size from styles
-    let slide_size = parse_slide_size(&styles_xml);
+    let mut warnings = Vec::new();
+    let slide_size = parse_slide_size(&parts[0].1, &parts[0].0, &mut warnings);
+
+    // Gradients are named styles, usually in styles.xml
+    let mut gradients = HashMap::new();
+    for (part, xml) in &parts {
+        parse_gradients(xml, part, &mut gradients, &mut warnings);
+    }
+    // So are the pictures of bitmap fills
+    let mut fill_images = HashMap::new();
+    for (part, xml) in &parts {
+        parse_fill_images(xml, part, &mut package, &mut fill_images, &mut warnings);
+    }
+
+    // Master pages are copied into the slides based on them
+    let (masters, pages): (Vec<ParsedPage>, Vec<ParsedPage>) = parts
+        .iter()
+        .flat_map(|(part, xml)| {
+            parse_pages(xml, part, &gradients, &fill_images, &mut package, &mut warnings)
+        })
+        .partition(|page| page.is_master);
+    let masters: HashMap<String, ParsedPage> = masters
+        .into_iter()
+        .map(|master| (master.name.clone(), master))
+        .collect();
 
     // Parse content
-    let mut doc = parse_content(&content_xml, &mut archive)?;
+    let mut doc = Document::new();
     doc.slide_size = slide_size;
+    for (part, xml) in &parts {
+        parse_meta(xml, part, &mut doc, &mut warnings);
+    }
+    doc.slides = pages
+        .into_iter()
+        .map(|page| apply_master(page, &masters))
+        .collect();
+    if doc.slides.is_empty() {
+        doc.slides.push(Slide::new());
+    }
 
-    Ok(doc)
+    // Every pass over a broken part stops at the same error
+    let mut seen = HashSet::new();
+    warnings.retain(|w| seen.insert((w.part.clone(), w.line, w.column, w.message.clone())));
+
+    Ok((doc, warnings))
+}
+
+/// Where the images referenced by `xlink:href` are read from
+enum Package {
+    Zip(ZipArchive<Cursor<Vec<u8>>>),
+    /// Flat XML embeds its images as `office:binary-data` instead
+    Flat,
+}
+
+impl Package {
+    fn read_bytes(&mut self, name: &str) -> io::Result<Vec<u8>> {
+        match self {
+            Package::Zip(archive) => read_zip_entry_bytes(archive, name),
+            Package::Flat => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{} is not embedded", name),
+            )),
+        }
+    }
 }
 
 fn read_zip_entry<R: Read + io::Seek>(
@@ -60,7 +142,7 @@ fn read_zip_entry_bytes<R: Read + io::Seek>(
     Ok(data)
 }
 
-fn parse_slide_size(styles_xml: &str) -> Size {
+fn parse_slide_size(styles_xml: &str, part: &str, warnings: &mut Vec<ParseWarning>) -> Size {
     let mut reader = Reader::from_str(styles_xml);
     let mut buf = Vec::new();
     let mut width = 960.0_f64;
@@ -87,70 +169,259 @@ fn parse_slide_size(styles_xml: &str) -> Size {
                 }
             }
             Ok(Event::Eof) => break,
-            Err(_) => break,
+            Err(e) => {
+                warnings.push(ParseWarning::new(
+                    part,
+                    styles_xml,
+                    reader.error_position(),
+                    &e,
+                ));
+                break;
+            }
             _ => {}
         }
         buf.clear();
     }
 
-    Size::new(width, height)
+    // Sizes converted from cm land slightly off the standard sizes
+    let size = Size::new(width, height);
+    SlideSizePreset::find(&size).map_or(size, SlideSizePreset::size)
+}
+
+/// Title, author and dates from the `office:meta` of meta.xml, or of a
+/// flat document.
+fn parse_meta(xml: &str, part: &str, doc: &mut Document, warnings: &mut Vec<ParseWarning>) {
+    let mut reader = Reader::from_str(xml);
+    let mut buf = Vec::new();
+    let mut in_meta = false;
+    let mut field = String::new();
+    let mut initial_creator = String::new();
+    let mut creator = String::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(ref e)) => {
+                let name = String::from_utf8_lossy(e.local_name().as_ref()).to_string();
+                if name == "meta" {
+                    in_meta = true;
+                } else if in_meta {
+                    field = name;
+                }
+            }
+            Ok(Event::Text(ref e)) if in_meta => {
+                let text = e.unescape().map(|t| t.trim().to_string()).unwrap_or_default();
+                match field.as_str() {
+                    "title" => doc.title = text,
+                    "initial-creator" => initial_creator = text,
+                    "creator" => creator = text,
+                    "creation-date" => doc.metadata.created = text,
+                    "date" => doc.metadata.modified = text,
+                    _ => {}
+                }
+            }
+            Ok(Event::End(ref e)) => {
+                if e.local_name().as_ref() == b"meta" {
+                    in_meta = false;
+                }
+                field.clear();
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => {
+                warnings.push(ParseWarning::new(part, xml, reader.error_position(), &e));
+                break;
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    // The initial creator is the author, the creator who saved it last
+    if !initial_creator.is_empty() {
+        doc.metadata.author = initial_creator;
+    } else if !creator.is_empty() {
+        doc.metadata.author = creator;
+    }
+}
+
+/// A `draw:page` or `style:master-page` with its content
+struct ParsedPage {
+    name: String,
+    /// Master page the page is based on, empty for master pages
+    master: String,
+    is_master: bool,
+    /// Whether the page style sets a background
+    has_background: bool,
+    slide: Slide,
+    /// Layout of a master's placeholders by `presentation:class`
+    placeholders: HashMap<String, Placeholder>,
+    /// Text elements of a page that fill one of its master's placeholders
+    placeholder_frames: Vec<PlaceholderFrame>,
+}
+
+/// Position and text style a master page gives a placeholder class
+struct Placeholder {
+    bounds: Rect,
+    font: Option<FontStyle>,
+    alignment: Option<TextAlignment>,
+}
+
+/// A page frame with a `presentation:class`, and what it left unspecified
+struct PlaceholderFrame {
+    id: Uuid,
+    class: String,
+    has_bounds: bool,
+    has_alignment: bool,
+}
+
+impl ParsedPage {
+    /// Empty page with the background of its drawing-page style.
+    fn new(
+        name: String,
+        master: String,
+        is_master: bool,
+        style: Option<&StyleInfo>,
+        gradients: &HashMap<String, Gradient>,
+        fill_images: &HashMap<String, ImageData>,
+    ) -> Self {
+        let background = style.and_then(|style| {
+            if style.has_fill {
+                style.fill_color.clone().map(Background::Solid)
+            } else if style.has_gradient {
+                let gradient = style.gradient_name.as_ref().and_then(|n| gradients.get(n))?;
+                Some(Background::Gradient(gradient.clone()))
+            } else if style.has_bitmap {
+                let image = style.fill_image_name.as_ref().and_then(|n| fill_images.get(n))?;
+                Some(Background::Image(image.clone()))
+            } else {
+                None
+            }
+        });
+
+        Self {
+            name,
+            master,
+            is_master,
+            has_background: background.is_some(),
+            slide: background.map_or_else(Slide::new, Slide::with_background),
+            placeholders: HashMap::new(),
+            placeholder_frames: Vec::new(),
+        }
+    }
 }
 
 struct StyleInfo {
     fill_color: Option<Color>,
+    /// `draw:opacity` of a solid fill, from 0 to 1
+    fill_opacity: Option<f64>,
     stroke_color: Option<Color>,
+    stroke_opacity: Option<f64>,
+    /// `draw:image-opacity` of a picture frame
+    image_opacity: Option<f64>,
+    /// `fo:clip` of a picture frame, in lengths of the picture
+    clip: Option<Insets>,
     stroke_width: Option<f64>,
+    line_cap: LineCap,
+    line_join: LineJoin,
     has_fill: bool,
+    has_gradient: bool,
+    gradient_name: Option<String>,
+    has_bitmap: bool,
+    fill_image_name: Option<String>,
     has_stroke: bool,
     font_size: Option<f64>,
     font_color: Option<Color>,
     font_family: Option<String>,
     font_bold: bool,
     font_italic: bool,
+    font_underline: bool,
+    font_strikethrough: bool,
+    text_outline: Option<TextOutline>,
+    text_shadow: Option<TextShadow>,
     text_align: Option<TextAlignment>,
+    tab_stops: Vec<f64>,
+    line_spacing: Option<f64>,
+    space_before: f64,
+    space_after: f64,
+    padding: Insets,
+    vertical_alignment: VerticalAlignment,
+    shrink_to_fit: bool,
+    start_marker: LineMarker,
+    end_marker: LineMarker,
 }
 
 impl Default for StyleInfo {
     fn default() -> Self {
         Self {
             fill_color: None,
+            fill_opacity: None,
             stroke_color: None,
+            stroke_opacity: None,
+            image_opacity: None,
+            clip: None,
             stroke_width: None,
+            line_cap: LineCap::default(),
+            line_join: LineJoin::default(),
             has_fill: false,
+            has_gradient: false,
+            gradient_name: None,
+            has_bitmap: false,
+            fill_image_name: None,
             has_stroke: false,
             font_size: None,
             font_color: None,
             font_family: None,
             font_bold: false,
             font_italic: false,
+            font_underline: false,
+            font_strikethrough: false,
+            text_outline: None,
+            text_shadow: None,
             text_align: None,
+            tab_stops: Vec::new(),
+            line_spacing: None,
+            space_before: 0.0,
+            space_after: 0.0,
+            padding: Insets::default(),
+            vertical_alignment: VerticalAlignment::default(),
+            shrink_to_fit: false,
+            start_marker: LineMarker::None,
+            end_marker: LineMarker::None,
         }
     }
 }
 
-fn parse_content<R: Read + io::Seek>(
-    content_xml: &str,
-    archive: &mut ZipArchive<R>,
-) -> io::Result<Document> {
-    let mut doc = Document::new();
-    doc.slides.clear();
+/// Parse the `draw:page`s and `style:master-page`s of a part, using the
+/// styles of the same part.
+fn parse_pages(
+    xml: &str,
+    part: &str,
+    gradients: &HashMap<String, Gradient>,
+    fill_images: &HashMap<String, ImageData>,
+    package: &mut Package,
+    warnings: &mut Vec<ParseWarning>,
+) -> Vec<ParsedPage> {
+    let mut pages = Vec::new();
 
-    let mut reader = Reader::from_str(content_xml);
+    let mut reader = Reader::from_str(xml);
     let mut buf = Vec::new();
 
-    // First pass: collect styles
+    // First pass: collect automatic and named styles
     let mut styles: HashMap<String, StyleInfo> = HashMap::new();
-    let mut in_auto_styles = false;
+    let mut in_styles = false;
     let mut current_style_name = String::new();
     let mut current_style = StyleInfo::default();
+    // Kind of each list style, taken from its first level
+    let mut list_styles: HashMap<String, ListStyle> = HashMap::new();
+    let mut current_list_style = String::new();
 
     loop {
         match reader.read_event_into(&mut buf) {
             Ok(Event::Start(ref e)) => {
                 let name = String::from_utf8_lossy(e.local_name().as_ref()).to_string();
-                if name == "automatic-styles" {
-                    in_auto_styles = true;
-                } else if in_auto_styles && name == "style" {
+                // Named styles hold the look of master page placeholders
+                if name == "automatic-styles" || name == "styles" {
+                    in_styles = true;
+                } else if in_styles && name == "style" {
                     current_style = StyleInfo::default();
                     for attr in e.attributes().flatten() {
                         let key = String::from_utf8_lossy(attr.key.local_name().as_ref()).to_string();
@@ -158,25 +429,41 @@ fn parse_content<R: Read + io::Seek>(
                             current_style_name = String::from_utf8_lossy(&attr.value).to_string();
                         }
                     }
+                } else if in_styles && name == "paragraph-properties" {
+                    parse_paragraph_props(e, &mut current_style);
+                } else if in_styles && name == "list-style" {
+                    current_list_style = get_attr(e, "name");
+                } else if in_styles && name.starts_with("list-level-style-") {
+                    list_styles
+                        .entry(current_list_style.clone())
+                        .or_insert_with(|| list_level_kind(&name));
                 }
             }
             Ok(Event::Empty(ref e)) => {
                 let name = String::from_utf8_lossy(e.local_name().as_ref()).to_string();
-                if in_auto_styles {
-                    if name == "graphic-properties" {
+                if in_styles {
+                    if name.starts_with("list-level-style-") {
+                        list_styles
+                            .entry(current_list_style.clone())
+                            .or_insert_with(|| list_level_kind(&name));
+                    } else if name == "graphic-properties" || name == "drawing-page-properties" {
                         parse_graphic_props(e, &mut current_style);
                     } else if name == "text-properties" {
                         parse_text_props(e, &mut current_style);
                     } else if name == "paragraph-properties" {
                         parse_paragraph_props(e, &mut current_style);
+                    } else if name == "tab-stop" {
+                        if let Some(position) = parse_cm(&get_attr(e, "position")) {
+                            current_style.tab_stops.push(position);
+                        }
                     }
                 }
             }
             Ok(Event::End(ref e)) => {
                 let name = String::from_utf8_lossy(e.local_name().as_ref()).to_string();
-                if name == "automatic-styles" {
-                    in_auto_styles = false;
-                } else if in_auto_styles && name == "style" {
+                if name == "automatic-styles" || name == "styles" {
+                    in_styles = false;
+                } else if in_styles && name == "style" {
                     if !current_style_name.is_empty() {
                         styles.insert(
                             current_style_name.clone(),
@@ -186,19 +473,40 @@ fn parse_content<R: Read + io::Seek>(
                 }
             }
             Ok(Event::Eof) => break,
-            Err(_) => break,
+            Err(e) => {
+                warnings.push(ParseWarning::new(
+                    part,
+                    xml,
+                    reader.error_position(),
+                    &e,
+                ));
+                break;
+            }
             _ => {}
         }
         buf.clear();
     }
 
     // Second pass: parse slides and elements
-    let mut reader = Reader::from_str(content_xml);
+    let mut reader = Reader::from_str(xml);
     buf.clear();
 
     let mut in_presentation = false;
+    let mut in_master_styles = false;
     let mut in_page = false;
+    let mut page_name = String::new();
+    let mut page_master = String::new();
+    let mut page_style_name = String::new();
+    // Speaker notes and master placeholders are not slide content
+    let mut skip_until: Option<&str> = None;
     let mut in_text_box = false;
+    // Whether each enclosing text:list is numbered, innermost last
+    let mut list_stack: Vec<ListStyle> = Vec::new();
+    let mut current_list = ListStyle::None;
+    let mut current_tab_stops: Vec<f64> = Vec::new();
+    // Line spacing, space before and space after of the current paragraph
+    let mut current_spacing = (1.0, 0.0, 0.0);
+    let mut current_level = 0;
     let mut in_paragraph = false;
     let mut in_span = false;
     let mut current_elements: Vec<SlideElement> = Vec::new();
@@ -206,57 +514,165 @@ fn parse_content<R: Read + io::Seek>(
     let mut current_runs: Vec<TextRun> = Vec::new();
     let mut current_run_text = String::new();
     let mut current_run_style = FontStyle::default();
+    // Outline and shadow of the text box, from the first run that has one
+    let mut current_outline: Option<TextOutline> = None;
+    let mut current_shadow: Option<TextShadow> = None;
     let mut current_text_align = TextAlignment::Left;
-    let mut frame_bounds = Rect::new(0.0, 0.0, 100.0, 100.0);
+    let mut frame = Frame::default();
     let mut in_frame = false;
+    let mut frame_class = String::new();
+    let mut frame_has_bounds = false;
+    let mut frame_has_alignment = false;
+    let mut current_placeholders: HashMap<String, Placeholder> = HashMap::new();
+    let mut current_placeholder_frames: Vec<PlaceholderFrame> = Vec::new();
+    // Title and description of the current frame's image, which follow it
+    let mut in_image_frame = false;
+    let mut in_alt_text = false;
+    let mut alt_text = String::new();
+    // Base64 image data inline in a flat document's draw:image
+    let mut in_binary_image = false;
+    let mut in_binary_data = false;
+    let mut binary_data = String::new();
+    // Bounds, rotation, style, type and attributes of the draw:custom-shape
+    // being read
+    let mut current_custom_shape: Option<(Rect, f64, String, ShapeType, ElementAttributes)> =
+        None;
 
     loop {
         match reader.read_event_into(&mut buf) {
             Ok(Event::Start(ref e)) => {
                 let name = String::from_utf8_lossy(e.local_name().as_ref()).to_string();
                 match name.as_str() {
+                    _ if skip_until.is_some() => {}
                     "presentation" => in_presentation = true,
+                    "master-styles" => in_master_styles = true,
                     "page" if in_presentation => {
                         in_page = true;
                         current_elements.clear();
+                        current_placeholders.clear();
+                        current_placeholder_frames.clear();
+                        page_name = get_attr(e, "name");
+                        page_master = get_attr(e, "master-page-name");
+                        page_style_name = get_attr(e, "style-name");
+                    }
+                    "master-page" if in_master_styles => {
+                        in_page = true;
+                        current_elements.clear();
+                        current_placeholders.clear();
+                        current_placeholder_frames.clear();
+                        page_name = get_attr(e, "name");
+                        page_master.clear();
+                        page_style_name = get_attr(e, "style-name");
+                    }
+                    "notes" if in_page => skip_until = Some("notes"),
+                    // Title and outline placeholders of a master only hold prompt
+                    // text, but their layout applies to the pages' placeholders
+                    "frame" if in_page && in_master_styles && !get_attr(e, "class").is_empty() => {
+                        let style = styles.get(&get_attr(e, "style-name"));
+                        current_placeholders.insert(
+                            get_attr(e, "class"),
+                            Placeholder {
+                                bounds: parse_bounds(e),
+                                font: style.filter(|s| s.font_size.is_some()).map(font_from_style),
+                                alignment: style.and_then(|s| s.text_align),
+                            },
+                        );
+                        skip_until = Some("frame");
                     }
                     "frame" if in_page => {
                         in_frame = true;
-                        frame_bounds = parse_bounds(e);
+                        frame = Frame::parse(e);
+                        frame_class = get_attr(e, "class");
+                        frame_has_bounds = !get_attr(e, "width").is_empty();
+                        frame_has_alignment = false;
+                    }
+                    "image" if in_frame && get_attr(e, "href").is_empty() => {
+                        in_binary_image = true;
+                        frame.filters = parse_filters(e);
+                    }
+                    "image" if in_frame => {
+                        frame.filters = parse_filters(e);
+                        let href = get_attr(e, "href");
+                        if let Ok(data) = package.read_bytes(&href) {
+                            let img = frame_image(data, guess_mime(&href), &href, &frame, &styles);
+                            current_elements.push(img);
+                            in_text_box = false;
+                            in_frame = false;
+                            in_image_frame = true;
+                        }
+                    }
+                    "binary-data" if in_binary_image => {
+                        in_binary_data = true;
+                        binary_data.clear();
+                    }
+                    "title" | "desc" if in_image_frame => {
+                        in_alt_text = true;
+                        alt_text.clear();
                     }
                     "text-box" if in_frame => {
                         in_text_box = true;
                         current_paragraphs.clear();
+                        current_outline = None;
+                        current_shadow = None;
+                    }
+                    "custom-shape" if in_page => {
+                        current_custom_shape = Some((
+                            parse_bounds(e),
+                            parse_rotation(e),
+                            get_attr(e, "style-name"),
+                            ShapeType::Rectangle,
+                            ElementAttributes::parse(e),
+                        ));
+                    }
+                    "enhanced-geometry" => {
+                        if let Some((bounds, _, _, shape_type, _)) = &mut current_custom_shape {
+                            *shape_type = custom_shape_type(&get_attr(e, "type"), bounds);
+                        }
+                    }
+                    "list" if in_text_box => {
+                        // Nested lists without a style continue their parent's kind
+                        let style_name = get_attr(e, "style-name");
+                        let list = if style_name.is_empty() {
+                            list_stack.last().copied().unwrap_or_default()
+                        } else {
+                            list_styles.get(&style_name).copied().unwrap_or_default()
+                        };
+                        list_stack.push(list);
                     }
                     "p" if in_text_box => {
                         in_paragraph = true;
                         current_runs.clear();
+                        (current_list, current_level) = match list_stack.last() {
+                            Some(&list) if list != ListStyle::None => {
+                                (list, list_stack.len() as u32 - 1)
+                            }
+                            _ => (ListStyle::None, 0),
+                        };
                         let ps_name = get_attr(e, "style-name");
-                        current_text_align = styles
+                        let text_align = styles.get(&ps_name).and_then(|s| s.text_align);
+                        frame_has_alignment |= text_align.is_some();
+                        current_text_align = text_align.unwrap_or(TextAlignment::Left);
+                        current_tab_stops = styles
                             .get(&ps_name)
-                            .and_then(|s| s.text_align)
-                            .unwrap_or(TextAlignment::Left);
+                            .map(|s| s.tab_stops.clone())
+                            .unwrap_or_default();
+                        current_spacing = styles.get(&ps_name).map_or((1.0, 0.0, 0.0), |s| {
+                            (s.line_spacing.unwrap_or(1.0), s.space_before, s.space_after)
+                        });
                     }
                     "span" if in_paragraph => {
                         in_span = true;
                         current_run_text.clear();
                         let ts_name = get_attr(e, "style-name");
+                        current_run_style =
+                            styles.get(&ts_name).map(font_from_style).unwrap_or_default();
                         if let Some(style) = styles.get(&ts_name) {
-                            current_run_style = FontStyle {
-                                family: style
-                                    .font_family
-                                    .clone()
-                                    .unwrap_or_else(|| "Sans".to_string()),
-                                size: style.font_size.unwrap_or(24.0),
-                                bold: style.font_bold,
-                                italic: style.font_italic,
-                                color: style
-                                    .font_color
-                                    .clone()
-                                    .unwrap_or_else(Color::black),
-                            };
-                        } else {
-                            current_run_style = FontStyle::default();
+                            if current_outline.is_none() {
+                                current_outline = style.text_outline.clone();
+                            }
+                            if current_shadow.is_none() {
+                                current_shadow = style.text_shadow.clone();
+                            }
                         }
                     }
                     _ => {}
@@ -265,34 +681,97 @@ fn parse_content<R: Read + io::Seek>(
             Ok(Event::Empty(ref e)) => {
                 let name = String::from_utf8_lossy(e.local_name().as_ref()).to_string();
                 match name.as_str() {
+                    _ if skip_until.is_some() => {}
+                    "page" if in_presentation => {
+                        pages.push(ParsedPage::new(
+                            get_attr(e, "name"),
+                            get_attr(e, "master-page-name"),
+                            false,
+                            styles.get(&get_attr(e, "style-name")),
+                            gradients,
+                            fill_images,
+                        ));
+                    }
                     "rect" if in_page => {
                         let bounds = parse_bounds(e);
                         let style_name = get_attr(e, "style-name");
-                        let shape = build_shape(ShapeType::Rectangle, bounds, &style_name, &styles);
-                        current_elements.push(SlideElement::Shape(shape));
+                        let shape_type = match parse_cm(&get_attr(e, "corner-radius")) {
+                            Some(radius) if radius > 0.0 => ShapeType::RoundedRectangle { radius },
+                            _ => ShapeType::Rectangle,
+                        };
+                        let mut shape = build_shape(
+                            shape_type,
+                            bounds,
+                            &style_name,
+                            &styles,
+                            gradients,
+                        );
+                        shape.rotation = parse_rotation(e);
+                        let attributes = ElementAttributes::parse(e);
+                        current_elements.push(attributes.apply(SlideElement::Shape(shape)));
                     }
                     "ellipse" if in_page => {
                         let bounds = parse_bounds(e);
                         let style_name = get_attr(e, "style-name");
-                        let shape = build_shape(ShapeType::Ellipse, bounds, &style_name, &styles);
-                        current_elements.push(SlideElement::Shape(shape));
+                        let mut shape = build_shape(
+                            ShapeType::Ellipse,
+                            bounds,
+                            &style_name,
+                            &styles,
+                            gradients,
+                        );
+                        shape.rotation = parse_rotation(e);
+                        let attributes = ElementAttributes::parse(e);
+                        current_elements.push(attributes.apply(SlideElement::Shape(shape)));
                     }
                     "line" if in_page => {
-                        let bounds = parse_line_bounds(e);
+                        let (start, end) = parse_line_points(e);
                         let style_name = get_attr(e, "style-name");
-                        let shape = build_shape(ShapeType::Line, bounds, &style_name, &styles);
-                        current_elements.push(SlideElement::Shape(shape));
+                        let mut shape = build_shape(
+                            ShapeType::Line,
+                            Rect::new(0.0, 0.0, 0.0, 0.0),
+                            &style_name,
+                            &styles,
+                            gradients,
+                        );
+                        shape.set_line_endpoints(start, end);
+                        let attributes = ElementAttributes::parse(e);
+                        current_elements.push(attributes.apply(SlideElement::Shape(shape)));
+                    }
+                    // A custom shape without geometry is drawn as its bounds
+                    "custom-shape" if in_page => {
+                        let mut shape = build_shape(
+                            ShapeType::Rectangle,
+                            parse_bounds(e),
+                            &get_attr(e, "style-name"),
+                            &styles,
+                            gradients,
+                        );
+                        shape.rotation = parse_rotation(e);
+                        let attributes = ElementAttributes::parse(e);
+                        current_elements.push(attributes.apply(SlideElement::Shape(shape)));
+                    }
+                    "enhanced-geometry" => {
+                        if let Some((bounds, _, _, shape_type, _)) = &mut current_custom_shape {
+                            *shape_type = custom_shape_type(&get_attr(e, "type"), bounds);
+                        }
+                    }
+                    "tab" if in_span => current_run_text.push('\t'),
+                    "tab" if in_paragraph => {
+                        current_runs.push(TextRun::new("\t", FontStyle::default()));
                     }
                     "image" if in_frame => {
+                        frame.filters = parse_filters(e);
                         let href = get_attr(e, "href");
                         if !href.is_empty() {
-                            if let Ok(data) = read_zip_entry_bytes(archive, &href) {
+                            if let Ok(data) = package.read_bytes(&href) {
                                 let mime = guess_mime(&href);
-                                let img = ImageElement::new(frame_bounds, data, mime.to_string());
-                                current_elements.push(SlideElement::Image(img));
+                                let img = frame_image(data, mime, &href, &frame, &styles);
+                                current_elements.push(img);
                                 // Skip creating a text element for this frame
                                 in_text_box = false;
                                 in_frame = false;
+                                in_image_frame = true;
                             }
                         }
                     }
@@ -300,7 +779,13 @@ fn parse_content<R: Read + io::Seek>(
                 }
             }
             Ok(Event::Text(ref e)) => {
-                if in_span {
+                if in_binary_data {
+                    binary_data.push_str(&String::from_utf8_lossy(e));
+                } else if in_alt_text {
+                    if let Ok(text) = e.unescape() {
+                        alt_text.push_str(&text);
+                    }
+                } else if in_span {
                     if let Ok(text) = e.unescape() {
                         current_run_text.push_str(&text);
                     }
@@ -317,28 +802,99 @@ fn parse_content<R: Read + io::Seek>(
             Ok(Event::End(ref e)) => {
                 let name = String::from_utf8_lossy(e.local_name().as_ref()).to_string();
                 match name.as_str() {
+                    _ if skip_until == Some(name.as_str()) => skip_until = None,
+                    _ if skip_until.is_some() => {}
                     "presentation" => in_presentation = false,
-                    "page" if in_page => {
+                    "master-styles" => in_master_styles = false,
+                    "page" | "master-page" if in_page => {
                         in_page = false;
-                        let mut slide = crate::model::slide::Slide::new();
-                        slide.elements = current_elements.drain(..).collect();
-                        doc.slides.push(slide);
+                        let mut page = ParsedPage::new(
+                            std::mem::take(&mut page_name),
+                            std::mem::take(&mut page_master),
+                            name == "master-page",
+                            styles.get(&page_style_name),
+                            gradients,
+                            fill_images,
+                        );
+                        page.slide.elements = current_elements.drain(..).collect();
+                        page.placeholders = std::mem::take(&mut current_placeholders);
+                        page.placeholder_frames = std::mem::take(&mut current_placeholder_frames);
+                        pages.push(page);
+                    }
+                    "binary-data" if in_binary_data => {
+                        in_binary_data = false;
+                        binary_data.retain(|c| !c.is_ascii_whitespace());
+                        let decoded = base64::engine::general_purpose::STANDARD.decode(&binary_data);
+                        if let Ok(data) = decoded {
+                            let mime = sniff_mime(&data);
+                            let img = frame_image(data, mime, "", &frame, &styles);
+                            current_elements.push(img);
+                            in_text_box = false;
+                            in_frame = false;
+                            in_image_frame = true;
+                        }
+                    }
+                    "image" if in_binary_image => in_binary_image = false,
+                    "custom-shape" if current_custom_shape.is_some() => {
+                        if let Some((bounds, rotation, style_name, shape_type, attributes)) =
+                            current_custom_shape.take()
+                        {
+                            let mut shape =
+                                build_shape(shape_type, bounds, &style_name, &styles, gradients);
+                            shape.rotation = rotation;
+                            current_elements.push(attributes.apply(SlideElement::Shape(shape)));
+                        }
                     }
                     "frame" if in_frame => {
                         in_frame = false;
                     }
+                    "frame" => in_image_frame = false,
+                    // The description wins over the shorter title
+                    "title" | "desc" if in_alt_text => {
+                        in_alt_text = false;
+                        if let Some(SlideElement::Image(img)) = current_elements.last_mut() {
+                            if name == "desc" || img.alt_text.is_empty() {
+                                img.alt_text = alt_text.trim().to_string();
+                            }
+                        }
+                    }
+                    "list" if in_text_box => {
+                        list_stack.pop();
+                    }
                     "text-box" if in_text_box => {
                         in_text_box = false;
-                        let mut text = TextElement::new(frame_bounds, "");
+                        let mut text = TextElement::new(frame.bounds, "");
                         text.paragraphs = current_paragraphs.drain(..).collect();
                         text.alignment = current_text_align;
+                        text.outline = current_outline.take();
+                        text.shadow = current_shadow.take();
+                        text.rotation = frame.rotation;
+                        if let Some(style) = styles.get(&frame.style_name) {
+                            text.inset = style.padding;
+                            text.vertical_alignment = style.vertical_alignment;
+                            text.auto_fit = style.shrink_to_fit;
+                            text.fill = style_fill(style, gradients);
+                        }
                         if !text.paragraphs.is_empty() {
-                            current_elements.push(SlideElement::Text(text));
+                            if !frame_class.is_empty() {
+                                current_placeholder_frames.push(PlaceholderFrame {
+                                    id: text.id,
+                                    class: frame_class.clone(),
+                                    has_bounds: frame_has_bounds,
+                                    has_alignment: frame_has_alignment,
+                                });
+                            }
+                            current_elements.push(frame.attributes.apply(SlideElement::Text(text)));
                         }
                     }
                     "p" if in_paragraph => {
                         in_paragraph = false;
-                        let para = TextParagraph::new(current_runs.drain(..).collect());
+                        let mut para = TextParagraph::new(current_runs.drain(..).collect());
+                        para.list = current_list;
+                        para.level = current_level;
+                        para.tab_stops = std::mem::take(&mut current_tab_stops);
+                        (para.line_spacing, para.space_before, para.space_after) =
+                            current_spacing;
                         current_paragraphs.push(para);
                     }
                     "span" if in_span => {
@@ -354,18 +910,189 @@ fn parse_content<R: Read + io::Seek>(
                 }
             }
             Ok(Event::Eof) => break,
-            Err(_) => break,
+            Err(e) => {
+                warnings.push(ParseWarning::new(
+                    part,
+                    xml,
+                    reader.error_position(),
+                    &e,
+                ));
+                break;
+            }
             _ => {}
         }
         buf.clear();
     }
 
-    // Ensure at least one slide
-    if doc.slides.is_empty() {
-        doc.slides.push(crate::model::slide::Slide::new());
+    pages
+}
+
+/// Attributes of the `draw:frame` being read that its content takes over
+struct Frame {
+    bounds: Rect,
+    rotation: f64,
+    scale_mode: ScaleMode,
+    crop: ImageCrop,
+    style_name: String,
+    attributes: ElementAttributes,
+    /// Filters of the frame's picture, from its `draw:image`
+    filters: ImageFilters,
+}
+
+impl Default for Frame {
+    fn default() -> Self {
+        Self {
+            bounds: Rect::new(0.0, 0.0, 100.0, 100.0),
+            rotation: 0.0,
+            scale_mode: ScaleMode::Stretch,
+            crop: ImageCrop::default(),
+            style_name: String::new(),
+            attributes: ElementAttributes::default(),
+            filters: ImageFilters::default(),
+        }
+    }
+}
+
+impl Frame {
+    fn parse(e: &quick_xml::events::BytesStart) -> Self {
+        Self {
+            bounds: parse_bounds(e),
+            rotation: parse_rotation(e),
+            scale_mode: parse_scale_mode(&get_attr(e, "scale-mode")),
+            crop: parse_crop(&get_attr(e, "crop")),
+            style_name: get_attr(e, "style-name"),
+            attributes: ElementAttributes::parse(e),
+            filters: ImageFilters::default(),
+        }
+    }
+}
+
+/// The `draw:name` of an element, and the settings Lumina writes as its
+/// own attributes
+struct ElementAttributes {
+    name: String,
+    visible: bool,
+    locked: bool,
+}
+
+impl Default for ElementAttributes {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            visible: true,
+            locked: false,
+        }
+    }
+}
+
+impl ElementAttributes {
+    fn parse(e: &quick_xml::events::BytesStart) -> Self {
+        Self {
+            name: get_attr(e, "name"),
+            visible: get_attr(e, "visible") != "false",
+            locked: get_attr(e, "locked") == "true",
+        }
+    }
+
+    /// `element` with these settings. An unnamed element keeps the name it
+    /// has, such as the file name of a metafile.
+    fn apply(&self, mut element: SlideElement) -> SlideElement {
+        if !self.name.is_empty() {
+            element.set_name(self.name.clone());
+        }
+        element.set_visible(self.visible);
+        element.set_locked(self.locked);
+        element
+    }
+}
+
+/// The picture of a frame, linked from `href` or inline when it is empty.
+fn frame_image(
+    data: Vec<u8>,
+    mime: &str,
+    href: &str,
+    frame: &Frame,
+    styles: &HashMap<String, StyleInfo>,
+) -> SlideElement {
+    let mut img = ImageElement::new(frame.bounds, data, mime.to_string());
+    img.name_metafile(href);
+    img.rotation = frame.rotation;
+    img.scale_mode = frame.scale_mode;
+    img.crop = image_crop(styles, &frame.style_name, frame.crop, &img);
+    img.opacity = image_opacity(styles, &frame.style_name);
+    img.filters = frame.filters;
+    frame.attributes.apply(SlideElement::Image(img))
+}
+
+/// Composite a page onto its master page: the master's elements go behind
+/// the page's own, and its background is used unless the page sets one.
+/// Placeholder frames take the position and text style the page leaves out
+/// from the master's placeholder of the same class.
+fn apply_master(page: ParsedPage, masters: &HashMap<String, ParsedPage>) -> Slide {
+    let mut slide = page.slide;
+    let Some(master) = masters.get(&page.master) else {
+        return slide;
+    };
+
+    for frame in &page.placeholder_frames {
+        let Some(placeholder) = master.placeholders.get(&frame.class) else {
+            continue;
+        };
+        let text = slide.elements.iter_mut().find_map(|element| match element {
+            SlideElement::Text(text) if text.id == frame.id => Some(text),
+            _ => None,
+        });
+        let Some(text) = text else { continue };
+
+        if !frame.has_bounds {
+            text.bounds = placeholder.bounds;
+        }
+        if let (false, Some(alignment)) = (frame.has_alignment, placeholder.alignment) {
+            text.alignment = alignment;
+        }
+        if let Some(font) = &placeholder.font {
+            // Runs without a text style inherit the placeholder's
+            let default_font = FontStyle::default();
+            for run in text.paragraphs.iter_mut().flat_map(|p| &mut p.runs) {
+                if run.font == default_font {
+                    run.font = font.clone();
+                }
+            }
+        }
+    }
+
+    if !page.has_background && master.has_background {
+        slide.background = master.slide.background.clone();
     }
+    let mut elements: Vec<SlideElement> = master
+        .slide
+        .elements
+        .iter()
+        .cloned()
+        .map(|mut element| {
+            element.set_id(Uuid::new_v4());
+            element
+        })
+        .collect();
+    elements.append(&mut slide.elements);
+    slide.elements = elements;
+    slide
+}
 
-    Ok(doc)
+/// Run font of a text style, with defaults for what it leaves out.
+fn font_from_style(style: &StyleInfo) -> FontStyle {
+    FontStyle {
+        family: style
+            .font_family
+            .clone()
+            .unwrap_or_else(|| "Sans".to_string()),
+        size: style.font_size.unwrap_or(24.0),
+        bold: style.font_bold,
+        italic: style.font_italic,
+        underline: style.font_underline,
+        strikethrough: style.font_strikethrough,
+        color: style.font_color.clone().unwrap_or_else(Color::black),
+    }
 }
 
 fn parse_graphic_props(e: &quick_xml::events::BytesStart, style: &mut StyleInfo) {
@@ -373,17 +1100,177 @@ fn parse_graphic_props(e: &quick_xml::events::BytesStart, style: &mut StyleInfo)
         let key = String::from_utf8_lossy(attr.key.local_name().as_ref()).to_string();
         let val = String::from_utf8_lossy(&attr.value).to_string();
         match key.as_str() {
-            "fill" => style.has_fill = val == "solid",
+            "fill" => {
+                style.has_fill = val == "solid";
+                style.has_gradient = val == "gradient";
+                style.has_bitmap = val == "bitmap";
+            }
             "fill-color" => style.fill_color = parse_color(&val),
+            "fill-gradient-name" => style.gradient_name = Some(val),
+            "fill-image-name" => style.fill_image_name = Some(val),
             "stroke" => style.has_stroke = val == "solid",
+            "opacity" => style.fill_opacity = parse_opacity(&val),
             "stroke-color" => style.stroke_color = parse_color(&val),
+            "stroke-opacity" => style.stroke_opacity = parse_opacity(&val),
+            "image-opacity" => style.image_opacity = parse_opacity(&val),
+            "clip" => style.clip = parse_clip(&val),
             "stroke-width" => style.stroke_width = parse_cm(&val),
+            "marker-start" => style.start_marker = parse_marker(&val),
+            "marker-end" => style.end_marker = parse_marker(&val),
+            "stroke-linecap" => {
+                style.line_cap = match val.as_str() {
+                    "round" => LineCap::Round,
+                    "square" => LineCap::Square,
+                    _ => LineCap::Butt,
+                };
+            }
+            "stroke-linejoin" => {
+                style.line_join = match val.as_str() {
+                    "round" => LineJoin::Round,
+                    "bevel" => LineJoin::Bevel,
+                    _ => LineJoin::Miter,
+                };
+            }
+            "padding" => {
+                if let Some(padding) = parse_cm(&val) {
+                    style.padding = Insets::uniform(padding);
+                }
+            }
+            // ODF 1.3 shrink-to-fit, or the draw:fit-to-size of LibreOffice
+            "shrink-to-fit" if val == "true" => style.shrink_to_fit = true,
+            "fit-to-size" if val == "shrink-to-fit" => style.shrink_to_fit = true,
+            "textarea-vertical-align" => {
+                style.vertical_alignment = match val.as_str() {
+                    "middle" => VerticalAlignment::Middle,
+                    "bottom" => VerticalAlignment::Bottom,
+                    _ => VerticalAlignment::Top,
+                };
+            }
+            "padding-left" => style.padding.left = parse_cm(&val).unwrap_or(0.0),
+            "padding-top" => style.padding.top = parse_cm(&val).unwrap_or(0.0),
+            "padding-right" => style.padding.right = parse_cm(&val).unwrap_or(0.0),
+            "padding-bottom" => style.padding.bottom = parse_cm(&val).unwrap_or(0.0),
+            _ => {}
+        }
+    }
+}
+
+/// Collect the `draw:gradient` definitions of a document part by name.
+fn parse_gradients(
+    xml: &str,
+    part: &str,
+    gradients: &mut HashMap<String, Gradient>,
+    warnings: &mut Vec<ParseWarning>,
+) {
+    let mut reader = Reader::from_str(xml);
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Empty(ref e)) | Ok(Event::Start(ref e))
+                if e.local_name().as_ref() == b"gradient" =>
+            {
+                let name = get_attr(e, "name");
+                let start = parse_color(&get_attr(e, "start-color")).unwrap_or_else(Color::black);
+                let end = parse_color(&get_attr(e, "end-color")).unwrap_or_else(Color::white);
+                let gradient = match get_attr(e, "style").as_str() {
+                    // ODF radial gradients start at the border and end in the center
+                    "radial" | "ellipsoid" | "square" | "rectangular" => {
+                        Gradient::radial(end, start)
+                    }
+                    _ => {
+                        // ODF angles run counter-clockwise from top-to-bottom
+                        let angle = parse_angle(&get_attr(e, "angle"));
+                        Gradient::linear(start, end, (90.0 - angle).rem_euclid(360.0))
+                    }
+                };
+                if !name.is_empty() {
+                    gradients.insert(name, gradient);
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => {
+                warnings.push(ParseWarning::new(part, xml, reader.error_position(), &e));
+                break;
+            }
             _ => {}
         }
+        buf.clear();
+    }
+}
+
+/// Collect the pictures of the `draw:fill-image` definitions of a document
+/// part by name, linked from the package or inline in flat XML.
+fn parse_fill_images(
+    xml: &str,
+    part: &str,
+    package: &mut Package,
+    images: &mut HashMap<String, ImageData>,
+    warnings: &mut Vec<ParseWarning>,
+) {
+    let mut reader = Reader::from_str(xml);
+    let mut buf = Vec::new();
+    // Name of the open fill-image waiting for its binary data
+    let mut pending: Option<String> = None;
+    let mut binary_data = String::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Empty(ref e)) | Ok(Event::Start(ref e))
+                if e.local_name().as_ref() == b"fill-image" =>
+            {
+                let name = get_attr(e, "name");
+                let href = get_attr(e, "href");
+                if href.is_empty() {
+                    pending = Some(name);
+                    binary_data.clear();
+                } else if let Ok(data) = package.read_bytes(&href) {
+                    let mime = guess_mime(&href).to_string();
+                    images.insert(name, ImageData::Embedded { data: data.into(), mime });
+                }
+            }
+            Ok(Event::Text(ref e)) if pending.is_some() => {
+                binary_data.push_str(&String::from_utf8_lossy(e));
+            }
+            Ok(Event::End(ref e)) if e.local_name().as_ref() == b"fill-image" => {
+                if let Some(name) = pending.take() {
+                    binary_data.retain(|c| !c.is_ascii_whitespace());
+                    let decoded = base64::engine::general_purpose::STANDARD.decode(&binary_data);
+                    if let Ok(data) = decoded {
+                        let mime = sniff_mime(&data).to_string();
+                        images.insert(name, ImageData::Embedded { data: data.into(), mime });
+                    }
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => {
+                warnings.push(ParseWarning::new(part, xml, reader.error_position(), &e));
+                break;
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+}
+
+/// Parse an ODF angle in degrees. Plain numbers are tenths of a degree.
+fn parse_angle(val: &str) -> f64 {
+    let val = val.trim();
+    if let Some(deg) = val.strip_suffix("deg") {
+        deg.parse().unwrap_or(0.0)
+    } else if let Some(rad) = val.strip_suffix("rad") {
+        rad.parse::<f64>().map(f64::to_degrees).unwrap_or(0.0)
+    } else if let Some(grad) = val.strip_suffix("grad") {
+        grad.parse::<f64>().map(|g| g * 0.9).unwrap_or(0.0)
+    } else {
+        val.parse::<f64>().map(|v| v / 10.0).unwrap_or(0.0)
     }
 }
 
 fn parse_text_props(e: &quick_xml::events::BytesStart, style: &mut StyleInfo) {
+    let mut outlined = false;
+    let (mut outline_width, mut outline_color, mut outline_opacity) = (None, None, None);
+    let mut shadow_opacity = None;
     for attr in e.attributes().flatten() {
         let key = String::from_utf8_lossy(attr.key.local_name().as_ref()).to_string();
         let val = String::from_utf8_lossy(&attr.value).to_string();
@@ -395,23 +1282,110 @@ fn parse_text_props(e: &quick_xml::events::BytesStart, style: &mut StyleInfo) {
             }
             "color" => style.font_color = parse_color(&val),
             "font-name" | "font-family" => style.font_family = Some(val),
-            "font-weight" => style.font_bold = val == "bold",
-            "font-style" => style.font_italic = val == "italic",
+            "font-weight" => style.font_bold = is_bold_weight(&val),
+            "font-style" => style.font_italic = val == "italic" || val == "oblique",
+            "text-underline-style" => style.font_underline = val != "none",
+            "text-line-through-style" => style.font_strikethrough = val != "none",
+            "text-outline" => outlined = val == "true",
+            "text-outline-width" => outline_width = parse_cm(&val),
+            "text-outline-color" => outline_color = parse_color(&val),
+            "text-outline-opacity" => outline_opacity = parse_opacity(&val),
+            "text-shadow" => style.text_shadow = parse_text_shadow(&val),
+            "text-shadow-opacity" => shadow_opacity = parse_opacity(&val),
             _ => {}
         }
     }
+
+    // Outlines from other applications are a thin line in the text color
+    if outlined {
+        let color = outline_color
+            .or_else(|| style.font_color.clone())
+            .unwrap_or_else(Color::black);
+        style.text_outline = Some(TextOutline {
+            color: with_opacity(color, outline_opacity),
+            width: outline_width.unwrap_or(0.5),
+        });
+    }
+    if let Some(shadow) = &mut style.text_shadow {
+        shadow.color = with_opacity(shadow.color.clone(), shadow_opacity);
+    }
+}
+
+/// Text shadow of an `fo:text-shadow` such as `1pt 1pt #808080`: its
+/// horizontal and vertical offset and an optional color. A blur radius
+/// is ignored, and so are shadows after the first.
+fn parse_text_shadow(value: &str) -> Option<TextShadow> {
+    let shadow = value.split(',').next()?;
+    let mut offsets = Vec::new();
+    let mut color = None;
+    for token in shadow.split_whitespace() {
+        if token.starts_with('#') {
+            color = parse_color(token);
+        } else if let Some(length) = parse_cm(token) {
+            offsets.push(length);
+        }
+    }
+    match offsets[..] {
+        [offset_x, offset_y, ..] => Some(TextShadow {
+            color: color.unwrap_or_else(|| Color::new(0.0, 0.0, 0.0, 0.5)),
+            offset_x,
+            offset_y,
+        }),
+        _ => None,
+    }
+}
+
+/// An opacity given as a percentage or as a number from 0 to 1.
+fn parse_opacity(value: &str) -> Option<f64> {
+    let opacity = match value.trim().strip_suffix('%') {
+        Some(percent) => percent.trim().parse::<f64>().ok()? / 100.0,
+        None => value.trim().parse().ok()?,
+    };
+    Some(opacity.clamp(0.0, 1.0))
+}
+
+/// `color` with the alpha of `opacity`, if the style gives one.
+fn with_opacity(mut color: Color, opacity: Option<f64>) -> Color {
+    if let Some(opacity) = opacity {
+        color.a = opacity;
+    }
+    color
+}
+
+/// The list kind of a `text:list-level-style-*` element: numbers, or a
+/// bullet for bullet characters and images.
+fn list_level_kind(element: &str) -> ListStyle {
+    if element == "list-level-style-number" {
+        ListStyle::Numbered
+    } else {
+        ListStyle::Bullet
+    }
 }
 
 fn parse_paragraph_props(e: &quick_xml::events::BytesStart, style: &mut StyleInfo) {
     for attr in e.attributes().flatten() {
         let key = String::from_utf8_lossy(attr.key.local_name().as_ref()).to_string();
         let val = String::from_utf8_lossy(&attr.value).to_string();
-        if key == "text-align" {
-            style.text_align = Some(match val.as_str() {
-                "center" => TextAlignment::Center,
-                "end" | "right" => TextAlignment::Right,
-                _ => TextAlignment::Left,
-            });
+        match key.as_str() {
+            "text-align" => {
+                style.text_align = Some(match val.as_str() {
+                    "center" => TextAlignment::Center,
+                    "end" | "right" => TextAlignment::Right,
+                    _ => TextAlignment::Left,
+                });
+            }
+            // Only proportional spacing maps onto the model; fixed line
+            // heights keep single spacing
+            "line-height" => {
+                style.line_spacing = val
+                    .trim()
+                    .strip_suffix('%')
+                    .and_then(|v| v.parse::<f64>().ok())
+                    .map(|percent| percent / 100.0);
+            }
+            "margin-top" => style.space_before = parse_cm(&val).unwrap_or(0.0),
+            "margin-bottom" => style.space_after = parse_cm(&val).unwrap_or(0.0),
+            _ => {}
         }
     }
 }
@@ -450,10 +1424,125 @@ fn parse_bounds(e: &quick_xml::events::BytesStart) -> Rect {
         }
     }
 
-    Rect::new(x, y, w, h)
+    // Without svg:x/y the transform moves the top-left corner into place
+    // after turning the element about it. The bounds are the unturned box
+    // with the same center.
+    let (rotation, corner) = parse_transform(&get_attr(e, "transform"));
+    let corner = corner.unwrap_or(Point::new(x, y));
+    let angle = rotation.to_radians();
+    let (half_w, half_h) = (w / 2.0, h / 2.0);
+    let center_x = corner.x + half_w * angle.cos() - half_h * angle.sin();
+    let center_y = corner.y + half_w * angle.sin() + half_h * angle.cos();
+    Rect::new(center_x - half_w, center_y - half_h, w, h)
+}
+
+/// Scale mode of a picture frame written by Lumina. Pictures from other
+/// applications always fill their frame.
+fn parse_scale_mode(value: &str) -> ScaleMode {
+    match value {
+        "fit" => ScaleMode::Fit,
+        "fill" => ScaleMode::Fill,
+        _ => ScaleMode::Stretch,
+    }
+}
+
+/// Crop of a picture frame written by Lumina, as the fractions cut away
+/// on the left, top, right and bottom.
+fn parse_crop(value: &str) -> ImageCrop {
+    let sides: Vec<f64> = value
+        .split_whitespace()
+        .filter_map(|v| v.parse().ok())
+        .map(|v: f64| v.clamp(0.0, 1.0))
+        .collect();
+    match sides[..] {
+        [left, top, right, bottom] if left + right < 1.0 && top + bottom < 1.0 => {
+            ImageCrop { left, top, right, bottom }
+        }
+        _ => ImageCrop::default(),
+    }
+}
+
+/// Lengths cut away from the top, right, bottom and left of a picture by
+/// an `fo:clip` such as `rect(1cm, 0cm, 1cm, 0cm)`.
+fn parse_clip(value: &str) -> Option<Insets> {
+    let sides = value.trim().strip_prefix("rect(")?.strip_suffix(')')?;
+    let sides: Vec<f64> = sides
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|side| !side.is_empty())
+        .map(|side| if side == "auto" { Some(0.0) } else { parse_cm(side) })
+        .collect::<Option<_>>()?;
+    match sides[..] {
+        [top, right, bottom, left] => Some(Insets::new(left, top, right, bottom)),
+        _ => None,
+    }
+}
+
+/// Crop of a picture in a frame of style `style_name`: the `fo:clip` of
+/// the style when the picture's size is known, or else the `lumina:crop`
+/// of the frame.
+fn image_crop(
+    styles: &HashMap<String, StyleInfo>,
+    style_name: &str,
+    frame_crop: ImageCrop,
+    img: &ImageElement,
+) -> ImageCrop {
+    let clip = styles.get(style_name).and_then(|style| style.clip);
+    let (Some(clip), Some(size)) = (clip, img.image_data.physical_size()) else {
+        return frame_crop;
+    };
+    let crop = ImageCrop {
+        left: (clip.left / size.width).clamp(0.0, 1.0),
+        top: (clip.top / size.height).clamp(0.0, 1.0),
+        right: (clip.right / size.width).clamp(0.0, 1.0),
+        bottom: (clip.bottom / size.height).clamp(0.0, 1.0),
+    };
+    let (visible_w, visible_h) = crop.visible();
+    if visible_w > 0.0 && visible_h > 0.0 {
+        crop
+    } else {
+        frame_crop
+    }
+}
+
+/// Rotation in degrees clockwise of an element with a `draw:transform`.
+fn parse_rotation(e: &quick_xml::events::BytesStart) -> f64 {
+    parse_transform(&get_attr(e, "transform")).0
+}
+
+/// Rotation in degrees clockwise and translation of a `draw:transform` such
+/// as `rotate (0.5236) translate (2cm 3cm)`, whose rotation is in radians
+/// counterclockwise. Skew and scale are ignored.
+fn parse_transform(value: &str) -> (f64, Option<Point>) {
+    let mut rotation = 0.0;
+    let mut translate = None;
+    for op in value.split(')') {
+        let Some((name, args)) = op.split_once('(') else {
+            continue;
+        };
+        let args: Vec<&str> = args
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|arg| !arg.is_empty())
+            .collect();
+        match name.trim() {
+            "rotate" => {
+                if let Some(Ok(angle)) = args.first().map(|arg| arg.parse::<f64>()) {
+                    rotation = (-angle.to_degrees()).rem_euclid(360.0);
+                }
+            }
+            "translate" => {
+                let x = args.first().and_then(|arg| parse_cm(arg));
+                let y = args.get(1).map_or(Some(0.0), |arg| parse_cm(arg));
+                if let (Some(x), Some(y)) = (x, y) {
+                    translate = Some(Point::new(x, y));
+                }
+            }
+            _ => {}
+        }
+    }
+    (rotation, translate)
 }
 
-fn parse_line_bounds(e: &quick_xml::events::BytesStart) -> Rect {
+fn parse_line_points(e: &quick_xml::events::BytesStart) -> (Point, Point) {
     let mut x1 = 0.0;
     let mut y1 = 0.0;
     let mut x2 = 100.0;
@@ -487,44 +1576,140 @@ fn parse_line_bounds(e: &quick_xml::events::BytesStart) -> Rect {
         }
     }
 
-    let x = x1.min(x2);
-    let y = y1.min(y2);
-    Rect::new(x, y, (x2 - x1).abs(), (y2 - y1).abs())
+    (Point::new(x1, y1), Point::new(x2, y2))
+}
+
+/// Filters of a picture written by Lumina as attributes of its
+/// `draw:image`, within the ranges the model allows.
+fn parse_filters(e: &quick_xml::events::BytesStart) -> ImageFilters {
+    let defaults = ImageFilters::default();
+    let number = |key: &str, default: f64, min: f64, max: f64| {
+        get_attr(e, key)
+            .parse::<f64>()
+            .ok()
+            .filter(|v| v.is_finite())
+            .map_or(default, |v| v.clamp(min, max))
+    };
+    ImageFilters {
+        grayscale: get_attr(e, "grayscale") == "true",
+        brightness: number("brightness", defaults.brightness, -1.0, 1.0),
+        contrast: number("contrast", defaults.contrast, 0.0, 2.0),
+        saturation: number("saturation", defaults.saturation, 0.0, 2.0),
+    }
+}
+
+/// Whether a `fo:font-weight` counts as bold: the keyword or a numeric
+/// weight of semibold (600) and up.
+fn is_bold_weight(val: &str) -> bool {
+    match val {
+        "bold" => true,
+        _ => val.parse::<u32>().is_ok_and(|weight| weight >= 600),
+    }
 }
 
+/// Unescaped value of the attribute named `local_name` in any namespace,
+/// or an empty string.
 fn get_attr(e: &quick_xml::events::BytesStart, local_name: &str) -> String {
     for attr in e.attributes().flatten() {
         let key = String::from_utf8_lossy(attr.key.local_name().as_ref()).to_string();
         if key == local_name {
-            return String::from_utf8_lossy(&attr.value).to_string();
+            return match attr.unescape_value() {
+                Ok(value) => value.into_owned(),
+                Err(_) => String::from_utf8_lossy(&attr.value).to_string(),
+            };
         }
     }
     String::new()
 }
 
+/// Shape type of a `draw:enhanced-geometry` preset, or a rectangle for
+/// presets without an equivalent.
+fn custom_shape_type(draw_type: &str, bounds: &Rect) -> ShapeType {
+    // LibreOffice names imported PowerPoint presets after them
+    let draw_type = draw_type.strip_prefix("ooxml-").unwrap_or(draw_type);
+    match draw_type {
+        "ellipse" => ShapeType::Ellipse,
+        "isosceles-triangle" | "triangle" => ShapeType::Triangle,
+        "round-rectangle" | "round-square" | "roundRect" => ShapeType::RoundedRectangle {
+            radius: bounds.size.width.min(bounds.size.height) * PRESET_CORNER_RATIO,
+        },
+        "right-arrow" | "rightArrow" => ShapeType::Arrow,
+        _ => match draw_type.strip_prefix("star").and_then(|n| n.parse().ok()) {
+            Some(points) => ShapeType::Star { points },
+            None => ShapeType::Rectangle,
+        },
+    }
+}
+
 fn parse_color(hex: &str) -> Option<Color> {
     Color::from_hex(hex)
 }
 
+/// Line marker for a `draw:marker` name. Markers other than dots, such as
+/// the triangles and arrows of LibreOffice, become arrowheads.
+fn parse_marker(name: &str) -> LineMarker {
+    let name = name.to_ascii_lowercase();
+    if name.is_empty() {
+        LineMarker::None
+    } else if name.contains("circle") || name.contains("dot") {
+        LineMarker::Circle
+    } else {
+        LineMarker::Arrow
+    }
+}
+
+/// Opacity of the picture in a frame of style `style_name`.
+fn image_opacity(styles: &HashMap<String, StyleInfo>, style_name: &str) -> f64 {
+    styles
+        .get(style_name)
+        .and_then(|style| style.image_opacity)
+        .unwrap_or(1.0)
+}
+
+/// Solid or gradient fill of a graphic style; `None` for any other fill.
+fn style_fill(style: &StyleInfo, gradients: &HashMap<String, Gradient>) -> Option<FillStyle> {
+    let gradient = style.gradient_name.as_ref().and_then(|n| gradients.get(n));
+    if style.has_fill {
+        style
+            .fill_color
+            .as_ref()
+            .map(|c| FillStyle::new(with_opacity(c.clone(), style.fill_opacity)))
+    } else if let (true, Some(gradient)) = (style.has_gradient, gradient) {
+        let color = style.fill_color.clone().unwrap_or_else(|| gradient.start.clone());
+        let mut fill = FillStyle::new(color);
+        fill.gradient = Some(gradient.clone());
+        Some(fill)
+    } else {
+        None
+    }
+}
+
 fn build_shape(
     shape_type: ShapeType,
     bounds: Rect,
     style_name: &str,
     styles: &HashMap<String, StyleInfo>,
+    gradients: &HashMap<String, Gradient>,
 ) -> ShapeElement {
     let mut shape = ShapeElement::new(bounds, shape_type);
 
     if let Some(style) = styles.get(style_name) {
-        if style.has_fill {
-            shape.fill = style.fill_color.as_ref().map(|c| FillStyle::new(c.clone()));
-        } else {
-            shape.fill = None;
-        }
+        shape.fill = style_fill(style, gradients);
         if style.has_stroke {
-            shape.stroke = Some(StrokeStyle::new(
-                style.stroke_color.clone().unwrap_or_else(Color::black),
+            let mut stroke = StrokeStyle::new(
+                with_opacity(
+                    style.stroke_color.clone().unwrap_or_else(Color::black),
+                    style.stroke_opacity,
+                ),
                 style.stroke_width.unwrap_or(2.0),
-            ));
+            );
+            stroke.line_cap = style.line_cap;
+            stroke.line_join = style.line_join;
+            shape.stroke = Some(stroke);
+            if shape_type == ShapeType::Line {
+                shape.start_marker = style.start_marker;
+                shape.end_marker = style.end_marker;
+            }
         } else {
             shape.stroke = None;
         }
@@ -533,6 +1718,27 @@ fn build_shape(
     shape
 }
 
+/// Image type of embedded data from its leading bytes, PNG when unknown.
+fn sniff_mime(data: &[u8]) -> &'static str {
+    if data.starts_with(b"\xFF\xD8\xFF") {
+        "image/jpeg"
+    } else if data.starts_with(b"GIF8") {
+        "image/gif"
+    } else if data.starts_with(b"RIFF") && data.get(8..12) == Some(b"WEBP".as_slice()) {
+        "image/webp"
+    } else if data.starts_with(b"<?xml") || data.starts_with(b"<svg") {
+        "image/svg+xml"
+    } else if data.starts_with(b"\x01\x00\x00\x00") && data.get(40..44) == Some(b" EMF".as_slice())
+    {
+        "image/x-emf"
+    } else if data.starts_with(b"\xD7\xCD\xC6\x9A") {
+        // Placeable WMF header
+        "image/x-wmf"
+    } else {
+        "image/png"
+    }
+}
+
 fn guess_mime(path: &str) -> &str {
     if path.ends_with(".png") {
         "image/png"
@@ -542,7 +1748,236 @@ fn guess_mime(path: &str) -> &str {
         "image/svg+xml"
     } else if path.ends_with(".webp") {
         "image/webp"
+    } else if path.ends_with(".emf") {
+        "image/x-emf"
+    } else if path.ends_with(".wmf") {
+        "image/x-wmf"
     } else {
         "image/png"
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::testing::{png_header, TempFile};
+    use crate::model::image::ImageData;
+
+    #[test]
+    fn truncated_xml_is_reported() {
+        let file = TempFile::with_zip(
+            "truncated.odp",
+            &[(
+                "content.xml",
+                b"<?xml version=\"1.0\"?>\n<office:document-content><office:body><office:presentation>\n\
+                  <draw:page draw:name=\"One\"><draw:frame svg:x=\"1cm\" svg:y=",
+            )],
+        );
+        let (doc, warnings) = load_document(file.path()).unwrap();
+        assert!(!warnings.is_empty());
+        assert_eq!(warnings[0].part, "content.xml");
+        assert_eq!(warnings[0].line, 3);
+        assert_eq!(doc.slides.len(), 1);
+    }
+
+    #[test]
+    fn each_broken_part_is_reported_once() {
+        let file = odp(
+            "<office:automatic-styles></office:styles>",
+            "<draw:page draw:name=\"One\"></draw:frame>",
+            &[],
+        );
+        let (_, warnings) = load_document(file.path()).unwrap();
+        let parts: Vec<&str> = warnings.iter().map(|w| w.part.as_str()).collect();
+        assert_eq!(parts, ["styles.xml", "content.xml"], "{:?}", warnings);
+    }
+
+    const NAMESPACES: &str = "xmlns:office=\"urn:oasis:names:tc:opendocument:xmlns:office:1.0\" \
+        xmlns:style=\"urn:oasis:names:tc:opendocument:xmlns:style:1.0\" \
+        xmlns:text=\"urn:oasis:names:tc:opendocument:xmlns:text:1.0\" \
+        xmlns:draw=\"urn:oasis:names:tc:opendocument:xmlns:drawing:1.0\" \
+        xmlns:fo=\"urn:oasis:names:tc:opendocument:xmlns:xsl-fo-compatible:1.0\" \
+        xmlns:xlink=\"http://www.w3.org/1999/xlink\" \
+        xmlns:svg=\"urn:oasis:names:tc:opendocument:xmlns:svg-compatible:1.0\" \
+        xmlns:presentation=\"urn:oasis:names:tc:opendocument:xmlns:presentation:1.0\"";
+
+    /// A packaged ODP whose styles.xml holds `styles` and content.xml the
+    /// `pages`, with `files` stored next to them.
+    fn odp(styles: &str, pages: &str, files: &[(&str, &[u8])]) -> TempFile {
+        let styles = format!(
+            "<?xml version=\"1.0\"?>\n<office:document-styles {}>{}</office:document-styles>",
+            NAMESPACES, styles
+        );
+        let content = format!(
+            "<?xml version=\"1.0\"?>\n<office:document-content {}><office:body>\
+             <office:presentation>{}</office:presentation></office:body>\
+             </office:document-content>",
+            NAMESPACES, pages
+        );
+        let mut entries = vec![
+            ("content.xml", content.as_bytes()),
+            ("styles.xml", styles.as_bytes()),
+        ];
+        entries.extend_from_slice(files);
+        TempFile::with_zip("fixture.odp", &entries)
+    }
+
+    #[test]
+    fn master_page_logo_appears_on_its_slides() {
+        let logo = png_header(64, 32);
+        let file = odp(
+            "<office:master-styles><style:master-page style:name=\"Branded\">\
+             <draw:frame svg:x=\"1cm\" svg:y=\"1cm\" svg:width=\"2cm\" svg:height=\"1cm\">\
+             <draw:image xlink:href=\"Pictures/logo.png\"/></draw:frame>\
+             </style:master-page></office:master-styles>",
+            "<draw:page draw:name=\"One\" draw:master-page-name=\"Branded\">\
+             <draw:frame svg:x=\"5cm\" svg:y=\"5cm\" svg:width=\"8cm\" svg:height=\"2cm\">\
+             <draw:text-box><text:p>Hello</text:p></draw:text-box></draw:frame></draw:page>\
+             <draw:page draw:name=\"Two\" draw:master-page-name=\"Branded\"/>",
+            &[("Pictures/logo.png", &logo)],
+        );
+
+        let (doc, warnings) = load_document(file.path()).unwrap();
+        assert!(warnings.is_empty(), "{:?}", warnings);
+        assert_eq!(doc.slides.len(), 2);
+        let mut ids = Vec::new();
+        for slide in &doc.slides {
+            // Behind the slide's own elements
+            let SlideElement::Image(img) = &slide.elements[0] else {
+                panic!("expected the logo first, got {:?}", slide.elements);
+            };
+            let ImageData::Embedded { data, mime } = &img.image_data;
+            assert_eq!((&data[..], mime.as_str()), (&logo[..], "image/png"));
+            assert!((img.bounds.origin.x - cm_to_pt(1.0)).abs() < 0.01);
+            assert!((img.bounds.size.width - cm_to_pt(2.0)).abs() < 0.01);
+            ids.push(img.id);
+        }
+        assert_ne!(ids[0], ids[1]);
+        assert!(matches!(doc.slides[0].elements[1], SlideElement::Text(_)));
+        assert_eq!(doc.slides[1].elements.len(), 1);
+    }
+
+    /// A packaged ODP whose content.xml holds the automatic `styles` and
+    /// the `pages`.
+    fn odp_with_automatic_styles(styles: &str, pages: &str) -> TempFile {
+        let content = format!(
+            "<?xml version=\"1.0\"?>\n<office:document-content {}>\
+             <office:automatic-styles>{}</office:automatic-styles><office:body>\
+             <office:presentation>{}</office:presentation></office:body>\
+             </office:document-content>",
+            NAMESPACES, styles, pages
+        );
+        TempFile::with_zip("fixture.odp", &[("content.xml", content.as_bytes())])
+    }
+
+    #[test]
+    fn numeric_font_weights_from_600_are_bold() {
+        let weights = ["400", "500", "600", "700", "900", "normal", "bold"];
+        let styles: String = weights
+            .iter()
+            .enumerate()
+            .map(|(i, weight)| {
+                format!(
+                    "<style:style style:name=\"T{}\" style:family=\"text\">\
+                     <style:text-properties fo:font-weight=\"{}\"/></style:style>",
+                    i, weight
+                )
+            })
+            .collect();
+        let spans: String = (0..weights.len())
+            .map(|i| format!("<text:span text:style-name=\"T{}\">w{} </text:span>", i, i))
+            .collect();
+        let file = odp_with_automatic_styles(
+            &styles,
+            &format!(
+                "<draw:page draw:name=\"One\"><draw:frame svg:x=\"1cm\" svg:y=\"1cm\" \
+                 svg:width=\"20cm\" svg:height=\"3cm\"><draw:text-box><text:p>{}</text:p>\
+                 </draw:text-box></draw:frame></draw:page>",
+                spans
+            ),
+        );
+
+        let (doc, warnings) = load_document(file.path()).unwrap();
+        assert!(warnings.is_empty(), "{:?}", warnings);
+        let SlideElement::Text(text) = &doc.slides[0].elements[0] else {
+            panic!("expected a text box, got {:?}", doc.slides[0].elements);
+        };
+        let bold: Vec<bool> = text.paragraphs[0].runs.iter().map(|run| run.font.bold).collect();
+        assert_eq!(bold, [false, false, true, true, true, false, true]);
+    }
+
+    #[test]
+    fn title_placeholder_takes_position_and_font_from_the_master() {
+        let file = odp(
+            "<office:automatic-styles>\
+             <style:style style:name=\"Mpr1\" style:family=\"presentation\">\
+             <style:paragraph-properties fo:text-align=\"center\"/>\
+             <style:text-properties fo:font-size=\"44pt\"/></style:style>\
+             </office:automatic-styles><office:master-styles>\
+             <style:master-page style:name=\"Default\">\
+             <draw:frame presentation:style-name=\"Mpr1\" presentation:class=\"title\" \
+             svg:x=\"2cm\" svg:y=\"1cm\" svg:width=\"24cm\" svg:height=\"3cm\">\
+             <draw:text-box/></draw:frame></style:master-page></office:master-styles>",
+            "<draw:page draw:name=\"One\" draw:master-page-name=\"Default\">\
+             <draw:frame presentation:class=\"title\"><draw:text-box>\
+             <text:p>Quarterly results</text:p></draw:text-box></draw:frame></draw:page>",
+            &[],
+        );
+
+        let (doc, warnings) = load_document(file.path()).unwrap();
+        assert!(warnings.is_empty(), "{:?}", warnings);
+        let SlideElement::Text(title) = &doc.slides[0].elements[0] else {
+            panic!("expected the title, got {:?}", doc.slides[0].elements);
+        };
+        let expected = Rect::new(cm_to_pt(2.0), cm_to_pt(1.0), cm_to_pt(24.0), cm_to_pt(3.0));
+        assert!((title.bounds.origin.x - expected.origin.x).abs() < 0.01);
+        assert!((title.bounds.origin.y - expected.origin.y).abs() < 0.01);
+        assert!((title.bounds.size.width - expected.size.width).abs() < 0.01);
+        assert!((title.bounds.size.height - expected.size.height).abs() < 0.01);
+        assert_eq!(title.alignment, TextAlignment::Center);
+        assert_eq!(title.paragraphs[0].runs[0].text, "Quarterly results");
+        assert_eq!(title.paragraphs[0].runs[0].font.size, 44.0);
+    }
+
+    #[test]
+    fn flat_xml_document_is_read_with_its_embedded_picture() {
+        let picture = png_header(8, 8);
+        let encoded = base64::engine::general_purpose::STANDARD.encode(&picture);
+        let file = TempFile::with_text(
+            "flat.fodp",
+            &format!(
+                "<?xml version=\"1.0\"?>\n<office:document {} office:mimetype=\"{}\">\
+                 <office:automatic-styles><style:page-layout style:name=\"PM1\">\
+                 <style:page-layout-properties fo:page-width=\"25.4cm\" \
+                 fo:page-height=\"14.2875cm\"/></style:page-layout></office:automatic-styles>\
+                 <office:master-styles><style:master-page style:name=\"Default\" \
+                 style:page-layout-name=\"PM1\"/></office:master-styles>\
+                 <office:body><office:presentation>\
+                 <draw:page draw:name=\"One\" draw:master-page-name=\"Default\">\
+                 <draw:frame svg:x=\"1cm\" svg:y=\"1cm\" svg:width=\"10cm\" svg:height=\"2cm\">\
+                 <draw:text-box><text:p>Flat</text:p></draw:text-box></draw:frame>\
+                 <draw:frame svg:x=\"1cm\" svg:y=\"4cm\" svg:width=\"4cm\" svg:height=\"4cm\">\
+                 <draw:image><office:binary-data>{}</office:binary-data></draw:image>\
+                 </draw:frame></draw:page></office:presentation></office:body></office:document>",
+                NAMESPACES, ODP_MIMETYPE, encoded
+            ),
+        );
+
+        let (doc, warnings) = load_document(file.path()).unwrap();
+        assert!(warnings.is_empty(), "{:?}", warnings);
+        assert!((doc.slide_size.width - 720.0).abs() < 0.1);
+        assert!((doc.slide_size.height - 405.0).abs() < 0.1);
+        assert_eq!(doc.slides.len(), 1);
+        let elements = &doc.slides[0].elements;
+        let SlideElement::Text(text) = &elements[0] else {
+            panic!("expected a text box, got {:?}", elements);
+        };
+        assert_eq!(text.paragraphs[0].full_text(), "Flat");
+        let SlideElement::Image(img) = &elements[1] else {
+            panic!("expected a picture, got {:?}", elements);
+        };
+        let ImageData::Embedded { data, mime } = &img.image_data;
+        assert_eq!((&data[..], mime.as_str()), (&picture[..], "image/png"));
+    }
+}
+
@@ -1,16 +1,27 @@
 use std::io::{self, Write};
 use std::path::Path;
+use std::sync::Arc;
 use zip::write::SimpleFileOptions;
 use zip::ZipWriter;
 
 use crate::model::document::Document;
 use crate::model::element::SlideElement;
-use crate::model::shape::ShapeType;
-use crate::model::style::Color;
-use crate::model::text::TextAlignment;
+use crate::model::geometry::{Rect, SlideSizePreset};
+use crate::model::image::{ImageFilters, ScaleMode};
+use crate::model::shape::{LineMarker, ShapeElement, ShapeType};
+use crate::model::slide::{Background, Gradient, GradientKind};
+use crate::model::style::{Color, FillStyle, LineCap, LineJoin};
+use crate::model::text::{self, ListStyle, TextAlignment, TextElement, VerticalAlignment};
 
 use super::constants::*;
 
+/// Name of the list style shared by all numbered paragraphs
+const NUMBER_LIST_STYLE: &str = "LNum";
+/// Name of the list style shared by all bulleted paragraphs
+const BULLET_LIST_STYLE: &str = "LBul";
+/// Name of the drawing-page style of the master page
+const MASTER_PAGE_STYLE: &str = "Mdp1";
+
 pub fn save_document(doc: &Document, path: &Path) -> io::Result<()> {
     let file = std::fs::File::create(path)?;
     let mut zip = ZipWriter::new(file);
@@ -71,7 +82,14 @@ fn build_manifest(doc: &Document) -> String {
 
     // Add image entries
     let mut img_idx = 0;
-    for slide in &doc.slides {
+    for (slide_idx, slide) in doc.slides.iter().enumerate() {
+        if let Background::Image(image_data) = &slide.background {
+            xml.push_str(&format!(
+                "  <manifest:file-entry manifest:full-path=\"{}\" manifest:media-type=\"{}\"/>\n",
+                background_image_path(slide_idx, image_data),
+                mime_from_data(image_data)
+            ));
+        }
         for element in &slide.elements {
             if let SlideElement::Image(img) = element {
                 let ext = mime_to_ext(&img.image_data);
@@ -89,7 +107,16 @@ fn build_manifest(doc: &Document) -> String {
     xml
 }
 
-fn build_meta(_doc: &Document) -> String {
+fn build_meta(doc: &Document) -> String {
+    // Every save stamps the modification date; the document itself is left
+    // alone so saving is not an edit
+    let now = timestamp_now();
+    let created = if doc.metadata.created.is_empty() {
+        &now
+    } else {
+        &doc.metadata.created
+    };
+
     let mut xml = String::new();
     xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
     xml.push_str(&format!(
@@ -98,70 +125,211 @@ fn build_meta(_doc: &Document) -> String {
     ));
     xml.push_str("  <office:meta>\n");
     xml.push_str("    <meta:generator>Lumina</meta:generator>\n");
+    if !doc.title.is_empty() {
+        xml.push_str(&format!("    <dc:title>{}</dc:title>\n", xml_escape(&doc.title)));
+    }
+    if !doc.metadata.author.is_empty() {
+        let author = xml_escape(&doc.metadata.author);
+        xml.push_str(&format!(
+            "    <meta:initial-creator>{}</meta:initial-creator>\n",
+            author
+        ));
+        xml.push_str(&format!("    <dc:creator>{}</dc:creator>\n", author));
+    }
+    xml.push_str(&format!(
+        "    <meta:creation-date>{}</meta:creation-date>\n",
+        xml_escape(created)
+    ));
+    xml.push_str(&format!("    <dc:date>{}</dc:date>\n", now));
     xml.push_str("  </office:meta>\n");
     xml.push_str("</office:document-meta>\n");
     xml
 }
 
+/// The current UTC time as an ISO 8601 date and time, as `meta.xml`
+/// stores dates.
+fn timestamp_now() -> String {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let (days, secs) = (secs / 86400, secs % 86400);
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60
+    )
+}
+
 fn build_styles(doc: &Document) -> String {
     let mut xml = String::new();
     xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
     xml.push_str(&format!(
-        "<office:document-styles xmlns:office=\"{}\" xmlns:style=\"{}\" xmlns:fo=\"{}\" xmlns:draw=\"{}\" xmlns:presentation=\"{}\" xmlns:svg=\"{}\" office:version=\"1.2\">\n",
-        NS_OFFICE, NS_STYLE, NS_FO, NS_DRAW, NS_PRESENTATION, NS_SVG
+        "<office:document-styles xmlns:office=\"{}\" xmlns:style=\"{}\" xmlns:fo=\"{}\" xmlns:draw=\"{}\" xmlns:presentation=\"{}\" xmlns:svg=\"{}\" xmlns:xlink=\"{}\" office:version=\"1.2\">\n",
+        NS_OFFICE, NS_STYLE, NS_FO, NS_DRAW, NS_PRESENTATION, NS_SVG, NS_XLINK
     ));
 
-    // Page layout
+    // Page layout. Standard sizes are written from their exact EMU values
+    // (360000 per cm) rather than through points.
+    let (page_width, page_height) = match SlideSizePreset::find(&doc.slide_size) {
+        Some(preset) => (
+            format!("{:.4}cm", preset.width_emu as f64 / EMU_PER_CM),
+            format!("{:.4}cm", preset.height_emu as f64 / EMU_PER_CM),
+        ),
+        None => (
+            format_cm(doc.slide_size.width),
+            format_cm(doc.slide_size.height),
+        ),
+    };
     xml.push_str("  <office:automatic-styles>\n");
     xml.push_str("    <style:page-layout style:name=\"PM1\">\n");
     xml.push_str(&format!(
         "      <style:page-layout-properties fo:page-width=\"{}\" fo:page-height=\"{}\" style:print-orientation=\"landscape\" fo:margin-top=\"0cm\" fo:margin-bottom=\"0cm\" fo:margin-left=\"0cm\" fo:margin-right=\"0cm\"/>\n",
-        format_cm(doc.slide_size.width),
-        format_cm(doc.slide_size.height)
+        page_width,
+        page_height
     ));
     xml.push_str("    </style:page-layout>\n");
+    // The master's own page style, so it does not share the name of the
+    // first slide's style in content.xml
+    xml.push_str(&format!(
+        "    <style:style style:name=\"{}\" style:family=\"drawing-page\">\n",
+        MASTER_PAGE_STYLE
+    ));
+    xml.push_str("      <style:drawing-page-properties draw:fill=\"solid\" draw:fill-color=\"#ffffff\"/>\n");
+    xml.push_str("    </style:style>\n");
     xml.push_str("  </office:automatic-styles>\n");
 
+    // Gradients and pictures used by slide backgrounds, gradients of shape
+    // and text box fills
+    xml.push_str("  <office:styles>\n");
+    for (slide_idx, slide) in doc.slides.iter().enumerate() {
+        match &slide.background {
+            Background::Gradient(gradient) => {
+                xml.push_str(&build_gradient(&gradient_style_name(slide_idx), gradient));
+            }
+            Background::Image(image_data) => {
+                xml.push_str(&format!(
+                    "    <draw:fill-image draw:name=\"{}\" xlink:href=\"{}\" xlink:type=\"simple\" xlink:show=\"embed\" xlink:actuate=\"onLoad\"/>\n",
+                    fill_image_style_name(slide_idx),
+                    background_image_path(slide_idx, image_data)
+                ));
+            }
+            Background::Solid(_) => {}
+        }
+        for (element_idx, element) in slide.elements.iter().enumerate() {
+            let fill = match element {
+                SlideElement::Shape(shape) => shape.fill.as_ref(),
+                SlideElement::Text(text) => text.fill.as_ref(),
+                SlideElement::Image(_) => None,
+            };
+            if let Some(gradient) = fill.and_then(|f| f.gradient.as_ref()) {
+                let name = shape_gradient_style_name(slide_idx, element_idx);
+                xml.push_str(&build_gradient(&name, gradient));
+            }
+        }
+    }
+    // Line markers, each defined once
+    for marker in [LineMarker::Arrow, LineMarker::Circle] {
+        let used = doc.slides.iter().flat_map(|s| &s.elements).any(|e| match e {
+            SlideElement::Shape(shape) => {
+                shape.shape_type == ShapeType::Line
+                    && (shape.start_marker == marker || shape.end_marker == marker)
+            }
+            _ => false,
+        });
+        if used {
+            xml.push_str(&build_marker(marker));
+        }
+    }
+    xml.push_str("  </office:styles>\n");
+
     // Master pages
     xml.push_str("  <office:master-styles>\n");
-    xml.push_str("    <style:master-page style:name=\"Default\" style:page-layout-name=\"PM1\" draw:style-name=\"dp1\"/>\n");
+    xml.push_str(&format!(
+        "    <style:master-page style:name=\"Default\" style:page-layout-name=\"PM1\" draw:style-name=\"{}\"/>\n",
+        MASTER_PAGE_STYLE
+    ));
     xml.push_str("  </office:master-styles>\n");
 
     xml.push_str("</office:document-styles>\n");
     xml
 }
 
-fn build_content(doc: &Document) -> (String, Vec<(String, Vec<u8>)>) {
+/// Pictures to store in the package, by path.
+type PackageImages = Vec<(String, Arc<[u8]>)>;
+
+fn build_content(doc: &Document) -> (String, PackageImages) {
     let mut xml = String::new();
-    let mut images: Vec<(String, Vec<u8>)> = Vec::new();
+    let mut images = PackageImages::new();
     let mut img_idx = 0;
     let mut style_idx = 0;
 
     xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
     xml.push_str(&format!(
-        "<office:document-content xmlns:office=\"{}\" xmlns:style=\"{}\" xmlns:text=\"{}\" xmlns:draw=\"{}\" xmlns:presentation=\"{}\" xmlns:fo=\"{}\" xmlns:svg=\"{}\" xmlns:xlink=\"{}\" office:version=\"1.2\">\n",
-        NS_OFFICE, NS_STYLE, NS_TEXT, NS_DRAW, NS_PRESENTATION, NS_FO, NS_SVG, NS_XLINK
+        "<office:document-content xmlns:office=\"{}\" xmlns:style=\"{}\" xmlns:text=\"{}\" xmlns:draw=\"{}\" xmlns:presentation=\"{}\" xmlns:fo=\"{}\" xmlns:svg=\"{}\" xmlns:xlink=\"{}\" xmlns:lumina=\"{}\" office:version=\"1.2\">\n",
+        NS_OFFICE, NS_STYLE, NS_TEXT, NS_DRAW, NS_PRESENTATION, NS_FO, NS_SVG, NS_XLINK, NS_LUMINA
     ));
 
     // Collect styles needed
     let mut auto_styles = String::new();
     let mut body = String::new();
 
-    // Drawing page style
-    auto_styles.push_str("    <style:style style:name=\"dp1\" style:family=\"drawing-page\">\n");
-    auto_styles.push_str("      <style:drawing-page-properties draw:fill=\"solid\" draw:fill-color=\"#ffffff\"/>\n");
-    auto_styles.push_str("    </style:style>\n");
-
     body.push_str("  <office:body>\n");
     body.push_str("    <office:presentation>\n");
 
     for (slide_idx, slide) in doc.slides.iter().enumerate() {
+        // Drawing page style carrying the slide background
+        let page_style_name = format!("dp{}", slide_idx + 1);
+        auto_styles.push_str(&format!(
+            "    <style:style style:name=\"{}\" style:family=\"drawing-page\">\n",
+            page_style_name
+        ));
+        match &slide.background {
+            Background::Solid(color) => {
+                auto_styles.push_str(&format!(
+                    "      <style:drawing-page-properties draw:fill=\"solid\" draw:fill-color=\"{}\"/>\n",
+                    color_to_hex(color)
+                ));
+            }
+            Background::Gradient(_) => {
+                auto_styles.push_str(&format!(
+                    "      <style:drawing-page-properties draw:fill=\"gradient\" draw:fill-gradient-name=\"{}\"/>\n",
+                    gradient_style_name(slide_idx)
+                ));
+            }
+            Background::Image(image_data) => {
+                auto_styles.push_str(&format!(
+                    "      <style:drawing-page-properties draw:fill=\"bitmap\" draw:fill-image-name=\"{}\" style:repeat=\"stretch\"/>\n",
+                    fill_image_style_name(slide_idx)
+                ));
+                let crate::model::image::ImageData::Embedded { data, .. } = image_data;
+                images.push((background_image_path(slide_idx, image_data), data.clone()));
+            }
+        }
+        auto_styles.push_str("    </style:style>\n");
+
         body.push_str(&format!(
-            "      <draw:page draw:name=\"Slide{}\" draw:style-name=\"dp1\" draw:master-page-name=\"Default\" presentation:presentation-page-layout-name=\"AL1T0\">\n",
-            slide_idx + 1
+            "      <draw:page draw:name=\"Slide{}\" draw:style-name=\"{}\" draw:master-page-name=\"Default\" presentation:presentation-page-layout-name=\"AL1T0\">\n",
+            slide_idx + 1,
+            page_style_name
         ));
 
-        for element in &slide.elements {
+        for (element_idx, element) in slide.elements.iter().enumerate() {
             match element {
                 SlideElement::Text(text) => {
                     let style_name = format!("gr{}", style_idx);
@@ -172,13 +340,30 @@ fn build_content(doc: &Document) -> (String, Vec<(String, Vec<u8>)>) {
                         "    <style:style style:name=\"{}\" style:family=\"graphic\" style:parent-style-name=\"standard\">\n",
                         style_name
                     ));
-                    auto_styles.push_str("      <style:graphic-properties draw:stroke=\"none\" draw:fill=\"none\" draw:textarea-vertical-align=\"top\" fo:padding=\"0cm\"/>\n");
+                    auto_styles.push_str(&format!(
+                        "      <style:graphic-properties draw:stroke=\"none\"{} draw:textarea-vertical-align=\"{}\" fo:padding-left=\"{}\" fo:padding-top=\"{}\" fo:padding-right=\"{}\" fo:padding-bottom=\"{}\"{}/>\n",
+                        fill_attributes(text.fill.as_ref(), 1.0, slide_idx, element_idx),
+                        match text.vertical_alignment {
+                            VerticalAlignment::Top => "top",
+                            VerticalAlignment::Middle => "middle",
+                            VerticalAlignment::Bottom => "bottom",
+                        },
+                        format_cm(text.inset.left),
+                        format_cm(text.inset.top),
+                        format_cm(text.inset.right),
+                        format_cm(text.inset.bottom),
+                        if text.auto_fit {
+                            " draw:fit-to-size=\"shrink-to-fit\" style:shrink-to-fit=\"true\""
+                        } else {
+                            ""
+                        }
+                    ));
                     auto_styles.push_str("    </style:style>\n");
 
                     // Text paragraph styles
                     let mut para_styles = Vec::new();
                     for (pi, para) in text.paragraphs.iter().enumerate() {
-                        let ps_name = format!("P{}_{}", slide_idx, pi);
+                        let ps_name = format!("P{}_{}_{}", slide_idx, element_idx, pi);
                         let align = match text.alignment {
                             TextAlignment::Left => "start",
                             TextAlignment::Center => "center",
@@ -188,27 +373,62 @@ fn build_content(doc: &Document) -> (String, Vec<(String, Vec<u8>)>) {
                             "    <style:style style:name=\"{}\" style:family=\"paragraph\">\n",
                             ps_name
                         ));
-                        auto_styles.push_str(&format!(
-                            "      <style:paragraph-properties fo:text-align=\"{}\"/>\n",
-                            align
-                        ));
+                        let para_props = format!(
+                            "fo:text-align=\"{}\" fo:line-height=\"{}%\" fo:margin-top=\"{}\" fo:margin-bottom=\"{}\"",
+                            align,
+                            (para.line_spacing * 100.0).round(),
+                            format_cm(para.space_before),
+                            format_cm(para.space_after)
+                        );
+                        if para.tab_stops.is_empty() {
+                            auto_styles.push_str(&format!(
+                                "      <style:paragraph-properties {}/>\n",
+                                para_props
+                            ));
+                        } else {
+                            auto_styles.push_str(&format!(
+                                "      <style:paragraph-properties {}>\n",
+                                para_props
+                            ));
+                            auto_styles.push_str("        <style:tab-stops>\n");
+                            for stop in &para.tab_stops {
+                                auto_styles.push_str(&format!(
+                                    "          <style:tab-stop style:position=\"{}\"/>\n",
+                                    format_cm(*stop)
+                                ));
+                            }
+                            auto_styles.push_str("        </style:tab-stops>\n");
+                            auto_styles.push_str("      </style:paragraph-properties>\n");
+                        }
                         auto_styles.push_str("    </style:style>\n");
 
                         // Text run styles
                         let mut run_styles = Vec::new();
                         for (ri, run) in para.runs.iter().enumerate() {
-                            let ts_name = format!("T{}_{}_{}", slide_idx, pi, ri);
+                            let ts_name =
+                                format!("T{}_{}_{}_{}", slide_idx, element_idx, pi, ri);
                             auto_styles.push_str(&format!(
                                 "    <style:style style:name=\"{}\" style:family=\"text\">\n",
                                 ts_name
                             ));
                             auto_styles.push_str(&format!(
-                                "      <style:text-properties fo:font-size=\"{}pt\" fo:color=\"{}\" style:font-name=\"{}\"{}{}/>",
+                                "      <style:text-properties fo:font-size=\"{}pt\" fo:color=\"{}\" style:font-name=\"{}\"{}{}{}{}{}/>",
                                 run.font.size,
                                 color_to_hex(&run.font.color),
                                 xml_escape(&run.font.family),
                                 if run.font.bold { " fo:font-weight=\"bold\"" } else { "" },
                                 if run.font.italic { " fo:font-style=\"italic\"" } else { "" },
+                                if run.font.underline {
+                                    " style:text-underline-style=\"solid\" style:text-underline-width=\"auto\" style:text-underline-color=\"font-color\""
+                                } else {
+                                    ""
+                                },
+                                if run.font.strikethrough {
+                                    " style:text-line-through-style=\"solid\""
+                                } else {
+                                    ""
+                                },
+                                text_effect_attributes(text),
                             ));
                             auto_styles.push('\n');
                             auto_styles.push_str("    </style:style>\n");
@@ -218,17 +438,22 @@ fn build_content(doc: &Document) -> (String, Vec<(String, Vec<u8>)>) {
                     }
 
                     body.push_str(&format!(
-                        "        <draw:frame draw:style-name=\"{}\" svg:x=\"{}\" svg:y=\"{}\" svg:width=\"{}\" svg:height=\"{}\">\n",
+                        "        <draw:frame draw:style-name=\"{}\" {}{}>\n",
                         style_name,
-                        format_cm(text.bounds.origin.x),
-                        format_cm(text.bounds.origin.y),
-                        format_cm(text.bounds.size.width),
-                        format_cm(text.bounds.size.height)
+                        placement(&text.bounds, text.rotation),
+                        element_attributes(element)
                     ));
                     body.push_str("          <draw:text-box>\n");
 
+                    let mut open_lists = Vec::new();
                     for (pi, para) in text.paragraphs.iter().enumerate() {
                         let (ref ps_name, ref run_styles) = para_styles[pi];
+                        let (target_depth, list_style) = match para.list {
+                            ListStyle::Numbered => (para.level as usize + 1, NUMBER_LIST_STYLE),
+                            ListStyle::Bullet => (para.level as usize + 1, BULLET_LIST_STYLE),
+                            ListStyle::None => (0, NUMBER_LIST_STYLE),
+                        };
+                        set_list_depth(&mut body, &mut open_lists, target_depth, list_style);
                         body.push_str(&format!(
                             "            <text:p text:style-name=\"{}\">\n",
                             ps_name
@@ -237,11 +462,12 @@ fn build_content(doc: &Document) -> (String, Vec<(String, Vec<u8>)>) {
                             body.push_str(&format!(
                                 "              <text:span text:style-name=\"{}\">{}</text:span>\n",
                                 run_styles[ri],
-                                xml_escape(&run.text)
+                                xml_escape(&run.text).replace('\t', "<text:tab/>")
                             ));
                         }
                         body.push_str("            </text:p>\n");
                     }
+                    set_list_depth(&mut body, &mut open_lists, 0, NUMBER_LIST_STYLE);
 
                     body.push_str("          </draw:text-box>\n");
                     body.push_str("        </draw:frame>\n");
@@ -255,21 +481,38 @@ fn build_content(doc: &Document) -> (String, Vec<(String, Vec<u8>)>) {
                         "    <style:style style:name=\"{}\" style:family=\"graphic\">\n",
                         style_name
                     ));
+                    // ODF has no opacity of a whole shape, so the opacity
+                    // fades the fill and the stroke
                     auto_styles.push_str("      <style:graphic-properties");
-                    if let Some(fill) = &shape.fill {
-                        auto_styles.push_str(&format!(
-                            " draw:fill=\"solid\" draw:fill-color=\"{}\"",
-                            color_to_hex(&fill.color)
-                        ));
-                    } else {
-                        auto_styles.push_str(" draw:fill=\"none\"");
-                    }
+                    auto_styles.push_str(&fill_attributes(
+                        shape.fill.as_ref(),
+                        shape.opacity,
+                        slide_idx,
+                        element_idx,
+                    ));
                     if let Some(stroke) = &shape.stroke {
                         auto_styles.push_str(&format!(
-                            " draw:stroke=\"solid\" svg:stroke-color=\"{}\" svg:stroke-width=\"{}\"",
+                            " draw:stroke=\"solid\" svg:stroke-color=\"{}\" svg:stroke-width=\"{}\" svg:stroke-linecap=\"{}\" draw:stroke-linejoin=\"{}\"",
                             color_to_hex(&stroke.color),
-                            format_cm(stroke.width)
+                            format_cm(stroke.width),
+                            match stroke.line_cap {
+                                LineCap::Butt => "butt",
+                                LineCap::Round => "round",
+                                LineCap::Square => "square",
+                            },
+                            match stroke.line_join {
+                                LineJoin::Miter => "miter",
+                                LineJoin::Round => "round",
+                                LineJoin::Bevel => "bevel",
+                            }
+                        ));
+                        auto_styles.push_str(&opacity_attribute(
+                            "svg:stroke-opacity",
+                            stroke.color.a * shape.opacity,
                         ));
+                        if shape.shape_type == ShapeType::Line {
+                            auto_styles.push_str(&marker_attributes(shape));
+                        }
                     } else {
                         auto_styles.push_str(" draw:stroke=\"none\"");
                     }
@@ -279,38 +522,51 @@ fn build_content(doc: &Document) -> (String, Vec<(String, Vec<u8>)>) {
                     match shape.shape_type {
                         ShapeType::Rectangle => {
                             body.push_str(&format!(
-                                "        <draw:rect draw:style-name=\"{}\" svg:x=\"{}\" svg:y=\"{}\" svg:width=\"{}\" svg:height=\"{}\"/>\n",
+                                "        <draw:rect draw:style-name=\"{}\" {}{}/>\n",
                                 style_name,
-                                format_cm(shape.bounds.origin.x),
-                                format_cm(shape.bounds.origin.y),
-                                format_cm(shape.bounds.size.width),
-                                format_cm(shape.bounds.size.height)
+                                placement(&shape.bounds, shape.rotation),
+                                element_attributes(element)
                             ));
                         }
                         ShapeType::Ellipse => {
                             body.push_str(&format!(
-                                "        <draw:ellipse draw:style-name=\"{}\" svg:x=\"{}\" svg:y=\"{}\" svg:width=\"{}\" svg:height=\"{}\"/>\n",
+                                "        <draw:ellipse draw:style-name=\"{}\" {}{}/>\n",
                                 style_name,
-                                format_cm(shape.bounds.origin.x),
-                                format_cm(shape.bounds.origin.y),
-                                format_cm(shape.bounds.size.width),
-                                format_cm(shape.bounds.size.height)
+                                placement(&shape.bounds, shape.rotation),
+                                element_attributes(element)
                             ));
                         }
                         ShapeType::Line => {
-                            let x1 = shape.bounds.origin.x;
-                            let y1 = shape.bounds.origin.y;
-                            let x2 = x1 + shape.bounds.size.width;
-                            let y2 = y1 + shape.bounds.size.height;
+                            let (start, end) = shape.line_endpoints();
                             body.push_str(&format!(
-                                "        <draw:line draw:style-name=\"{}\" svg:x1=\"{}\" svg:y1=\"{}\" svg:x2=\"{}\" svg:y2=\"{}\"/>\n",
+                                "        <draw:line draw:style-name=\"{}\" svg:x1=\"{}\" svg:y1=\"{}\" svg:x2=\"{}\" svg:y2=\"{}\"{}/>\n",
                                 style_name,
-                                format_cm(x1),
-                                format_cm(y1),
-                                format_cm(x2),
-                                format_cm(y2)
+                                format_cm(start.x),
+                                format_cm(start.y),
+                                format_cm(end.x),
+                                format_cm(end.y),
+                                element_attributes(element)
                             ));
                         }
+                        ShapeType::RoundedRectangle { radius } => {
+                            body.push_str(&format!(
+                                "        <draw:rect draw:style-name=\"{}\" {} draw:corner-radius=\"{}\"{}/>\n",
+                                style_name,
+                                placement(&shape.bounds, shape.rotation),
+                                format_cm(radius),
+                                element_attributes(element)
+                            ));
+                        }
+                        ShapeType::Triangle | ShapeType::Star { .. } | ShapeType::Arrow => {
+                            body.push_str(&format!(
+                                "        <draw:custom-shape draw:style-name=\"{}\" {}{}>\n",
+                                style_name,
+                                placement(&shape.bounds, shape.rotation),
+                                element_attributes(element)
+                            ));
+                            body.push_str(&build_enhanced_geometry(shape));
+                            body.push_str("        </draw:custom-shape>\n");
+                        }
                     }
                 }
                 SlideElement::Image(img) => {
@@ -324,21 +580,60 @@ fn build_content(doc: &Document) -> (String, Vec<(String, Vec<u8>)>) {
                         "    <style:style style:name=\"{}\" style:family=\"graphic\">\n",
                         style_name
                     ));
-                    auto_styles.push_str("      <style:graphic-properties draw:stroke=\"none\" draw:fill=\"none\"/>\n");
+                    // Other applications always stretch the picture to its
+                    // frame and cut it with fo:clip, which is in lengths of
+                    // the picture. A picture whose size is not known keeps
+                    // its crop as fractions instead.
+                    let (clip, crop) = match img.image_data.physical_size() {
+                        _ if img.crop.is_empty() => (String::new(), String::new()),
+                        Some(size) => (
+                            format!(
+                                " fo:clip=\"rect({}, {}, {}, {})\"",
+                                format_cm(img.crop.top * size.height),
+                                format_cm(img.crop.right * size.width),
+                                format_cm(img.crop.bottom * size.height),
+                                format_cm(img.crop.left * size.width)
+                            ),
+                            String::new(),
+                        ),
+                        None => (
+                            String::new(),
+                            format!(
+                                " lumina:crop=\"{} {} {} {}\"",
+                                img.crop.left, img.crop.top, img.crop.right, img.crop.bottom
+                            ),
+                        ),
+                    };
+                    auto_styles.push_str(&format!(
+                        "      <style:graphic-properties draw:stroke=\"none\" draw:fill=\"none\"{}{}/>\n",
+                        opacity_attribute("draw:image-opacity", img.opacity),
+                        clip
+                    ));
                     auto_styles.push_str("    </style:style>\n");
 
                     body.push_str(&format!(
-                        "        <draw:frame draw:style-name=\"{}\" svg:x=\"{}\" svg:y=\"{}\" svg:width=\"{}\" svg:height=\"{}\">\n",
+                        "        <draw:frame draw:style-name=\"{}\" {} lumina:scale-mode=\"{}\"{}{}>\n",
                         style_name,
-                        format_cm(img.bounds.origin.x),
-                        format_cm(img.bounds.origin.y),
-                        format_cm(img.bounds.size.width),
-                        format_cm(img.bounds.size.height)
+                        placement(&img.bounds, img.rotation),
+                        match img.scale_mode {
+                            ScaleMode::Fit => "fit",
+                            ScaleMode::Fill => "fill",
+                            ScaleMode::Stretch => "stretch",
+                        },
+                        crop,
+                        element_attributes(element)
                     ));
                     body.push_str(&format!(
-                        "          <draw:image xlink:href=\"{}\" xlink:type=\"simple\" xlink:show=\"embed\" xlink:actuate=\"onLoad\"/>\n",
-                        img_path
+                        "          <draw:image xlink:href=\"{}\" xlink:type=\"simple\" xlink:show=\"embed\" xlink:actuate=\"onLoad\"{}/>\n",
+                        img_path,
+                        filter_attributes(&img.filters)
                     ));
+                    if !img.alt_text.is_empty() {
+                        body.push_str(&format!(
+                            "          <svg:desc>{}</svg:desc>\n",
+                            xml_escape(&img.alt_text)
+                        ));
+                    }
                     body.push_str("        </draw:frame>\n");
 
                     let crate::model::image::ImageData::Embedded { data, .. } = &img.image_data;
@@ -355,6 +650,12 @@ fn build_content(doc: &Document) -> (String, Vec<(String, Vec<u8>)>) {
     body.push_str("  </office:body>\n");
 
     xml.push_str("  <office:automatic-styles>\n");
+    if uses_list_style(doc, ListStyle::Numbered) {
+        xml.push_str(&build_number_list_style());
+    }
+    if uses_list_style(doc, ListStyle::Bullet) {
+        xml.push_str(&build_bullet_list_style());
+    }
     xml.push_str(&auto_styles);
     xml.push_str("  </office:automatic-styles>\n");
     xml.push_str(&body);
@@ -363,6 +664,241 @@ fn build_content(doc: &Document) -> (String, Vec<(String, Vec<u8>)>) {
     (xml, images)
 }
 
+fn uses_list_style(doc: &Document, list: ListStyle) -> bool {
+    doc.slides.iter().flat_map(|s| &s.elements).any(|e| match e {
+        SlideElement::Text(text) => text.paragraphs.iter().any(|p| p.list == list),
+        _ => false,
+    })
+}
+
+/// List style numbering every level as "1.", "2.", ...
+fn build_number_list_style() -> String {
+    let mut xml = format!("    <text:list-style style:name=\"{}\">\n", NUMBER_LIST_STYLE);
+    for level in 1..=10 {
+        xml.push_str(&format!(
+            "      <text:list-level-style-number text:level=\"{}\" style:num-format=\"1\" style:num-suffix=\".\"/>\n",
+            level
+        ));
+    }
+    xml.push_str("    </text:list-style>\n");
+    xml
+}
+
+/// List style with the bullets of the renderer per level
+fn build_bullet_list_style() -> String {
+    let mut xml = format!("    <text:list-style style:name=\"{}\">\n", BULLET_LIST_STYLE);
+    for level in 1..=10 {
+        xml.push_str(&format!(
+            "      <text:list-level-style-bullet text:level=\"{}\" text:bullet-char=\"{}\"/>\n",
+            level,
+            text::bullet_for_level(level - 1)
+        ));
+    }
+    xml.push_str("    </text:list-style>\n");
+    xml
+}
+
+/// Open or close nested `text:list` elements so the next paragraph sits at
+/// `target` list depth, in a list of style `style`. `open` holds the styles
+/// of the open lists. Paragraphs staying at the same depth and style become
+/// siblings; a different style there starts a new list.
+fn set_list_depth(
+    body: &mut String,
+    open: &mut Vec<&'static str>,
+    target: usize,
+    style: &'static str,
+) {
+    let keep = if target > 0 && open.len() >= target && open[target - 1] != style {
+        target - 1
+    } else {
+        target
+    };
+    while open.len() > keep {
+        body.push_str("            </text:list-item></text:list>\n");
+        open.pop();
+    }
+    if target > 0 && open.len() == target {
+        body.push_str("            </text:list-item><text:list-item>\n");
+    }
+    while open.len() < target {
+        body.push_str(&format!(
+            "            <text:list text:style-name=\"{}\"><text:list-item>\n",
+            style
+        ));
+        open.push(style);
+    }
+}
+
+fn gradient_style_name(slide_idx: usize) -> String {
+    format!("Gradient{}", slide_idx + 1)
+}
+
+/// Name of the `draw:fill-image` holding the background picture of a slide
+fn fill_image_style_name(slide_idx: usize) -> String {
+    format!("Background{}", slide_idx + 1)
+}
+
+fn background_image_path(slide_idx: usize, image_data: &crate::model::image::ImageData) -> String {
+    format!("Pictures/background{}.{}", slide_idx + 1, mime_to_ext(image_data))
+}
+
+/// `draw:enhanced-geometry` of a polygon shape: the preset name for other
+/// applications, and the exact outline in hundredths of a point.
+fn build_enhanced_geometry(shape: &ShapeElement) -> String {
+    let draw_type = match shape.shape_type {
+        ShapeType::Triangle => "isosceles-triangle".to_string(),
+        ShapeType::Star { points } => format!("star{}", points),
+        ShapeType::Arrow => "right-arrow".to_string(),
+        _ => "rectangle".to_string(),
+    };
+    let size = shape.bounds.size;
+    let to_units = |v: f64| (v * 100.0).round() as i64;
+    let corners = shape.shape_type.polygon(size).unwrap_or_default();
+    let path = corners
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let command = if i == 0 { "M" } else { "L" };
+            format!("{} {} {}", command, to_units(p.x), to_units(p.y))
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    format!(
+        "          <draw:enhanced-geometry svg:viewBox=\"0 0 {} {}\" draw:type=\"{}\" draw:enhanced-path=\"{} Z N\"/>\n",
+        to_units(size.width),
+        to_units(size.height),
+        draw_type,
+        path
+    )
+}
+
+/// Position and size attributes of an element. A rotated element is
+/// placed by `draw:transform`, which turns it about its top-left corner
+/// counterclockwise in radians and then moves that corner into place.
+fn placement(bounds: &Rect, rotation: f64) -> String {
+    let size = format!(
+        "svg:width=\"{}\" svg:height=\"{}\"",
+        format_cm(bounds.size.width),
+        format_cm(bounds.size.height)
+    );
+    if rotation == 0.0 {
+        return format!(
+            "svg:x=\"{}\" svg:y=\"{}\" {}",
+            format_cm(bounds.origin.x),
+            format_cm(bounds.origin.y),
+            size
+        );
+    }
+    // The element turns about its center, so the corner is the center
+    // minus the rotated half size
+    let angle = rotation.to_radians();
+    let (half_w, half_h) = (bounds.size.width / 2.0, bounds.size.height / 2.0);
+    let center = bounds.center();
+    let x = center.x - (half_w * angle.cos() - half_h * angle.sin());
+    let y = center.y - (half_w * angle.sin() + half_h * angle.cos());
+    format!(
+        "{} draw:transform=\"rotate ({}) translate ({} {})\"",
+        size,
+        (-angle).rem_euclid(std::f64::consts::TAU),
+        format_cm(x),
+        format_cm(y)
+    )
+}
+
+/// `draw:fill` attributes of the graphic style of element `element_idx`,
+/// led by a space, with the fill faded by `opacity`. The solid color is
+/// kept alongside a gradient.
+fn fill_attributes(
+    fill: Option<&FillStyle>,
+    opacity: f64,
+    slide_idx: usize,
+    element_idx: usize,
+) -> String {
+    match fill {
+        Some(fill) => match &fill.gradient {
+            Some(_) => format!(
+                " draw:fill=\"gradient\" draw:fill-gradient-name=\"{}\" draw:fill-color=\"{}\"{}",
+                shape_gradient_style_name(slide_idx, element_idx),
+                color_to_hex(&fill.color),
+                opacity_attribute("draw:opacity", opacity)
+            ),
+            None => format!(
+                " draw:fill=\"solid\" draw:fill-color=\"{}\"{}",
+                color_to_hex(&fill.color),
+                opacity_attribute("draw:opacity", fill.color.a * opacity)
+            ),
+        },
+        None => " draw:fill=\"none\"".to_string(),
+    }
+}
+
+/// `draw:marker-start` and `draw:marker-end` attributes of a line, led by a
+/// space. A circle is centered on the end point, an arrow ends at its tip.
+fn marker_attributes(shape: &ShapeElement) -> String {
+    let mut attributes = String::new();
+    for (side, marker) in [("start", shape.start_marker), ("end", shape.end_marker)] {
+        let Some(name) = marker_name(marker) else {
+            continue;
+        };
+        attributes.push_str(&format!(
+            " draw:marker-{side}=\"{}\" draw:marker-{side}-width=\"{}\" draw:marker-{side}-center=\"{}\"",
+            name,
+            format_cm(shape.marker_size()),
+            marker == LineMarker::Circle
+        ));
+    }
+    attributes
+}
+
+fn marker_name(marker: LineMarker) -> Option<&'static str> {
+    match marker {
+        LineMarker::None => None,
+        LineMarker::Arrow => Some("Arrow"),
+        LineMarker::Circle => Some("Circle"),
+    }
+}
+
+/// `draw:marker` definition of a line marker, pointing up as ODF expects.
+fn build_marker(marker: LineMarker) -> String {
+    let path = match marker {
+        LineMarker::None => return String::new(),
+        LineMarker::Arrow => "M10 0l-10 20h20z",
+        LineMarker::Circle => "M0 10a10 10 0 1 0 20 0a10 10 0 1 0-20 0z",
+    };
+    format!(
+        "    <draw:marker draw:name=\"{}\" svg:viewBox=\"0 0 20 20\" svg:d=\"{}\"/>\n",
+        marker_name(marker).unwrap_or_default(),
+        path
+    )
+}
+
+fn shape_gradient_style_name(slide_idx: usize, element_idx: usize) -> String {
+    format!("Gradient{}_{}", slide_idx + 1, element_idx + 1)
+}
+
+fn build_gradient(name: &str, gradient: &Gradient) -> String {
+    match gradient.kind {
+        GradientKind::Linear => {
+            // ODF angles run counter-clockwise from top-to-bottom, in tenths of a degree
+            let angle = (90.0 - gradient.angle).rem_euclid(360.0);
+            format!(
+                "    <draw:gradient draw:name=\"{}\" draw:style=\"linear\" draw:start-color=\"{}\" draw:end-color=\"{}\" draw:angle=\"{}\" draw:border=\"0%\"/>\n",
+                name,
+                color_to_hex(&gradient.start),
+                color_to_hex(&gradient.end),
+                (angle * 10.0).round() as i64
+            )
+        }
+        // ODF radial gradients start at the border and end in the center
+        GradientKind::Radial => format!(
+            "    <draw:gradient draw:name=\"{}\" draw:style=\"radial\" draw:cx=\"50%\" draw:cy=\"50%\" draw:start-color=\"{}\" draw:end-color=\"{}\" draw:border=\"0%\"/>\n",
+            name,
+            color_to_hex(&gradient.end),
+            color_to_hex(&gradient.start)
+        ),
+    }
+}
+
 fn color_to_hex(color: &Color) -> String {
     format!(
         "#{:02x}{:02x}{:02x}",
@@ -380,6 +916,76 @@ fn xml_escape(s: &str) -> String {
         .replace('\'', "&apos;")
 }
 
+/// Name attribute of an element's frame or shape, and `lumina:` attributes
+/// for the settings ODF has no equivalent for, led by a space.
+fn element_attributes(element: &SlideElement) -> String {
+    let mut attributes = String::new();
+    if !element.name().is_empty() {
+        attributes.push_str(&format!(" draw:name=\"{}\"", xml_escape(element.name())));
+    }
+    if !element.is_visible() {
+        attributes.push_str(" lumina:visible=\"false\"");
+    }
+    if element.is_locked() {
+        attributes.push_str(" lumina:locked=\"true\"");
+    }
+    attributes
+}
+
+/// Text properties of the outline and shadow of a text box, led by a
+/// space. ODF only tells whether text is outlined, so the width and color
+/// of the outline are Lumina's own, as are the opacities.
+fn text_effect_attributes(text: &TextElement) -> String {
+    let mut attributes = String::new();
+    if let Some(outline) = &text.outline {
+        attributes.push_str(&format!(
+            " style:text-outline=\"true\" lumina:text-outline-width=\"{}pt\" lumina:text-outline-color=\"{}\"{}",
+            outline.width,
+            color_to_hex(&outline.color),
+            opacity_attribute("lumina:text-outline-opacity", outline.color.a)
+        ));
+    }
+    if let Some(shadow) = &text.shadow {
+        attributes.push_str(&format!(
+            " fo:text-shadow=\"{}pt {}pt {}\"{}",
+            shadow.offset_x,
+            shadow.offset_y,
+            color_to_hex(&shadow.color),
+            opacity_attribute("lumina:text-shadow-opacity", shadow.color.a)
+        ));
+    }
+    attributes
+}
+
+/// Percentage attribute `name` for an opacity from 0 to 1, led by a space.
+/// Empty when opaque, as ODF colors carry no alpha of their own.
+fn opacity_attribute(name: &str, opacity: f64) -> String {
+    if opacity >= 1.0 {
+        return String::new();
+    }
+    format!(" {}=\"{}%\"", name, (opacity.max(0.0) * 100.0).round())
+}
+
+/// `lumina:` attributes of the filters of a picture that change it, led by
+/// a space.
+fn filter_attributes(filters: &ImageFilters) -> String {
+    let defaults = ImageFilters::default();
+    let mut attributes = String::new();
+    if filters.grayscale {
+        attributes.push_str(" lumina:grayscale=\"true\"");
+    }
+    for (name, value, default) in [
+        ("brightness", filters.brightness, defaults.brightness),
+        ("contrast", filters.contrast, defaults.contrast),
+        ("saturation", filters.saturation, defaults.saturation),
+    ] {
+        if value != default {
+            attributes.push_str(&format!(" lumina:{}=\"{}\"", name, value));
+        }
+    }
+    attributes
+}
+
 fn mime_to_ext(img_data: &crate::model::image::ImageData) -> &'static str {
     match img_data {
         crate::model::image::ImageData::Embedded { mime, .. } => match mime.as_str() {
@@ -387,6 +993,8 @@ fn mime_to_ext(img_data: &crate::model::image::ImageData) -> &'static str {
             "image/jpeg" => "jpg",
             "image/svg+xml" => "svg",
             "image/webp" => "webp",
+            "image/x-emf" => "emf",
+            "image/x-wmf" => "wmf",
             _ => "png",
         },
     }
@@ -397,3 +1005,295 @@ fn mime_from_data(img_data: &crate::model::image::ImageData) -> &str {
         crate::model::image::ImageData::Embedded { mime, .. } => mime.as_str(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::odp::reader::load_document;
+    use crate::format::testing::{png_header, TempFile};
+    use crate::model::geometry::{Point, Rect, Size};
+    use crate::model::image::{ImageCrop, ImageElement};
+    use crate::model::shape::ShapeElement;
+    use crate::model::text::{TextOutline, TextShadow};
+
+    /// `doc` as it is read back after saving it.
+    fn round_trip(doc: &Document) -> Document {
+        let file = TempFile::new("round-trip.odp");
+        save_document(doc, file.path()).unwrap();
+        let (doc, warnings) = load_document(file.path()).unwrap();
+        assert!(warnings.is_empty(), "{:?}", warnings);
+        doc
+    }
+
+    fn styles_xml(doc: &Document) -> String {
+        let file = TempFile::new("size.odp");
+        save_document(doc, file.path()).unwrap();
+        let mut zip = zip::ZipArchive::new(std::fs::File::open(file.path()).unwrap()).unwrap();
+        let mut xml = String::new();
+        io::Read::read_to_string(&mut zip.by_name("styles.xml").unwrap(), &mut xml).unwrap();
+        xml
+    }
+
+    #[test]
+    fn widescreen_document_has_the_canonical_page_size() {
+        let mut doc = Document::new();
+        // As read back from a file written by another application
+        doc.slide_size = Size::new(959.9953, 540.0024);
+        let xml = styles_xml(&doc);
+        assert!(
+            xml.contains("fo:page-width=\"33.8667cm\" fo:page-height=\"19.0500cm\""),
+            "{}",
+            xml
+        );
+    }
+
+    #[test]
+    fn custom_page_size_survives() {
+        let mut doc = Document::new();
+        doc.slide_size = Size::new(600.0, 600.0);
+        let xml = styles_xml(&doc);
+        assert!(xml.contains("fo:page-width=\"21.1666cm\""), "{}", xml);
+
+        let doc = round_trip(&doc);
+        assert!((doc.slide_size.width - 600.0).abs() < 0.01);
+        assert!((doc.slide_size.height - 600.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn image_filters_survive() {
+        let mut doc = Document::new();
+        let bounds = Rect::new(0.0, 0.0, 150.0, 90.0);
+        let filters = ImageFilters {
+            grayscale: true,
+            brightness: -0.25,
+            contrast: 1.5,
+            saturation: 1.0,
+        };
+        let mut img = ImageElement::new(bounds, png_header(8, 8), "image/png".into());
+        img.filters = filters;
+        doc.slides[0].elements.push(SlideElement::Image(img));
+        let img = ImageElement::new(bounds, png_header(8, 8), "image/png".into());
+        doc.slides[0].elements.push(SlideElement::Image(img));
+
+        let doc = round_trip(&doc);
+        let read: Vec<ImageFilters> = doc.slides[0]
+            .elements
+            .iter()
+            .map(|e| match e {
+                SlideElement::Image(img) => img.filters,
+                other => panic!("expected a picture, got {:?}", other),
+            })
+            .collect();
+        assert_eq!(read, [filters, ImageFilters::default()]);
+    }
+
+    #[test]
+    fn hidden_elements_stay_hidden() {
+        let mut doc = Document::new();
+        let bounds = Rect::new(0.0, 0.0, 200.0, 50.0);
+        let mut text = TextElement::new(bounds, "Alternate");
+        text.visible = false;
+        doc.slides[0].elements.push(SlideElement::Text(text));
+        let mut shape = ShapeElement::new(bounds, ShapeType::Star { points: 5 });
+        shape.visible = false;
+        doc.slides[0].elements.push(SlideElement::Shape(shape));
+        let img = ImageElement::new(bounds, png_header(8, 8), "image/png".into());
+        doc.slides[0].elements.push(SlideElement::Image(img));
+
+        let doc = round_trip(&doc);
+        let visible: Vec<bool> = doc.slides[0].elements.iter().map(|e| e.is_visible()).collect();
+        assert_eq!(visible, [false, false, true]);
+    }
+
+    #[test]
+    fn element_names_and_locks_survive() {
+        let mut doc = Document::new();
+        let bounds = Rect::new(0.0, 0.0, 200.0, 50.0);
+        let mut text = TextElement::new(bounds, "Title");
+        text.name = "Title & subtitle".into();
+        text.locked = true;
+        doc.slides[0].elements.push(SlideElement::Text(text));
+        let mut shape = ShapeElement::new(bounds, ShapeType::RoundedRectangle { radius: 12.0 });
+        shape.name = "Badge".into();
+        doc.slides[0].elements.push(SlideElement::Shape(shape));
+        let mut img = ImageElement::new(bounds, png_header(8, 8), "image/png".into());
+        img.locked = true;
+        doc.slides[0].elements.push(SlideElement::Image(img));
+
+        let doc = round_trip(&doc);
+        let elements: Vec<(&str, bool)> = doc.slides[0]
+            .elements
+            .iter()
+            .map(|e| (e.name(), e.is_locked()))
+            .collect();
+        assert_eq!(elements, [("Title & subtitle", true), ("Badge", false), ("", true)]);
+    }
+
+    #[test]
+    fn text_outline_and_shadow_survive() {
+        let mut doc = Document::new();
+        let mut text = TextElement::new(Rect::new(0.0, 0.0, 300.0, 60.0), "Title");
+        text.outline = Some(TextOutline { color: Color::rgb(1.0, 1.0, 1.0), width: 1.5 });
+        text.shadow = Some(TextShadow {
+            color: Color::new(0.0, 0.0, 0.0, 0.5),
+            offset_x: 2.0,
+            offset_y: -1.0,
+        });
+        doc.slides[0].elements.push(SlideElement::Text(text.clone()));
+        text.outline = None;
+        text.shadow = None;
+        doc.slides[0].elements.push(SlideElement::Text(text));
+
+        let doc = round_trip(&doc);
+        let texts = texts(&doc);
+        let outline = texts[0].outline.as_ref().expect("an outline");
+        assert_eq!((color_to_hex(&outline.color).as_str(), outline.width), ("#ffffff", 1.5));
+        let shadow = texts[0].shadow.as_ref().expect("a shadow");
+        assert_eq!((shadow.offset_x, shadow.offset_y), (2.0, -1.0));
+        assert_eq!((shadow.color.r, shadow.color.a), (0.0, 0.5));
+        assert_eq!((&texts[1].outline, &texts[1].shadow), (&None, &None));
+    }
+
+    #[test]
+    fn slide_background_color_survives() {
+        let mut doc = Document::new();
+        doc.slides[0].background = Background::Solid(Color::rgb(0.2, 0.4, 0.6));
+
+        let doc = round_trip(&doc);
+        let Background::Solid(color) = &doc.slides[0].background else {
+            panic!("expected a solid background");
+        };
+        assert!((color.r - 0.2).abs() < 0.01);
+        assert!((color.g - 0.4).abs() < 0.01);
+        assert!((color.b - 0.6).abs() < 0.01);
+    }
+
+    #[test]
+    fn line_sloping_up_right_keeps_its_endpoints() {
+        let mut doc = Document::new();
+        let line = ShapeElement::line(Point::new(100.0, 300.0), Point::new(400.0, 150.0));
+        doc.slides[0].elements.push(SlideElement::Shape(line));
+
+        let doc = round_trip(&doc);
+        let SlideElement::Shape(line) = &doc.slides[0].elements[0] else {
+            panic!("expected a line");
+        };
+        let (start, end) = line.line_endpoints();
+        for (actual, expected) in [(start, (100.0, 300.0)), (end, (400.0, 150.0))] {
+            assert!((actual.x - expected.0).abs() < 0.01, "{:?}", actual);
+            assert!((actual.y - expected.1).abs() < 0.01, "{:?}", actual);
+        }
+    }
+
+    #[test]
+    fn every_page_has_one_drawing_page_style() {
+        let mut doc = Document::new();
+        doc.add_slide();
+        doc.slides[0].background = Background::Solid(Color::rgb(1.0, 0.0, 0.0));
+        doc.slides[1].background = Background::Solid(Color::rgb(0.0, 0.0, 1.0));
+
+        let file = TempFile::new("pages.odp");
+        save_document(&doc, file.path()).unwrap();
+        let mut zip = zip::ZipArchive::new(std::fs::File::open(file.path()).unwrap()).unwrap();
+        let mut xml = String::new();
+        for part in ["styles.xml", "content.xml"] {
+            io::Read::read_to_string(&mut zip.by_name(part).unwrap(), &mut xml).unwrap();
+        }
+        let page_styles: Vec<&str> = xml
+            .split("<style:style style:name=\"")
+            .skip(1)
+            .filter(|style| style.contains("style:family=\"drawing-page\""))
+            .filter_map(|style| style.split('"').next())
+            .collect();
+        assert_eq!(page_styles, [MASTER_PAGE_STYLE, "dp1", "dp2"]);
+
+        let (doc, _) = load_document(file.path()).unwrap();
+        let colors: Vec<_> = doc
+            .slides
+            .iter()
+            .map(|slide| match &slide.background {
+                Background::Solid(color) => (color.r, color.b),
+                other => panic!("expected a solid background, got {:?}", other),
+            })
+            .collect();
+        assert_eq!(colors, [(1.0, 0.0), (0.0, 1.0)]);
+    }
+
+    fn texts(doc: &Document) -> Vec<&TextElement> {
+        doc.slides[0]
+            .elements
+            .iter()
+            .filter_map(|e| match e {
+                SlideElement::Text(text) => Some(text),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn text_boxes_on_one_slide_keep_their_own_tab_stops() {
+        let mut doc = Document::new();
+        for stops in [vec![36.0], vec![72.0, 144.0]] {
+            let mut text = TextElement::new(Rect::new(0.0, 0.0, 200.0, 50.0), "a\tb");
+            text.paragraphs[0].tab_stops = stops;
+            doc.slides[0].elements.push(SlideElement::Text(text));
+        }
+
+        let doc = round_trip(&doc);
+        let stops: Vec<Vec<f64>> = texts(&doc)
+            .iter()
+            .map(|text| text.paragraphs[0].tab_stops.iter().map(|s| s.round()).collect())
+            .collect();
+        assert_eq!(stops, [vec![36.0], vec![72.0, 144.0]]);
+    }
+
+    #[test]
+    fn text_boxes_on_one_slide_keep_their_own_paragraph_styles() {
+        let mut doc = Document::new();
+        for (i, spacing) in [1.5, 2.0].into_iter().enumerate() {
+            let mut text = TextElement::new(Rect::new(0.0, 100.0 * i as f64, 200.0, 50.0), "Text");
+            let paragraph = &mut text.paragraphs[0];
+            paragraph.line_spacing = spacing;
+            paragraph.space_before = 6.0 * spacing;
+            paragraph.runs[0].font.size = 12.0 * spacing;
+            doc.slides[0].elements.push(SlideElement::Text(text));
+        }
+
+        let doc = round_trip(&doc);
+        let texts = texts(&doc);
+        assert_eq!(texts.len(), 2);
+        for (text, spacing) in texts.iter().zip([1.5, 2.0]) {
+            let paragraph = &text.paragraphs[0];
+            assert_eq!(paragraph.line_spacing, spacing);
+            assert!((paragraph.space_before - 6.0 * spacing).abs() < 0.01);
+            assert_eq!(paragraph.runs[0].font.size, 12.0 * spacing);
+        }
+    }
+
+    #[test]
+    fn image_crop_is_written_as_clip() {
+        let mut doc = Document::new();
+        let bounds = Rect::new(0.0, 0.0, 150.0, 90.0);
+        let mut img = ImageElement::new(bounds, png_header(200, 100), "image/png".into());
+        img.scale_mode = ScaleMode::Stretch;
+        img.crop = ImageCrop { left: 0.25, top: 0.1, right: 0.0, bottom: 0.0 };
+        doc.slides[0].elements.push(SlideElement::Image(img));
+
+        let file = TempFile::new("crop.odp");
+        save_document(&doc, file.path()).unwrap();
+        let mut zip = zip::ZipArchive::new(std::fs::File::open(file.path()).unwrap()).unwrap();
+        let mut content = String::new();
+        io::Read::read_to_string(&mut zip.by_name("content.xml").unwrap(), &mut content).unwrap();
+        // 200 by 100 pixels at 96 DPI are 5.2917 by 2.6458 cm
+        assert!(content.contains("fo:clip=\"rect(0.2646cm, 0.0000cm, 0.0000cm, 1.3229cm)\""));
+        assert!(!content.contains("lumina:crop"));
+
+        let (doc, _) = load_document(file.path()).unwrap();
+        let SlideElement::Image(img) = &doc.slides[0].elements[0] else {
+            panic!("expected an image");
+        };
+        assert!((img.crop.left - 0.25).abs() < 1e-4);
+        assert!((img.crop.top - 0.1).abs() < 1e-4);
+        assert_eq!((img.crop.right, img.crop.bottom), (0.0, 0.0));
+    }
+}
@@ -1,3 +1,36 @@
+// OOXML namespaces
+pub const NS_PRESENTATIONML: &str =
+    "http://schemas.openxmlformats.org/presentationml/2006/main";
+pub const NS_DRAWINGML: &str = "http://schemas.openxmlformats.org/drawingml/2006/main";
+pub const NS_RELATIONSHIPS: &str =
+    "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+pub const NS_PACKAGE_RELATIONSHIPS: &str =
+    "http://schemas.openxmlformats.org/package/2006/relationships";
+pub const NS_CONTENT_TYPES: &str =
+    "http://schemas.openxmlformats.org/package/2006/content-types";
+
+// Relationship types, relative to NS_RELATIONSHIPS
+pub const REL_OFFICE_DOCUMENT: &str = "officeDocument";
+pub const REL_SLIDE: &str = "slide";
+pub const REL_SLIDE_MASTER: &str = "slideMaster";
+pub const REL_SLIDE_LAYOUT: &str = "slideLayout";
+pub const REL_THEME: &str = "theme";
+pub const REL_IMAGE: &str = "image";
+
+// Content types of the package parts
+pub const CT_PRESENTATION: &str =
+    "application/vnd.openxmlformats-officedocument.presentationml.presentation.main+xml";
+pub const CT_SLIDE: &str = "application/vnd.openxmlformats-officedocument.presentationml.slide+xml";
+pub const CT_SLIDE_MASTER: &str =
+    "application/vnd.openxmlformats-officedocument.presentationml.slideMaster+xml";
+pub const CT_SLIDE_LAYOUT: &str =
+    "application/vnd.openxmlformats-officedocument.presentationml.slideLayout+xml";
+pub const CT_THEME: &str = "application/vnd.openxmlformats-officedocument.theme+xml";
+pub const CT_RELATIONSHIPS: &str = "application/vnd.openxmlformats-package.relationships+xml";
+
+/// Tip counts of the star preset geometries
+pub const PRESET_STAR_POINTS: [u32; 10] = [4, 5, 6, 7, 8, 10, 12, 16, 24, 32];
+
 /// 1 point = 12700 EMU (English Metric Units)
 pub const EMU_PER_PT: f64 = 12700.0;
 
@@ -6,6 +39,11 @@ pub fn emu_to_pt(emu: i64) -> f64 {
     emu as f64 / EMU_PER_PT
 }
 
+/// Convert points to EMU
+pub fn pt_to_emu(pt: f64) -> i64 {
+    (pt * EMU_PER_PT).round() as i64
+}
+
 /// Convert half-points (used for font sizes) to points
 pub fn half_pt_to_pt(half_pt: f64) -> f64 {
     half_pt / 100.0
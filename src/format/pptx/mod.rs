@@ -1,2 +1,3 @@
 pub mod constants;
 pub mod reader;
+pub mod writer;
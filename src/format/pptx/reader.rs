@@ -5,34 +5,44 @@ use std::io::{self, Read};
 use std::path::Path;
 use zip::ZipArchive;
 
+use crate::format::warning::ParseWarning;
 use crate::model::document::Document;
 use crate::model::element::SlideElement;
-use crate::model::geometry::{Rect, Size};
-use crate::model::image::ImageElement;
-use crate::model::shape::{ShapeElement, ShapeType};
+use crate::model::geometry::{Insets, Rect, Size};
+use crate::model::image::{ImageCrop, ImageData, ImageElement, ScaleMode};
+use crate::model::shape::{ShapeElement, ShapeType, PRESET_CORNER_RATIO};
+use crate::model::slide::{Background, Gradient};
 use crate::model::style::{Color, FillStyle, FontStyle, StrokeStyle};
-use crate::model::text::{TextAlignment, TextElement, TextParagraph, TextRun};
+use crate::model::text::{
+    ListStyle, TextAlignment, TextElement, TextOutline, TextParagraph, TextRun, TextShadow,
+    VerticalAlignment,
+};
 
 use super::constants::*;
 
-pub fn load_document(path: &Path) -> io::Result<Document> {
+/// Load a document together with the XML errors that cut parsing short.
+pub fn load_document(path: &Path) -> io::Result<(Document, Vec<ParseWarning>)> {
     let file = std::fs::File::open(path)?;
     let mut archive = ZipArchive::new(file)
         .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
 
     // Parse presentation.xml for slide size and slide list
     let presentation_xml = read_zip_entry(&mut archive, "ppt/presentation.xml")?;
-    let (slide_size, slide_refs) = parse_presentation(&presentation_xml);
+    let mut warnings = Vec::new();
+    let (slide_size, slide_refs) = parse_presentation(&presentation_xml, &mut warnings);
 
     // Parse presentation.xml.rels for slide paths
     let pres_rels = read_zip_entry(&mut archive, "ppt/_rels/presentation.xml.rels")
         .unwrap_or_default();
-    let rel_map = parse_rels(&pres_rels);
+    let rel_map = parse_rels(&pres_rels, "ppt/_rels/presentation.xml.rels", &mut warnings);
 
     let mut doc = Document::new();
     doc.slide_size = slide_size;
     doc.slides.clear();
 
+    // Placeholders by layout path, shared by the slides using a layout
+    let mut layouts: HashMap<String, Placeholders> = HashMap::new();
+
     for slide_ref in &slide_refs {
         let slide_path = rel_map
             .get(slide_ref)
@@ -44,12 +54,19 @@ pub fn load_document(path: &Path) -> io::Result<Document> {
             continue;
         }
 
-        // Parse slide relationships for images
-        let slide_rels_path = slide_path
-            .replace("slides/", "slides/_rels/")
-            + ".rels";
+        // Parse slide relationships for images and the layout
+        let slide_rels_path = rels_path(&slide_path);
         let slide_rels_xml = read_zip_entry(&mut archive, &slide_rels_path).unwrap_or_default();
-        let slide_rel_map = parse_rels(&slide_rels_xml);
+        let slide_rel_map = parse_rels(&slide_rels_xml, &slide_rels_path, &mut warnings);
+
+        let layout_path = slide_rel_map
+            .values()
+            .find(|target| target.contains("slideLayouts/"))
+            .map(|target| resolve_path(part_dir(&slide_path), target))
+            .unwrap_or_default();
+        let placeholders = layouts
+            .entry(layout_path)
+            .or_insert_with_key(|path| load_placeholders(&mut archive, path, &mut warnings));
 
         let slide_xml = match read_zip_entry(&mut archive, &slide_path) {
             Ok(xml) => xml,
@@ -59,7 +76,14 @@ pub fn load_document(path: &Path) -> io::Result<Document> {
             }
         };
 
-        let slide = parse_slide(&slide_xml, &slide_rel_map, &slide_path, &mut archive);
+        let slide = parse_slide(
+            &slide_xml,
+            &slide_rel_map,
+            &slide_path,
+            placeholders,
+            &mut archive,
+            &mut warnings,
+        );
         doc.slides.push(slide);
     }
 
@@ -67,7 +91,7 @@ pub fn load_document(path: &Path) -> io::Result<Document> {
         doc.slides.push(crate::model::slide::Slide::new());
     }
 
-    Ok(doc)
+    Ok((doc, warnings))
 }
 
 fn read_zip_entry<R: Read + io::Seek>(
@@ -94,7 +118,19 @@ fn read_zip_bytes<R: Read + io::Seek>(
     Ok(data)
 }
 
-fn parse_presentation(xml: &str) -> (Size, Vec<String>) {
+/// Path of the relationships part of `part`, e.g. `ppt/slides/_rels/slide1.xml.rels`.
+fn rels_path(part: &str) -> String {
+    let (dir, file) = part.split_at(part.rfind('/').map_or(0, |idx| idx + 1));
+    format!("{}_rels/{}.rels", dir, file)
+}
+
+/// Directory of `part` including the trailing slash, the base of its
+/// relationship targets.
+fn part_dir(part: &str) -> &str {
+    part.rfind('/').map_or("", |idx| &part[..idx + 1])
+}
+
+fn parse_presentation(xml: &str, warnings: &mut Vec<ParseWarning>) -> (Size, Vec<String>) {
     let mut reader = Reader::from_str(xml);
     let mut buf = Vec::new();
     let mut width = 960.0_f64;
@@ -135,7 +171,15 @@ fn parse_presentation(xml: &str) -> (Size, Vec<String>) {
                 }
             }
             Ok(Event::Eof) => break,
-            Err(_) => break,
+            Err(e) => {
+                warnings.push(ParseWarning::new(
+                    "ppt/presentation.xml",
+                    xml,
+                    reader.error_position(),
+                    &e,
+                ));
+                break;
+            }
             _ => {}
         }
         buf.clear();
@@ -144,7 +188,11 @@ fn parse_presentation(xml: &str) -> (Size, Vec<String>) {
     (Size::new(width, height), slide_refs)
 }
 
-fn parse_rels(xml: &str) -> HashMap<String, String> {
+fn parse_rels(
+    xml: &str,
+    part: &str,
+    warnings: &mut Vec<ParseWarning>,
+) -> HashMap<String, String> {
     let mut map = HashMap::new();
     let mut reader = Reader::from_str(xml);
     let mut buf = Vec::new();
@@ -172,7 +220,10 @@ fn parse_rels(xml: &str) -> HashMap<String, String> {
                 }
             }
             Ok(Event::Eof) => break,
-            Err(_) => break,
+            Err(e) => {
+                warnings.push(ParseWarning::new(part, xml, reader.error_position(), &e));
+                break;
+            }
             _ => {}
         }
         buf.clear();
@@ -181,82 +232,200 @@ fn parse_rels(xml: &str) -> HashMap<String, String> {
     map
 }
 
+/// Shape of a `p:sp` without text, filled with `fill` and outlined with
+/// `stroke_color`, 2 points wide unless `stroke_width` says otherwise.
+/// `no_line` leaves it without an outline.
+fn sp_shape(
+    bounds: Rect,
+    shape_type: ShapeType,
+    fill: Option<Color>,
+    no_line: bool,
+    stroke_color: Option<Color>,
+    stroke_width: Option<f64>,
+) -> ShapeElement {
+    let mut shape = ShapeElement::new(bounds, shape_type);
+    shape.fill = fill.map(FillStyle::new);
+    if no_line {
+        shape.stroke = None;
+    } else if let Some(color) = stroke_color {
+        shape.stroke = Some(StrokeStyle::new(color, stroke_width.unwrap_or(2.0)));
+    }
+    shape
+}
+
 fn parse_slide<R: Read + io::Seek>(
     xml: &str,
     rels: &HashMap<String, String>,
     slide_path: &str,
+    placeholders: &Placeholders,
     archive: &mut ZipArchive<R>,
+    warnings: &mut Vec<ParseWarning>,
 ) -> crate::model::slide::Slide {
     let mut slide = crate::model::slide::Slide::new();
     let mut reader = Reader::from_str(xml);
     let mut buf = Vec::new();
 
     let mut in_sp = false; // shape
+    let mut in_sp_pr = false; // shape properties, where fill and line live
+    let mut in_ln = false; // line inside the shape properties
+    let mut in_sp_fill = false; // solid or gradient fill, not e.g. a shadow color
     let mut in_pic = false; // picture
     let mut in_tx_body = false;
     let mut in_p = false;
     let mut in_r = false;
+    let mut in_r_pr = false;
 
     let mut sp_bounds = Rect::new(0.0, 0.0, 100.0, 100.0);
-    let mut _sp_is_text_box = false;
     let mut sp_shape_type: Option<ShapeType> = None;
     let mut sp_fill_color: Option<Color> = None;
     let mut sp_stroke_color: Option<Color> = None;
     let mut sp_stroke_width: Option<f64> = None;
+    let mut sp_no_line = false;
+    let mut sp_inset = Insets::default();
+    let mut sp_anchor = VerticalAlignment::Top;
+    let mut sp_auto_fit = false;
+    let mut sp_rotation = 0.0;
+    let mut sp_flip = (false, false);
+    let mut sp_text_effects = TextEffects::default();
+    let mut sp_text_defaults = TextDefaults::default();
 
     let mut text_paragraphs: Vec<TextParagraph> = Vec::new();
     let mut text_runs: Vec<TextRun> = Vec::new();
     let mut run_text = String::new();
     let mut run_font = FontStyle::default();
     let mut para_align = TextAlignment::Left;
+    let mut para_list = ListStyle::None;
+    let mut para_tab_stops: Vec<f64> = Vec::new();
+    let mut para_level = 0;
 
     let mut pic_bounds = Rect::new(0.0, 0.0, 100.0, 100.0);
     let mut pic_rel_id = String::new();
+    let mut pic_alt_text = String::new();
+    let mut pic_rotation = 0.0;
+    let mut pic_flip = (false, false);
+    let mut pic_crop = ImageCrop::default();
 
-    let slide_dir = if let Some(idx) = slide_path.rfind('/') {
-        &slide_path[..idx + 1]
-    } else {
-        ""
-    };
+    let mut in_bg = false;
+    let mut bg_fill = BackgroundFill::default();
+
+    let slide_dir = part_dir(slide_path);
 
     loop {
         match reader.read_event_into(&mut buf) {
             Ok(Event::Start(ref e)) => {
                 let name = String::from_utf8_lossy(e.local_name().as_ref()).to_string();
+                if in_bg {
+                    bg_fill.handle_element(&name, e);
+                }
+                if in_r_pr {
+                    sp_text_effects.handle_element(&name, e);
+                }
                 match name.as_str() {
+                    "bg" => {
+                        in_bg = true;
+                        bg_fill = BackgroundFill::default();
+                    }
                     "sp" => {
                         in_sp = true;
-                        _sp_is_text_box = false;
+                        in_sp_pr = false;
+                        in_ln = false;
+                        in_sp_fill = false;
+                        sp_bounds = Rect::new(0.0, 0.0, 100.0, 100.0);
                         sp_shape_type = None;
                         sp_fill_color = None;
                         sp_stroke_color = None;
                         sp_stroke_width = None;
+                        sp_no_line = false;
+                        sp_inset = Insets::default();
+                        sp_anchor = VerticalAlignment::Top;
+                        sp_auto_fit = false;
+                        sp_rotation = 0.0;
+                        sp_flip = (false, false);
+                        sp_text_effects = TextEffects::default();
+                        sp_text_defaults = TextDefaults::default();
                         text_paragraphs.clear();
                     }
                     "pic" => {
                         in_pic = true;
                         pic_rel_id.clear();
+                        pic_alt_text.clear();
+                        pic_rotation = 0.0;
+                        pic_flip = (false, false);
+                        pic_crop = ImageCrop::default();
+                    }
+                    "cNvPr" if in_pic => pic_alt_text = parse_description(e),
+                    "ph" if in_sp => {
+                        if let Some(ph) = placeholders.find_for(e) {
+                            sp_bounds = ph.bounds.unwrap_or(sp_bounds);
+                            sp_text_defaults = ph.text.clone();
+                        }
+                    }
+                    "xfrm" if in_pic => (pic_rotation, pic_flip) = parse_transform(e),
+                    "xfrm" if in_sp => (sp_rotation, sp_flip) = parse_transform(e),
+                    "srcRect" if in_pic => pic_crop = parse_crop(e),
+                    "spPr" if in_sp => in_sp_pr = true,
+                    "ln" if in_sp_pr => {
+                        in_ln = true;
+                        sp_stroke_width = parse_line_width(e);
+                    }
+                    "solidFill" | "gradFill" if in_sp_pr => in_sp_fill = true,
+                    "srgbClr" if in_sp_fill => {
+                        assign_shape_color(e, in_ln, &mut sp_fill_color, &mut sp_stroke_color);
+                    }
+                    // With adjustments, e.g. the corner radius of a rounded rectangle
+                    "prstGeom" if in_sp => {
+                        let prst = get_attr(e, "prst").unwrap_or_default();
+                        sp_shape_type = Some(preset_shape_type(&prst, &sp_bounds));
                     }
                     "txBody" if in_sp || in_pic => {
                         in_tx_body = true;
                         text_paragraphs.clear();
                     }
+                    "bodyPr" if in_tx_body => {
+                        sp_inset = parse_body_insets(e);
+                        sp_anchor = parse_body_anchor(e);
+                    }
                     "p" if in_tx_body => {
                         in_p = true;
                         text_runs.clear();
-                        para_align = TextAlignment::Left;
+                        para_align = sp_text_defaults.align.unwrap_or(TextAlignment::Left);
+                        para_list = ListStyle::None;
+                        para_level = 0;
+                        para_tab_stops.clear();
+                    }
+                    "pPr" if in_p => {
+                        parse_paragraph_properties(e, &mut para_align, &mut para_level);
                     }
                     "r" if in_p => {
                         in_r = true;
                         run_text.clear();
-                        run_font = FontStyle::default();
+                        run_font = sp_text_defaults.font();
+                    }
+                    "rPr" if in_r => {
+                        in_r_pr = true;
+                        parse_run_properties(e, &mut run_font);
                     }
                     _ => {}
                 }
             }
             Ok(Event::Empty(ref e)) => {
                 let name = String::from_utf8_lossy(e.local_name().as_ref()).to_string();
+                if in_bg {
+                    bg_fill.handle_element(&name, e);
+                }
+                if in_r_pr {
+                    sp_text_effects.handle_element(&name, e);
+                    sp_text_effects.end_element(&name);
+                }
                 match name.as_str() {
+                    "cNvPr" if in_pic => pic_alt_text = parse_description(e),
+                    "ph" if in_sp => {
+                        if let Some(ph) = placeholders.find_for(e) {
+                            sp_bounds = ph.bounds.unwrap_or(sp_bounds);
+                            sp_text_defaults = ph.text.clone();
+                        }
+                    }
+                    "srcRect" if in_pic => pic_crop = parse_crop(e),
                     "off" if in_sp || in_pic => {
                         let (x, y) = parse_emu_position(e);
                         if in_pic {
@@ -278,50 +447,40 @@ fn parse_slide<R: Read + io::Seek>(
                         }
                     }
                     "prstGeom" if in_sp => {
-                        for attr in e.attributes().flatten() {
-                            let key =
-                                String::from_utf8_lossy(attr.key.local_name().as_ref()).to_string();
-                            if key == "prst" {
-                                let val = String::from_utf8_lossy(&attr.value).to_string();
-                                sp_shape_type = match val.as_str() {
-                                    "rect" | "roundRect" | "snip1Rect" | "snip2SameRect" => {
-                                        Some(ShapeType::Rectangle)
-                                    }
-                                    "ellipse" | "circle" => Some(ShapeType::Ellipse),
-                                    "line" | "straightConnector1" => Some(ShapeType::Line),
-                                    _ => Some(ShapeType::Rectangle),
-                                };
-                            }
-                        }
+                        let prst = get_attr(e, "prst").unwrap_or_default();
+                        sp_shape_type = Some(preset_shape_type(&prst, &sp_bounds));
                     }
-                    "srgbClr" if in_sp && !in_tx_body => {
-                        for attr in e.attributes().flatten() {
-                            let key =
-                                String::from_utf8_lossy(attr.key.local_name().as_ref()).to_string();
-                            if key == "val" {
-                                let val = String::from_utf8_lossy(&attr.value).to_string();
-                                let color = Color::from_hex(&val);
-                                // Simple heuristic: first color found is fill, second is stroke
-                                if sp_fill_color.is_none() {
-                                    sp_fill_color = color;
-                                } else {
-                                    sp_stroke_color = color;
-                                }
+                    // The corner radius of a rounded rectangle, relative to
+                    // the shorter side in 100000ths
+                    "gd" if in_sp && get_attr(e, "name").as_deref() == Some("adj") => {
+                        if let Some(ShapeType::RoundedRectangle { radius }) = &mut sp_shape_type {
+                            let adj = get_attr(e, "fmla")
+                                .and_then(|f| f.strip_prefix("val ")?.trim().parse::<f64>().ok());
+                            if let Some(adj) = adj {
+                                let shorter = sp_bounds.size.width.min(sp_bounds.size.height);
+                                *radius = shorter * adj / 100_000.0;
                             }
                         }
                     }
+                    "ln" if in_sp_pr => sp_stroke_width = parse_line_width(e),
+                    "noFill" if in_ln => sp_no_line = true,
+                    "srgbClr" if in_sp_fill => {
+                        assign_shape_color(e, in_ln, &mut sp_fill_color, &mut sp_stroke_color);
+                    }
+                    "bodyPr" if in_tx_body => {
+                        sp_inset = parse_body_insets(e);
+                        sp_anchor = parse_body_anchor(e);
+                    }
+                    "normAutofit" if in_tx_body => sp_auto_fit = true,
                     "pPr" if in_p => {
-                        for attr in e.attributes().flatten() {
-                            let key =
-                                String::from_utf8_lossy(attr.key.local_name().as_ref()).to_string();
-                            if key == "algn" {
-                                let val = String::from_utf8_lossy(&attr.value).to_string();
-                                para_align = match val.as_str() {
-                                    "ctr" => TextAlignment::Center,
-                                    "r" => TextAlignment::Right,
-                                    _ => TextAlignment::Left,
-                                };
-                            }
+                        parse_paragraph_properties(e, &mut para_align, &mut para_level);
+                    }
+                    "buAutoNum" if in_p => para_list = ListStyle::Numbered,
+                    "buChar" if in_p => para_list = ListStyle::Bullet,
+                    "buNone" if in_p => para_list = ListStyle::None,
+                    "tab" if in_p => {
+                        if let Some(Ok(pos)) = get_attr(e, "pos").map(|v| v.parse::<i64>()) {
+                            para_tab_stops.push(emu_to_pt(pos));
                         }
                     }
                     "rPr" if in_r => {
@@ -359,38 +518,54 @@ fn parse_slide<R: Read + io::Seek>(
             }
             Ok(Event::End(ref e)) => {
                 let name = String::from_utf8_lossy(e.local_name().as_ref()).to_string();
+                if in_r_pr {
+                    sp_text_effects.end_element(&name);
+                }
                 match name.as_str() {
+                    "bg" => {
+                        in_bg = false;
+                        let picture = bg_fill.blip.take().and_then(|rel_id| {
+                            let img_path = resolve_path(slide_dir, rels.get(&rel_id)?);
+                            let data = read_zip_bytes(archive, &img_path).ok()?;
+                            let mime = guess_mime(&img_path).to_string();
+                            Some(Background::Image(ImageData::Embedded { data: data.into(), mime }))
+                        });
+                        let background =
+                            picture.or_else(|| std::mem::take(&mut bg_fill).into_background());
+                        if let Some(background) = background {
+                            slide.background = background;
+                        }
+                    }
+                    "gs" if in_bg => bg_fill.stop_pos = None,
+                    "spPr" if in_sp => in_sp_pr = false,
+                    "ln" if in_sp_pr => in_ln = false,
+                    "solidFill" | "gradFill" if in_sp_pr => in_sp_fill = false,
                     "sp" => {
                         in_sp = false;
-                        if !text_paragraphs.is_empty() {
-                            let has_text = text_paragraphs
-                                .iter()
-                                .any(|p| !p.full_text().trim().is_empty());
-                            if has_text {
-                                let mut text_elem = TextElement::new(sp_bounds, "");
-                                text_elem.paragraphs = text_paragraphs.drain(..).collect();
-                                text_elem.alignment = para_align;
-                                slide.add_element(SlideElement::Text(text_elem));
-                            } else if let Some(shape_type) = sp_shape_type {
-                                let mut shape = ShapeElement::new(sp_bounds, shape_type);
-                                shape.fill = sp_fill_color.as_ref().map(|c| FillStyle::new(c.clone()));
-                                if let Some(sc) = &sp_stroke_color {
-                                    shape.stroke = Some(StrokeStyle::new(
-                                        sc.clone(),
-                                        sp_stroke_width.unwrap_or(2.0),
-                                    ));
-                                }
-                                slide.add_element(SlideElement::Shape(shape));
-                            }
+                        let has_text =
+                            text_paragraphs.iter().any(|p| !p.full_text().trim().is_empty());
+                        if has_text {
+                            let mut text_elem = TextElement::new(sp_bounds, "");
+                            text_elem.paragraphs = text_paragraphs.drain(..).collect();
+                            text_elem.alignment = para_align;
+                            text_elem.inset = sp_inset;
+                            text_elem.vertical_alignment = sp_anchor;
+                            text_elem.auto_fit = sp_auto_fit;
+                            text_elem.rotation = sp_rotation;
+                            text_elem.outline = sp_text_effects.outline.take();
+                            text_elem.shadow = sp_text_effects.shadow.take();
+                            slide.add_element(SlideElement::Text(text_elem));
                         } else if let Some(shape_type) = sp_shape_type {
-                            let mut shape = ShapeElement::new(sp_bounds, shape_type);
-                            shape.fill = sp_fill_color.as_ref().map(|c| FillStyle::new(c.clone()));
-                            if let Some(sc) = &sp_stroke_color {
-                                shape.stroke = Some(StrokeStyle::new(
-                                    sc.clone(),
-                                    sp_stroke_width.unwrap_or(2.0),
-                                ));
-                            }
+                            let mut shape = sp_shape(
+                                sp_bounds,
+                                shape_type,
+                                sp_fill_color.take(),
+                                sp_no_line,
+                                sp_stroke_color.take(),
+                                sp_stroke_width,
+                            );
+                            shape.rotation = sp_rotation;
+                            (shape.flip_h, shape.flip_v) = sp_flip;
                             slide.add_element(SlideElement::Shape(shape));
                         }
                     }
@@ -401,17 +576,29 @@ fn parse_slide<R: Read + io::Seek>(
                                 let img_path = resolve_path(slide_dir, rel_target);
                                 if let Ok(data) = read_zip_bytes(archive, &img_path) {
                                     let mime = guess_mime(&img_path);
-                                    let img =
+                                    let mut img =
                                         ImageElement::new(pic_bounds, data, mime.to_string());
+                                    img.name_metafile(&img_path);
+                                    img.alt_text = std::mem::take(&mut pic_alt_text);
+                                    // Pictures always fill their frame, the
+                                    // frame carries the aspect ratio
+                                    img.scale_mode = ScaleMode::Stretch;
+                                    img.rotation = pic_rotation;
+                                    (img.flip_h, img.flip_v) = pic_flip;
+                                    img.crop = pic_crop;
                                     slide.add_element(SlideElement::Image(img));
                                 }
                             }
                         }
                     }
                     "txBody" => in_tx_body = false,
+                    "rPr" => in_r_pr = false,
                     "p" if in_p => {
                         in_p = false;
-                        let para = TextParagraph::new(text_runs.drain(..).collect());
+                        let mut para = TextParagraph::new(text_runs.drain(..).collect());
+                        para.list = para_list;
+                        para.level = para_level;
+                        para.tab_stops = std::mem::take(&mut para_tab_stops);
                         text_paragraphs.push(para);
                     }
                     "r" if in_r => {
@@ -427,7 +614,10 @@ fn parse_slide<R: Read + io::Seek>(
                 }
             }
             Ok(Event::Eof) => break,
-            Err(_) => break,
+            Err(e) => {
+                warnings.push(ParseWarning::new(slide_path, xml, reader.error_position(), &e));
+                break;
+            }
             _ => {}
         }
         buf.clear();
@@ -436,6 +626,562 @@ fn parse_slide<R: Read + io::Seek>(
     slide
 }
 
+/// Fill of a slide `<p:bg>` collected while reading it
+#[derive(Default)]
+struct BackgroundFill {
+    solid: Option<Color>,
+    in_gradient: bool,
+    /// Position of the gradient stop being read, 0..1
+    stop_pos: Option<f64>,
+    stops: Vec<(f64, Color)>,
+    radial: bool,
+    angle: f64,
+    /// Relationship id of a picture fill
+    blip: Option<String>,
+}
+
+impl BackgroundFill {
+    fn handle_element(&mut self, name: &str, e: &quick_xml::events::BytesStart) {
+        match name {
+            "gradFill" => self.in_gradient = true,
+            "gs" => {
+                self.stop_pos = get_attr(e, "pos")
+                    .and_then(|v| v.parse::<f64>().ok())
+                    .map(|pos| pos / 100_000.0);
+            }
+            "srgbClr" => {
+                if let Some(color) = get_attr(e, "val").and_then(|v| Color::from_hex(&v)) {
+                    if let Some(pos) = self.stop_pos {
+                        self.stops.push((pos, color));
+                    } else if !self.in_gradient {
+                        self.solid = Some(color);
+                    }
+                }
+            }
+            // Angle in 60000ths of a degree, clockwise from left-to-right
+            "lin" => {
+                if let Some(ang) = get_attr(e, "ang").and_then(|v| v.parse::<f64>().ok()) {
+                    self.angle = ang / 60_000.0;
+                }
+            }
+            "path" => self.radial = true,
+            "blip" => self.blip = get_attr(e, "embed"),
+            _ => {}
+        }
+    }
+
+    fn into_background(mut self) -> Option<Background> {
+        if self.in_gradient {
+            self.stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+            let start = self.stops.first()?.1.clone();
+            let end = self.stops.last()?.1.clone();
+            let gradient = if self.radial {
+                Gradient::radial(start, end)
+            } else {
+                Gradient::linear(start, end, self.angle)
+            };
+            return Some(Background::Gradient(gradient));
+        }
+        self.solid.map(Background::Solid)
+    }
+}
+
+/// Text outline and shadow of a `<p:sp>`, read from `<a:ln>` and
+/// `<a:effectLst><a:outerShdw>` in its run properties. The first run with
+/// an effect sets it for the whole box.
+#[derive(Default)]
+struct TextEffects {
+    outline: Option<TextOutline>,
+    shadow: Option<TextShadow>,
+    /// Width of the `<a:ln>` being read, in points
+    line_width: Option<f64>,
+    /// Offset of the `<a:outerShdw>` being read, in points
+    shadow_offset: Option<(f64, f64)>,
+    color: Option<Color>,
+}
+
+impl TextEffects {
+    fn handle_element(&mut self, name: &str, e: &quick_xml::events::BytesStart) {
+        match name {
+            // The line is centred on the glyph edge, so half of it lies
+            // outside. A missing width is the 0.75pt default.
+            "ln" => {
+                let emu = get_attr(e, "w").and_then(|v| v.parse::<i64>().ok());
+                self.line_width = Some(emu_to_pt(emu.unwrap_or(9525)) / 2.0);
+                self.color = None;
+            }
+            // Distance in EMU along a direction in 60000ths of a degree,
+            // clockwise from the positive x axis
+            "outerShdw" => {
+                let attr = |key| get_attr(e, key).and_then(|v| v.parse::<f64>().ok());
+                let dist = emu_to_pt(attr("dist").unwrap_or(0.0) as i64);
+                let dir = (attr("dir").unwrap_or(0.0) / 60_000.0).to_radians();
+                self.shadow_offset = Some((dist * dir.cos(), dist * dir.sin()));
+                self.color = None;
+            }
+            "srgbClr" if self.line_width.is_some() || self.shadow_offset.is_some() => {
+                self.color = get_attr(e, "val").and_then(|v| Color::from_hex(&v));
+            }
+            // Opacity in 1000ths of a percent
+            "alpha" => {
+                let alpha = get_attr(e, "val").and_then(|v| v.parse::<f64>().ok());
+                if let (Some(color), Some(alpha)) = (self.color.as_mut(), alpha) {
+                    color.a = alpha / 100_000.0;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn end_element(&mut self, name: &str) {
+        match name {
+            "ln" => {
+                let width = self.line_width.take();
+                if let (None, Some(width), Some(color)) = (&self.outline, width, self.color.take()) {
+                    self.outline = Some(TextOutline { color, width });
+                }
+            }
+            "outerShdw" => {
+                let offset = self.shadow_offset.take();
+                if let (None, Some((offset_x, offset_y)), Some(color)) =
+                    (&self.shadow, offset, self.color.take())
+                {
+                    self.shadow = Some(TextShadow {
+                        color,
+                        offset_x,
+                        offset_y,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Text defaults of a placeholder from the first level of a list style,
+/// `<a:lvl1pPr>` and its `<a:defRPr>`. `None` where it inherits.
+#[derive(Debug, Clone, Default)]
+struct TextDefaults {
+    align: Option<TextAlignment>,
+    size: Option<f64>,
+    bold: Option<bool>,
+    italic: Option<bool>,
+    family: Option<String>,
+    in_level1: bool,
+}
+
+impl TextDefaults {
+    fn handle_element(&mut self, name: &str, e: &quick_xml::events::BytesStart) {
+        match name {
+            "lvl1pPr" => {
+                self.in_level1 = true;
+                let mut align = TextAlignment::Left;
+                let mut level = 0;
+                parse_paragraph_properties(e, &mut align, &mut level);
+                if get_attr(e, "algn").is_some() {
+                    self.align = Some(align);
+                }
+            }
+            "defRPr" if self.in_level1 => {
+                let flag = |key| get_attr(e, key).map(|v| v == "1" || v == "true");
+                // Font size in hundredths of a point
+                if let Some(sz) = get_attr(e, "sz").and_then(|v| v.parse::<f64>().ok()) {
+                    self.size = Some(half_pt_to_pt(sz));
+                }
+                self.bold = flag("b").or(self.bold);
+                self.italic = flag("i").or(self.italic);
+            }
+            // Theme fonts such as "+mj-lt" are not resolved
+            "latin" if self.in_level1 => {
+                if let Some(typeface) = get_attr(e, "typeface").filter(|t| !t.starts_with('+')) {
+                    self.family = Some(typeface);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn end_element(&mut self, name: &str) {
+        if name == "lvl1pPr" {
+            self.in_level1 = false;
+        }
+    }
+
+    /// These defaults with the unset ones taken from `parent`.
+    fn inherit(&self, parent: &TextDefaults) -> TextDefaults {
+        TextDefaults {
+            align: self.align.or(parent.align),
+            size: self.size.or(parent.size),
+            bold: self.bold.or(parent.bold),
+            italic: self.italic.or(parent.italic),
+            family: self.family.clone().or_else(|| parent.family.clone()),
+            in_level1: false,
+        }
+    }
+
+    /// Font of a run that sets none of its own properties.
+    fn font(&self) -> FontStyle {
+        let mut font = FontStyle::default();
+        if let Some(size) = self.size {
+            font.size = size;
+        }
+        font.bold = self.bold.unwrap_or(font.bold);
+        font.italic = self.italic.unwrap_or(font.italic);
+        if let Some(family) = &self.family {
+            font.family = family.clone();
+        }
+        font
+    }
+}
+
+/// A `<p:ph>` shape on a slide layout or master
+#[derive(Debug, Clone)]
+struct Placeholder {
+    /// `type` attribute; a missing type is "obj", a content placeholder
+    kind: String,
+    idx: Option<String>,
+    bounds: Option<Rect>,
+    text: TextDefaults,
+}
+
+/// Text styles of a slide master, `<p:txStyles>`, for placeholders that do
+/// not set their own
+#[derive(Debug, Default)]
+struct MasterTextStyles {
+    title: TextDefaults,
+    body: TextDefaults,
+    other: TextDefaults,
+}
+
+impl MasterTextStyles {
+    fn for_kind(&self, kind: &str) -> &TextDefaults {
+        match placeholder_group(kind) {
+            "title" => &self.title,
+            "body" => &self.body,
+            _ => &self.other,
+        }
+    }
+}
+
+/// Title placeholders all take the title style and content placeholders
+/// the body style; dates, footers and slide numbers keep their own type.
+fn placeholder_group(kind: &str) -> &str {
+    match kind {
+        "title" | "ctrTitle" => "title",
+        "body" | "subTitle" | "obj" => "body",
+        _ => kind,
+    }
+}
+
+/// Placeholders a slide inherits from: those of its layout, already
+/// completed from the master, then those of the master.
+#[derive(Debug, Default)]
+struct Placeholders {
+    list: Vec<Placeholder>,
+}
+
+impl Placeholders {
+    /// The placeholder with the same `idx`, else the first of the same type,
+    /// else the first of the same group.
+    fn find(&self, kind: &str, idx: Option<&str>) -> Option<&Placeholder> {
+        idx.and_then(|idx| self.list.iter().find(|ph| ph.idx.as_deref() == Some(idx)))
+            .or_else(|| self.list.iter().find(|ph| ph.kind == kind))
+            .or_else(|| {
+                let group = placeholder_group(kind);
+                self.list.iter().find(|ph| placeholder_group(&ph.kind) == group)
+            })
+    }
+
+    /// The placeholder a slide `<p:ph>` inherits from.
+    fn find_for(&self, e: &quick_xml::events::BytesStart) -> Option<&Placeholder> {
+        let kind = get_attr(e, "type").unwrap_or_else(|| "obj".to_string());
+        self.find(&kind, get_attr(e, "idx").as_deref())
+    }
+}
+
+/// Placeholders of the slide layout at `layout_path` and of its master.
+/// Missing parts leave nothing to inherit.
+fn load_placeholders<R: Read + io::Seek>(
+    archive: &mut ZipArchive<R>,
+    layout_path: &str,
+    warnings: &mut Vec<ParseWarning>,
+) -> Placeholders {
+    let Ok(layout_xml) = read_zip_entry(archive, layout_path) else {
+        return Placeholders::default();
+    };
+    let (layout, _) = parse_placeholders(&layout_xml, layout_path, warnings);
+
+    let layout_rels_path = rels_path(layout_path);
+    let layout_rels_xml = read_zip_entry(archive, &layout_rels_path).unwrap_or_default();
+    let master_path = parse_rels(&layout_rels_xml, &layout_rels_path, warnings)
+        .values()
+        .find(|target| target.contains("slideMasters/"))
+        .map(|target| resolve_path(part_dir(layout_path), target));
+    let (mut master, styles) = match master_path {
+        Some(path) => match read_zip_entry(archive, &path) {
+            Ok(xml) => parse_placeholders(&xml, &path, warnings),
+            Err(_) => Default::default(),
+        },
+        None => Default::default(),
+    };
+
+    for ph in &mut master {
+        ph.text = ph.text.inherit(styles.for_kind(&ph.kind));
+    }
+    let master = Placeholders { list: master };
+    // A layout placeholder matches the master one by type only
+    let mut list: Vec<Placeholder> = layout
+        .into_iter()
+        .map(|mut ph| {
+            match master.find(&ph.kind, None) {
+                Some(parent) => {
+                    ph.bounds = ph.bounds.or(parent.bounds);
+                    ph.text = ph.text.inherit(&parent.text);
+                }
+                None => ph.text = ph.text.inherit(styles.for_kind(&ph.kind)),
+            }
+            ph
+        })
+        .collect();
+    list.extend(master.list);
+    Placeholders { list }
+}
+
+/// Placeholders of a slide layout or master, with the text styles of a
+/// master.
+fn parse_placeholders(
+    xml: &str,
+    part: &str,
+    warnings: &mut Vec<ParseWarning>,
+) -> (Vec<Placeholder>, MasterTextStyles) {
+    let mut reader = Reader::from_str(xml);
+    let mut buf = Vec::new();
+    let mut parser = PlaceholderParser::default();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(ref e)) => {
+                let name = String::from_utf8_lossy(e.local_name().as_ref()).to_string();
+                parser.start_element(&name, e);
+            }
+            Ok(Event::Empty(ref e)) => {
+                let name = String::from_utf8_lossy(e.local_name().as_ref()).to_string();
+                parser.start_element(&name, e);
+                parser.end_element(&name);
+            }
+            Ok(Event::End(ref e)) => {
+                let name = String::from_utf8_lossy(e.local_name().as_ref()).to_string();
+                parser.end_element(&name);
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => {
+                warnings.push(ParseWarning::new(part, xml, reader.error_position(), &e));
+                break;
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    (parser.placeholders, parser.styles)
+}
+
+/// Placeholders and master text styles collected while reading a layout or
+/// master
+#[derive(Default)]
+struct PlaceholderParser {
+    placeholders: Vec<Placeholder>,
+    styles: MasterTextStyles,
+    in_sp: bool,
+    in_xfrm: bool,
+    in_lst_style: bool,
+    /// Placeholder of the shape being read, `None` for other shapes
+    ph: Option<Placeholder>,
+    /// Element name of the master text style being read
+    style: Option<String>,
+}
+
+impl PlaceholderParser {
+    fn start_element(&mut self, name: &str, e: &quick_xml::events::BytesStart) {
+        if let Some(text) = self.text_defaults() {
+            text.handle_element(name, e);
+        }
+        match name {
+            "sp" => self.in_sp = true,
+            "ph" if self.in_sp => {
+                self.ph = Some(Placeholder {
+                    kind: get_attr(e, "type").unwrap_or_else(|| "obj".to_string()),
+                    idx: get_attr(e, "idx"),
+                    bounds: None,
+                    text: TextDefaults::default(),
+                });
+            }
+            "xfrm" if self.in_sp => self.in_xfrm = true,
+            "off" if self.in_xfrm => {
+                if let Some(ph) = &mut self.ph {
+                    let (x, y) = parse_emu_position(e);
+                    let bounds = ph.bounds.get_or_insert(Rect::new(0.0, 0.0, 0.0, 0.0));
+                    bounds.origin.x = x;
+                    bounds.origin.y = y;
+                }
+            }
+            "ext" if self.in_xfrm => {
+                if let Some(ph) = &mut self.ph {
+                    let (w, h) = parse_emu_size(e);
+                    let bounds = ph.bounds.get_or_insert(Rect::new(0.0, 0.0, 0.0, 0.0));
+                    bounds.size.width = w;
+                    bounds.size.height = h;
+                }
+            }
+            "lstStyle" if self.in_sp => self.in_lst_style = true,
+            "titleStyle" | "bodyStyle" | "otherStyle" => self.style = Some(name.to_string()),
+            _ => {}
+        }
+    }
+
+    fn end_element(&mut self, name: &str) {
+        if let Some(text) = self.text_defaults() {
+            text.end_element(name);
+        }
+        match name {
+            "sp" => {
+                self.in_sp = false;
+                self.placeholders.extend(self.ph.take());
+            }
+            "xfrm" => self.in_xfrm = false,
+            "lstStyle" => self.in_lst_style = false,
+            "titleStyle" | "bodyStyle" | "otherStyle" => self.style = None,
+            _ => {}
+        }
+    }
+
+    /// The text defaults being read, in a placeholder list style or a
+    /// master text style.
+    fn text_defaults(&mut self) -> Option<&mut TextDefaults> {
+        if self.in_lst_style {
+            return self.ph.as_mut().map(|ph| &mut ph.text);
+        }
+        match self.style.as_deref()? {
+            "titleStyle" => Some(&mut self.styles.title),
+            "bodyStyle" => Some(&mut self.styles.body),
+            _ => Some(&mut self.styles.other),
+        }
+    }
+}
+
+fn get_attr(e: &quick_xml::events::BytesStart, local_name: &str) -> Option<String> {
+    e.attributes().flatten().find_map(|attr| {
+        (attr.key.local_name().as_ref() == local_name.as_bytes())
+            .then(|| String::from_utf8_lossy(&attr.value).to_string())
+    })
+}
+
+/// Shape type of a preset geometry, or a rectangle for presets without an
+/// equivalent.
+fn preset_shape_type(prst: &str, bounds: &Rect) -> ShapeType {
+    match prst {
+        "ellipse" | "circle" => ShapeType::Ellipse,
+        "line" | "straightConnector1" => ShapeType::Line,
+        "triangle" => ShapeType::Triangle,
+        "roundRect" => ShapeType::RoundedRectangle {
+            radius: bounds.size.width.min(bounds.size.height) * PRESET_CORNER_RATIO,
+        },
+        "rightArrow" => ShapeType::Arrow,
+        _ => match prst.strip_prefix("star").and_then(|n| n.parse().ok()) {
+            Some(points) => ShapeType::Star { points },
+            None => ShapeType::Rectangle,
+        },
+    }
+}
+
+/// Alternative text of a `<p:cNvPr>`.
+fn parse_description(e: &quick_xml::events::BytesStart) -> String {
+    e.attributes()
+        .flatten()
+        .find(|attr| attr.key.local_name().as_ref() == b"descr")
+        .and_then(|attr| attr.unescape_value().ok())
+        .map(|value| value.to_string())
+        .unwrap_or_default()
+}
+
+/// Rotation in degrees clockwise and horizontal/vertical flip of an
+/// `<a:xfrm>`. The rotation is stored in 60000ths of a degree.
+fn parse_transform(e: &quick_xml::events::BytesStart) -> (f64, (bool, bool)) {
+    let rotation = get_attr(e, "rot")
+        .and_then(|v| v.parse::<f64>().ok())
+        .map_or(0.0, |rot| rot / 60_000.0);
+    let flag = |name| get_attr(e, name).is_some_and(|v| v == "1" || v == "true");
+    (rotation, (flag("flipH"), flag("flipV")))
+}
+
+/// Crop of an `<a:srcRect>`, given per edge in 1000ths of a percent.
+/// Negative values pad the picture instead, which is not supported.
+fn parse_crop(e: &quick_xml::events::BytesStart) -> ImageCrop {
+    let edge = |name| {
+        get_attr(e, name)
+            .and_then(|v| v.parse::<f64>().ok())
+            .map_or(0.0, |v| (v / 100_000.0).clamp(0.0, 1.0))
+    };
+    ImageCrop {
+        left: edge("l"),
+        top: edge("t"),
+        right: edge("r"),
+        bottom: edge("b"),
+    }
+}
+
+/// Width of a `<a:ln>` in points; `None` without a `w` attribute.
+fn parse_line_width(e: &quick_xml::events::BytesStart) -> Option<f64> {
+    let emu = get_attr(e, "w")?.parse::<i64>().ok()?;
+    Some(emu_to_pt(emu))
+}
+
+/// A `<a:srgbClr>` of a solid or gradient fill in the shape properties is
+/// the line color inside `<a:ln>` and the fill color otherwise. A gradient
+/// fill keeps its first stop, the closest a plain fill gets. Colors of
+/// effects, such as a shadow, are not passed here.
+fn assign_shape_color(
+    e: &quick_xml::events::BytesStart,
+    in_ln: bool,
+    fill: &mut Option<Color>,
+    stroke: &mut Option<Color>,
+) {
+    let Some(color) = get_attr(e, "val").and_then(|v| Color::from_hex(&v)) else {
+        return;
+    };
+    if in_ln {
+        *stroke = Some(color);
+    } else if fill.is_none() {
+        *fill = Some(color);
+    }
+}
+
+/// Text insets of a `<a:bodyPr>`. Missing attributes fall back to the
+/// OOXML defaults of 0.1" left/right and 0.05" top/bottom.
+fn parse_body_insets(e: &quick_xml::events::BytesStart) -> Insets {
+    let inset = |name: &str, default_emu: i64| {
+        let emu = get_attr(e, name)
+            .and_then(|v| v.parse::<i64>().ok())
+            .unwrap_or(default_emu);
+        emu_to_pt(emu)
+    };
+    Insets::new(
+        inset("lIns", 91440),
+        inset("tIns", 45720),
+        inset("rIns", 91440),
+        inset("bIns", 45720),
+    )
+}
+
+/// Vertical alignment of the text of a `<a:bodyPr>`, top when not given.
+fn parse_body_anchor(e: &quick_xml::events::BytesStart) -> VerticalAlignment {
+    match get_attr(e, "anchor").as_deref() {
+        Some("ctr") => VerticalAlignment::Middle,
+        Some("b") => VerticalAlignment::Bottom,
+        _ => VerticalAlignment::Top,
+    }
+}
+
 fn parse_emu_position(e: &quick_xml::events::BytesStart) -> (f64, f64) {
     let mut x = 0.0;
     let mut y = 0.0;
@@ -474,6 +1220,28 @@ fn parse_emu_size(e: &quick_xml::events::BytesStart) -> (f64, f64) {
     (w, h)
 }
 
+fn parse_paragraph_properties(
+    e: &quick_xml::events::BytesStart,
+    align: &mut TextAlignment,
+    level: &mut u32,
+) {
+    for attr in e.attributes().flatten() {
+        let key = String::from_utf8_lossy(attr.key.local_name().as_ref()).to_string();
+        let val = String::from_utf8_lossy(&attr.value).to_string();
+        match key.as_str() {
+            "algn" => {
+                *align = match val.as_str() {
+                    "ctr" => TextAlignment::Center,
+                    "r" => TextAlignment::Right,
+                    _ => TextAlignment::Left,
+                };
+            }
+            "lvl" => *level = val.parse().unwrap_or(0),
+            _ => {}
+        }
+    }
+}
+
 fn parse_run_properties(e: &quick_xml::events::BytesStart, font: &mut FontStyle) {
     for attr in e.attributes().flatten() {
         let key = String::from_utf8_lossy(attr.key.local_name().as_ref()).to_string();
@@ -487,6 +1255,8 @@ fn parse_run_properties(e: &quick_xml::events::BytesStart, font: &mut FontStyle)
             }
             "b" => font.bold = val == "1" || val == "true",
             "i" => font.italic = val == "1" || val == "true",
+            "u" => font.underline = val != "none",
+            "strike" => font.strikethrough = val != "noStrike",
             _ => {}
         }
     }
@@ -515,9 +1285,156 @@ fn guess_mime(path: &str) -> &str {
         "image/svg+xml"
     } else if path.ends_with(".webp") {
         "image/webp"
-    } else if path.ends_with(".emf") || path.ends_with(".wmf") {
-        "image/png" // Fallback - these won't render properly
+    } else if path.ends_with(".emf") {
+        "image/x-emf"
+    } else if path.ends_with(".wmf") {
+        "image/x-wmf"
     } else {
         "image/png"
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::testing::{png_header, TempFile};
+    use crate::model::image::ImageData;
+    use crate::model::slide::Slide;
+
+    /// Read a presentation of one slide with the content `slide_xml`.
+    fn single_slide(name: &str, slide_xml: &str) -> Document {
+        let file = TempFile::with_zip(
+            name,
+            &[
+                (
+                    "ppt/presentation.xml",
+                    b"<p:presentation><p:sldIdLst><p:sldId r:id=\"rId2\"/></p:sldIdLst>\
+                      </p:presentation>",
+                ),
+                (
+                    "ppt/_rels/presentation.xml.rels",
+                    b"<Relationships><Relationship Id=\"rId2\" Target=\"slides/slide1.xml\"/>\
+                      </Relationships>",
+                ),
+                ("ppt/slides/slide1.xml", slide_xml.as_bytes()),
+            ],
+        );
+        let (doc, warnings) = load_document(file.path()).unwrap();
+        assert!(warnings.is_empty(), "{:?}", warnings);
+        doc
+    }
+
+    fn shapes(slide: &Slide) -> Vec<&ShapeElement> {
+        slide
+            .elements
+            .iter()
+            .filter_map(|e| match e {
+                SlideElement::Shape(shape) => Some(shape),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn truncated_xml_is_reported() {
+        let file = TempFile::with_zip(
+            "truncated.pptx",
+            &[(
+                "ppt/presentation.xml",
+                b"<p:presentation><p:sldSz cx=\"9144000\" cy=\"5143500\"/><p:sldIdLst><p:sldId",
+            )],
+        );
+        let (doc, warnings) = load_document(file.path()).unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].part, "ppt/presentation.xml");
+        assert_eq!(doc.slides.len(), 1);
+    }
+
+    #[test]
+    fn rotated_picture_keeps_its_transform_and_crop() {
+        let picture = png_header(40, 30);
+        let file = TempFile::with_zip(
+            "picture.pptx",
+            &[
+                (
+                    "ppt/presentation.xml",
+                    b"<p:presentation><p:sldIdLst><p:sldId id=\"256\" r:id=\"rId2\"/></p:sldIdLst>\
+                      <p:sldSz cx=\"12192000\" cy=\"6858000\"/></p:presentation>",
+                ),
+                (
+                    "ppt/_rels/presentation.xml.rels",
+                    b"<Relationships><Relationship Id=\"rId2\" Target=\"slides/slide1.xml\"/>\
+                      </Relationships>",
+                ),
+                (
+                    "ppt/slides/_rels/slide1.xml.rels",
+                    b"<Relationships><Relationship Id=\"rId1\" Target=\"../media/image1.png\"/>\
+                      </Relationships>",
+                ),
+                (
+                    "ppt/slides/slide1.xml",
+                    b"<p:sld><p:cSld><p:spTree><p:pic><p:nvPicPr><p:cNvPr id=\"2\" name=\"Photo\"/>\
+                      </p:nvPicPr><p:blipFill><a:blip r:embed=\"rId1\"/>\
+                      <a:srcRect l=\"10000\" t=\"20000\"/><a:stretch/></p:blipFill>\
+                      <p:spPr><a:xfrm rot=\"1800000\" flipH=\"1\">\
+                      <a:off x=\"1270000\" y=\"635000\"/><a:ext cx=\"2540000\" cy=\"1270000\"/>\
+                      </a:xfrm></p:spPr></p:pic>\
+                      </p:spTree></p:cSld></p:sld>",
+                ),
+                ("ppt/media/image1.png", &picture),
+            ],
+        );
+
+        let (doc, warnings) = load_document(file.path()).unwrap();
+        assert!(warnings.is_empty(), "{:?}", warnings);
+        let SlideElement::Image(img) = &doc.slides[0].elements[0] else {
+            panic!("expected a picture, got {:?}", doc.slides[0].elements);
+        };
+        assert_eq!(img.rotation, 30.0);
+        assert_eq!((img.flip_h, img.flip_v), (true, false));
+        assert_eq!(img.scale_mode, ScaleMode::Stretch);
+        assert_eq!(img.bounds, Rect::new(100.0, 50.0, 200.0, 100.0));
+        assert_eq!((img.crop.left, img.crop.top, img.crop.right), (0.1, 0.2, 0.0));
+        let ImageData::Embedded { data, mime } = &img.image_data;
+        assert_eq!((&data[..], mime.as_str()), (&picture[..], "image/png"));
+    }
+
+    #[test]
+    fn shapes_without_text_keep_their_fill_and_outline() {
+        let slide_xml = "<p:sld><p:cSld><p:spTree>\
+            <p:sp><p:spPr><a:xfrm><a:off x=\"0\" y=\"0\"/><a:ext cx=\"127000\" cy=\"127000\"/>\
+            </a:xfrm><a:prstGeom prst=\"rect\"/><a:solidFill><a:srgbClr val=\"FF0000\"/>\
+            </a:solidFill><a:ln><a:noFill/></a:ln></p:spPr></p:sp>\
+            <p:sp><p:spPr><a:prstGeom prst=\"ellipse\"/><a:ln w=\"38100\"><a:solidFill>\
+            <a:srgbClr val=\"0000FF\"/></a:solidFill></a:ln></p:spPr>\
+            <p:txBody><a:p><a:r><a:t> </a:t></a:r></a:p></p:txBody></p:sp>\
+            </p:spTree></p:cSld></p:sld>";
+        let doc = single_slide("shapes.pptx", slide_xml);
+        let slide = &doc.slides[0];
+        let shapes = shapes(slide);
+        assert_eq!(shapes.len(), 2, "{:?}", slide.elements);
+        assert_eq!(shapes[0].shape_type, ShapeType::Rectangle);
+        assert_eq!(shapes[0].fill, Some(FillStyle::new(Color::rgb(1.0, 0.0, 0.0))));
+        assert_eq!(shapes[0].stroke, None);
+        // Blank text leaves just the shape
+        assert_eq!(shapes[1].shape_type, ShapeType::Ellipse);
+        assert_eq!(shapes[1].fill, None);
+        assert_eq!(shapes[1].stroke, Some(StrokeStyle::new(Color::rgb(0.0, 0.0, 1.0), 3.0)));
+    }
+
+    #[test]
+    fn shadow_color_is_not_taken_as_the_fill() {
+        let slide_xml = "<p:sld><p:cSld><p:spTree>\
+            <p:sp><p:spPr><a:xfrm><a:off x=\"0\" y=\"0\"/><a:ext cx=\"127000\" cy=\"127000\"/>\
+            </a:xfrm><a:prstGeom prst=\"rect\"/><a:noFill/><a:ln w=\"12700\"><a:solidFill>\
+            <a:srgbClr val=\"00FF00\"/></a:solidFill></a:ln><a:effectLst><a:outerShdw \
+            blurRad=\"40000\" dist=\"23000\"><a:srgbClr val=\"000000\"><a:alpha val=\"35000\"/>\
+            </a:srgbClr></a:outerShdw></a:effectLst></p:spPr></p:sp>\
+            </p:spTree></p:cSld></p:sld>";
+        let doc = single_slide("shadow.pptx", slide_xml);
+        let shapes = shapes(&doc.slides[0]);
+        assert_eq!(shapes.len(), 1, "{:?}", doc.slides[0].elements);
+        assert_eq!(shapes[0].fill, None);
+        assert_eq!(shapes[0].stroke, Some(StrokeStyle::new(Color::rgb(0.0, 1.0, 0.0), 1.0)));
+    }
+}
@@ -0,0 +1,828 @@
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::Arc;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+use crate::model::document::Document;
+use crate::model::element::SlideElement;
+use crate::model::geometry::{Rect, SlideSizePreset};
+use crate::model::image::{ImageData, ImageElement};
+use crate::model::shape::{ShapeElement, ShapeType};
+use crate::model::slide::{Background, Gradient, GradientKind, Slide};
+use crate::model::style::{Color, FillStyle, LineCap, LineJoin, StrokeStyle};
+use crate::model::text::{
+    bullet_for_level, ListStyle, TextAlignment, TextElement, TextParagraph, TextRun,
+    VerticalAlignment,
+};
+
+use super::constants::*;
+
+/// Notes page size PowerPoint writes by default, 7.5 x 10 inches
+const NOTES_WIDTH_EMU: i64 = 6_858_000;
+const NOTES_HEIGHT_EMU: i64 = 9_144_000;
+
+/// The first id of the master and layout id lists; lower ids are reserved
+const FIRST_MASTER_ID: u32 = 2_147_483_648;
+/// The first id of the slide id list
+const FIRST_SLIDE_ID: u32 = 256;
+
+const XML_HEADER: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n";
+
+/// A picture stored in `ppt/media`
+struct Media {
+    file_name: String,
+    mime: String,
+    data: Arc<[u8]>,
+}
+
+/// Write `doc` as a PowerPoint package. Every slide uses a single blank
+/// layout, so all content is written onto the slides themselves.
+pub fn save_document(doc: &Document, path: &Path) -> io::Result<()> {
+    let file = std::fs::File::create(path)?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+
+    let mut media = Vec::new();
+    let mut slides = Vec::new();
+    for slide in &doc.slides {
+        slides.push(build_slide(slide, &mut media));
+    }
+
+    let mut write_part = |name: &str, content: &[u8]| -> io::Result<()> {
+        zip.start_file(name, options)?;
+        zip.write_all(content)
+    };
+
+    write_part("[Content_Types].xml", build_content_types(doc, &media).as_bytes())?;
+    write_part("_rels/.rels", build_package_rels().as_bytes())?;
+    write_part("ppt/presentation.xml", build_presentation(doc).as_bytes())?;
+    write_part(
+        "ppt/_rels/presentation.xml.rels",
+        build_presentation_rels(doc).as_bytes(),
+    )?;
+    write_part("ppt/slideMasters/slideMaster1.xml", build_slide_master().as_bytes())?;
+    write_part(
+        "ppt/slideMasters/_rels/slideMaster1.xml.rels",
+        build_rels(&[
+            (REL_SLIDE_LAYOUT, "../slideLayouts/slideLayout1.xml"),
+            (REL_THEME, "../theme/theme1.xml"),
+        ])
+        .as_bytes(),
+    )?;
+    write_part("ppt/slideLayouts/slideLayout1.xml", build_slide_layout().as_bytes())?;
+    write_part(
+        "ppt/slideLayouts/_rels/slideLayout1.xml.rels",
+        build_rels(&[(REL_SLIDE_MASTER, "../slideMasters/slideMaster1.xml")]).as_bytes(),
+    )?;
+    write_part("ppt/theme/theme1.xml", build_theme().as_bytes())?;
+
+    for (slide_idx, (xml, images)) in slides.iter().enumerate() {
+        let slide_name = format!("slide{}.xml", slide_idx + 1);
+        write_part(&format!("ppt/slides/{}", slide_name), xml.as_bytes())?;
+
+        // The layout is rId1, pictures follow in the order they were added
+        let targets: Vec<String> = images
+            .iter()
+            .map(|&i| format!("../media/{}", media[i].file_name))
+            .collect();
+        let mut rels = vec![(REL_SLIDE_LAYOUT, "../slideLayouts/slideLayout1.xml")];
+        rels.extend(targets.iter().map(|target| (REL_IMAGE, target.as_str())));
+        write_part(
+            &format!("ppt/slides/_rels/{}.rels", slide_name),
+            build_rels(&rels).as_bytes(),
+        )?;
+    }
+
+    for item in &media {
+        write_part(&format!("ppt/media/{}", item.file_name), &item.data)?;
+    }
+
+    zip.finish()?;
+    Ok(())
+}
+
+fn build_content_types(doc: &Document, media: &[Media]) -> String {
+    let mut xml = String::from(XML_HEADER);
+    xml.push_str(&format!("<Types xmlns=\"{}\">\n", NS_CONTENT_TYPES));
+    xml.push_str(&format!(
+        "  <Default Extension=\"rels\" ContentType=\"{}\"/>\n",
+        CT_RELATIONSHIPS
+    ));
+    xml.push_str("  <Default Extension=\"xml\" ContentType=\"application/xml\"/>\n");
+
+    // One default per picture extension
+    let mut extensions: Vec<(&str, &str)> = Vec::new();
+    for item in media {
+        let ext = item.file_name.rsplit('.').next().unwrap_or_default();
+        if !extensions.iter().any(|(e, _)| *e == ext) {
+            extensions.push((ext, &item.mime));
+        }
+    }
+    for (ext, mime) in extensions {
+        xml.push_str(&format!(
+            "  <Default Extension=\"{}\" ContentType=\"{}\"/>\n",
+            ext,
+            xml_escape(mime)
+        ));
+    }
+
+    let mut overrides = vec![
+        ("/ppt/presentation.xml".to_string(), CT_PRESENTATION),
+        ("/ppt/slideMasters/slideMaster1.xml".to_string(), CT_SLIDE_MASTER),
+        ("/ppt/slideLayouts/slideLayout1.xml".to_string(), CT_SLIDE_LAYOUT),
+        ("/ppt/theme/theme1.xml".to_string(), CT_THEME),
+    ];
+    for slide_idx in 0..doc.slides.len() {
+        overrides.push((format!("/ppt/slides/slide{}.xml", slide_idx + 1), CT_SLIDE));
+    }
+    for (part, content_type) in overrides {
+        xml.push_str(&format!(
+            "  <Override PartName=\"{}\" ContentType=\"{}\"/>\n",
+            part, content_type
+        ));
+    }
+
+    xml.push_str("</Types>\n");
+    xml
+}
+
+fn build_package_rels() -> String {
+    build_rels(&[(REL_OFFICE_DOCUMENT, "ppt/presentation.xml")])
+}
+
+/// A relationships part whose ids are rId1, rId2, ... in the order given.
+fn build_rels(rels: &[(&str, &str)]) -> String {
+    let mut xml = String::from(XML_HEADER);
+    xml.push_str(&format!(
+        "<Relationships xmlns=\"{}\">\n",
+        NS_PACKAGE_RELATIONSHIPS
+    ));
+    for (i, (rel_type, target)) in rels.iter().enumerate() {
+        xml.push_str(&format!(
+            "  <Relationship Id=\"rId{}\" Type=\"{}/{}\" Target=\"{}\"/>\n",
+            i + 1,
+            NS_RELATIONSHIPS,
+            rel_type,
+            xml_escape(target)
+        ));
+    }
+    xml.push_str("</Relationships>\n");
+    xml
+}
+
+fn build_presentation(doc: &Document) -> String {
+    let mut xml = String::from(XML_HEADER);
+    xml.push_str(&format!(
+        "<p:presentation xmlns:a=\"{}\" xmlns:r=\"{}\" xmlns:p=\"{}\" saveSubsetFonts=\"1\">\n",
+        NS_DRAWINGML, NS_RELATIONSHIPS, NS_PRESENTATIONML
+    ));
+    xml.push_str("  <p:sldMasterIdLst>\n");
+    xml.push_str(&format!(
+        "    <p:sldMasterId id=\"{}\" r:id=\"rId1\"/>\n",
+        FIRST_MASTER_ID
+    ));
+    xml.push_str("  </p:sldMasterIdLst>\n");
+
+    // Slides are rId2.. in presentation.xml.rels, after the master
+    xml.push_str("  <p:sldIdLst>\n");
+    for slide_idx in 0..doc.slides.len() {
+        xml.push_str(&format!(
+            "    <p:sldId id=\"{}\" r:id=\"rId{}\"/>\n",
+            FIRST_SLIDE_ID + slide_idx as u32,
+            slide_idx + 2
+        ));
+    }
+    xml.push_str("  </p:sldIdLst>\n");
+
+    // Standard sizes are written from their exact EMU values
+    let (width, height) = match SlideSizePreset::find(&doc.slide_size) {
+        Some(preset) => (preset.width_emu, preset.height_emu),
+        None => (
+            pt_to_emu(doc.slide_size.width),
+            pt_to_emu(doc.slide_size.height),
+        ),
+    };
+    xml.push_str(&format!("  <p:sldSz cx=\"{}\" cy=\"{}\"/>\n", width, height));
+    xml.push_str(&format!(
+        "  <p:notesSz cx=\"{}\" cy=\"{}\"/>\n",
+        NOTES_WIDTH_EMU, NOTES_HEIGHT_EMU
+    ));
+    xml.push_str("</p:presentation>\n");
+    xml
+}
+
+fn build_presentation_rels(doc: &Document) -> String {
+    let slide_targets: Vec<String> = (0..doc.slides.len())
+        .map(|slide_idx| format!("slides/slide{}.xml", slide_idx + 1))
+        .collect();
+    let mut rels = vec![(REL_SLIDE_MASTER, "slideMasters/slideMaster1.xml")];
+    rels.extend(slide_targets.iter().map(|target| (REL_SLIDE, target.as_str())));
+    rels.push((REL_THEME, "theme/theme1.xml"));
+    build_rels(&rels)
+}
+
+fn build_slide_master() -> String {
+    let mut xml = String::from(XML_HEADER);
+    xml.push_str(&format!(
+        "<p:sldMaster xmlns:a=\"{}\" xmlns:r=\"{}\" xmlns:p=\"{}\">\n",
+        NS_DRAWINGML, NS_RELATIONSHIPS, NS_PRESENTATIONML
+    ));
+    xml.push_str("  <p:cSld>\n");
+    xml.push_str("    <p:bg><p:bgRef idx=\"1001\"><a:schemeClr val=\"bg1\"/></p:bgRef></p:bg>\n");
+    xml.push_str(&empty_shape_tree());
+    xml.push_str("  </p:cSld>\n");
+    xml.push_str("  <p:clrMap bg1=\"lt1\" tx1=\"dk1\" bg2=\"lt2\" tx2=\"dk2\" accent1=\"accent1\" accent2=\"accent2\" accent3=\"accent3\" accent4=\"accent4\" accent5=\"accent5\" accent6=\"accent6\" hlink=\"hlink\" folHlink=\"folHlink\"/>\n");
+    xml.push_str("  <p:sldLayoutIdLst>\n");
+    xml.push_str(&format!(
+        "    <p:sldLayoutId id=\"{}\" r:id=\"rId1\"/>\n",
+        FIRST_MASTER_ID + 1
+    ));
+    xml.push_str("  </p:sldLayoutIdLst>\n");
+    xml.push_str("</p:sldMaster>\n");
+    xml
+}
+
+fn build_slide_layout() -> String {
+    let mut xml = String::from(XML_HEADER);
+    xml.push_str(&format!(
+        "<p:sldLayout xmlns:a=\"{}\" xmlns:r=\"{}\" xmlns:p=\"{}\" type=\"blank\" preserve=\"1\">\n",
+        NS_DRAWINGML, NS_RELATIONSHIPS, NS_PRESENTATIONML
+    ));
+    xml.push_str("  <p:cSld name=\"Blank\">\n");
+    xml.push_str(&empty_shape_tree());
+    xml.push_str("  </p:cSld>\n");
+    xml.push_str("  <p:clrMapOvr><a:masterClrMapping/></p:clrMapOvr>\n");
+    xml.push_str("</p:sldLayout>\n");
+    xml
+}
+
+/// The group properties every `<p:spTree>` starts with
+const SHAPE_TREE_HEADER: &str = "      <p:nvGrpSpPr><p:cNvPr id=\"1\" name=\"\"/><p:cNvGrpSpPr/><p:nvPr/></p:nvGrpSpPr>\n      <p:grpSpPr/>\n";
+
+fn empty_shape_tree() -> String {
+    format!("    <p:spTree>\n{}    </p:spTree>\n", SHAPE_TREE_HEADER)
+}
+
+/// A theme with the Office colors and fonts. PowerPoint refuses packages
+/// without one, even though slides only use explicit colors.
+fn build_theme() -> String {
+    let mut xml = String::from(XML_HEADER);
+    xml.push_str(&format!(
+        "<a:theme xmlns:a=\"{}\" name=\"Lumina\">\n",
+        NS_DRAWINGML
+    ));
+    xml.push_str("  <a:themeElements>\n");
+    xml.push_str("    <a:clrScheme name=\"Lumina\">\n");
+    xml.push_str("      <a:dk1><a:sysClr val=\"windowText\" lastClr=\"000000\"/></a:dk1>\n");
+    xml.push_str("      <a:lt1><a:sysClr val=\"window\" lastClr=\"FFFFFF\"/></a:lt1>\n");
+    for (name, hex) in [
+        ("dk2", "44546A"),
+        ("lt2", "E7E6E6"),
+        ("accent1", "4A86CF"),
+        ("accent2", "ED7D31"),
+        ("accent3", "A5A5A5"),
+        ("accent4", "FFC000"),
+        ("accent5", "5B9BD5"),
+        ("accent6", "70AD47"),
+        ("hlink", "0563C1"),
+        ("folHlink", "954F72"),
+    ] {
+        xml.push_str(&format!(
+            "      <a:{0}><a:srgbClr val=\"{1}\"/></a:{0}>\n",
+            name, hex
+        ));
+    }
+    xml.push_str("    </a:clrScheme>\n");
+    xml.push_str("    <a:fontScheme name=\"Lumina\">\n");
+    for kind in ["majorFont", "minorFont"] {
+        xml.push_str(&format!(
+            "      <a:{0}><a:latin typeface=\"Calibri\"/><a:ea typeface=\"\"/><a:cs typeface=\"\"/></a:{0}>\n",
+            kind
+        ));
+    }
+    xml.push_str("    </a:fontScheme>\n");
+    xml.push_str("    <a:fmtScheme name=\"Lumina\">\n");
+    xml.push_str("      <a:fillStyleLst>\n");
+    for _ in 0..3 {
+        xml.push_str("        <a:solidFill><a:schemeClr val=\"phClr\"/></a:solidFill>\n");
+    }
+    xml.push_str("      </a:fillStyleLst>\n");
+    xml.push_str("      <a:lnStyleLst>\n");
+    for width in [6350, 12700, 19050] {
+        xml.push_str(&format!(
+            "        <a:ln w=\"{}\"><a:solidFill><a:schemeClr val=\"phClr\"/></a:solidFill></a:ln>\n",
+            width
+        ));
+    }
+    xml.push_str("      </a:lnStyleLst>\n");
+    xml.push_str("      <a:effectStyleLst>\n");
+    for _ in 0..3 {
+        xml.push_str("        <a:effectStyle><a:effectLst/></a:effectStyle>\n");
+    }
+    xml.push_str("      </a:effectStyleLst>\n");
+    xml.push_str("      <a:bgFillStyleLst>\n");
+    for _ in 0..3 {
+        xml.push_str("        <a:solidFill><a:schemeClr val=\"phClr\"/></a:solidFill>\n");
+    }
+    xml.push_str("      </a:bgFillStyleLst>\n");
+    xml.push_str("    </a:fmtScheme>\n");
+    xml.push_str("  </a:themeElements>\n");
+    xml.push_str("</a:theme>\n");
+    xml
+}
+
+/// Slide XML and the indices into `media` of its pictures, which become
+/// rId2, rId3, ... of the slide. A background picture comes first.
+fn build_slide(slide: &Slide, media: &mut Vec<Media>) -> (String, Vec<usize>) {
+    let mut xml = String::from(XML_HEADER);
+    let mut images = Vec::new();
+
+    xml.push_str(&format!(
+        "<p:sld xmlns:a=\"{}\" xmlns:r=\"{}\" xmlns:p=\"{}\">\n",
+        NS_DRAWINGML, NS_RELATIONSHIPS, NS_PRESENTATIONML
+    ));
+    xml.push_str("  <p:cSld>\n");
+    xml.push_str(&format!(
+        "    <p:bg><p:bgPr>{}<a:effectLst/></p:bgPr></p:bg>\n",
+        background_fill(&slide.background, media, &mut images)
+    ));
+    xml.push_str("    <p:spTree>\n");
+    xml.push_str(SHAPE_TREE_HEADER);
+
+    // Id 1 is the shape tree itself
+    for (i, element) in slide.elements.iter().enumerate() {
+        let shape_id = i + 2;
+        match element {
+            SlideElement::Shape(shape) => xml.push_str(&build_shape(shape, shape_id)),
+            SlideElement::Text(text) => xml.push_str(&build_text(text, shape_id)),
+            SlideElement::Image(img) => {
+                let rel_id = add_media(&img.image_data, media, &mut images);
+                xml.push_str(&build_picture(img, shape_id, rel_id));
+            }
+        }
+    }
+
+    xml.push_str("    </p:spTree>\n");
+    xml.push_str("  </p:cSld>\n");
+    xml.push_str("  <p:clrMapOvr><a:masterClrMapping/></p:clrMapOvr>\n");
+    xml.push_str("</p:sld>\n");
+    (xml, images)
+}
+
+/// Store a picture of the slide in `media` and return its relationship id.
+fn add_media(image_data: &ImageData, media: &mut Vec<Media>, images: &mut Vec<usize>) -> usize {
+    let ImageData::Embedded { data, mime } = image_data;
+    media.push(Media {
+        file_name: format!("image{}.{}", media.len() + 1, mime_to_ext(mime)),
+        mime: mime.clone(),
+        data: data.clone(),
+    });
+    images.push(media.len() - 1);
+    images.len() + 1
+}
+
+fn build_shape(shape: &ShapeElement, shape_id: usize) -> String {
+    let mut xml = String::from("      <p:sp>\n");
+    xml.push_str(&format!(
+        "        <p:nvSpPr>{}<p:cNvSpPr/><p:nvPr/></p:nvSpPr>\n",
+        non_visual_properties(shape_id, &shape.name, "Shape", shape.visible, "")
+    ));
+    xml.push_str("        <p:spPr>");
+    xml.push_str(&transform(&shape.bounds, shape.rotation, shape.flip_h, shape.flip_v));
+    xml.push_str(&preset_geometry(shape));
+    match (&shape.fill, shape.shape_type) {
+        (Some(fill), shape_type) if shape_type != ShapeType::Line => {
+            xml.push_str(&fill_properties(fill))
+        }
+        _ => xml.push_str("<a:noFill/>"),
+    }
+    xml.push_str(&line_properties(shape.stroke.as_ref()));
+    xml.push_str("</p:spPr>\n");
+    xml.push_str("      </p:sp>\n");
+    xml
+}
+
+/// `<a:prstGeom>` of a shape. The empty form is used unless the preset
+/// needs an adjustment.
+fn preset_geometry(shape: &ShapeElement) -> String {
+    let prst = match shape.shape_type {
+        ShapeType::Rectangle => "rect".to_string(),
+        ShapeType::Ellipse => "ellipse".to_string(),
+        ShapeType::Line => "line".to_string(),
+        ShapeType::Triangle => "triangle".to_string(),
+        ShapeType::Arrow => "rightArrow".to_string(),
+        // Only some star sizes are presets, so use the closest one
+        ShapeType::Star { points } => {
+            let closest = PRESET_STAR_POINTS
+                .iter()
+                .min_by_key(|p| p.abs_diff(points))
+                .unwrap_or(&5);
+            format!("star{}", closest)
+        }
+        ShapeType::RoundedRectangle { radius } => {
+            // Radius relative to the shorter side in 100000ths, at most half
+            let shorter = shape.bounds.size.width.min(shape.bounds.size.height);
+            let adj = if shorter > 0.0 {
+                (radius / shorter * 100_000.0).clamp(0.0, 50_000.0).round() as i64
+            } else {
+                0
+            };
+            return format!(
+                "<a:prstGeom prst=\"roundRect\"><a:avLst><a:gd name=\"adj\" fmla=\"val {}\"/></a:avLst></a:prstGeom>",
+                adj
+            );
+        }
+    };
+    format!("<a:prstGeom prst=\"{}\"/>", prst)
+}
+
+fn build_text(text: &TextElement, shape_id: usize) -> String {
+    let mut xml = String::from("      <p:sp>\n");
+    xml.push_str(&format!(
+        "        <p:nvSpPr>{}<p:cNvSpPr txBox=\"1\"/><p:nvPr/></p:nvSpPr>\n",
+        non_visual_properties(shape_id, &text.name, "Text", text.visible, "")
+    ));
+    xml.push_str("        <p:spPr>");
+    xml.push_str(&transform(&text.bounds, text.rotation, false, false));
+    xml.push_str("<a:prstGeom prst=\"rect\"/>");
+    match &text.fill {
+        Some(fill) => xml.push_str(&fill_properties(fill)),
+        None => xml.push_str("<a:noFill/>"),
+    }
+    xml.push_str("</p:spPr>\n");
+
+    xml.push_str("        <p:txBody>\n");
+    xml.push_str(&format!(
+        "          <a:bodyPr wrap=\"square\" lIns=\"{}\" tIns=\"{}\" rIns=\"{}\" bIns=\"{}\" anchor=\"{}\">{}</a:bodyPr>\n",
+        pt_to_emu(text.inset.left),
+        pt_to_emu(text.inset.top),
+        pt_to_emu(text.inset.right),
+        pt_to_emu(text.inset.bottom),
+        match text.vertical_alignment {
+            VerticalAlignment::Top => "t",
+            VerticalAlignment::Middle => "ctr",
+            VerticalAlignment::Bottom => "b",
+        },
+        if text.auto_fit { "<a:normAutofit/>" } else { "<a:noAutofit/>" }
+    ));
+    xml.push_str("          <a:lstStyle/>\n");
+    for para in &text.paragraphs {
+        xml.push_str(&build_paragraph(text, para));
+    }
+    xml.push_str("        </p:txBody>\n");
+    xml.push_str("      </p:sp>\n");
+    xml
+}
+
+fn build_paragraph(text: &TextElement, para: &TextParagraph) -> String {
+    let align = match text.alignment {
+        TextAlignment::Left => "l",
+        TextAlignment::Center => "ctr",
+        TextAlignment::Right => "r",
+    };
+    let mut xml = format!("          <a:p><a:pPr lvl=\"{}\" algn=\"{}\">", para.level, align);
+    match para.list {
+        ListStyle::Numbered => xml.push_str("<a:buAutoNum type=\"arabicPeriod\"/>"),
+        ListStyle::Bullet => xml.push_str(&format!(
+            "<a:buChar char=\"{}\"/>",
+            bullet_for_level(para.level)
+        )),
+        ListStyle::None => xml.push_str("<a:buNone/>"),
+    }
+    if !para.tab_stops.is_empty() {
+        xml.push_str("<a:tabLst>");
+        for stop in &para.tab_stops {
+            xml.push_str(&format!("<a:tab pos=\"{}\" algn=\"l\"/>", pt_to_emu(*stop)));
+        }
+        xml.push_str("</a:tabLst>");
+    }
+    xml.push_str("</a:pPr>");
+
+    // Line breaks within a run become `<a:br>`, which carries the run
+    // properties too
+    for run in &para.runs {
+        let properties = run_properties(text, run);
+        for (i, line) in run.text.split('\n').enumerate() {
+            if i > 0 {
+                xml.push_str(&format!("<a:br>{}</a:br>", properties));
+            }
+            if !line.is_empty() {
+                xml.push_str(&format!(
+                    "<a:r>{}<a:t>{}</a:t></a:r>",
+                    properties,
+                    xml_escape(line)
+                ));
+            }
+        }
+    }
+    xml.push_str("</a:p>\n");
+    xml
+}
+
+/// `<a:rPr>` of a run. Outline and shadow belong to the whole box and are
+/// repeated on every run.
+fn run_properties(text: &TextElement, run: &TextRun) -> String {
+    let font = &run.font;
+    let mut xml = format!(
+        "<a:rPr lang=\"en-US\" sz=\"{}\" b=\"{}\" i=\"{}\" u=\"{}\" strike=\"{}\" dirty=\"0\">",
+        (font.size * 100.0).round() as i64,
+        font.bold as u8,
+        font.italic as u8,
+        if font.underline { "sng" } else { "none" },
+        if font.strikethrough { "sngStrike" } else { "noStrike" }
+    );
+    // The line is centred on the glyph edge, so only half lies outside
+    if let Some(outline) = &text.outline {
+        xml.push_str(&format!(
+            "<a:ln w=\"{}\">{}</a:ln>",
+            pt_to_emu(outline.width * 2.0),
+            solid_fill(&outline.color)
+        ));
+    }
+    xml.push_str(&solid_fill(&font.color));
+    // Distance in EMU along a direction in 60000ths of a degree, clockwise
+    // from the positive x axis
+    if let Some(shadow) = &text.shadow {
+        let dist = shadow.offset_x.hypot(shadow.offset_y);
+        let dir = shadow.offset_y.atan2(shadow.offset_x).to_degrees().rem_euclid(360.0);
+        xml.push_str(&format!(
+            "<a:effectLst><a:outerShdw dist=\"{}\" dir=\"{}\" algn=\"ctr\" rotWithShape=\"0\">{}</a:outerShdw></a:effectLst>",
+            pt_to_emu(dist),
+            (dir * 60_000.0).round() as i64,
+            srgb_color(&shadow.color)
+        ));
+    }
+    xml.push_str(&format!(
+        "<a:latin typeface=\"{0}\"/><a:cs typeface=\"{0}\"/>",
+        xml_escape(&font.family)
+    ));
+    xml.push_str("</a:rPr>");
+    xml
+}
+
+/// PowerPoint always stretches a picture to its frame, so the frame is the
+/// element bounds whatever the scale mode.
+fn build_picture(img: &ImageElement, shape_id: usize, rel_id: usize) -> String {
+    let mut xml = String::from("      <p:pic>\n");
+    xml.push_str(&format!(
+        "        <p:nvPicPr>{}<p:cNvPicPr><a:picLocks noChangeAspect=\"1\"/></p:cNvPicPr><p:nvPr/></p:nvPicPr>\n",
+        non_visual_properties(shape_id, &img.name, "Picture", img.visible, &img.alt_text)
+    ));
+    xml.push_str(&format!("        <p:blipFill><a:blip r:embed=\"rId{}\"/>", rel_id));
+    // Crop per edge in 1000ths of a percent
+    if !img.crop.is_empty() {
+        let edge = |v: f64| (v * 100_000.0).round() as i64;
+        xml.push_str(&format!(
+            "<a:srcRect l=\"{}\" t=\"{}\" r=\"{}\" b=\"{}\"/>",
+            edge(img.crop.left),
+            edge(img.crop.top),
+            edge(img.crop.right),
+            edge(img.crop.bottom)
+        ));
+    }
+    xml.push_str("<a:stretch><a:fillRect/></a:stretch></p:blipFill>\n");
+    xml.push_str("        <p:spPr>");
+    xml.push_str(&transform(&img.bounds, img.rotation, img.flip_h, img.flip_v));
+    xml.push_str("<a:prstGeom prst=\"rect\"/></p:spPr>\n");
+    xml.push_str("      </p:pic>\n");
+    xml
+}
+
+/// `<p:cNvPr>` of an element. Unnamed elements get a generated name, which
+/// PowerPoint requires.
+fn non_visual_properties(
+    shape_id: usize,
+    name: &str,
+    fallback_name: &str,
+    visible: bool,
+    description: &str,
+) -> String {
+    let name = if name.is_empty() {
+        format!("{} {}", fallback_name, shape_id - 1)
+    } else {
+        name.to_string()
+    };
+    let mut xml = format!("<p:cNvPr id=\"{}\" name=\"{}\"", shape_id, xml_escape(&name));
+    if !description.is_empty() {
+        xml.push_str(&format!(" descr=\"{}\"", xml_escape(description)));
+    }
+    if !visible {
+        xml.push_str(" hidden=\"1\"");
+    }
+    xml.push_str("/>");
+    xml
+}
+
+/// `<a:xfrm>` placing an element. The rotation is stored in 60000ths of a
+/// degree, clockwise.
+fn transform(bounds: &Rect, rotation: f64, flip_h: bool, flip_v: bool) -> String {
+    let mut xml = String::from("<a:xfrm");
+    let rot = (rotation.rem_euclid(360.0) * 60_000.0).round() as i64;
+    if rot != 0 {
+        xml.push_str(&format!(" rot=\"{}\"", rot));
+    }
+    if flip_h {
+        xml.push_str(" flipH=\"1\"");
+    }
+    if flip_v {
+        xml.push_str(" flipV=\"1\"");
+    }
+    xml.push_str(&format!(
+        "><a:off x=\"{}\" y=\"{}\"/><a:ext cx=\"{}\" cy=\"{}\"/></a:xfrm>",
+        pt_to_emu(bounds.origin.x),
+        pt_to_emu(bounds.origin.y),
+        pt_to_emu(bounds.size.width),
+        pt_to_emu(bounds.size.height)
+    ));
+    xml
+}
+
+fn line_properties(stroke: Option<&StrokeStyle>) -> String {
+    let Some(stroke) = stroke else {
+        return "<a:ln><a:noFill/></a:ln>".to_string();
+    };
+    let cap = match stroke.line_cap {
+        LineCap::Butt => "flat",
+        LineCap::Round => "rnd",
+        LineCap::Square => "sq",
+    };
+    let join = match stroke.line_join {
+        LineJoin::Miter => "<a:miter lim=\"800000\"/>",
+        LineJoin::Round => "<a:round/>",
+        LineJoin::Bevel => "<a:bevel/>",
+    };
+    format!(
+        "<a:ln w=\"{}\" cap=\"{}\">{}{}</a:ln>",
+        pt_to_emu(stroke.width),
+        cap,
+        solid_fill(&stroke.color),
+        join
+    )
+}
+
+fn background_fill(
+    background: &Background,
+    media: &mut Vec<Media>,
+    images: &mut Vec<usize>,
+) -> String {
+    match background {
+        Background::Solid(color) => solid_fill(color),
+        Background::Gradient(gradient) => gradient_fill(gradient),
+        Background::Image(image_data) => format!(
+            "<a:blipFill><a:blip r:embed=\"rId{}\"/><a:stretch><a:fillRect/></a:stretch></a:blipFill>",
+            add_media(image_data, media, images)
+        ),
+    }
+}
+
+fn fill_properties(fill: &FillStyle) -> String {
+    match &fill.gradient {
+        Some(gradient) => gradient_fill(gradient),
+        None => solid_fill(&fill.color),
+    }
+}
+
+fn gradient_fill(gradient: &Gradient) -> String {
+    let shade = match gradient.kind {
+        // Angle in 60000ths of a degree, clockwise from left-to-right
+        GradientKind::Linear => format!(
+            "<a:lin ang=\"{}\" scaled=\"0\"/>",
+            (gradient.angle.rem_euclid(360.0) * 60_000.0).round() as i64
+        ),
+        GradientKind::Radial => "<a:path path=\"circle\"><a:fillToRect l=\"50000\" t=\"50000\" r=\"50000\" b=\"50000\"/></a:path>".to_string(),
+    };
+    format!(
+        "<a:gradFill rotWithShape=\"1\"><a:gsLst><a:gs pos=\"0\">{}</a:gs><a:gs pos=\"100000\">{}</a:gs></a:gsLst>{}</a:gradFill>",
+        srgb_color(&gradient.start),
+        srgb_color(&gradient.end),
+        shade
+    )
+}
+
+fn solid_fill(color: &Color) -> String {
+    format!("<a:solidFill>{}</a:solidFill>", srgb_color(color))
+}
+
+/// `<a:srgbClr>` of `color`, with its opacity in 1000ths of a percent
+/// unless it is opaque.
+fn srgb_color(color: &Color) -> String {
+    let hex = format!(
+        "{:02X}{:02X}{:02X}",
+        (color.r * 255.0).round() as u8,
+        (color.g * 255.0).round() as u8,
+        (color.b * 255.0).round() as u8
+    );
+    if color.a >= 1.0 {
+        format!("<a:srgbClr val=\"{}\"/>", hex)
+    } else {
+        format!(
+            "<a:srgbClr val=\"{}\"><a:alpha val=\"{}\"/></a:srgbClr>",
+            hex,
+            (color.a * 100_000.0).round() as i64
+        )
+    }
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn mime_to_ext(mime: &str) -> &'static str {
+    match mime {
+        "image/png" => "png",
+        "image/jpeg" => "jpg",
+        "image/gif" => "gif",
+        "image/svg+xml" => "svg",
+        "image/webp" => "webp",
+        "image/bmp" => "bmp",
+        "image/x-emf" => "emf",
+        "image/x-wmf" => "wmf",
+        _ => "png",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::pptx::reader::load_document;
+    use crate::format::testing::{png_header, TempFile};
+    use crate::model::geometry::Size;
+    use crate::model::style::FillStyle;
+
+    fn presentation_xml(doc: &Document) -> String {
+        let file = TempFile::new("size.pptx");
+        save_document(doc, file.path()).unwrap();
+        let mut zip = zip::ZipArchive::new(std::fs::File::open(file.path()).unwrap()).unwrap();
+        let mut xml = String::new();
+        io::Read::read_to_string(&mut zip.by_name("ppt/presentation.xml").unwrap(), &mut xml)
+            .unwrap();
+        xml
+    }
+
+    #[test]
+    fn widescreen_document_has_the_canonical_slide_size() {
+        let mut doc = Document::new();
+        // As read back from a file written by another application
+        doc.slide_size = Size::new(959.9953, 540.0024);
+        let xml = presentation_xml(&doc);
+        assert!(xml.contains("<p:sldSz cx=\"12192000\" cy=\"6858000\"/>"), "{}", xml);
+    }
+
+    #[test]
+    fn custom_slide_size_is_converted_to_emu() {
+        let mut doc = Document::new();
+        doc.slide_size = Size::new(600.0, 600.0);
+        let xml = presentation_xml(&doc);
+        assert!(xml.contains("<p:sldSz cx=\"7620000\" cy=\"7620000\"/>"), "{}", xml);
+
+        let file = TempFile::new("square.pptx");
+        save_document(&doc, file.path()).unwrap();
+        let (doc, _) = load_document(file.path()).unwrap();
+        assert_eq!(doc.slide_size, Size::new(600.0, 600.0));
+    }
+
+    #[test]
+    fn elements_keep_their_bounds_when_read_back() {
+        let mut doc = Document::new();
+        let shape_bounds = Rect::new(10.3, 20.7, 150.45, 80.25);
+        let mut shape = ShapeElement::new(shape_bounds, ShapeType::Rectangle);
+        shape.fill = Some(FillStyle::new(Color::rgb(1.0, 0.0, 0.0)));
+        let text_bounds = Rect::new(200.6, 40.15, 300.35, 60.9);
+        let text = TextElement::new(text_bounds, "Fractional");
+        let image_bounds = Rect::new(500.55, 300.45, 120.75, 90.33);
+        let image = ImageElement::new(image_bounds, png_header(4, 3), "image/png".into());
+        doc.slides[0].elements = vec![
+            SlideElement::Shape(shape),
+            SlideElement::Text(text),
+            SlideElement::Image(image),
+        ];
+
+        let file = TempFile::new("bounds.pptx");
+        save_document(&doc, file.path()).unwrap();
+        let (read, _) = load_document(file.path()).unwrap();
+
+        let elements = &read.slides[0].elements;
+        let bounds_of = |matches: fn(&SlideElement) -> bool| {
+            let element = elements.iter().find(|e| matches(e));
+            *element.unwrap_or_else(|| panic!("missing in {:?}", elements)).bounds()
+        };
+        let close = |a: Rect, b: Rect| {
+            (a.origin.x - b.origin.x).abs() <= 1.0
+                && (a.origin.y - b.origin.y).abs() <= 1.0
+                && (a.size.width - b.size.width).abs() <= 1.0
+                && (a.size.height - b.size.height).abs() <= 1.0
+        };
+        let shape = bounds_of(|e| matches!(e, SlideElement::Shape(_)));
+        assert!(close(shape, shape_bounds), "{:?}", shape);
+        let text = bounds_of(|e| matches!(e, SlideElement::Text(_)));
+        assert!(close(text, text_bounds), "{:?}", text);
+        let image = bounds_of(|e| matches!(e, SlideElement::Image(_)));
+        assert!(close(image, image_bounds), "{:?}", image);
+    }
+}
@@ -0,0 +1,54 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// A file in the temporary directory, deleted when dropped.
+pub struct TempFile(PathBuf);
+
+impl TempFile {
+    /// A path ending in `name` that no other test uses.
+    pub fn new(name: &str) -> Self {
+        let unique = format!("lumina-test-{}-{}", uuid::Uuid::new_v4(), name);
+        Self(std::env::temp_dir().join(unique))
+    }
+
+    /// A flat XML document or other text file holding `content`.
+    pub fn with_text(name: &str, content: &str) -> Self {
+        let file = Self::new(name);
+        std::fs::write(file.path(), content).expect("write test file");
+        file
+    }
+
+    /// A ZIP package holding the `(entry name, content)` pairs.
+    pub fn with_zip(name: &str, entries: &[(&str, &[u8])]) -> Self {
+        let file = Self::new(name);
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(file.path()).expect("create"));
+        for (entry, content) in entries {
+            zip.start_file(*entry, zip::write::SimpleFileOptions::default())
+                .expect("start zip entry");
+            zip.write_all(content).expect("write zip entry");
+        }
+        zip.finish().expect("finish zip");
+        file
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+/// The start of a PNG of `width` by `height` pixels, enough to tell its
+/// size but not to decode it.
+pub fn png_header(width: u32, height: u32) -> Vec<u8> {
+    let mut data = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+    data.extend_from_slice(&width.to_be_bytes());
+    data.extend_from_slice(&height.to_be_bytes());
+    // Bit depth, color type, compression, filter, interlace and CRC
+    data.extend_from_slice(&[8, 6, 0, 0, 0, 0, 0, 0, 0]);
+    data
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
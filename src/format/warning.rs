@@ -0,0 +1,64 @@
+use std::fmt;
+
+/// Characters of XML shown on each side of a parse error
+const CONTEXT_CHARS: usize = 30;
+
+/// A malformed XML part that was only read up to the error. The document
+/// is still loaded with everything parsed before that point.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseWarning {
+    /// Archive entry, e.g. "content.xml"
+    pub part: String,
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+    /// XML around the error position
+    pub context: String,
+}
+
+impl ParseWarning {
+    /// Locate the error at byte `position` of `xml`.
+    pub fn new(part: &str, xml: &str, position: u64, error: &quick_xml::Error) -> Self {
+        let mut position = (position as usize).min(xml.len());
+        while !xml.is_char_boundary(position) {
+            position -= 1;
+        }
+
+        let before = &xml[..position];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let column = xml[line_start..position].chars().count() + 1;
+
+        let context_start = before
+            .char_indices()
+            .rev()
+            .nth(CONTEXT_CHARS - 1)
+            .map_or(0, |(i, _)| i);
+        let context_end = xml[position..]
+            .char_indices()
+            .nth(CONTEXT_CHARS)
+            .map_or(xml.len(), |(i, _)| position + i);
+        let context = xml[context_start..context_end]
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        Self {
+            part: part.to_string(),
+            line,
+            column,
+            message: error.to_string(),
+            context,
+        }
+    }
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}: {} (near \"{}\")",
+            self.part, self.line, self.column, self.message, self.context
+        )
+    }
+}
@@ -1,5 +1,6 @@
 mod application;
 mod config;
+mod convert;
 mod format;
 mod i18n;
 mod model;
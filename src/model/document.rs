@@ -1,9 +1,12 @@
 use serde::{Deserialize, Serialize};
 
+use super::element::SlideElement;
 use super::geometry::{Size, DEFAULT_SLIDE_SIZE};
+use super::shape::ShapeType;
 use super::slide::Slide;
+use super::text::TextRun;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DocumentMetadata {
     pub author: String,
     pub created: String,
@@ -20,7 +23,7 @@ impl Default for DocumentMetadata {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Document {
     pub title: String,
     pub slides: Vec<Slide>,
@@ -49,6 +52,13 @@ impl Document {
         idx
     }
 
+    /// Insert a copy of the slide at `index` right after it.
+    pub fn duplicate_slide(&mut self, index: usize) -> Option<usize> {
+        let copy = self.slides.get(index)?.duplicate();
+        self.slides.insert(index + 1, copy);
+        Some(index + 1)
+    }
+
     pub fn remove_slide(&mut self, index: usize) -> Option<Slide> {
         if self.slides.len() > 1 && index < self.slides.len() {
             Some(self.slides.remove(index))
@@ -63,6 +73,48 @@ impl Document {
             self.slides.insert(to, slide);
         }
     }
+
+    /// All font families used by text runs, sorted and without duplicates.
+    pub fn font_families(&self) -> Vec<String> {
+        let mut families: Vec<String> = self
+            .text_runs()
+            .map(|run| run.font.family.clone())
+            .collect();
+        families.sort();
+        families.dedup();
+        families
+    }
+
+    /// Replace a font family throughout the document. Returns the number of
+    /// runs that changed.
+    pub fn replace_font_family(&mut self, from: &str, to: &str) -> usize {
+        let mut count = 0;
+        for slide in &mut self.slides {
+            for element in &mut slide.elements {
+                if let SlideElement::Text(text) = element {
+                    for run in text.paragraphs.iter_mut().flat_map(|p| p.runs.iter_mut()) {
+                        if run.font.family == from {
+                            run.font.family = to.to_string();
+                            count += 1;
+                        }
+                    }
+                }
+            }
+        }
+        count
+    }
+
+    fn text_runs(&self) -> impl Iterator<Item = &TextRun> {
+        self.slides
+            .iter()
+            .flat_map(|slide| slide.elements.iter())
+            .filter_map(|element| match element {
+                SlideElement::Text(text) => Some(text),
+                _ => None,
+            })
+            .flat_map(|text| text.paragraphs.iter())
+            .flat_map(|paragraph| paragraph.runs.iter())
+    }
 }
 
 impl Default for Document {
@@ -70,3 +122,127 @@ impl Default for Document {
         Self::new()
     }
 }
+
+/// Change the slide size. With `keep_layout` the content is scaled by the
+/// smaller of the width and height ratios, so nothing gets distorted, and
+/// centered on the new slide. Otherwise elements keep their coordinates.
+pub fn rescale_document(doc: &mut Document, new_size: Size, keep_layout: bool) {
+    let old_size = doc.slide_size;
+    doc.slide_size = new_size;
+    if !keep_layout || old_size.width <= 0.0 || old_size.height <= 0.0 {
+        return;
+    }
+
+    let scale = (new_size.width / old_size.width).min(new_size.height / old_size.height);
+    let offset_x = (new_size.width - old_size.width * scale) / 2.0;
+    let offset_y = (new_size.height - old_size.height * scale) / 2.0;
+
+    for element in doc.slides.iter_mut().flat_map(|s| s.elements.iter_mut()) {
+        let bounds = element.bounds_mut();
+        bounds.origin.x = bounds.origin.x * scale + offset_x;
+        bounds.origin.y = bounds.origin.y * scale + offset_y;
+        bounds.size.width *= scale;
+        bounds.size.height *= scale;
+
+        match element {
+            SlideElement::Text(text) => {
+                text.inset.left *= scale;
+                text.inset.top *= scale;
+                text.inset.right *= scale;
+                text.inset.bottom *= scale;
+                text.scale_fonts(scale);
+                for paragraph in &mut text.paragraphs {
+                    for stop in &mut paragraph.tab_stops {
+                        *stop *= scale;
+                    }
+                }
+                if let Some(outline) = &mut text.outline {
+                    outline.width *= scale;
+                }
+                if let Some(shadow) = &mut text.shadow {
+                    shadow.offset_x *= scale;
+                    shadow.offset_y *= scale;
+                }
+            }
+            SlideElement::Shape(shape) => {
+                if let Some(stroke) = &mut shape.stroke {
+                    stroke.width *= scale;
+                }
+                if let ShapeType::RoundedRectangle { radius } = &mut shape.shape_type {
+                    *radius *= scale;
+                }
+            }
+            SlideElement::Image(_) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::geometry::{Insets, Rect};
+    use crate::model::shape::{ShapeElement, ShapeType};
+    use crate::model::style::{Color, StrokeStyle};
+    use crate::model::text::{TextElement, TextOutline, TextShadow};
+
+    #[test]
+    fn rescaling_with_layout_scales_and_centers_content() {
+        let mut doc = Document::new();
+        doc.slide_size = Size::new(960.0, 540.0);
+        let mut text = TextElement::new(Rect::new(100.0, 100.0, 200.0, 80.0), "Text");
+        text.inset = Insets::uniform(4.0);
+        let paragraph = &mut text.paragraphs[0];
+        paragraph.space_before = 8.0;
+        paragraph.space_after = 12.0;
+        paragraph.tab_stops = vec![40.0];
+        paragraph.runs[0].font.size = 20.0;
+        text.outline = Some(TextOutline {
+            color: Color::black(),
+            width: 2.0,
+        });
+        text.shadow = Some(TextShadow {
+            color: Color::black(),
+            offset_x: 4.0,
+            offset_y: -8.0,
+        });
+        let shape_type = ShapeType::RoundedRectangle { radius: 12.0 };
+        let mut shape = ShapeElement::new(Rect::new(0.0, 0.0, 960.0, 540.0), shape_type);
+        shape.stroke = Some(StrokeStyle::new(Color::black(), 4.0));
+        doc.slides[0].elements = vec![SlideElement::Text(text), SlideElement::Shape(shape)];
+
+        // 4:3 at the same height: three quarters, centered vertically
+        rescale_document(&mut doc, Size::new(720.0, 540.0), true);
+
+        assert_eq!(doc.slide_size, Size::new(720.0, 540.0));
+        let SlideElement::Text(text) = &doc.slides[0].elements[0] else {
+            panic!("expected a text box");
+        };
+        assert_eq!(text.bounds, Rect::new(75.0, 142.5, 150.0, 60.0));
+        assert_eq!(text.inset, Insets::uniform(3.0));
+        let paragraph = &text.paragraphs[0];
+        assert_eq!((paragraph.space_before, paragraph.space_after), (6.0, 9.0));
+        assert_eq!(paragraph.tab_stops, vec![30.0]);
+        assert_eq!(paragraph.runs[0].font.size, 15.0);
+        assert_eq!(text.outline.as_ref().unwrap().width, 1.5);
+        let shadow = text.shadow.as_ref().unwrap();
+        assert_eq!((shadow.offset_x, shadow.offset_y), (3.0, -6.0));
+        let SlideElement::Shape(shape) = &doc.slides[0].elements[1] else {
+            panic!("expected a shape");
+        };
+        assert_eq!(shape.bounds, Rect::new(0.0, 67.5, 720.0, 405.0));
+        assert_eq!(shape.stroke.as_ref().unwrap().width, 3.0);
+        assert_eq!(shape.shape_type, ShapeType::RoundedRectangle { radius: 9.0 });
+    }
+
+    #[test]
+    fn rescaling_without_layout_keeps_coordinates() {
+        let mut doc = Document::new();
+        let bounds = Rect::new(100.0, 100.0, 200.0, 80.0);
+        doc.slides[0].elements.push(SlideElement::Text(TextElement::new(bounds, "Text")));
+
+        rescale_document(&mut doc, Size::new(720.0, 540.0), false);
+
+        assert_eq!(doc.slide_size, Size::new(720.0, 540.0));
+        assert_eq!(*doc.slides[0].elements[0].bounds(), bounds);
+    }
+}
@@ -2,11 +2,23 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use super::geometry::Rect;
-use super::image::ImageElement;
+use super::image::{ImageElement, ImageFilters};
 use super::shape::ShapeElement;
+use super::style::FontStyle;
 use super::text::TextElement;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Serde default for the `visible` flag, which older documents lack
+pub fn default_visible() -> bool {
+    true
+}
+
+/// Serde default for the `opacity` of shapes and images, which older
+/// documents lack
+pub fn default_opacity() -> f64 {
+    1.0
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum SlideElement {
     Text(TextElement),
     Image(ImageElement),
@@ -22,6 +34,14 @@ impl SlideElement {
         }
     }
 
+    pub fn set_id(&mut self, id: Uuid) {
+        match self {
+            SlideElement::Text(e) => e.id = id,
+            SlideElement::Image(e) => e.id = id,
+            SlideElement::Shape(e) => e.id = id,
+        }
+    }
+
     pub fn bounds(&self) -> &Rect {
         match self {
             SlideElement::Text(e) => &e.bounds,
@@ -38,6 +58,77 @@ impl SlideElement {
         }
     }
 
+    /// Restore the look of a freshly created element, keeping its geometry
+    /// and content.
+    pub fn reset_style(&mut self) {
+        match self {
+            SlideElement::Text(e) => {
+                for run in e.paragraphs.iter_mut().flat_map(|p| &mut p.runs) {
+                    run.font = FontStyle::default();
+                }
+                e.fill = None;
+            }
+            SlideElement::Image(e) => {
+                e.filters = ImageFilters::default();
+                e.opacity = default_opacity();
+            }
+            SlideElement::Shape(e) => {
+                let defaults = ShapeElement::new(e.bounds, e.shape_type);
+                e.fill = defaults.fill;
+                e.stroke = defaults.stroke;
+                e.opacity = defaults.opacity;
+            }
+        }
+    }
+
+    pub fn is_visible(&self) -> bool {
+        match self {
+            SlideElement::Text(e) => e.visible,
+            SlideElement::Image(e) => e.visible,
+            SlideElement::Shape(e) => e.visible,
+        }
+    }
+
+    pub fn set_visible(&mut self, visible: bool) {
+        match self {
+            SlideElement::Text(e) => e.visible = visible,
+            SlideElement::Image(e) => e.visible = visible,
+            SlideElement::Shape(e) => e.visible = visible,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            SlideElement::Text(e) => &e.name,
+            SlideElement::Image(e) => &e.name,
+            SlideElement::Shape(e) => &e.name,
+        }
+    }
+
+    pub fn set_name(&mut self, name: String) {
+        match self {
+            SlideElement::Text(e) => e.name = name,
+            SlideElement::Image(e) => e.name = name,
+            SlideElement::Shape(e) => e.name = name,
+        }
+    }
+
+    pub fn is_locked(&self) -> bool {
+        match self {
+            SlideElement::Text(e) => e.locked,
+            SlideElement::Image(e) => e.locked,
+            SlideElement::Shape(e) => e.locked,
+        }
+    }
+
+    pub fn set_locked(&mut self, locked: bool) {
+        match self {
+            SlideElement::Text(e) => e.locked = locked,
+            SlideElement::Image(e) => e.locked = locked,
+            SlideElement::Shape(e) => e.locked = locked,
+        }
+    }
+
     pub fn rotation(&self) -> f64 {
         match self {
             SlideElement::Text(e) => e.rotation,
@@ -45,4 +136,12 @@ impl SlideElement {
             SlideElement::Shape(e) => e.rotation,
         }
     }
+
+    pub fn set_rotation(&mut self, rotation: f64) {
+        match self {
+            SlideElement::Text(e) => e.rotation = rotation,
+            SlideElement::Image(e) => e.rotation = rotation,
+            SlideElement::Shape(e) => e.rotation = rotation,
+        }
+    }
 }
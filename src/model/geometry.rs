@@ -28,12 +28,98 @@ impl Size {
     }
 }
 
+/// Distances from the edges of a box to its content, in points
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct Insets {
+    pub left: f64,
+    pub top: f64,
+    pub right: f64,
+    pub bottom: f64,
+}
+
+impl Insets {
+    pub fn new(left: f64, top: f64, right: f64, bottom: f64) -> Self {
+        Self {
+            left,
+            top,
+            right,
+            bottom,
+        }
+    }
+
+    pub fn uniform(inset: f64) -> Self {
+        Self::new(inset, inset, inset, inset)
+    }
+}
+
 /// Default slide size: 960x540 points (16:9 widescreen, ~13.3 x 7.5 inches)
 pub const DEFAULT_SLIDE_SIZE: Size = Size {
     width: 960.0,
     height: 540.0,
 };
 
+/// EMU (English Metric Units) per point, the unit of OOXML sizes
+const EMU_PER_PT: f64 = 12700.0;
+
+/// Points a size may be off a preset after unit conversions and still count
+/// as that preset
+const PRESET_TOLERANCE: f64 = 0.5;
+
+/// A standard slide size. The exact size is kept in EMU, which converts to
+/// both inches and centimeters without rounding, so files written at it
+/// open at the canonical size in PowerPoint and Impress.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SlideSizePreset {
+    /// Untranslated name, for gettext
+    pub name: &'static str,
+    pub width_emu: i64,
+    pub height_emu: i64,
+}
+
+impl SlideSizePreset {
+    pub fn size(&self) -> Size {
+        Size::new(
+            self.width_emu as f64 / EMU_PER_PT,
+            self.height_emu as f64 / EMU_PER_PT,
+        )
+    }
+
+    /// The preset `size` is, if any.
+    pub fn find(size: &Size) -> Option<&'static SlideSizePreset> {
+        SLIDE_SIZE_PRESETS.iter().find(|preset| {
+            let preset_size = preset.size();
+            (preset_size.width - size.width).abs() < PRESET_TOLERANCE
+                && (preset_size.height - size.height).abs() < PRESET_TOLERANCE
+        })
+    }
+}
+
+pub const SLIDE_SIZE_PRESETS: &[SlideSizePreset] = &[
+    // PowerPoint's default, 13.333 x 7.5 inches
+    SlideSizePreset {
+        name: "Widescreen (16:9)",
+        width_emu: 12_192_000,
+        height_emu: 6_858_000,
+    },
+    SlideSizePreset {
+        name: "Widescreen (16:10)",
+        width_emu: 10_972_800,
+        height_emu: 6_858_000,
+    },
+    // Impress' default, 28 x 15.75 cm
+    SlideSizePreset {
+        name: "Widescreen (16:9, Impress)",
+        width_emu: 10_080_000,
+        height_emu: 5_670_000,
+    },
+    // 10 x 7.5 inches
+    SlideSizePreset {
+        name: "Standard (4:3)",
+        width_emu: 9_144_000,
+        height_emu: 6_858_000,
+    },
+];
+
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct Rect {
     pub origin: Point,
@@ -69,4 +155,24 @@ impl Rect {
             self.origin.y + self.size.height / 2.0,
         )
     }
+
+    /// Whether the rects overlap; touching edges count.
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.origin.x <= other.right()
+            && other.origin.x <= self.right()
+            && self.origin.y <= other.bottom()
+            && other.origin.y <= self.bottom()
+    }
+
+    /// The smallest rect containing both.
+    pub fn union(&self, other: &Rect) -> Rect {
+        let x = self.origin.x.min(other.origin.x);
+        let y = self.origin.y.min(other.origin.y);
+        Rect::new(
+            x,
+            y,
+            self.right().max(other.right()) - x,
+            self.bottom().max(other.bottom()) - y,
+        )
+    }
 }
@@ -0,0 +1,191 @@
+use super::document::Document;
+
+/// Steps kept for undo. Snapshots share image bytes but copy everything
+/// else, so the stack is bounded.
+const MAX_UNDO_STEPS: usize = 50;
+
+/// Undo and redo as whole-document snapshots. `current` is the state after
+/// the last recorded edit; an edit is pushed once it is complete, e.g. at the
+/// end of a drag, so intermediate states never become undo steps.
+#[derive(Debug, Clone)]
+pub struct History {
+    undo: Vec<Document>,
+    redo: Vec<Document>,
+    current: Document,
+}
+
+impl History {
+    pub fn new(doc: &Document) -> Self {
+        Self {
+            undo: Vec::new(),
+            redo: Vec::new(),
+            current: doc.clone(),
+        }
+    }
+
+    /// Forget all steps, e.g. after opening another document.
+    pub fn reset(&mut self, doc: &Document) {
+        *self = Self::new(doc);
+    }
+
+    /// Record `doc` as the result of an edit. Returns false, and keeps the
+    /// redo steps, when nothing changed since the last recorded state.
+    pub fn push(&mut self, doc: &Document) -> bool {
+        if *doc == self.current {
+            return false;
+        }
+        let previous = std::mem::replace(&mut self.current, doc.clone());
+        self.undo.push(previous);
+        if self.undo.len() > MAX_UNDO_STEPS {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+        true
+    }
+
+    /// The state before the last edit, which becomes the current state.
+    pub fn undo(&mut self) -> Option<Document> {
+        let previous = self.undo.pop()?;
+        let undone = std::mem::replace(&mut self.current, previous.clone());
+        self.redo.push(undone);
+        Some(previous)
+    }
+
+    /// The state of the last undone edit, which becomes the current state.
+    pub fn redo(&mut self) -> Option<Document> {
+        let next = self.redo.pop()?;
+        let redone = std::mem::replace(&mut self.current, next.clone());
+        self.undo.push(redone);
+        Some(next)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+}
+
+/// Index of the first slide that differs between `a` and `b`, so the view can
+/// follow an undone change. Slides added or removed at the end count too.
+pub fn first_changed_slide(a: &Document, b: &Document) -> Option<usize> {
+    let common = a.slides.len().min(b.slides.len());
+    (0..common)
+        .find(|&i| a.slides[i] != b.slides[i])
+        .or((a.slides.len() != b.slides.len()).then_some(common))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::element::SlideElement;
+    use crate::model::geometry::Rect;
+    use crate::model::image::{ImageData, ImageElement};
+    use crate::model::shape::{ShapeElement, ShapeType};
+    use std::sync::Arc;
+
+    fn three_shapes() -> Document {
+        let mut doc = Document::new();
+        for x in [10.0, 20.0, 30.0] {
+            let shape = ShapeElement::new(Rect::new(x, x, 50.0, 50.0), ShapeType::Rectangle);
+            doc.slides[0].add_element(SlideElement::Shape(shape));
+        }
+        doc
+    }
+
+    #[test]
+    fn undoing_a_resize_restores_the_exact_bounds() {
+        let mut doc = three_shapes();
+        let original = Rect::new(12.345, 67.891, 101.25, 33.125);
+        *doc.slides[0].elements[1].bounds_mut() = original;
+        let mut history = History::new(&doc);
+
+        *doc.slides[0].elements[1].bounds_mut() = Rect::new(12.345, 67.891, 240.5, 80.75);
+        assert!(history.push(&doc));
+
+        let undone = history.undo().unwrap();
+        assert_eq!(*undone.slides[0].elements[1].bounds(), original);
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn undoing_a_delete_puts_the_element_back_at_its_place_in_the_stack() {
+        let mut doc = three_shapes();
+        let ids: Vec<_> = doc.slides[0].elements.iter().map(|e| e.id()).collect();
+        let mut history = History::new(&doc);
+
+        doc.slides[0].remove_element(ids[1]);
+        assert!(history.push(&doc));
+
+        let undone = history.undo().unwrap();
+        let restored: Vec<_> = undone.slides[0].elements.iter().map(|e| e.id()).collect();
+        assert_eq!(restored, ids);
+    }
+
+    #[test]
+    fn redo_reapplies_the_undone_edit_until_a_new_edit() {
+        let mut doc = three_shapes();
+        let mut history = History::new(&doc);
+        doc.slides[0].elements.pop();
+        history.push(&doc);
+
+        let undone = history.undo().unwrap();
+        assert_eq!(undone.slides[0].elements.len(), 3);
+        assert!(history.can_redo());
+        let redone = history.redo().unwrap();
+        assert_eq!(redone, doc);
+        assert!(!history.can_redo());
+
+        // An unchanged push keeps redo, a new edit drops it
+        history.undo();
+        assert!(!history.push(&undone));
+        assert!(history.can_redo());
+        let mut edited = undone.clone();
+        edited.slides[0].elements.clear();
+        assert!(history.push(&edited));
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn only_the_latest_steps_are_kept() {
+        let mut doc = three_shapes();
+        let mut history = History::new(&doc);
+        for step in 1..=MAX_UNDO_STEPS + 5 {
+            doc.slides[0].elements[0].bounds_mut().origin.x = step as f64;
+            history.push(&doc);
+        }
+
+        let mut undone = 0;
+        let mut oldest = None;
+        while let Some(previous) = history.undo() {
+            undone += 1;
+            oldest = Some(previous);
+        }
+        assert_eq!(undone, MAX_UNDO_STEPS);
+        let oldest = oldest.unwrap();
+        assert_eq!(oldest.slides[0].elements[0].bounds().origin.x, 5.0);
+    }
+
+    #[test]
+    fn snapshots_share_image_bytes() {
+        let mut doc = Document::new();
+        let bounds = Rect::new(0.0, 0.0, 80.0, 60.0);
+        let image = ImageElement::new(bounds, vec![7; 4096], "image/png".into());
+        doc.slides[0].add_element(SlideElement::Image(image));
+        let mut history = History::new(&doc);
+        doc.slides[0].elements[0].bounds_mut().origin.x = 40.0;
+        history.push(&doc);
+
+        let bytes = |doc: &Document| match &doc.slides[0].elements[0] {
+            SlideElement::Image(image) => {
+                let ImageData::Embedded { data, .. } = &image.image_data;
+                data.clone()
+            }
+            _ => panic!("expected an image"),
+        };
+        assert!(Arc::ptr_eq(&bytes(&history.undo[0]), &bytes(&doc)));
+        assert!(Arc::ptr_eq(&bytes(&history.current), &bytes(&doc)));
+    }
+}
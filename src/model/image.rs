@@ -1,11 +1,117 @@
+use std::sync::Arc;
+
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use super::geometry::Rect;
+use super::geometry::{Rect, Size};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Picture bytes are shared, so undo snapshots and copies of an image do not
+/// duplicate them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ImageData {
-    Embedded { data: Vec<u8>, mime: String },
+    Embedded { data: Arc<[u8]>, mime: String },
+}
+
+impl ImageData {
+    /// Size in points of a PNG, JPEG or GIF picture as other applications
+    /// lay it out: its pixels at the resolution stored in the file, or at
+    /// 96 DPI without one. `None` for other formats or damaged headers.
+    pub fn physical_size(&self) -> Option<Size> {
+        let ImageData::Embedded { data, .. } = self;
+        let (width, height, dpi_x, dpi_y) = png_header(data)
+            .or_else(|| jpeg_header(data))
+            .or_else(|| gif_header(data))?;
+        Some(Size::new(width * 72.0 / dpi_x, height * 72.0 / dpi_y))
+    }
+}
+
+const DEFAULT_DPI: f64 = 96.0;
+
+fn be_u16(data: &[u8], at: usize) -> Option<f64> {
+    Some(u16::from_be_bytes(data.get(at..at + 2)?.try_into().ok()?) as f64)
+}
+
+fn be_u32(data: &[u8], at: usize) -> Option<f64> {
+    Some(u32::from_be_bytes(data.get(at..at + 4)?.try_into().ok()?) as f64)
+}
+
+/// Pixel size and resolution of a PNG from its IHDR and pHYs chunks.
+fn png_header(data: &[u8]) -> Option<(f64, f64, f64, f64)> {
+    if !data.starts_with(b"\x89PNG\r\n\x1a\n") || data.get(12..16)? != b"IHDR" {
+        return None;
+    }
+    let (width, height) = (be_u32(data, 16)?, be_u32(data, 20)?);
+    let (mut dpi_x, mut dpi_y) = (DEFAULT_DPI, DEFAULT_DPI);
+    // pHYs must come before the image data
+    let mut at = 8;
+    while let (Some(len), Some(kind)) = (be_u32(data, at), data.get(at + 4..at + 8)) {
+        match kind {
+            b"pHYs" if data.get(at + 16) == Some(&1) => {
+                // Pixels per meter
+                let (x, y) = (be_u32(data, at + 8)?, be_u32(data, at + 12)?);
+                if x > 0.0 && y > 0.0 {
+                    (dpi_x, dpi_y) = (x * 0.0254, y * 0.0254);
+                }
+                break;
+            }
+            b"IDAT" | b"IEND" => break,
+            _ => at += len as usize + 12,
+        }
+    }
+    (width > 0.0 && height > 0.0).then_some((width, height, dpi_x, dpi_y))
+}
+
+/// Pixel size of a JPEG from its first start-of-frame segment, and its
+/// resolution from a JFIF header in dots per inch or per centimeter.
+fn jpeg_header(data: &[u8]) -> Option<(f64, f64, f64, f64)> {
+    if !data.starts_with(b"\xFF\xD8") {
+        return None;
+    }
+    let (mut dpi_x, mut dpi_y) = (DEFAULT_DPI, DEFAULT_DPI);
+    let mut at = 2;
+    loop {
+        if *data.get(at)? != 0xFF {
+            return None;
+        }
+        let marker = *data.get(at + 1)?;
+        let len = be_u16(data, at + 2)? as usize;
+        match marker {
+            0xE0 if data.get(at + 4..at + 9)? == b"JFIF\0" => {
+                let (x, y) = (be_u16(data, at + 12)?, be_u16(data, at + 14)?);
+                if x > 0.0 && y > 0.0 {
+                    match data.get(at + 11)? {
+                        1 => (dpi_x, dpi_y) = (x, y),
+                        2 => (dpi_x, dpi_y) = (x * 2.54, y * 2.54),
+                        _ => {}
+                    }
+                }
+            }
+            // Start of frame, except the DHT, JPG and DAC markers among them
+            0xC0..=0xCF if !matches!(marker, 0xC4 | 0xC8 | 0xCC) => {
+                let (height, width) = (be_u16(data, at + 5)?, be_u16(data, at + 7)?);
+                return (width > 0.0 && height > 0.0).then_some((width, height, dpi_x, dpi_y));
+            }
+            0xD9 | 0xDA => return None,
+            _ => {}
+        }
+        at += 2 + len;
+    }
+}
+
+/// Pixel size of a GIF from its logical screen descriptor.
+fn gif_header(data: &[u8]) -> Option<(f64, f64, f64, f64)> {
+    if !data.starts_with(b"GIF8") {
+        return None;
+    }
+    let width = u16::from_le_bytes(data.get(6..8)?.try_into().ok()?) as f64;
+    let height = u16::from_le_bytes(data.get(8..10)?.try_into().ok()?) as f64;
+    (width > 0.0 && height > 0.0).then_some((width, height, DEFAULT_DPI, DEFAULT_DPI))
+}
+
+/// Windows metafiles (EMF, WMF), which are kept and saved as they are but
+/// cannot be drawn
+pub fn is_metafile(mime: &str) -> bool {
+    matches!(mime, "image/x-emf" | "image/x-wmf")
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
@@ -21,13 +127,101 @@ impl Default for ScaleMode {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Non-destructive adjustments applied when the image is decoded for display.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ImageFilters {
+    pub grayscale: bool,
+    /// Additive brightness offset, -1.0..1.0 (0.0 = unchanged)
+    pub brightness: f64,
+    /// Contrast factor, 0.0..2.0 (1.0 = unchanged)
+    pub contrast: f64,
+    /// Saturation factor, 0.0..2.0 (1.0 = unchanged)
+    pub saturation: f64,
+}
+
+impl ImageFilters {
+    pub fn is_identity(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl Default for ImageFilters {
+    fn default() -> Self {
+        Self {
+            grayscale: false,
+            brightness: 0.0,
+            contrast: 1.0,
+            saturation: 1.0,
+        }
+    }
+}
+
+/// Part of the picture cut away on each side, as fractions of its width
+/// or height. The rest is scaled into the bounds.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct ImageCrop {
+    pub left: f64,
+    pub top: f64,
+    pub right: f64,
+    pub bottom: f64,
+}
+
+impl ImageCrop {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Fractions of the picture's width and height left after cropping
+    pub fn visible(&self) -> (f64, f64) {
+        (1.0 - self.left - self.right, 1.0 - self.top - self.bottom)
+    }
+
+    /// The crop as seen on the slide, where a mirrored picture shows the
+    /// crop of its other side. Mirroring twice gives the crop back.
+    pub fn mirrored(&self, flip_h: bool, flip_v: bool) -> Self {
+        let mut crop = *self;
+        if flip_h {
+            std::mem::swap(&mut crop.left, &mut crop.right);
+        }
+        if flip_v {
+            std::mem::swap(&mut crop.top, &mut crop.bottom);
+        }
+        crop
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ImageElement {
     pub id: Uuid,
     pub bounds: Rect,
     pub rotation: f64,
     pub image_data: ImageData,
     pub scale_mode: ScaleMode,
+    #[serde(default)]
+    pub crop: ImageCrop,
+    /// Mirrored left to right, within the bounds
+    #[serde(default)]
+    pub flip_h: bool,
+    /// Mirrored top to bottom, within the bounds
+    #[serde(default)]
+    pub flip_v: bool,
+    #[serde(default)]
+    pub filters: ImageFilters,
+    /// Opacity of the picture, from 0 (invisible) to 1
+    #[serde(default = "super::element::default_opacity")]
+    pub opacity: f64,
+    /// Description read out by screen readers
+    #[serde(default)]
+    pub alt_text: String,
+    /// Hidden elements stay in the document but are not drawn or exported
+    #[serde(default = "super::element::default_visible")]
+    pub visible: bool,
+    /// Name shown in the objects panel; empty uses a generated label
+    #[serde(default)]
+    pub name: String,
+    /// Locked elements can be selected but not moved or resized
+    #[serde(default)]
+    pub locked: bool,
 }
 
 impl ImageElement {
@@ -36,8 +230,85 @@ impl ImageElement {
             id: Uuid::new_v4(),
             bounds,
             rotation: 0.0,
-            image_data: ImageData::Embedded { data, mime },
+            image_data: ImageData::Embedded { data: data.into(), mime },
             scale_mode: ScaleMode::Fit,
+            crop: ImageCrop::default(),
+            flip_h: false,
+            flip_v: false,
+            filters: ImageFilters::default(),
+            opacity: 1.0,
+            alt_text: String::new(),
+            visible: true,
+            name: String::new(),
+            locked: false,
+        }
+    }
+
+    /// Switch to `ScaleMode::Stretch` without changing what is shown, so
+    /// the crop lines up with the bounds: a fitted picture gets bounds
+    /// around just the picture, a filled one a crop of its overflowing
+    /// sides. `natural` is the size of the picture in pixels.
+    pub fn stretch_in_place(&mut self, natural: Size) {
+        let (visible_w, visible_h) = self.crop.visible();
+        let img_width = (natural.width * visible_w).max(1.0);
+        let img_height = (natural.height * visible_h).max(1.0);
+        let scale_x = self.bounds.size.width / img_width;
+        let scale_y = self.bounds.size.height / img_height;
+        match self.scale_mode {
+            ScaleMode::Stretch => return,
+            ScaleMode::Fit => {
+                let scale = scale_x.min(scale_y);
+                let center = self.bounds.center();
+                let (width, height) = (img_width * scale, img_height * scale);
+                self.bounds =
+                    Rect::new(center.x - width / 2.0, center.y - height / 2.0, width, height);
+            }
+            ScaleMode::Fill => {
+                let scale = scale_x.max(scale_y);
+                let extra_x = (img_width - self.bounds.size.width / scale) / 2.0 / natural.width;
+                let extra_y =
+                    (img_height - self.bounds.size.height / scale) / 2.0 / natural.height;
+                self.crop.left += extra_x;
+                self.crop.right += extra_x;
+                self.crop.top += extra_y;
+                self.crop.bottom += extra_y;
+            }
         }
+        self.scale_mode = ScaleMode::Stretch;
+    }
+
+    /// Name a metafile image after the file at `path` it was read from, so
+    /// the placeholder drawn in its place tells which picture it is.
+    pub fn name_metafile(&mut self, path: &str) {
+        let ImageData::Embedded { ref mime, .. } = self.image_data;
+        if is_metafile(mime) {
+            self.name = path.rsplit('/').next().unwrap_or(path).to_string();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn embedded(data: Vec<u8>) -> ImageData {
+        ImageData::Embedded { data: data.into(), mime: String::new() }
+    }
+
+    #[test]
+    fn physical_size_uses_the_resolution_of_a_jpeg() {
+        let mut data = b"\xFF\xD8\xFF\xE0\x00\x10JFIF\x00\x01\x01".to_vec();
+        // 144 dots per inch, then a start of frame of 300 by 150 pixels
+        data.extend_from_slice(&[1, 0, 144, 0, 144, 0, 0]);
+        data.extend_from_slice(&[0xFF, 0xC0, 0, 17, 8, 0, 150, 1, 44]);
+        assert_eq!(embedded(data).physical_size(), Some(Size::new(150.0, 75.0)));
+    }
+
+    #[test]
+    fn physical_size_needs_a_known_header() {
+        let gif = embedded(b"GIF89a\x20\x00\x10\x00".to_vec());
+        assert_eq!(gif.physical_size(), Some(Size::new(24.0, 12.0)));
+        assert_eq!(embedded(b"<svg/>".to_vec()).physical_size(), None);
+        assert_eq!(embedded(b"\xFF\xD8\xFF\xE0".to_vec()).physical_size(), None);
     }
 }
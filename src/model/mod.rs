@@ -1,6 +1,7 @@
 pub mod document;
 pub mod element;
 pub mod geometry;
+pub mod history;
 pub mod image;
 pub mod shape;
 pub mod slide;
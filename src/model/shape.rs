@@ -1,17 +1,104 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use super::geometry::Rect;
+use super::geometry::{Point, Rect, Size};
 use super::style::{FillStyle, StrokeStyle};
 
+/// Corner radius of new rounded rectangles
+pub const DEFAULT_CORNER_RADIUS: f64 = 12.0;
+/// Corner radius of the rounded rectangle presets of ODF and OOXML,
+/// relative to the shorter side
+pub const PRESET_CORNER_RATIO: f64 = 1.0 / 6.0;
+/// Tips of new stars
+pub const DEFAULT_STAR_POINTS: u32 = 5;
+/// Inner radius of a star relative to the outer one, as in the five-pointed
+/// star of PowerPoint
+const STAR_INNER_RATIO: f64 = 0.382;
+/// Thickness of an arrow shaft relative to the height of the bounds
+const ARROW_SHAFT_RATIO: f64 = 0.5;
+/// Length of an arrow head relative to the shorter side of the bounds
+const ARROW_HEAD_RATIO: f64 = 0.5;
+/// Size of a line marker relative to the stroke width
+const MARKER_SIZE_RATIO: f64 = 3.0;
+/// Smallest line marker, so markers of thin lines stay visible
+const MIN_MARKER_SIZE: f64 = 6.0;
+
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum ShapeType {
     Rectangle,
     Ellipse,
     Line,
+    /// Isosceles, pointing up
+    Triangle,
+    /// Corners rounded by `radius`, at most half the shorter side
+    RoundedRectangle { radius: f64 },
+    /// `points` tips on the ellipse inside the bounds, the first at the top
+    Star { points: u32 },
+    /// Block arrow pointing right
+    Arrow,
+}
+
+impl ShapeType {
+    /// Corners of the outline of a polygon shape filling bounds of `size`
+    /// at the origin. `None` for shapes with curves and for lines.
+    pub fn polygon(&self, size: Size) -> Option<Vec<Point>> {
+        let (w, h) = (size.width, size.height);
+        match *self {
+            ShapeType::Triangle => Some(vec![
+                Point::new(w / 2.0, 0.0),
+                Point::new(w, h),
+                Point::new(0.0, h),
+            ]),
+            ShapeType::Star { points } => {
+                let tips = points.max(3) as usize;
+                let (cx, cy) = (w / 2.0, h / 2.0);
+                let corners = (0..tips * 2)
+                    .map(|i| {
+                        let angle = std::f64::consts::PI * i as f64 / tips as f64
+                            - std::f64::consts::FRAC_PI_2;
+                        let scale = if i % 2 == 0 { 1.0 } else { STAR_INNER_RATIO };
+                        Point::new(
+                            cx + angle.cos() * cx * scale,
+                            cy + angle.sin() * cy * scale,
+                        )
+                    })
+                    .collect();
+                Some(corners)
+            }
+            ShapeType::Arrow => {
+                let head = w.min(h) * ARROW_HEAD_RATIO;
+                let shaft_top = h * (1.0 - ARROW_SHAFT_RATIO) / 2.0;
+                let shaft_bottom = h - shaft_top;
+                Some(vec![
+                    Point::new(0.0, shaft_top),
+                    Point::new(w - head, shaft_top),
+                    Point::new(w - head, 0.0),
+                    Point::new(w, h / 2.0),
+                    Point::new(w - head, h),
+                    Point::new(w - head, shaft_bottom),
+                    Point::new(0.0, shaft_bottom),
+                ])
+            }
+            ShapeType::Rectangle
+            | ShapeType::Ellipse
+            | ShapeType::Line
+            | ShapeType::RoundedRectangle { .. } => None,
+        }
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Decoration at the start or end of a line
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum LineMarker {
+    #[default]
+    None,
+    /// Arrowhead with its tip on the end point
+    Arrow,
+    /// Dot centered on the end point
+    Circle,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ShapeElement {
     pub id: Uuid,
     pub bounds: Rect,
@@ -19,6 +106,30 @@ pub struct ShapeElement {
     pub shape_type: ShapeType,
     pub fill: Option<FillStyle>,
     pub stroke: Option<StrokeStyle>,
+    /// Mirror the shape within its bounds. Lines run from the top-left to the
+    /// bottom-right corner unless flipped.
+    #[serde(default)]
+    pub flip_h: bool,
+    #[serde(default)]
+    pub flip_v: bool,
+    /// Markers at the start and end point of a line, ignored for other shapes
+    #[serde(default)]
+    pub start_marker: LineMarker,
+    #[serde(default)]
+    pub end_marker: LineMarker,
+    /// Opacity of the whole shape, from 0 (invisible) to 1, on top of the
+    /// alpha of its fill and stroke
+    #[serde(default = "super::element::default_opacity")]
+    pub opacity: f64,
+    /// Hidden elements stay in the document but are not drawn or exported
+    #[serde(default = "super::element::default_visible")]
+    pub visible: bool,
+    /// Name shown in the objects panel; empty uses a generated label
+    #[serde(default)]
+    pub name: String,
+    /// Locked elements can be selected but not moved or resized
+    #[serde(default)]
+    pub locked: bool,
 }
 
 impl ShapeElement {
@@ -38,6 +149,55 @@ impl ShapeElement {
             shape_type,
             fill,
             stroke,
+            flip_h: false,
+            flip_v: false,
+            start_marker: LineMarker::None,
+            end_marker: LineMarker::None,
+            opacity: 1.0,
+            visible: true,
+            name: String::new(),
+            locked: false,
         }
     }
+
+    /// Create a line running from `start` to `end`.
+    pub fn line(start: Point, end: Point) -> Self {
+        let mut shape = Self::new(Rect::new(start.x, start.y, 0.0, 0.0), ShapeType::Line);
+        shape.set_line_endpoints(start, end);
+        shape
+    }
+
+    /// Start and end point of a line, honoring the flips.
+    pub fn line_endpoints(&self) -> (Point, Point) {
+        let b = &self.bounds;
+        let (x1, x2) = if self.flip_h {
+            (b.right(), b.origin.x)
+        } else {
+            (b.origin.x, b.right())
+        };
+        let (y1, y2) = if self.flip_v {
+            (b.bottom(), b.origin.y)
+        } else {
+            (b.origin.y, b.bottom())
+        };
+        (Point::new(x1, y1), Point::new(x2, y2))
+    }
+
+    /// Width and length of the line markers, growing with the stroke width.
+    pub fn marker_size(&self) -> f64 {
+        let width = self.stroke.as_ref().map_or(0.0, |stroke| stroke.width);
+        (width * MARKER_SIZE_RATIO).max(MIN_MARKER_SIZE)
+    }
+
+    /// Set bounds and flips so the line runs from `start` to `end`.
+    pub fn set_line_endpoints(&mut self, start: Point, end: Point) {
+        self.bounds = Rect::new(
+            start.x.min(end.x),
+            start.y.min(end.y),
+            (end.x - start.x).abs(),
+            (end.y - start.y).abs(),
+        );
+        self.flip_h = end.x < start.x;
+        self.flip_v = end.y < start.y;
+    }
 }
@@ -2,11 +2,52 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use super::element::SlideElement;
+use super::image::ImageData;
 use super::style::Color;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum GradientKind {
+    Linear,
+    /// Runs from the center of the filled area outwards
+    Radial,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Gradient {
+    pub kind: GradientKind,
+    pub start: Color,
+    pub end: Color,
+    /// Direction of a linear gradient in degrees, clockwise from
+    /// left-to-right (90 runs top to bottom)
+    pub angle: f64,
+}
+
+impl Gradient {
+    pub fn linear(start: Color, end: Color, angle: f64) -> Self {
+        Self {
+            kind: GradientKind::Linear,
+            start,
+            end,
+            angle,
+        }
+    }
+
+    pub fn radial(start: Color, end: Color) -> Self {
+        Self {
+            kind: GradientKind::Radial,
+            start,
+            end,
+            angle: 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Background {
     Solid(Color),
+    Gradient(Gradient),
+    /// Picture stretched over the whole slide
+    Image(ImageData),
 }
 
 impl Default for Background {
@@ -15,7 +56,7 @@ impl Default for Background {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Slide {
     pub id: Uuid,
     pub elements: Vec<SlideElement>,
@@ -42,6 +83,16 @@ impl Slide {
         }
     }
 
+    /// Copy of this slide where the slide and all its elements get fresh ids.
+    pub fn duplicate(&self) -> Self {
+        let mut slide = self.clone();
+        slide.id = Uuid::new_v4();
+        for element in &mut slide.elements {
+            element.set_id(Uuid::new_v4());
+        }
+        slide
+    }
+
     pub fn add_element(&mut self, element: SlideElement) {
         self.elements.push(element);
     }
@@ -54,18 +105,67 @@ impl Slide {
         }
     }
 
+    /// Move an element one step up the stacking order. Returns false when it
+    /// is already on top or not on this slide.
+    pub fn bring_forward(&mut self, id: Uuid) -> bool {
+        match self.elements.iter().position(|e| e.id() == id) {
+            Some(pos) if pos + 1 < self.elements.len() => {
+                self.elements.swap(pos, pos + 1);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Move an element one step down the stacking order. Returns false when
+    /// it is already at the bottom or not on this slide.
+    pub fn send_backward(&mut self, id: Uuid) -> bool {
+        match self.elements.iter().position(|e| e.id() == id) {
+            Some(pos) if pos > 0 => {
+                self.elements.swap(pos, pos - 1);
+                true
+            }
+            _ => false,
+        }
+    }
+
     pub fn find_element_at(
         &self,
         point: super::geometry::Point,
     ) -> Option<(usize, &SlideElement)> {
-        // Iterate in reverse to find topmost element first
+        // Iterate in reverse to find topmost element first; hidden elements
+        // cannot be clicked
         for (i, element) in self.elements.iter().enumerate().rev() {
-            if element.bounds().contains(point) {
+            if element.is_visible() && element.bounds().contains(point) {
                 return Some((i, element));
             }
         }
         None
     }
+
+    /// The color behind element `id`: the fill of the topmost visible
+    /// element below it that covers its center, or else the slide
+    /// background. A gradient counts as the midpoint of its colors. `None`
+    /// for an element not on this slide or over a background picture.
+    pub fn backdrop_color(&self, id: Uuid) -> Option<Color> {
+        let pos = self.elements.iter().position(|e| e.id() == id)?;
+        let center = self.elements[pos].bounds().center();
+        let fill = self.elements[..pos]
+            .iter()
+            .rev()
+            .filter(|e| e.is_visible() && e.bounds().contains(center))
+            .find_map(|e| match e {
+                SlideElement::Shape(shape) => shape.fill.as_ref(),
+                SlideElement::Text(text) => text.fill.as_ref(),
+                SlideElement::Image(_) => None,
+            });
+        match (fill, &self.background) {
+            (Some(fill), _) => Some(fill.average_color()),
+            (None, Background::Solid(color)) => Some(color.clone()),
+            (None, Background::Gradient(gradient)) => Some(gradient.start.mix(&gradient.end, 0.5)),
+            (None, Background::Image(_)) => None,
+        }
+    }
 }
 
 impl Default for Slide {
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use super::slide::Gradient;
+
 #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
 pub struct Color {
     pub r: f64,
@@ -25,15 +27,68 @@ impl Color {
         Self::rgb(0.0, 0.0, 0.0)
     }
 
+    /// Parse `#rrggbb`, or `#rrggbbaa` with alpha.
     pub fn from_hex(hex: &str) -> Option<Self> {
         let hex = hex.trim_start_matches('#');
-        if hex.len() != 6 {
+        if !hex.is_ascii() || (hex.len() != 6 && hex.len() != 8) {
             return None;
         }
-        let r = u8::from_str_radix(&hex[0..2], 16).ok()? as f64 / 255.0;
-        let g = u8::from_str_radix(&hex[2..4], 16).ok()? as f64 / 255.0;
-        let b = u8::from_str_radix(&hex[4..6], 16).ok()? as f64 / 255.0;
-        Some(Self::rgb(r, g, b))
+        let channel = |i: usize| -> Option<f64> {
+            Some(u8::from_str_radix(&hex[i..i + 2], 16).ok()? as f64 / 255.0)
+        };
+        let a = if hex.len() == 8 { channel(6)? } else { 1.0 };
+        Some(Self::new(channel(0)?, channel(2)?, channel(4)?, a))
+    }
+
+    /// `#rrggbb`, or `#rrggbbaa` when the color is not opaque.
+    pub fn to_hex(&self) -> String {
+        let byte = |c: f64| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+        let mut hex = format!("#{:02x}{:02x}{:02x}", byte(self.r), byte(self.g), byte(self.b));
+        if byte(self.a) < 255 {
+            hex.push_str(&format!("{:02x}", byte(self.a)));
+        }
+        hex
+    }
+
+    /// Relative luminance as defined by WCAG 2, from 0 (black) to 1 (white).
+    /// Alpha is ignored.
+    pub fn luminance(&self) -> f64 {
+        let linear = |c: f64| {
+            if c <= 0.03928 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        0.2126 * linear(self.r) + 0.7152 * linear(self.g) + 0.0722 * linear(self.b)
+    }
+
+    /// WCAG contrast ratio between two colors, from 1 (none) to 21
+    /// (black on white).
+    pub fn contrast_ratio(&self, other: &Color) -> f64 {
+        let (a, b) = (self.luminance(), other.luminance());
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+
+    /// Black or white, whichever is easier to read on this color.
+    pub fn contrasting_text_color(&self) -> Color {
+        let (black, white) = (Color::black(), Color::white());
+        if self.contrast_ratio(&black) >= self.contrast_ratio(&white) {
+            black
+        } else {
+            white
+        }
+    }
+
+    /// The color `t` of the way from this one to `other`.
+    pub fn mix(&self, other: &Color, t: f64) -> Color {
+        let lerp = |a: f64, b: f64| a + (b - a) * t;
+        Color::new(
+            lerp(self.r, other.r),
+            lerp(self.g, other.g),
+            lerp(self.b, other.b),
+            lerp(self.a, other.a),
+        )
     }
 }
 
@@ -41,31 +96,73 @@ impl Color {
 pub struct StrokeStyle {
     pub color: Color,
     pub width: f64,
+    #[serde(default)]
+    pub line_cap: LineCap,
+    #[serde(default)]
+    pub line_join: LineJoin,
 }
 
 impl StrokeStyle {
     pub fn new(color: Color, width: f64) -> Self {
-        Self { color, width }
+        Self {
+            color,
+            width,
+            line_cap: LineCap::default(),
+            line_join: LineJoin::default(),
+        }
     }
 }
 
 impl Default for StrokeStyle {
     fn default() -> Self {
-        Self {
-            color: Color::black(),
-            width: 2.0,
-        }
+        Self::new(Color::black(), 2.0)
     }
 }
 
+/// Shape of open line ends
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum LineCap {
+    /// Ends exactly at the endpoint
+    #[default]
+    Butt,
+    Round,
+    /// Extends half the line width past the endpoint
+    Square,
+}
+
+/// Shape of the corners where stroke segments meet
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum LineJoin {
+    #[default]
+    Miter,
+    Round,
+    Bevel,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FillStyle {
     pub color: Color,
+    /// Fills the bounds instead of `color`, which stays as the solid color
+    /// to return to
+    #[serde(default)]
+    pub gradient: Option<Gradient>,
 }
 
 impl FillStyle {
     pub fn new(color: Color) -> Self {
-        Self { color }
+        Self {
+            color,
+            gradient: None,
+        }
+    }
+
+    /// The single color closest to this fill, e.g. to pick contrasting text.
+    /// A gradient counts as the midpoint of its colors.
+    pub fn average_color(&self) -> Color {
+        match &self.gradient {
+            Some(gradient) => gradient.start.mix(&gradient.end, 0.5),
+            None => self.color.clone(),
+        }
     }
 }
 
@@ -75,6 +172,10 @@ pub struct FontStyle {
     pub size: f64,
     pub bold: bool,
     pub italic: bool,
+    #[serde(default)]
+    pub underline: bool,
+    #[serde(default)]
+    pub strikethrough: bool,
     pub color: Color,
 }
 
@@ -85,7 +186,46 @@ impl Default for FontStyle {
             size: 24.0,
             bold: false,
             italic: false,
+            underline: false,
+            strikethrough: false,
             color: Color::black(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gray(value: f64) -> Color {
+        Color::rgb(value, value, value)
+    }
+
+    #[test]
+    fn luminance_of_known_colors() {
+        assert_eq!(Color::black().luminance(), 0.0);
+        assert!((Color::white().luminance() - 1.0).abs() < 1e-9);
+        assert!((Color::rgb(1.0, 0.0, 0.0).luminance() - 0.2126).abs() < 1e-9);
+        assert!((Color::rgb(0.0, 1.0, 0.0).luminance() - 0.7152).abs() < 1e-9);
+        // Either side of the knee between the linear and the power segment
+        assert!((gray(0.03928).luminance() - 0.03928 / 12.92).abs() < 1e-9);
+        assert!((gray(0.5).luminance() - 0.2140).abs() < 1e-4);
+    }
+
+    #[test]
+    fn contrast_ratio_spans_one_to_twenty_one() {
+        assert!((Color::black().contrast_ratio(&Color::white()) - 21.0).abs() < 1e-9);
+        assert!((Color::white().contrast_ratio(&Color::black()) - 21.0).abs() < 1e-9);
+        assert_eq!(gray(0.3).contrast_ratio(&gray(0.3)), 1.0);
+    }
+
+    #[test]
+    fn text_color_switches_at_equal_contrast() {
+        // Black and white contrast equally at a luminance of about 0.179,
+        // which a gray of about 0.46 has
+        assert_eq!(gray(0.47).contrasting_text_color(), Color::black());
+        assert_eq!(gray(0.45).contrasting_text_color(), Color::white());
+        assert_eq!(Color::rgb(1.0, 1.0, 0.0).contrasting_text_color(), Color::black());
+        assert_eq!(Color::rgb(0.0, 0.0, 0.5).contrasting_text_color(), Color::white());
+    }
+}
@@ -1,8 +1,8 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use super::geometry::Rect;
-use super::style::{FillStyle, FontStyle};
+use super::geometry::{Insets, Rect};
+use super::style::{Color, FillStyle, FontStyle};
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum TextAlignment {
@@ -17,7 +17,16 @@ impl Default for TextAlignment {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Where the text sits in a box taller than the text
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum VerticalAlignment {
+    #[default]
+    Top,
+    Middle,
+    Bottom,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TextRun {
     pub text: String,
     pub font: FontStyle,
@@ -39,20 +48,69 @@ impl TextRun {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum ListStyle {
+    #[default]
+    None,
+    /// Auto-numbered "1.", "2.", ... per level
+    Numbered,
+    /// A bullet per item, changing with the level
+    Bullet,
+}
+
+/// Deepest list level, 0-based; ODF list styles define ten levels
+pub const MAX_LIST_LEVEL: u32 = 9;
+
+/// Bullets of the list levels, repeating for deeper ones
+const BULLETS: [&str; 3] = ["•", "◦", "▪"];
+
+/// The bullet shown for items at `level`.
+pub fn bullet_for_level(level: u32) -> &'static str {
+    BULLETS[level as usize % BULLETS.len()]
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TextParagraph {
     pub runs: Vec<TextRun>,
+    #[serde(default)]
+    pub list: ListStyle,
+    /// Nesting level within a list, 0 is the outermost
+    #[serde(default)]
+    pub level: u32,
+    /// Left-aligned tab stop positions in points from the start of the line.
+    /// Empty uses the default stops every eight spaces.
+    #[serde(default)]
+    pub tab_stops: Vec<f64>,
+    /// Distance between lines as a multiple of the line height
+    #[serde(default = "default_line_spacing")]
+    pub line_spacing: f64,
+    /// Extra space above and below the paragraph in points
+    #[serde(default)]
+    pub space_before: f64,
+    #[serde(default)]
+    pub space_after: f64,
+}
+
+/// Serde default for `line_spacing`, single spacing
+fn default_line_spacing() -> f64 {
+    1.0
 }
 
 impl TextParagraph {
     pub fn new(runs: Vec<TextRun>) -> Self {
-        Self { runs }
+        Self {
+            runs,
+            list: ListStyle::None,
+            level: 0,
+            tab_stops: Vec::new(),
+            line_spacing: default_line_spacing(),
+            space_before: 0.0,
+            space_after: 0.0,
+        }
     }
 
     pub fn plain(text: impl Into<String>) -> Self {
-        Self {
-            runs: vec![TextRun::plain(text)],
-        }
+        Self::new(vec![TextRun::plain(text)])
     }
 
     pub fn full_text(&self) -> String {
@@ -60,14 +118,86 @@ impl TextParagraph {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Stroke drawn around the glyph outlines, e.g. for titles on busy backgrounds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextOutline {
+    pub color: Color,
+    /// Width in points the stroke extends beyond the glyph edge
+    pub width: f64,
+}
+
+/// Copy of the text drawn behind it, offset in points. Unlike an element
+/// shadow it follows the glyphs rather than the box.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextShadow {
+    pub color: Color,
+    pub offset_x: f64,
+    pub offset_y: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TextElement {
     pub id: Uuid,
     pub bounds: Rect,
     pub rotation: f64,
     pub paragraphs: Vec<TextParagraph>,
     pub alignment: TextAlignment,
+    #[serde(default)]
+    pub vertical_alignment: VerticalAlignment,
+    /// Shrink the text while it does not fit the box height. The run font
+    /// sizes are the largest it is drawn at.
+    #[serde(default)]
+    pub auto_fit: bool,
     pub fill: Option<FillStyle>,
+    /// Padding between the box edges and the text. Zero by default so text
+    /// starts at the box edge, as it did before insets were supported.
+    #[serde(default)]
+    pub inset: Insets,
+    /// Line shape on the same slide whose direction the text follows on a
+    /// single baseline. `None` (or a missing line) lays out the box normally.
+    #[serde(default)]
+    pub path_line: Option<Uuid>,
+    /// Hidden elements stay in the document but are not drawn or exported
+    #[serde(default = "super::element::default_visible")]
+    pub visible: bool,
+    /// Name shown in the objects panel; empty uses a generated label
+    #[serde(default)]
+    pub name: String,
+    /// Locked elements can be selected but not moved or resized
+    #[serde(default)]
+    pub locked: bool,
+    #[serde(default)]
+    pub outline: Option<TextOutline>,
+    #[serde(default)]
+    pub shadow: Option<TextShadow>,
+}
+
+/// Number labels ("1.", "2.", ...) for the numbered paragraphs, bullets for
+/// the bulleted ones and `None` for the others. Consecutive list paragraphs
+/// form one list: a level continues counting until a paragraph outside the
+/// list, and deeper levels restart whenever a shallower item comes in
+/// between. A bullet item restarts the numbering of its own level.
+pub fn list_labels(paragraphs: &[TextParagraph]) -> Vec<Option<String>> {
+    let mut counters: Vec<u32> = Vec::new();
+    paragraphs
+        .iter()
+        .map(|paragraph| match paragraph.list {
+            ListStyle::Numbered => {
+                let level = paragraph.level as usize;
+                counters.resize(level + 1, 0);
+                counters[level] += 1;
+                Some(format!("{}.", counters[level]))
+            }
+            ListStyle::Bullet => {
+                counters.truncate(paragraph.level as usize);
+                Some(bullet_for_level(paragraph.level).to_string())
+            }
+            ListStyle::None => {
+                counters.clear();
+                None
+            }
+        })
+        .collect()
 }
 
 impl TextElement {
@@ -78,7 +208,149 @@ impl TextElement {
             rotation: 0.0,
             paragraphs: vec![TextParagraph::plain(text)],
             alignment: TextAlignment::Left,
+            vertical_alignment: VerticalAlignment::Top,
+            auto_fit: false,
             fill: None,
+            inset: Insets::default(),
+            path_line: None,
+            visible: true,
+            name: String::new(),
+            locked: false,
+            outline: None,
+            shadow: None,
+        }
+    }
+
+    /// The text without formatting, one line per paragraph.
+    pub fn plain_text(&self) -> String {
+        self.paragraphs
+            .iter()
+            .map(TextParagraph::full_text)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Replace the text with `text`, one paragraph per line. Lines that did
+    /// not change keep their formatting; changed lines take the paragraph
+    /// settings and first font of the paragraph they replace, and added lines
+    /// those of the last paragraph.
+    pub fn set_plain_text(&mut self, text: &str) {
+        let old = std::mem::take(&mut self.paragraphs);
+        let fallback = old
+            .last()
+            .cloned()
+            .unwrap_or_else(|| TextParagraph::plain(""));
+        self.paragraphs = text
+            .split('\n')
+            .enumerate()
+            .map(|(i, line)| {
+                let template = old.get(i).unwrap_or(&fallback);
+                if i < old.len() && template.full_text() == line {
+                    return template.clone();
+                }
+                let font = template
+                    .runs
+                    .first()
+                    .map(|run| run.font.clone())
+                    .unwrap_or_default();
+                TextParagraph {
+                    runs: vec![TextRun::new(line, font)],
+                    ..template.clone()
+                }
+            })
+            .collect();
+    }
+
+    /// Scale the font sizes and the space around paragraphs by `scale`.
+    pub fn scale_fonts(&mut self, scale: f64) {
+        for paragraph in &mut self.paragraphs {
+            paragraph.space_before *= scale;
+            paragraph.space_after *= scale;
+            for run in &mut paragraph.runs {
+                run.font.size *= scale;
+            }
         }
     }
+
+    /// The paragraph at `index`, or all paragraphs when `None`. Empty when
+    /// `index` is past the end.
+    pub fn paragraphs_at(&self, index: Option<usize>) -> &[TextParagraph] {
+        let range = match index {
+            Some(i) => i..i + 1,
+            None => 0..self.paragraphs.len(),
+        };
+        self.paragraphs.get(range).unwrap_or_default()
+    }
+
+    pub fn paragraphs_at_mut(&mut self, index: Option<usize>) -> &mut [TextParagraph] {
+        let range = match index {
+            Some(i) => i..i + 1,
+            None => 0..self.paragraphs.len(),
+        };
+        self.paragraphs.get_mut(range).unwrap_or_default()
+    }
+
+    /// The value `property` has on every run of the paragraphs at
+    /// `paragraph`, or `None` when the runs disagree (mixed formatting) or
+    /// there are no runs.
+    pub fn uniform_font_value<T: PartialEq>(
+        &self,
+        paragraph: Option<usize>,
+        property: impl Fn(&FontStyle) -> T,
+    ) -> Option<T> {
+        let mut values = self
+            .paragraphs_at(paragraph)
+            .iter()
+            .flat_map(|p| &p.runs)
+            .map(|run| property(&run.font));
+        let first = values.next()?;
+        values.all(|v| v == first).then_some(first)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(list: ListStyle, level: u32) -> TextParagraph {
+        TextParagraph {
+            list,
+            level,
+            ..TextParagraph::plain("item")
+        }
+    }
+
+    #[test]
+    fn list_labels_count_each_level_of_a_nested_list() {
+        let paragraphs = [
+            item(ListStyle::Numbered, 0),
+            item(ListStyle::Numbered, 1),
+            item(ListStyle::Numbered, 1),
+            item(ListStyle::Numbered, 0),
+            item(ListStyle::Numbered, 1),
+            item(ListStyle::Numbered, 2),
+            item(ListStyle::Numbered, 0),
+        ];
+        let labels = list_labels(&paragraphs);
+        let expected = ["1.", "1.", "2.", "2.", "1.", "1.", "3."];
+        assert_eq!(labels, expected.map(|label| Some(label.to_string())));
+    }
+
+    #[test]
+    fn list_labels_restart_after_a_paragraph_outside_the_list() {
+        let paragraphs = [
+            item(ListStyle::Numbered, 0),
+            item(ListStyle::Numbered, 0),
+            item(ListStyle::None, 0),
+            item(ListStyle::Numbered, 0),
+            item(ListStyle::Bullet, 0),
+            item(ListStyle::Numbered, 0),
+        ];
+        let labels = list_labels(&paragraphs);
+        assert_eq!(labels[1].as_deref(), Some("2."));
+        assert_eq!(labels[2], None);
+        assert_eq!(labels[3].as_deref(), Some("1."));
+        assert_eq!(labels[4].as_deref(), Some(bullet_for_level(0)));
+        assert_eq!(labels[5].as_deref(), Some("1."));
+    }
 }
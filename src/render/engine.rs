@@ -1,8 +1,10 @@
 use cairo::Context;
+use uuid::Uuid;
 
 use crate::model::element::SlideElement;
-use crate::model::geometry::Size;
-use crate::model::slide::{Background, Slide};
+use crate::model::geometry::{Point, Size};
+use crate::model::shape::ShapeType;
+use crate::model::slide::{Background, Gradient, GradientKind, Slide};
 
 use super::image_render;
 use super::shape_render;
@@ -10,22 +12,183 @@ use super::text_render;
 
 pub fn render_slide(cr: &Context, slide: &Slide, size: &Size) {
     render_background(cr, &slide.background, size);
+    render_elements(cr, slide);
+}
 
-    for element in &slide.elements {
+/// Draw the visible elements of `slide` without its background, e.g. over
+/// an overlay the editor puts on the background.
+pub fn render_elements(cr: &Context, slide: &Slide) {
+    for element in slide.elements.iter().filter(|e| e.is_visible()) {
         match element {
-            SlideElement::Text(text) => text_render::render_text(cr, text),
-            SlideElement::Image(img) => image_render::render_image(cr, img),
-            SlideElement::Shape(shape) => shape_render::render_shape(cr, shape),
+            SlideElement::Text(text) => match find_path_line(slide, text.path_line) {
+                Some((start, end)) => text_render::render_text_on_line(cr, text, start, end),
+                None => text_render::render_text(cr, text),
+            },
+            SlideElement::Image(img) => {
+                with_opacity(cr, img.opacity, || image_render::render_image(cr, img))
+            }
+            SlideElement::Shape(shape) => {
+                with_opacity(cr, shape.opacity, || shape_render::render_shape(cr, shape))
+            }
         }
     }
 }
 
-fn render_background(cr: &Context, bg: &Background, size: &Size) {
+/// Draw into a group that is painted with `opacity`, so the fill of a
+/// translucent shape does not show through its stroke.
+fn with_opacity(cr: &Context, opacity: f64, draw: impl FnOnce()) {
+    if opacity >= 1.0 {
+        draw();
+        return;
+    }
+    cr.push_group();
+    draw();
+    let _ = cr.pop_group_to_source();
+    let _ = cr.paint_with_alpha(opacity.max(0.0));
+}
+
+/// Endpoints of the line shape a text element follows, if it still exists.
+fn find_path_line(slide: &Slide, id: Option<Uuid>) -> Option<(Point, Point)> {
+    let id = id?;
+    slide.elements.iter().find_map(|element| match element {
+        SlideElement::Shape(shape) if shape.id == id && shape.shape_type == ShapeType::Line => {
+            Some(shape.line_endpoints())
+        }
+        _ => None,
+    })
+}
+
+pub fn render_background(cr: &Context, bg: &Background, size: &Size) {
     match bg {
         Background::Solid(color) => {
             cr.set_source_rgba(color.r, color.g, color.b, color.a);
             cr.rectangle(0.0, 0.0, size.width, size.height);
             let _ = cr.fill();
         }
+        Background::Gradient(gradient) => {
+            set_gradient_source(cr, gradient, size);
+            cr.rectangle(0.0, 0.0, size.width, size.height);
+            let _ = cr.fill();
+        }
+        Background::Image(image_data) => {
+            image_render::render_background_image(cr, image_data, size);
+        }
+    }
+}
+
+/// Use a gradient spanning an area of `size` at the origin, e.g. the whole
+/// slide, as the cairo source.
+pub fn set_gradient_source(cr: &Context, gradient: &Gradient, size: &Size) {
+    let cx = size.width / 2.0;
+    let cy = size.height / 2.0;
+
+    let add_stops = |pattern: &cairo::Gradient| {
+        let (start, end) = (&gradient.start, &gradient.end);
+        pattern.add_color_stop_rgba(0.0, start.r, start.g, start.b, start.a);
+        pattern.add_color_stop_rgba(1.0, end.r, end.g, end.b, end.a);
+    };
+
+    match gradient.kind {
+        GradientKind::Linear => {
+            // Project the area onto the gradient direction so both ends
+            // touch the outermost corners
+            let (sin, cos) = gradient.angle.to_radians().sin_cos();
+            let half = (size.width * cos.abs() + size.height * sin.abs()) / 2.0;
+            let pattern = cairo::LinearGradient::new(
+                cx - cos * half,
+                cy - sin * half,
+                cx + cos * half,
+                cy + sin * half,
+            );
+            add_stops(&pattern);
+            let _ = cr.set_source(&pattern);
+        }
+        GradientKind::Radial => {
+            let radius = (cx * cx + cy * cy).sqrt();
+            let pattern = cairo::RadialGradient::new(cx, cy, 0.0, cx, cy, radius);
+            add_stops(&pattern);
+            let _ = cr.set_source(&pattern);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::geometry::{Point, Rect};
+    use crate::model::shape::ShapeElement;
+    use crate::model::style::Color;
+    use crate::model::text::TextElement;
+
+    /// Red, green and blue of the pixels of `slide` drawn at one pixel per
+    /// point, with the width of a row.
+    fn render(slide: &Slide, size: &Size) -> (i32, Vec<[u8; 3]>) {
+        let (width, height) = (size.width as i32, size.height as i32);
+        let mut surface =
+            cairo::ImageSurface::create(cairo::Format::ARgb32, width, height).unwrap();
+        {
+            let cr = Context::new(&surface).unwrap();
+            render_slide(&cr, slide, size);
+        }
+        let stride = surface.stride();
+        let data = surface.data().unwrap();
+        let pixels = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (y * stride + x * 4) as usize))
+            .map(|at| {
+                let pixel = u32::from_ne_bytes(data[at..at + 4].try_into().unwrap());
+                [(pixel >> 16) as u8, (pixel >> 8) as u8, pixel as u8]
+            })
+            .collect();
+        (width, pixels)
+    }
+
+    #[test]
+    fn gradient_background_runs_between_its_colors() {
+        let mut slide = Slide::new();
+        let (red, blue) = (Color::rgb(1.0, 0.0, 0.0), Color::rgb(0.0, 0.0, 1.0));
+        slide.background = Background::Gradient(Gradient::linear(red, blue, 0.0));
+
+        let (width, pixels) = render(&slide, &Size::new(200.0, 100.0));
+        let at = |x: i32, y: i32| pixels[(y * width + x) as usize];
+        // Left to right, so both left corners are red and both right ones blue
+        for y in [0, 99] {
+            let [r, g, b] = at(0, y);
+            assert!(r > 245 && g < 10 && b < 10, "{:?}", (r, g, b));
+            let [r, g, b] = at(199, y);
+            assert!(r < 10 && g < 10 && b > 245, "{:?}", (r, g, b));
+        }
+        let [r, _, b] = at(100, 50);
+        assert!(r.abs_diff(b) < 10, "{:?}", (r, b));
+    }
+
+    #[test]
+    fn text_on_a_line_is_drawn_along_it() {
+        let mut slide = Slide::new();
+        // A line running upwards at x = 50, left of the text box
+        let line = ShapeElement::line(Point::new(50.0, 250.0), Point::new(50.0, 50.0));
+        let mut text = TextElement::new(Rect::new(200.0, 100.0, 150.0, 50.0), "Lumina");
+        text.path_line = Some(line.id);
+        slide.elements.push(SlideElement::Shape(line));
+        slide.elements.push(SlideElement::Text(text.clone()));
+
+        let dark_pixels = |slide: &Slide, area: Rect| {
+            let (width, pixels) = render(slide, &Size::new(400.0, 300.0));
+            let (x, y) = (area.origin.x as i32, area.origin.y as i32);
+            let (right, bottom) = (area.right() as i32, area.bottom() as i32);
+            (y..bottom)
+                .flat_map(|y| (x..right).map(move |x| (x, y)))
+                .filter(|&(x, y)| pixels[(y * width + x) as usize][0] < 128)
+                .count()
+        };
+        // Glyphs stand on the line, so turned with it they lie to its left
+        let beside_line = Rect::new(10.0, 50.0, 36.0, 200.0);
+        assert!(dark_pixels(&slide, beside_line) > 50);
+        assert_eq!(dark_pixels(&slide, text.bounds), 0);
+
+        // Without the line the text stays in its box
+        text.path_line = None;
+        slide.elements[1] = SlideElement::Text(text.clone());
+        assert_eq!(dark_pixels(&slide, beside_line), 0);
+        assert!(dark_pixels(&slide, text.bounds) > 50);
     }
 }
@@ -0,0 +1,56 @@
+use pango::prelude::*;
+
+/// Generic family names that fontconfig always resolves to some font.
+const GENERIC_FAMILIES: &[&str] = &["sans", "sans-serif", "serif", "monospace", "system-ui"];
+
+/// A font family that is not installed, together with the family pango
+/// substitutes when laying out text in it.
+#[derive(Debug, Clone)]
+pub struct MissingFont {
+    pub family: String,
+    pub substitute: String,
+}
+
+/// Names of all installed font families, sorted case-insensitively.
+pub fn installed_families() -> Vec<String> {
+    let font_map = pangocairo::FontMap::default();
+    let mut families: Vec<String> = font_map
+        .list_families()
+        .iter()
+        .map(|family| family.name().to_string())
+        .collect();
+    families.sort_by_key(|name| name.to_lowercase());
+    families
+}
+
+/// Check which of `families` are not installed on this system.
+pub fn find_missing_fonts(families: &[String]) -> Vec<MissingFont> {
+    let font_map = pangocairo::FontMap::default();
+    let installed: Vec<String> = font_map
+        .list_families()
+        .iter()
+        .map(|family| family.name().to_lowercase())
+        .collect();
+    let context = font_map.create_context();
+
+    families
+        .iter()
+        .filter(|family| {
+            let name = family.to_lowercase();
+            !GENERIC_FAMILIES.contains(&name.as_str()) && !installed.contains(&name)
+        })
+        .map(|family| {
+            let mut desc = pango::FontDescription::new();
+            desc.set_family(family);
+            let substitute = font_map
+                .load_font(&context, &desc)
+                .and_then(|font| font.describe().family())
+                .map(|name| name.to_string())
+                .unwrap_or_default();
+            MissingFont {
+                family: family.clone(),
+                substitute,
+            }
+        })
+        .collect()
+}
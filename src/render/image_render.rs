@@ -1,7 +1,106 @@
 use cairo::Context;
 use gdk_pixbuf::prelude::*;
+use gettextrs::gettext;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::rc::Rc;
+use std::sync::Arc;
+use uuid::Uuid;
 
-use crate::model::image::{ImageData, ImageElement, ScaleMode};
+use crate::model::element::SlideElement;
+use crate::model::geometry::Size;
+use crate::model::image::{ImageData, ImageElement, ImageFilters, ScaleMode};
+use crate::model::slide::{Background, Slide};
+
+/// Decoded images kept at most; the one drawn longest ago is dropped first
+const MAX_CACHED_IMAGES: usize = 64;
+/// Decoded background pictures kept at most
+const MAX_CACHED_BACKGROUNDS: usize = 32;
+/// Fallback size in pixels of an SVG that only gives relative dimensions
+const DEFAULT_SVG_SIZE: f64 = 100.0;
+
+/// Image data ready to draw
+#[derive(Clone)]
+enum Decoded {
+    Raster(cairo::ImageSurface),
+    /// Drawn as vectors, so it stays sharp at every zoom and export scale
+    Svg(Rc<rsvg::SvgHandle>),
+}
+
+impl Decoded {
+    /// Natural size in pixels, the space crops and scaling work in
+    fn size(&self) -> (f64, f64) {
+        match self {
+            Decoded::Raster(surface) => (surface.width() as f64, surface.height() as f64),
+            Decoded::Svg(handle) => {
+                let renderer = rsvg::CairoRenderer::new(handle);
+                renderer
+                    .intrinsic_size_in_pixels()
+                    .or_else(|| {
+                        let vbox = renderer.intrinsic_dimensions().vbox?;
+                        Some((vbox.width(), vbox.height()))
+                    })
+                    .filter(|&(w, h)| w > 0.0 && h > 0.0)
+                    .unwrap_or((DEFAULT_SVG_SIZE, DEFAULT_SVG_SIZE))
+            }
+        }
+    }
+
+    /// Paint the image at its natural size with its top-left corner at
+    /// (`x`, `y`). `pad_edges` keeps the edges of a scaled-up raster image
+    /// opaque where it meets the clip.
+    fn paint(&self, cr: &Context, x: f64, y: f64, pad_edges: bool) {
+        match self {
+            Decoded::Raster(surface) => {
+                cr.set_source_surface(surface, x, y)
+                    .expect("set source surface");
+                if pad_edges {
+                    cr.source().set_extend(cairo::Extend::Pad);
+                }
+                let _ = cr.paint();
+            }
+            Decoded::Svg(handle) => {
+                let (width, height) = self.size();
+                let viewport = cairo::Rectangle::new(x, y, width, height);
+                if let Err(e) = rsvg::CairoRenderer::new(handle).render_document(cr, &viewport) {
+                    eprintln!("SVG render error: {}", e);
+                }
+            }
+        }
+    }
+}
+
+struct CachedSurface {
+    /// Hash of the data, which a replaced picture changes while the
+    /// element keeps its id
+    data_hash: u64,
+    mime: String,
+    filters: ImageFilters,
+    /// `None` when the data could not be decoded, so it is not retried on
+    /// every redraw
+    surface: Option<Decoded>,
+    last_used: u64,
+}
+
+struct CachedBackground {
+    /// Data the picture was decoded from, compared on lookup as backgrounds
+    /// have no id
+    data: Arc<[u8]>,
+    decoded: Option<Decoded>,
+    last_used: u64,
+}
+
+thread_local! {
+    /// Decoded and filtered surfaces keyed by element id, so redraws don't
+    /// re-run the pixbuf loader and filters on every frame.
+    static SURFACE_CACHE: RefCell<HashMap<Uuid, CachedSurface>> = RefCell::new(HashMap::new());
+
+    static BACKGROUND_CACHE: RefCell<Vec<CachedBackground>> = const { RefCell::new(Vec::new()) };
+
+    /// Counts cache lookups, to tell which entry was drawn longest ago
+    static CACHE_CLOCK: Cell<u64> = const { Cell::new(0) };
+}
 
 pub fn render_image(cr: &Context, image: &ImageElement) {
     let bounds = &image.bounds;
@@ -15,65 +114,289 @@ pub fn render_image(cr: &Context, image: &ImageElement) {
         cr.translate(-bounds.size.width / 2.0, -bounds.size.height / 2.0);
     }
 
-    let ImageData::Embedded { ref data, ref mime } = image.image_data;
-    let _ = mime;
+    if image.flip_h || image.flip_v {
+        cr.translate(bounds.size.width / 2.0, bounds.size.height / 2.0);
+        cr.scale(
+            if image.flip_h { -1.0 } else { 1.0 },
+            if image.flip_v { -1.0 } else { 1.0 },
+        );
+        cr.translate(-bounds.size.width / 2.0, -bounds.size.height / 2.0);
+    }
 
-    let pixbuf_loader = gdk_pixbuf::PixbufLoader::new();
-    if pixbuf_loader.write(data).is_ok() {
-        let _ = pixbuf_loader.close();
-        if let Some(pixbuf) = pixbuf_loader.pixbuf() {
-            let img_width = pixbuf.width() as f64;
-            let img_height = pixbuf.height() as f64;
-
-            let (scale_x, scale_y, offset_x, offset_y) = match image.scale_mode {
-                ScaleMode::Stretch => {
-                    let sx = bounds.size.width / img_width;
-                    let sy = bounds.size.height / img_height;
-                    (sx, sy, 0.0, 0.0)
-                }
-                ScaleMode::Fit => {
-                    let scale =
-                        (bounds.size.width / img_width).min(bounds.size.height / img_height);
-                    let offset_x = (bounds.size.width - img_width * scale) / 2.0;
-                    let offset_y = (bounds.size.height - img_height * scale) / 2.0;
-                    (scale, scale, offset_x, offset_y)
-                }
-                ScaleMode::Fill => {
-                    let scale =
-                        (bounds.size.width / img_width).max(bounds.size.height / img_height);
-                    let offset_x = (bounds.size.width - img_width * scale) / 2.0;
-                    let offset_y = (bounds.size.height - img_height * scale) / 2.0;
-                    (scale, scale, offset_x, offset_y)
-                }
-            };
+    if let Some(decoded) = decoded_surface(image) {
+        // Only the uncropped part is placed in the bounds
+        let (natural_width, natural_height) = decoded.size();
+        let crop = &image.crop;
+        let crop_x = natural_width * crop.left;
+        let crop_y = natural_height * crop.top;
+        let img_width = (natural_width * (1.0 - crop.left - crop.right)).max(1.0);
+        let img_height = (natural_height * (1.0 - crop.top - crop.bottom)).max(1.0);
 
-            // Clip to bounds
-            cr.rectangle(0.0, 0.0, bounds.size.width, bounds.size.height);
-            cr.clip();
+        let (scale_x, scale_y, offset_x, offset_y) = match image.scale_mode {
+            ScaleMode::Stretch => {
+                let sx = bounds.size.width / img_width;
+                let sy = bounds.size.height / img_height;
+                (sx, sy, 0.0, 0.0)
+            }
+            ScaleMode::Fit => {
+                let scale = (bounds.size.width / img_width).min(bounds.size.height / img_height);
+                let offset_x = (bounds.size.width - img_width * scale) / 2.0;
+                let offset_y = (bounds.size.height - img_height * scale) / 2.0;
+                (scale, scale, offset_x, offset_y)
+            }
+            ScaleMode::Fill => {
+                let scale = (bounds.size.width / img_width).max(bounds.size.height / img_height);
+                let offset_x = (bounds.size.width - img_width * scale) / 2.0;
+                let offset_y = (bounds.size.height - img_height * scale) / 2.0;
+                (scale, scale, offset_x, offset_y)
+            }
+        };
 
-            cr.translate(offset_x, offset_y);
-            cr.scale(scale_x, scale_y);
+        // Clip to bounds
+        cr.rectangle(0.0, 0.0, bounds.size.width, bounds.size.height);
+        cr.clip();
 
-            // Convert Pixbuf to Cairo ImageSurface
-            if let Some(surface) = pixbuf_to_surface(&pixbuf) {
-                cr.set_source_surface(&surface, 0.0, 0.0)
-                    .expect("set source surface");
-                let _ = cr.paint();
-            }
+        cr.translate(offset_x, offset_y);
+        cr.scale(scale_x, scale_y);
+        if !crop.is_empty() {
+            cr.rectangle(0.0, 0.0, img_width, img_height);
+            cr.clip();
         }
+
+        decoded.paint(cr, -crop_x, -crop_y, false);
     } else {
-        let _ = pixbuf_loader.close();
+        render_placeholder(cr, image);
     }
 
     cr.restore().expect("cairo restore");
 }
 
-fn pixbuf_to_surface(pixbuf: &gdk_pixbuf::Pixbuf) -> Option<cairo::ImageSurface> {
+/// Stretch a background picture over an area of `size` at the origin. A
+/// picture that cannot be decoded leaves the area gray.
+pub fn render_background_image(cr: &Context, image_data: &ImageData, size: &Size) {
+    cr.save().expect("cairo save");
+    cr.rectangle(0.0, 0.0, size.width, size.height);
+    match decoded_background(image_data) {
+        Some(decoded) => {
+            cr.clip();
+            let (width, height) = decoded.size();
+            cr.scale(size.width / width, size.height / height);
+            decoded.paint(cr, 0.0, 0.0, true);
+        }
+        None => {
+            cr.set_source_rgb(0.85, 0.85, 0.85);
+            let _ = cr.fill();
+        }
+    }
+    cr.restore().expect("cairo restore");
+}
+
+/// Stand-in for an image that cannot be decoded: a gray box crossed out and
+/// labeled with the image name and type, so the gap is obvious in exports.
+fn render_placeholder(cr: &Context, image: &ImageElement) {
+    let (width, height) = (image.bounds.size.width, image.bounds.size.height);
+
+    cr.rectangle(0.0, 0.0, width, height);
+    cr.set_source_rgb(0.85, 0.85, 0.85);
+    let _ = cr.fill_preserve();
+    cr.set_source_rgb(0.55, 0.55, 0.55);
+    cr.set_line_width(1.0);
+    cr.move_to(0.0, 0.0);
+    cr.line_to(width, height);
+    cr.move_to(width, 0.0);
+    cr.line_to(0.0, height);
+    let _ = cr.stroke();
+
+    let ImageData::Embedded { ref mime, .. } = image.image_data;
+    let kind = match mime.as_str() {
+        "" => "?".to_string(),
+        "image/x-emf" => gettext("EMF (not supported)"),
+        "image/x-wmf" => gettext("WMF (not supported)"),
+        mime => mime.to_string(),
+    };
+    let label = if image.name.is_empty() {
+        kind
+    } else {
+        format!("{}\n{}", image.name, kind)
+    };
+    let layout = pangocairo::functions::create_layout(cr);
+    let mut font = pango::FontDescription::from_string("Sans");
+    font.set_size(((height / 8.0).clamp(6.0, 14.0) * pango::SCALE as f64) as i32);
+    layout.set_font_description(Some(&font));
+    layout.set_alignment(pango::Alignment::Center);
+    layout.set_text(&label);
+    let (_, extents) = layout.pixel_extents();
+    let (text_width, text_height) = (extents.width() as f64, extents.height() as f64);
+
+    // Label on a backing so the cross doesn't run through it
+    let (x, y) = ((width - text_width) / 2.0, (height - text_height) / 2.0);
+    cr.rectangle(x - 4.0, y - 2.0, text_width + 8.0, text_height + 4.0);
+    cr.set_source_rgb(0.85, 0.85, 0.85);
+    let _ = cr.fill();
+    cr.move_to(x, y);
+    cr.set_source_rgb(0.3, 0.3, 0.3);
+    pangocairo::functions::show_layout(cr, &layout);
+}
+
+/// Log the images on slide `index` (0-based) that cannot be decoded and are
+/// exported as placeholders.
+pub fn log_undecodable_images(index: usize, slide: &Slide) {
+    if let Background::Image(image_data) = &slide.background {
+        if decoded_background(image_data).is_none() {
+            let ImageData::Embedded { ref mime, .. } = *image_data;
+            eprintln!(
+                "Slide {}: background picture ({}) could not be decoded, exported gray",
+                index + 1,
+                mime
+            );
+        }
+    }
+    for element in &slide.elements {
+        if let SlideElement::Image(image) = element {
+            if image.visible && decoded_surface(image).is_none() {
+                let ImageData::Embedded { ref mime, .. } = image.image_data;
+                eprintln!(
+                    "Slide {}: image {} ({}) could not be decoded, exported as a placeholder",
+                    index + 1,
+                    image.id,
+                    mime
+                );
+            }
+        }
+    }
+}
+
+/// Size of the whole picture of `image` in pixels, before cropping. `None`
+/// when it cannot be decoded.
+pub fn natural_size(image: &ImageElement) -> Option<Size> {
+    let (width, height) = decoded_surface(image)?.size();
+    Some(Size::new(width, height))
+}
+
+/// Return the decoded image, decoding and filtering it only when the
+/// element is new or its data or filters changed.
+fn decoded_surface(image: &ImageElement) -> Option<Decoded> {
+    let ImageData::Embedded { ref data, ref mime } = image.image_data;
+
+    let now = tick();
+    SURFACE_CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        let data_hash = hash_data(data);
+        if let Some(entry) = cache.get_mut(&image.id) {
+            if entry.data_hash == data_hash
+                && entry.mime == *mime
+                && entry.filters == image.filters
+            {
+                entry.last_used = now;
+                return entry.surface.clone();
+            }
+        }
+
+        if cache.len() >= MAX_CACHED_IMAGES && !cache.contains_key(&image.id) {
+            let oldest = cache.iter().min_by_key(|(_, entry)| entry.last_used).map(|(id, _)| *id);
+            if let Some(oldest) = oldest {
+                cache.remove(&oldest);
+            }
+        }
+        let surface = decode(data, mime, &image.filters);
+        cache.insert(
+            image.id,
+            CachedSurface {
+                data_hash,
+                mime: mime.clone(),
+                filters: image.filters,
+                surface: surface.clone(),
+                last_used: now,
+            },
+        );
+        surface
+    })
+}
+
+/// Return the decoded surface for a background picture, decoding it only
+/// the first time the same data is drawn.
+fn decoded_background(image_data: &ImageData) -> Option<Decoded> {
+    let ImageData::Embedded { ref data, ref mime } = *image_data;
+
+    let now = tick();
+    BACKGROUND_CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        if let Some(entry) = cache.iter_mut().find(|entry| entry.data == *data) {
+            entry.last_used = now;
+            return entry.decoded.clone();
+        }
+
+        if cache.len() >= MAX_CACHED_BACKGROUNDS {
+            let oldest = (0..cache.len()).min_by_key(|&i| cache[i].last_used);
+            if let Some(oldest) = oldest {
+                cache.swap_remove(oldest);
+            }
+        }
+        let decoded = decode(data, mime, &ImageFilters::default());
+        cache.push(CachedBackground {
+            data: data.clone(),
+            decoded: decoded.clone(),
+            last_used: now,
+        });
+        decoded
+    })
+}
+
+/// Hash of image data, to notice a changed picture without keeping a copy.
+fn hash_data(data: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    data.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Advance the cache clock and return the new time.
+fn tick() -> u64 {
+    CACHE_CLOCK.with(|clock| {
+        let now = clock.get() + 1;
+        clock.set(now);
+        now
+    })
+}
+
+/// Decode SVGs for drawing as vectors, other formats and filtered SVGs
+/// through the pixbuf loader.
+fn decode(data: &[u8], mime: &str, filters: &ImageFilters) -> Option<Decoded> {
+    if mime == "image/svg+xml" && filters.is_identity() {
+        if let Some(handle) = load_svg(data) {
+            return Some(Decoded::Svg(Rc::new(handle)));
+        }
+    }
+    decode_surface(data, filters).map(Decoded::Raster)
+}
+
+fn load_svg(data: &[u8]) -> Option<rsvg::SvgHandle> {
+    let stream = gio::MemoryInputStream::from_bytes(&glib::Bytes::from(data));
+    rsvg::Loader::new()
+        .read_stream(&stream, None::<&gio::File>, None::<&gio::Cancellable>)
+        .ok()
+}
+
+fn decode_surface(data: &[u8], filters: &ImageFilters) -> Option<cairo::ImageSurface> {
+    let pixbuf_loader = gdk_pixbuf::PixbufLoader::new();
+    if pixbuf_loader.write(data).is_err() {
+        let _ = pixbuf_loader.close();
+        return None;
+    }
+    let _ = pixbuf_loader.close();
+    let pixbuf = pixbuf_loader.pixbuf()?;
+    pixbuf_to_surface(&pixbuf, filters)
+}
+
+fn pixbuf_to_surface(
+    pixbuf: &gdk_pixbuf::Pixbuf,
+    filters: &ImageFilters,
+) -> Option<cairo::ImageSurface> {
     let width = pixbuf.width();
     let height = pixbuf.height();
     let has_alpha = pixbuf.has_alpha();
     let src_stride = pixbuf.rowstride() as usize;
     let pixels = unsafe { pixbuf.pixels() };
+    let apply_filters = !filters.is_identity();
 
     let format = if has_alpha {
         cairo::Format::ARgb32
@@ -91,7 +414,7 @@ fn pixbuf_to_surface(pixbuf: &gdk_pixbuf::Pixbuf) -> Option<cairo::ImageSurface>
             let dst_row = &mut data[y * dst_stride..];
 
             for x in 0..width as usize {
-                let (r, g, b, a) = if has_alpha {
+                let (mut r, mut g, mut b, a) = if has_alpha {
                     let offset = x * 4;
                     (
                         src_row[offset] as u32,
@@ -109,6 +432,10 @@ fn pixbuf_to_surface(pixbuf: &gdk_pixbuf::Pixbuf) -> Option<cairo::ImageSurface>
                     )
                 };
 
+                if apply_filters {
+                    (r, g, b) = filter_pixel(r, g, b, filters);
+                }
+
                 // Cairo expects premultiplied ARGB in native byte order
                 let pr = r * a / 255;
                 let pg = g * a / 255;
@@ -126,3 +453,24 @@ fn pixbuf_to_surface(pixbuf: &gdk_pixbuf::Pixbuf) -> Option<cairo::ImageSurface>
 
     Some(surface)
 }
+
+/// Apply saturation, contrast and brightness (in that order) to one
+/// unpremultiplied pixel.
+fn filter_pixel(r: u32, g: u32, b: u32, filters: &ImageFilters) -> (u32, u32, u32) {
+    let saturation = if filters.grayscale {
+        0.0
+    } else {
+        filters.saturation
+    };
+    let (r, g, b) = (r as f64, g as f64, b as f64);
+    let luma = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+
+    let adjust = |v: f64| -> u32 {
+        let v = luma + (v - luma) * saturation;
+        let v = (v - 127.5) * filters.contrast + 127.5;
+        let v = v + filters.brightness * 255.0;
+        v.round().clamp(0.0, 255.0) as u32
+    };
+
+    (adjust(r), adjust(g), adjust(b))
+}
@@ -1,5 +1,8 @@
 pub mod engine;
+pub mod fonts;
 pub mod image_render;
 pub mod pdf_export;
+pub mod raster;
 pub mod shape_render;
+pub mod svg_export;
 pub mod text_render;
@@ -2,10 +2,58 @@ use std::io;
 use std::path::Path;
 
 use crate::model::document::Document;
+use crate::model::geometry::Size;
+use crate::model::slide::Slide;
 
-use super::engine;
+use super::{engine, image_render};
+use super::fonts::{self, MissingFont};
+
+/// Width of the slide on a notes page, as a fraction of the page width
+const NOTES_SLIDE_SCALE: f64 = 0.5;
+/// Space around the slide and the notes on a notes page, in points
+const NOTES_MARGIN: f64 = 36.0;
+const NOTES_FONT: &str = "Sans 12";
+
+/// Fonts the document uses that are not installed. Cairo embeds a subset of
+/// every font it draws with, so the PDF contains their substitutes instead.
+pub fn substituted_fonts(doc: &Document) -> Vec<MissingFont> {
+    fonts::find_missing_fonts(&doc.font_families())
+}
+
+/// Slide indices of a range such as "1-3, 5" of slide numbers, in order and
+/// each once. `None` when it is not a valid range of the `count` slides.
+pub fn parse_slide_range(text: &str, count: usize) -> Option<Vec<usize>> {
+    let mut slides = Vec::new();
+    for part in text.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let (first, last) = match part.split_once('-') {
+            Some((first, last)) => (first.trim(), last.trim()),
+            None => (part, part),
+        };
+        let first: usize = first.parse().ok()?;
+        let last: usize = last.parse().ok()?;
+        if first == 0 || first > last || last > count {
+            return None;
+        }
+        slides.extend(first - 1..last);
+    }
+    slides.sort_unstable();
+    slides.dedup();
+    (!slides.is_empty()).then_some(slides)
+}
+
+/// Write the slides at the indices `slides` as pages of a PDF, one point
+/// per slide unit. Text stays text, so it can be selected and searched.
+/// With `notes`, every slide is followed by a handout page showing it
+/// smaller above its speaker notes.
+pub fn export_pdf(doc: &Document, slides: &[usize], notes: bool, path: &Path) -> io::Result<()> {
+    let slides: Vec<_> = slides
+        .iter()
+        .filter_map(|&i| doc.slides.get(i).map(|slide| (i, slide)))
+        .collect();
+    if slides.is_empty() {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "No slides to export"));
+    }
 
-pub fn export_pdf(doc: &Document, path: &Path) -> io::Result<()> {
     let slide_size = &doc.slide_size;
     let pdf_width = slide_size.width;
     let pdf_height = slide_size.height;
@@ -16,18 +64,147 @@ pub fn export_pdf(doc: &Document, path: &Path) -> io::Result<()> {
     let cr = cairo::Context::new(&surface)
         .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("Cairo context error: {}", e)))?;
 
-    for (i, slide) in doc.slides.iter().enumerate() {
-        if i > 0 {
-            cr.show_page()
-                .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
+    let show_page = || {
+        cr.show_page()
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))
+    };
+    for (page, (i, slide)) in slides.into_iter().enumerate() {
+        if page > 0 {
+            show_page()?;
         }
 
+        image_render::log_undecodable_images(i, slide);
         engine::render_slide(&cr, slide, slide_size);
+
+        if notes {
+            show_page()?;
+            render_notes_page(&cr, slide, slide_size);
+        }
     }
 
-    cr.show_page()
-        .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
+    show_page()?;
 
     surface.finish();
     Ok(())
 }
+
+/// A page of the slide size with `slide` shrunk at the top and its speaker
+/// notes below, cut off where they leave the page.
+fn render_notes_page(cr: &cairo::Context, slide: &Slide, size: &Size) {
+    let scale = NOTES_SLIDE_SCALE;
+    let (slide_width, slide_height) = (size.width * scale, size.height * scale);
+    let slide_x = (size.width - slide_width) / 2.0;
+
+    cr.save().expect("save");
+    cr.translate(slide_x, NOTES_MARGIN);
+    cr.rectangle(0.0, 0.0, slide_width, slide_height);
+    cr.clip();
+    cr.scale(scale, scale);
+    engine::render_slide(cr, slide, size);
+    cr.restore().expect("restore");
+
+    cr.set_source_rgba(0.0, 0.0, 0.0, 0.3);
+    cr.set_line_width(0.5);
+    cr.rectangle(slide_x, NOTES_MARGIN, slide_width, slide_height);
+    let _ = cr.stroke();
+
+    let notes = slide.notes.trim();
+    if notes.is_empty() {
+        return;
+    }
+    let top = NOTES_MARGIN + slide_height + NOTES_MARGIN / 2.0;
+    let layout = pangocairo::functions::create_layout(cr);
+    layout.set_font_description(Some(&pango::FontDescription::from_string(NOTES_FONT)));
+    layout.set_width(((size.width - 2.0 * NOTES_MARGIN) * pango::SCALE as f64) as i32);
+    layout.set_wrap(pango::WrapMode::WordChar);
+    layout.set_text(notes);
+
+    cr.save().expect("save");
+    cr.rectangle(0.0, top, size.width, size.height - NOTES_MARGIN - top);
+    cr.clip();
+    cr.move_to(NOTES_MARGIN, top);
+    cr.set_source_rgb(0.0, 0.0, 0.0);
+    pangocairo::functions::show_layout(cr, &layout);
+    cr.restore().expect("restore");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::element::SlideElement;
+    use crate::model::geometry::Rect;
+    use crate::model::image::ImageElement;
+    use crate::model::text::TextElement;
+    use std::io::Read;
+
+    /// Export `doc` to a temporary file and return the bytes written.
+    fn export(doc: &Document) -> io::Result<Vec<u8>> {
+        let path = std::env::temp_dir().join(format!("lumina-test-{}.pdf", uuid::Uuid::new_v4()));
+        let result = export_pdf(doc, &[0], false, &path);
+        let pdf = std::fs::read(&path);
+        let _ = std::fs::remove_file(&path);
+        result.and(pdf)
+    }
+
+    /// `pdf` followed by each of its streams inflated, so objects Cairo
+    /// packs into compressed object streams can be searched too.
+    fn inflated(pdf: &[u8]) -> Vec<u8> {
+        let mut out = pdf.to_vec();
+        let mut rest = pdf;
+        while let Some(start) = rest.windows(6).position(|w| w == b"stream") {
+            rest = &rest[start + 6..];
+            let body = rest.strip_prefix(b"\r").unwrap_or(rest);
+            let body = body.strip_prefix(b"\n").unwrap_or(body);
+            let _ = flate2::read::ZlibDecoder::new(body).read_to_end(&mut out);
+        }
+        out
+    }
+
+    fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+        haystack.windows(needle.len()).any(|w| w == needle)
+    }
+
+    #[test]
+    fn slide_range_lists_each_slide_once_in_order() {
+        assert_eq!(parse_slide_range("1-3,5", 6), Some(vec![0, 1, 2, 4]));
+        assert_eq!(parse_slide_range("5, 1-3, 2", 6), Some(vec![0, 1, 2, 4]));
+        assert_eq!(parse_slide_range("  2 - 4 ,  6 ", 6), Some(vec![1, 2, 3, 5]));
+        assert_eq!(parse_slide_range("4", 4), Some(vec![3]));
+    }
+
+    #[test]
+    fn slide_range_rejects_what_is_not_a_range_of_the_slides() {
+        assert_eq!(parse_slide_range("3-1", 6), None);
+        assert_eq!(parse_slide_range("0", 6), None);
+        assert_eq!(parse_slide_range("0-2", 6), None);
+        assert_eq!(parse_slide_range("7", 6), None);
+        assert_eq!(parse_slide_range("5-7", 6), None);
+        assert_eq!(parse_slide_range("1-", 6), None);
+        assert_eq!(parse_slide_range("two", 6), None);
+        assert_eq!(parse_slide_range("", 6), None);
+        assert_eq!(parse_slide_range(" , ", 6), None);
+    }
+
+    #[test]
+    fn text_is_exported_as_text() {
+        let mut doc = Document::new();
+        let text = TextElement::new(Rect::new(50.0, 50.0, 400.0, 100.0), "Quarterly results");
+        doc.slides[0].elements.push(SlideElement::Text(text));
+
+        let pdf = inflated(&export(&doc).unwrap());
+        assert!(contains(&pdf, b"/Font"));
+        // Maps the glyphs back to characters for selecting and searching
+        assert!(contains(&pdf, b"/ToUnicode"));
+    }
+
+    #[test]
+    fn document_with_a_corrupt_picture_still_exports() {
+        let mut doc = Document::new();
+        let bounds = Rect::new(50.0, 50.0, 200.0, 100.0);
+        let corrupt = b"\xFF\xD8\xFFnot really a picture".to_vec();
+        let image = ImageElement::new(bounds, corrupt, "image/jpeg".into());
+        doc.slides[0].elements.push(SlideElement::Image(image));
+
+        assert!(export(&doc).unwrap().starts_with(b"%PDF"));
+    }
+}
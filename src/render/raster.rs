@@ -0,0 +1,69 @@
+use std::io;
+
+use crate::model::geometry::Size;
+use crate::model::slide::Slide;
+
+use super::engine;
+
+/// Render a slide to PNG bytes, `scale` pixels per point.
+pub fn render_slide_png(slide: &Slide, slide_size: &Size, scale: f64) -> io::Result<Vec<u8>> {
+    let width = (slide_size.width * scale).round().max(1.0) as i32;
+    let height = (slide_size.height * scale).round().max(1.0) as i32;
+
+    let surface = cairo::ImageSurface::create(cairo::Format::ARgb32, width, height)
+        .map_err(|e| io::Error::other(format!("Cairo surface error: {}", e)))?;
+    {
+        let cr = cairo::Context::new(&surface)
+            .map_err(|e| io::Error::other(format!("Cairo context error: {}", e)))?;
+        cr.scale(scale, scale);
+        engine::render_slide(&cr, slide, slide_size);
+    }
+
+    let mut png = Vec::new();
+    surface
+        .write_to_png(&mut png)
+        .map_err(|e| io::Error::other(e.to_string()))?;
+    Ok(png)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::element::SlideElement;
+    use crate::model::geometry::Rect;
+    use crate::model::image::ImageElement;
+
+    /// Red, green and blue of the pixels of a rendered PNG.
+    fn decode(png: &[u8]) -> (i32, Vec<[u8; 3]>) {
+        let mut surface = cairo::ImageSurface::create_from_png(&mut &png[..]).unwrap();
+        let (width, height, stride) = (surface.width(), surface.height(), surface.stride());
+        let data = surface.data().unwrap();
+        let pixels = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (y * stride + x * 4) as usize))
+            .map(|at| {
+                let pixel = u32::from_ne_bytes(data[at..at + 4].try_into().unwrap());
+                [(pixel >> 16) as u8, (pixel >> 8) as u8, pixel as u8]
+            })
+            .collect();
+        (width, pixels)
+    }
+
+    #[test]
+    fn undecodable_picture_is_drawn_as_a_placeholder() {
+        let mut slide = Slide::new();
+        let bounds = Rect::new(50.0, 50.0, 200.0, 100.0);
+        let corrupt = b"\x89PNG\r\n\x1a\nnot really a picture".to_vec();
+        let image = ImageElement::new(bounds, corrupt, "image/png".into());
+        slide.elements.push(SlideElement::Image(image));
+        let png = render_slide_png(&slide, &Size::new(300.0, 200.0), 1.0).unwrap();
+
+        let (width, pixels) = decode(&png);
+        let at = |x: i32, y: i32| pixels[(y * width + x) as usize];
+        // A gray box with a cross, on the white slide
+        assert_eq!(at(10, 10), [255, 255, 255]);
+        let [r, g, b] = at(60, 120);
+        assert!(r == g && g == b && (200..240).contains(&r), "{:?}", (r, g, b));
+        // The cross runs from (50, 50) through (70, 60)
+        assert!((55..65).any(|y| at(70, y)[0] < 200));
+    }
+}
@@ -1,7 +1,11 @@
 use cairo::Context;
 use std::f64::consts::PI;
 
-use crate::model::shape::{ShapeElement, ShapeType};
+use crate::model::geometry::Point;
+use crate::model::shape::{LineMarker, ShapeElement, ShapeType};
+use crate::model::style::{LineCap, LineJoin};
+
+use super::engine;
 
 pub fn render_shape(cr: &Context, shape: &ShapeElement) {
     let bounds = &shape.bounds;
@@ -29,14 +33,41 @@ pub fn render_shape(cr: &Context, shape: &ShapeElement) {
             cr.restore().expect("cairo restore");
         }
         ShapeType::Line => {
-            cr.move_to(0.0, 0.0);
-            cr.line_to(bounds.size.width, bounds.size.height);
+            // The stroke stops inside an arrowhead, so its end does not
+            // stick out at the tip
+            let (start, end) = local_line_endpoints(shape);
+            let size = shape.marker_size();
+            let inset = |marker| if marker == LineMarker::Arrow { size / 2.0 } else { 0.0 };
+            let line_start = toward(start, end, inset(shape.start_marker));
+            let line_end = toward(end, start, inset(shape.end_marker));
+            cr.move_to(line_start.x, line_start.y);
+            cr.line_to(line_end.x, line_end.y);
+        }
+        ShapeType::RoundedRectangle { radius } => {
+            let (w, h) = (bounds.size.width, bounds.size.height);
+            let r = radius.min(w / 2.0).min(h / 2.0).max(0.0);
+            cr.new_sub_path();
+            cr.arc(w - r, r, r, -PI / 2.0, 0.0);
+            cr.arc(w - r, h - r, r, 0.0, PI / 2.0);
+            cr.arc(r, h - r, r, PI / 2.0, PI);
+            cr.arc(r, r, r, PI, 3.0 * PI / 2.0);
+            cr.close_path();
+        }
+        ShapeType::Triangle | ShapeType::Star { .. } | ShapeType::Arrow => {
+            let corners = shape.shape_type.polygon(bounds.size).unwrap_or_default();
+            for corner in &corners {
+                cr.line_to(corner.x, corner.y);
+            }
+            cr.close_path();
         }
     }
 
     if shape.shape_type != ShapeType::Line {
         if let Some(fill) = &shape.fill {
-            cr.set_source_rgba(fill.color.r, fill.color.g, fill.color.b, fill.color.a);
+            match &fill.gradient {
+                Some(gradient) => engine::set_gradient_source(cr, gradient, &bounds.size),
+                None => cr.set_source_rgba(fill.color.r, fill.color.g, fill.color.b, fill.color.a),
+            }
             let _ = cr.fill_preserve();
         }
     }
@@ -49,10 +80,72 @@ pub fn render_shape(cr: &Context, shape: &ShapeElement) {
             stroke.color.a,
         );
         cr.set_line_width(stroke.width);
+        cr.set_line_cap(match stroke.line_cap {
+            LineCap::Butt => cairo::LineCap::Butt,
+            LineCap::Round => cairo::LineCap::Round,
+            LineCap::Square => cairo::LineCap::Square,
+        });
+        cr.set_line_join(match stroke.line_join {
+            LineJoin::Miter => cairo::LineJoin::Miter,
+            LineJoin::Round => cairo::LineJoin::Round,
+            LineJoin::Bevel => cairo::LineJoin::Bevel,
+        });
         let _ = cr.stroke();
+        if shape.shape_type == ShapeType::Line {
+            render_line_markers(cr, shape);
+        }
     } else {
         cr.new_path();
     }
 
     cr.restore().expect("cairo restore");
 }
+
+/// End points of a line relative to the origin of its bounds.
+fn local_line_endpoints(shape: &ShapeElement) -> (Point, Point) {
+    let origin = shape.bounds.origin;
+    let (start, end) = shape.line_endpoints();
+    (
+        Point::new(start.x - origin.x, start.y - origin.y),
+        Point::new(end.x - origin.x, end.y - origin.y),
+    )
+}
+
+/// `point` moved `distance` toward `target`, at most halfway.
+fn toward(point: Point, target: Point, distance: f64) -> Point {
+    let length = (target.x - point.x).hypot(target.y - point.y);
+    if length <= 0.0 {
+        return point;
+    }
+    let t = distance.min(length / 2.0) / length;
+    Point::new(
+        point.x + (target.x - point.x) * t,
+        point.y + (target.y - point.y) * t,
+    )
+}
+
+/// The arrowheads and dots of a line, filled with the current source.
+fn render_line_markers(cr: &Context, shape: &ShapeElement) {
+    let (start, end) = local_line_endpoints(shape);
+    let size = shape.marker_size();
+    for (marker, tip, from) in [(shape.start_marker, start, end), (shape.end_marker, end, start)] {
+        match marker {
+            LineMarker::None => continue,
+            LineMarker::Arrow => {
+                cr.save().expect("cairo save");
+                cr.translate(tip.x, tip.y);
+                cr.rotate((tip.y - from.y).atan2(tip.x - from.x));
+                cr.move_to(0.0, 0.0);
+                cr.line_to(-size, -size / 2.0);
+                cr.line_to(-size, size / 2.0);
+                cr.close_path();
+                cr.restore().expect("cairo restore");
+            }
+            LineMarker::Circle => {
+                cr.new_sub_path();
+                cr.arc(tip.x, tip.y, size / 2.0, 0.0, 2.0 * PI);
+            }
+        }
+        let _ = cr.fill();
+    }
+}
@@ -0,0 +1,27 @@
+use std::io;
+use std::path::Path;
+
+use crate::model::document::Document;
+
+use super::{engine, image_render};
+
+/// Write the slide at `slide_index` as an SVG image of the slide size, for
+/// web pages or further editing in a vector graphics program.
+pub fn export_svg(doc: &Document, slide_index: usize, path: &Path) -> io::Result<()> {
+    let slide = doc.slides.get(slide_index).ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidInput, format!("No slide {}", slide_index + 1))
+    })?;
+    let slide_size = &doc.slide_size;
+
+    let surface = cairo::SvgSurface::new(slide_size.width, slide_size.height, Some(path))
+        .map_err(|e| io::Error::other(format!("Cairo SVG error: {}", e)))?;
+    {
+        let cr = cairo::Context::new(&surface)
+            .map_err(|e| io::Error::other(format!("Cairo context error: {}", e)))?;
+        image_render::log_undecodable_images(slide_index, slide);
+        engine::render_slide(&cr, slide, slide_size);
+    }
+
+    surface.finish();
+    Ok(())
+}
@@ -1,8 +1,17 @@
 use cairo::Context;
 use pango::FontDescription;
+use std::borrow::Cow;
 
-use crate::model::style::FontStyle;
-use crate::model::text::{TextAlignment, TextElement};
+use crate::model::geometry::{Point, Size};
+use crate::model::style::{Color, FontStyle};
+use crate::model::text::{self, ListStyle, TextAlignment, TextElement, TextRun, VerticalAlignment};
+
+use super::engine;
+
+/// Smallest share of its font sizes auto-fit shrinks text to
+const MIN_FIT_SCALE: f64 = 0.25;
+/// Halvings of the range searched for the largest font scale that fits
+const FIT_STEPS: usize = 8;
 
 fn to_pango_alignment(alignment: TextAlignment) -> pango::Alignment {
     match alignment {
@@ -13,6 +22,8 @@ fn to_pango_alignment(alignment: TextAlignment) -> pango::Alignment {
 }
 
 pub fn render_text(cr: &Context, text: &TextElement) {
+    let text = fitted_text(text);
+    let text = text.as_ref();
     let bounds = &text.bounds;
 
     cr.save().expect("cairo save");
@@ -25,41 +36,241 @@ pub fn render_text(cr: &Context, text: &TextElement) {
     }
 
     if let Some(fill) = &text.fill {
-        cr.set_source_rgba(fill.color.r, fill.color.g, fill.color.b, fill.color.a);
+        match &fill.gradient {
+            Some(gradient) => engine::set_gradient_source(cr, gradient, &bounds.size),
+            None => cr.set_source_rgba(fill.color.r, fill.color.g, fill.color.b, fill.color.a),
+        }
         cr.rectangle(0.0, 0.0, bounds.size.width, bounds.size.height);
         let _ = cr.fill();
     }
 
+    // Share of the room below the text that goes above it instead. Text
+    // that overflows the box starts at the top whatever the alignment.
+    let share = match text.vertical_alignment {
+        VerticalAlignment::Top => 0.0,
+        VerticalAlignment::Middle => 0.5,
+        VerticalAlignment::Bottom => 1.0,
+    };
+    if share > 0.0 {
+        let room = (bounds.size.height - layout_text(cr, text, false).height).max(0.0);
+        cr.translate(0.0, room * share);
+    }
+    layout_text(cr, text, true);
+
+    cr.restore().expect("cairo restore");
+}
+
+/// Draw all runs of a text element on one baseline running from `start`
+/// along the direction of `end`, ignoring the box. The text alignment places
+/// it at the start, middle or end of the line.
+pub fn render_text_on_line(cr: &Context, text: &TextElement, start: Point, end: Point) {
+    let layouts: Vec<_> = text
+        .paragraphs
+        .iter()
+        .flat_map(|paragraph| &paragraph.runs)
+        .filter(|run| !run.text.is_empty())
+        .map(|run| {
+            let layout = pangocairo::functions::create_layout(cr);
+            layout.set_font_description(Some(&build_font_description(&run.font)));
+            layout.set_attributes(Some(&build_attributes(&run.font)));
+            layout.set_text(&run.text);
+            (run, layout)
+        })
+        .collect();
+
+    let text_width: f64 = layouts
+        .iter()
+        .map(|(_, layout)| layout.pixel_extents().1.width() as f64)
+        .sum();
+    let line_length = (end.x - start.x).hypot(end.y - start.y);
+    let mut x = match text.alignment {
+        TextAlignment::Left => 0.0,
+        TextAlignment::Center => (line_length - text_width) / 2.0,
+        TextAlignment::Right => line_length - text_width,
+    };
+
+    cr.save().expect("cairo save");
+    cr.translate(start.x, start.y);
+    cr.rotate((end.y - start.y).atan2(end.x - start.x));
+
+    for (run, layout) in &layouts {
+        let baseline = layout.baseline() as f64 / pango::SCALE as f64;
+        draw_layout(cr, layout, x, -baseline, &run.font.color, text);
+        x += layout.pixel_extents().1.width() as f64;
+    }
+
+    cr.restore().expect("cairo restore");
+}
+
+/// The text as drawn: with auto-fit, its fonts shrunk as little as needed
+/// for it to fit the box height, down to `MIN_FIT_SCALE`.
+pub fn fitted_text(text: &TextElement) -> Cow<'_, TextElement> {
+    let height = text.bounds.size.height;
+    if !text.auto_fit || measure_text(text).height <= height {
+        return Cow::Borrowed(text);
+    }
+
+    let scaled = |scale: f64| {
+        let mut scaled = text.clone();
+        scaled.scale_fonts(scale);
+        scaled
+    };
+    let (mut low, mut high) = (MIN_FIT_SCALE, 1.0);
+    for _ in 0..FIT_STEPS {
+        let mid = (low + high) / 2.0;
+        if measure_text(&scaled(mid)).height <= height {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    Cow::Owned(scaled(low))
+}
+
+/// Measure the size of a text element's laid-out content at its current box width.
+pub fn measure_text(text: &TextElement) -> Size {
+    let Ok(surface) = cairo::ImageSurface::create(cairo::Format::ARgb32, 1, 1) else {
+        return Size::new(0.0, 0.0);
+    };
+    let Ok(cr) = Context::new(&surface) else {
+        return Size::new(0.0, 0.0);
+    };
+    layout_text(&cr, text, false)
+}
+
+/// Lay out all runs of a text element inside its insets, drawing them when
+/// `draw` is set, and return the extents of the content including the insets.
+fn layout_text(cr: &Context, text: &TextElement, draw: bool) -> Size {
+    let inset = &text.inset;
+    let layout_width = (text.bounds.size.width - inset.left - inset.right).max(1.0);
+
     let layout = pangocairo::functions::create_layout(cr);
-    layout.set_width((bounds.size.width * pango::SCALE as f64) as i32);
     layout.set_alignment(to_pango_alignment(text.alignment));
     layout.set_wrap(pango::WrapMode::WordChar);
 
+    let labels = text::list_labels(&text.paragraphs);
+
     let mut y_offset = 0.0;
-    for paragraph in &text.paragraphs {
-        for run in &paragraph.runs {
-            let font_desc = build_font_description(&run.font);
-            layout.set_font_description(Some(&font_desc));
-            layout.set_text(&run.text);
+    let mut max_width: f64 = 0.0;
+    for (paragraph, label) in text.paragraphs.iter().zip(labels) {
+        // List items are indented one step per level, with the number or
+        // bullet in the step before the text
+        let indent_step = paragraph.runs.first().map_or(24.0, |run| run.font.size * 1.5);
+        let indent = match paragraph.list {
+            ListStyle::None => 0.0,
+            ListStyle::Numbered | ListStyle::Bullet => indent_step * (paragraph.level + 1) as f64,
+        };
+        layout.set_width(((layout_width - indent).max(1.0) * pango::SCALE as f64) as i32);
+        layout.set_tabs(build_tab_array(&paragraph.tab_stops).as_ref());
+        layout.set_line_spacing(paragraph.line_spacing as f32);
+        y_offset += paragraph.space_before;
 
-            cr.move_to(0.0, y_offset);
-            cr.set_source_rgba(
-                run.font.color.r,
-                run.font.color.g,
-                run.font.color.b,
-                run.font.color.a,
+        if let (true, Some(label), Some(run)) = (draw, label, paragraph.runs.first()) {
+            let label_layout = pangocairo::functions::create_layout(cr);
+            label_layout.set_font_description(Some(&build_font_description(&run.font)));
+            label_layout.set_text(&label);
+            draw_layout(
+                cr,
+                &label_layout,
+                inset.left + indent - indent_step,
+                inset.top + y_offset,
+                &run.font.color,
+                text,
             );
-            pangocairo::functions::show_layout(cr, &layout);
+        }
 
-            let (_, logical_rect) = layout.pixel_extents();
-            y_offset += logical_rect.height() as f64;
+        // All runs of a paragraph share one layout so they flow on the same
+        // lines. The first font also sizes an empty paragraph.
+        let first_font = paragraph.runs.first().map(|run| run.font.clone()).unwrap_or_default();
+        layout.set_font_description(Some(&build_font_description(&first_font)));
+        layout.set_text(&paragraph.full_text());
+        layout.set_attributes(Some(&build_run_attributes(&paragraph.runs)));
+
+        if draw {
+            draw_layout(
+                cr,
+                &layout,
+                inset.left + indent,
+                inset.top + y_offset,
+                &first_font.color,
+                text,
+            );
         }
+
+        let (_, logical_rect) = layout.pixel_extents();
+        y_offset += logical_rect.height() as f64 + paragraph.space_after;
+        max_width = max_width.max(indent + logical_rect.width() as f64);
     }
 
-    cr.restore().expect("cairo restore");
+    Size::new(
+        max_width + inset.left + inset.right,
+        y_offset + inset.top + inset.bottom,
+    )
+}
+
+/// Draw `layout` at (`x`, `y`) in `color`, with the text shadow and outline
+/// of `text` underneath. The outline is stroked at twice its width so the
+/// fill drawn on top leaves exactly `width` visible outside the glyphs.
+fn draw_layout(
+    cr: &Context,
+    layout: &pango::Layout,
+    x: f64,
+    y: f64,
+    color: &Color,
+    text: &TextElement,
+) {
+    if let Some(shadow) = &text.shadow {
+        // Run colors would override the shadow color
+        let attrs = layout.attributes();
+        let plain = attrs.as_ref().and_then(|attrs| attrs.copy()).and_then(|copy| {
+            copy.filter(|attr| {
+                !matches!(
+                    attr.type_(),
+                    pango::AttrType::Foreground | pango::AttrType::ForegroundAlpha
+                )
+            })
+        });
+        if plain.is_some() {
+            layout.set_attributes(plain.as_ref());
+        }
+        cr.move_to(x + shadow.offset_x, y + shadow.offset_y);
+        cr.set_source_rgba(shadow.color.r, shadow.color.g, shadow.color.b, shadow.color.a);
+        pangocairo::functions::show_layout(cr, layout);
+        layout.set_attributes(attrs.as_ref());
+    }
+
+    if let Some(outline) = text.outline.as_ref().filter(|o| o.width > 0.0) {
+        cr.save().expect("cairo save");
+        cr.move_to(x, y);
+        pangocairo::functions::layout_path(cr, layout);
+        cr.set_source_rgba(outline.color.r, outline.color.g, outline.color.b, outline.color.a);
+        cr.set_line_width(outline.width * 2.0);
+        cr.set_line_join(cairo::LineJoin::Round);
+        let _ = cr.stroke();
+        cr.restore().expect("cairo restore");
+    }
+
+    cr.move_to(x, y);
+    cr.set_source_rgba(color.r, color.g, color.b, color.a);
+    pangocairo::functions::show_layout(cr, layout);
+}
+
+fn build_tab_array(stops: &[f64]) -> Option<pango::TabArray> {
+    if stops.is_empty() {
+        return None;
+    }
+    let mut tabs = pango::TabArray::new(stops.len() as i32, false);
+    for (i, stop) in stops.iter().enumerate() {
+        tabs.set_tab(
+            i as i32,
+            pango::TabAlign::Left,
+            (stop * pango::SCALE as f64) as i32,
+        );
+    }
+    Some(tabs)
 }
 
-fn build_font_description(font: &FontStyle) -> FontDescription {
+pub fn build_font_description(font: &FontStyle) -> FontDescription {
     let mut desc = FontDescription::new();
     desc.set_family(&font.family);
     desc.set_size((font.size * pango::SCALE as f64) as i32);
@@ -71,3 +282,51 @@ fn build_font_description(font: &FontStyle) -> FontDescription {
     }
     desc
 }
+
+/// Font, decorations and color of each run over the concatenated text of
+/// `runs`, as laid out for a whole paragraph.
+fn build_run_attributes(runs: &[TextRun]) -> pango::AttrList {
+    let attrs = pango::AttrList::new();
+    let mut start = 0;
+    for run in runs {
+        let end = start + run.text.len() as u32;
+        let font = &run.font;
+        let to_u16 = |c: f64| (c.clamp(0.0, 1.0) * 65535.0).round() as u16;
+
+        let mut run_attrs: Vec<pango::Attribute> = vec![
+            pango::AttrFontDesc::new(&build_font_description(font)).into(),
+            pango::AttrColor::new_foreground(
+                to_u16(font.color.r),
+                to_u16(font.color.g),
+                to_u16(font.color.b),
+            )
+            .into(),
+            pango::AttrInt::new_foreground_alpha(to_u16(font.color.a)).into(),
+        ];
+        if font.underline {
+            run_attrs.push(pango::AttrInt::new_underline(pango::Underline::Single).into());
+        }
+        if font.strikethrough {
+            run_attrs.push(pango::AttrInt::new_strikethrough(true).into());
+        }
+        for mut attr in run_attrs {
+            attr.set_start_index(start);
+            attr.set_end_index(end);
+            attrs.insert(attr);
+        }
+        start = end;
+    }
+    attrs
+}
+
+/// Text decorations of `font`, which a font description cannot carry.
+pub fn build_attributes(font: &FontStyle) -> pango::AttrList {
+    let attrs = pango::AttrList::new();
+    if font.underline {
+        attrs.insert(pango::AttrInt::new_underline(pango::Underline::Single));
+    }
+    if font.strikethrough {
+        attrs.insert(pango::AttrInt::new_strikethrough(true));
+    }
+    attrs
+}
@@ -1,9 +1,10 @@
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
 use crate::model::document::Document;
 use crate::model::element::SlideElement;
 use crate::model::geometry::Rect;
-use crate::model::shape::{ShapeElement, ShapeType};
+use crate::model::shape::{ShapeElement, ShapeType, DEFAULT_CORNER_RADIUS, DEFAULT_STAR_POINTS};
 use crate::model::slide::{Background, Slide};
 use crate::model::style::{Color, FillStyle, FontStyle, StrokeStyle};
 use crate::model::text::{TextAlignment, TextElement, TextParagraph, TextRun};
@@ -39,6 +40,10 @@ pub struct TemplateElement {
     pub bold: bool,
     #[serde(default)]
     pub italic: bool,
+    #[serde(default)]
+    pub underline: bool,
+    #[serde(default)]
+    pub strikethrough: bool,
     #[serde(default = "default_color")]
     pub color: String,
     #[serde(default)]
@@ -53,6 +58,9 @@ pub struct TemplateElement {
     pub stroke_width: f64,
 }
 
+/// Directory of the user's templates, below the user data directory
+const USER_TEMPLATES_DIR: &str = "lumina/templates";
+
 fn default_font_family() -> String {
     "Sans".to_string()
 }
@@ -76,6 +84,38 @@ pub fn built_in_templates() -> Vec<TemplateDefinition> {
         .collect()
 }
 
+/// Templates the user dropped as `.json` files into
+/// `~/.local/share/lumina/templates`, in file name order. Files that cannot
+/// be read or parsed are skipped with a warning.
+pub fn user_templates() -> Vec<TemplateDefinition> {
+    let dir = glib::user_data_dir().join(USER_TEMPLATES_DIR);
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+
+    paths
+        .iter()
+        .filter_map(|path| {
+            let parsed = std::fs::read_to_string(path)
+                .map_err(|e| e.to_string())
+                .and_then(|json| serde_json::from_str(&json).map_err(|e| e.to_string()));
+            match parsed {
+                Ok(template) => Some(template),
+                Err(e) => {
+                    eprintln!("Skipping template {}: {}", path.display(), e);
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
 pub fn create_document_from_template(template: &TemplateDefinition) -> Document {
     let mut doc = Document::new();
     doc.slides.clear();
@@ -97,6 +137,8 @@ pub fn create_document_from_template(template: &TemplateDefinition) -> Document
                         size: tmpl_elem.font_size,
                         bold: tmpl_elem.bold,
                         italic: tmpl_elem.italic,
+                        underline: tmpl_elem.underline,
+                        strikethrough: tmpl_elem.strikethrough,
                         color: Color::from_hex(&tmpl_elem.color).unwrap_or_else(Color::black),
                     };
                     let mut text = TextElement::new(bounds, "");
@@ -115,6 +157,14 @@ pub fn create_document_from_template(template: &TemplateDefinition) -> Document
                     let shape_type = match tmpl_elem.shape.as_str() {
                         "ellipse" | "circle" => ShapeType::Ellipse,
                         "line" => ShapeType::Line,
+                        "triangle" => ShapeType::Triangle,
+                        "rounded-rectangle" => ShapeType::RoundedRectangle {
+                            radius: DEFAULT_CORNER_RADIUS,
+                        },
+                        "star" => ShapeType::Star {
+                            points: DEFAULT_STAR_POINTS,
+                        },
+                        "arrow" => ShapeType::Arrow,
                         _ => ShapeType::Rectangle,
                     };
                     let mut shape = ShapeElement::new(bounds, shape_type);
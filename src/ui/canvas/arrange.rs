@@ -0,0 +1,125 @@
+use crate::model::geometry::{Point, Rect, Size};
+
+/// Where the first inserted element goes
+const CASCADE_ORIGIN: Point = Point { x: 100.0, y: 100.0 };
+
+/// Offset between successive inserted elements, in both directions
+const CASCADE_STEP: f64 = 24.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MatchDimension {
+    Width,
+    Height,
+    Size,
+}
+
+impl MatchDimension {
+    /// Parse the target of the `win.match-size` action.
+    pub fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "width" => Some(MatchDimension::Width),
+            "height" => Some(MatchDimension::Height),
+            "size" => Some(MatchDimension::Size),
+            _ => None,
+        }
+    }
+}
+
+/// Give every rect the width and/or height of the first one. Origins are left
+/// untouched, so elements grow and shrink from their top-left corner.
+pub fn match_sizes(rects: &mut [Rect], dimension: MatchDimension) {
+    let Some(reference) = rects.first().map(|r| r.size) else {
+        return;
+    };
+    for rect in rects.iter_mut().skip(1) {
+        if matches!(dimension, MatchDimension::Width | MatchDimension::Size) {
+            rect.size.width = reference.width;
+        }
+        if matches!(dimension, MatchDimension::Height | MatchDimension::Size) {
+            rect.size.height = reference.height;
+        }
+    }
+}
+
+/// Bounds for a new element of `size` that does not sit exactly on top of an
+/// existing one. Candidates cascade diagonally from the default spot; when a
+/// diagonal runs off the slide the next one starts a step further right.
+pub fn place_new(existing: &[Rect], size: Size, slide_size: &Size) -> Rect {
+    let max_x = (slide_size.width - size.width).max(0.0);
+    let max_y = (slide_size.height - size.height).max(0.0);
+    let origin = Point::new(CASCADE_ORIGIN.x.min(max_x), CASCADE_ORIGIN.y.min(max_y));
+    let steps_per_diagonal = ((max_y - origin.y) / CASCADE_STEP).floor() as usize + 1;
+
+    let occupied = |x: f64, y: f64| {
+        existing
+            .iter()
+            .any(|r| (r.origin.x - x).abs() < 1.0 && (r.origin.y - y).abs() < 1.0)
+    };
+
+    for i in 0.. {
+        let (diagonal, step) = (i / steps_per_diagonal, i % steps_per_diagonal);
+        if origin.x + diagonal as f64 * CASCADE_STEP > max_x {
+            break;
+        }
+        let x = origin.x + (diagonal + step) as f64 * CASCADE_STEP;
+        let y = origin.y + step as f64 * CASCADE_STEP;
+        if x <= max_x && !occupied(x, y) {
+            return Rect::new(x, y, size.width, size.height);
+        }
+    }
+
+    // Every spot is taken, fall back to the default one
+    Rect::new(origin.x, origin.y, size.width, size.height)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matching_width_keeps_positions_and_heights() {
+        let original = [
+            Rect::new(10.0, 20.0, 200.0, 50.0),
+            Rect::new(300.0, 40.0, 80.0, 120.0),
+            Rect::new(50.0, 400.0, 500.0, 30.0),
+        ];
+        let mut rects = original;
+        match_sizes(&mut rects, MatchDimension::Width);
+
+        for (rect, before) in rects.iter().zip(&original) {
+            assert_eq!(rect.origin, before.origin);
+            assert_eq!(rect.size.width, 200.0);
+            assert_eq!(rect.size.height, before.size.height);
+        }
+    }
+
+    #[test]
+    fn place_new_cascades_from_the_default_spot() {
+        let slide = Size::new(960.0, 540.0);
+        let size = Size::new(200.0, 100.0);
+        let first = place_new(&[], size, &slide);
+        assert_eq!(first, Rect::new(100.0, 100.0, 200.0, 100.0));
+
+        let second = place_new(&[first], size, &slide);
+        assert_eq!(second.origin, Point::new(124.0, 124.0));
+        let third = place_new(&[first, second], size, &slide);
+        assert_eq!(third.origin, Point::new(148.0, 148.0));
+    }
+
+    #[test]
+    fn place_new_wraps_to_the_next_diagonal_at_the_bottom() {
+        // Room for the default spot and two steps down
+        let slide = Size::new(960.0, 250.0);
+        let size = Size::new(200.0, 100.0);
+        let mut existing = Vec::new();
+        for _ in 0..3 {
+            existing.push(place_new(&existing, size, &slide));
+        }
+        assert_eq!(existing[2].origin, Point::new(148.0, 148.0));
+
+        let wrapped = place_new(&existing, size, &slide);
+        assert_eq!(wrapped.origin, Point::new(124.0, 100.0));
+        assert!(wrapped.bottom() <= slide.height);
+    }
+}
+
@@ -0,0 +1,109 @@
+use crate::model::geometry::{Rect, Size};
+use crate::ui::canvas::selection::HandlePosition;
+
+/// Aspect ratios that resizing snaps to, in both orientations
+const COMMON_RATIOS: &[(u32, u32)] = &[(1, 1), (4, 3), (3, 2), (16, 9)];
+
+/// Relative deviation from a ratio that still counts as a match
+const TOLERANCE: f64 = 0.03;
+
+/// Find the common aspect ratio closest to `size`, if it is within the
+/// snapping tolerance. Portrait sizes match the flipped ratio (e.g. 9:16).
+pub fn match_ratio(size: Size) -> Option<(u32, u32)> {
+    if size.width <= 0.0 || size.height <= 0.0 {
+        return None;
+    }
+    let actual = size.width / size.height;
+
+    COMMON_RATIOS
+        .iter()
+        .flat_map(|&(w, h)| [(w, h), (h, w)])
+        .map(|(w, h)| {
+            let target = w as f64 / h as f64;
+            ((w, h), (actual / target - 1.0).abs())
+        })
+        .filter(|(_, deviation)| *deviation <= TOLERANCE)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(ratio, _)| ratio)
+}
+
+/// Snap resized bounds to a nearby common aspect ratio. The dimension the
+/// handle does not drive is adjusted, keeping the opposite edge in place.
+pub fn snap_bounds(bounds: Rect, handle: HandlePosition) -> (Rect, Option<(u32, u32)>) {
+    let Some((rw, rh)) = match_ratio(bounds.size) else {
+        return (bounds, None);
+    };
+    let ratio = rw as f64 / rh as f64;
+    let mut snapped = bounds;
+
+    match handle {
+        // Top and bottom handles drive the height, so the width follows
+        HandlePosition::TopCenter | HandlePosition::BottomCenter => {
+            snapped.size.width = bounds.size.height * ratio;
+        }
+        _ => {
+            snapped.size.height = bounds.size.width / ratio;
+            if matches!(handle, HandlePosition::TopLeft | HandlePosition::TopRight) {
+                snapped.origin.y = bounds.bottom() - snapped.size.height;
+            }
+        }
+    }
+
+    (snapped, Some((rw, rh)))
+}
+
+/// Draw the matched ratio as a small badge below the bounds.
+pub fn render_ratio_badge(cr: &cairo::Context, bounds: &Rect, ratio: (u32, u32), scale: f64) {
+    let layout = pangocairo::functions::create_layout(cr);
+    let mut font = pango::FontDescription::from_string("Sans Bold");
+    font.set_absolute_size(11.0 / scale * pango::SCALE as f64);
+    layout.set_font_description(Some(&font));
+    layout.set_text(&format!("{}:{}", ratio.0, ratio.1));
+
+    let (ink, _) = layout.pixel_extents();
+    let padding = 4.0 / scale;
+    let width = ink.width() as f64 + 2.0 * padding;
+    let height = ink.height() as f64 + 2.0 * padding;
+    let x = bounds.right() - width;
+    let y = bounds.bottom() + padding;
+
+    cr.save().expect("cairo save");
+    cr.set_source_rgba(0.2, 0.52, 0.89, 0.9);
+    cr.rectangle(x, y, width, height);
+    let _ = cr.fill();
+
+    cr.set_source_rgb(1.0, 1.0, 1.0);
+    cr.move_to(x + padding - ink.x() as f64, y + padding - ink.y() as f64);
+    pangocairo::functions::show_layout(cr, &layout);
+    cr.restore().expect("cairo restore");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn match_ratio_finds_nearby_ratios_in_both_orientations() {
+        assert_eq!(match_ratio(Size::new(100.0, 101.0)), Some((1, 1)));
+        assert_eq!(match_ratio(Size::new(1600.0, 905.0)), Some((16, 9)));
+        assert_eq!(match_ratio(Size::new(300.0, 402.0)), Some((3, 4)));
+        assert_eq!(match_ratio(Size::new(100.0, 150.0)), Some((2, 3)));
+    }
+
+    #[test]
+    fn match_ratio_ignores_distant_and_empty_sizes() {
+        assert_eq!(match_ratio(Size::new(250.0, 100.0)), None);
+        assert_eq!(match_ratio(Size::new(100.0, 90.0)), None);
+        assert_eq!(match_ratio(Size::new(0.0, 100.0)), None);
+    }
+
+    #[test]
+    fn snap_bounds_keeps_the_edge_opposite_the_handle() {
+        let bounds = Rect::new(10.0, 20.0, 160.0, 92.0);
+        let (snapped, ratio) = snap_bounds(bounds, HandlePosition::TopRight);
+        assert_eq!(ratio, Some((16, 9)));
+        assert_eq!(snapped.size.width, 160.0);
+        assert_eq!(snapped.size.height, 90.0);
+        assert_eq!(snapped.bottom(), bounds.bottom());
+    }
+}
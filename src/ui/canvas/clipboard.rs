@@ -0,0 +1,267 @@
+use gtk::prelude::*;
+use serde::de::DeserializeOwned;
+use uuid::Uuid;
+
+use crate::model::element::SlideElement;
+use crate::model::geometry::{Point, Rect, Size};
+use crate::model::slide::Slide;
+use crate::model::text::{TextElement, TextParagraph};
+use crate::ui::canvas::{arrange, overflow};
+
+/// Clipboard format carrying whole elements as JSON
+pub const ELEMENTS_MIME: &str = "application/x-lumina-elements+json";
+/// Clipboard format carrying styled paragraphs as JSON
+pub const TEXT_MIME: &str = "application/x-lumina-text+json";
+
+/// Points a pasted element is moved from where it was copied, and again
+/// for every earlier paste still in that spot
+const PASTE_OFFSET: f64 = 20.0;
+
+/// Put elements on the clipboard, with the styled paragraphs of their text
+/// for pasting into text boxes and the plain text for other applications.
+pub fn copy_elements(clipboard: &gdk::Clipboard, elements: &[SlideElement]) {
+    let paragraphs: Vec<TextParagraph> = elements
+        .iter()
+        .filter_map(|e| match e {
+            SlideElement::Text(text) => Some(text),
+            _ => None,
+        })
+        .flat_map(|text| text.paragraphs.iter().cloned())
+        .collect();
+    let plain = paragraphs
+        .iter()
+        .map(TextParagraph::full_text)
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let mut providers = Vec::new();
+    for (mime, json) in [
+        (ELEMENTS_MIME, serde_json::to_vec(elements)),
+        (TEXT_MIME, serde_json::to_vec(&paragraphs)),
+    ] {
+        if let Ok(json) = json {
+            providers.push(gdk::ContentProvider::for_bytes(
+                mime,
+                &glib::Bytes::from_owned(json),
+            ));
+        }
+    }
+    providers.push(gdk::ContentProvider::for_value(&plain.to_value()));
+    let _ = clipboard.set_content(Some(&gdk::ContentProvider::new_union(&providers)));
+}
+
+/// Whether the clipboard holds elements copied in Lumina.
+pub fn has_elements(clipboard: &gdk::Clipboard) -> bool {
+    clipboard.formats().contain_mime_type(ELEMENTS_MIME)
+}
+
+pub fn read_elements<F: FnOnce(Vec<SlideElement>) + 'static>(
+    clipboard: &gdk::Clipboard,
+    callback: F,
+) {
+    read_json(clipboard, ELEMENTS_MIME, callback);
+}
+
+/// Whether copied `elements` go into the text box `target` as text rather
+/// than onto the slide: they must all be text boxes, copied from elsewhere
+/// than the target, so copying a box and pasting still duplicates it.
+pub fn pastes_into_text(elements: &[SlideElement], target: Option<Uuid>) -> bool {
+    let Some(target) = target else { return false };
+    !elements.is_empty()
+        && elements
+            .iter()
+            .all(|e| matches!(e, SlideElement::Text(_)) && e.id() != target)
+}
+
+/// Add copies of `elements` to the slide with fresh ids, moved off the
+/// spot they were copied from. Returns the new ids.
+pub fn paste_elements(slide: &mut Slide, mut elements: Vec<SlideElement>) -> Vec<Uuid> {
+    let Some(origin) = elements.first().map(|e| e.bounds().origin) else {
+        return Vec::new();
+    };
+    let taken = |offset: f64| {
+        let spot = Point::new(origin.x + offset, origin.y + offset);
+        slide.elements.iter().any(|e| e.bounds().origin == spot)
+    };
+    let mut offset = PASTE_OFFSET;
+    while taken(offset) {
+        offset += PASTE_OFFSET;
+    }
+
+    let mut ids = Vec::with_capacity(elements.len());
+    for mut element in elements.drain(..) {
+        let id = Uuid::new_v4();
+        element.set_id(id);
+        let bounds = element.bounds_mut();
+        bounds.origin.x += offset;
+        bounds.origin.y += offset;
+        slide.add_element(element);
+        ids.push(id);
+    }
+    ids
+}
+
+/// Read paragraphs from the clipboard. Styled paragraphs are preferred; plain
+/// text becomes one unstyled paragraph per line.
+pub fn read_paragraphs<F: FnOnce(Vec<TextParagraph>) + 'static>(
+    clipboard: &gdk::Clipboard,
+    callback: F,
+) {
+    if clipboard.formats().contain_mime_type(TEXT_MIME) {
+        read_json(clipboard, TEXT_MIME, callback);
+        return;
+    }
+    clipboard.read_text_async(gio::Cancellable::NONE, move |result| {
+        if let Ok(Some(text)) = result {
+            callback(text.lines().map(TextParagraph::plain).collect());
+        }
+    });
+}
+
+/// Read clipboard content of type `mime` and parse it as JSON.
+fn read_json<T: DeserializeOwned, F: FnOnce(T) + 'static>(
+    clipboard: &gdk::Clipboard,
+    mime: &str,
+    callback: F,
+) {
+    clipboard.read_async(
+        &[mime],
+        glib::Priority::DEFAULT,
+        gio::Cancellable::NONE,
+        move |result| {
+            let Ok((stream, _)) = result else {
+                return;
+            };
+            let output = gio::MemoryOutputStream::new_resizable();
+            output.clone().splice_async(
+                &stream,
+                gio::OutputStreamSpliceFlags::CLOSE_SOURCE
+                    | gio::OutputStreamSpliceFlags::CLOSE_TARGET,
+                glib::Priority::DEFAULT,
+                gio::Cancellable::NONE,
+                move |result| {
+                    if result.is_err() {
+                        return;
+                    }
+                    let bytes = output.steal_as_bytes();
+                    if let Ok(content) = serde_json::from_slice::<T>(&bytes) {
+                        callback(content);
+                    }
+                },
+            );
+        },
+    );
+}
+
+/// Paste paragraphs into the text element `target`, or into a new text box
+/// when there is no such element. Returns the id of the element pasted into.
+pub fn paste_paragraphs(
+    slide: &mut Slide,
+    slide_size: &Size,
+    target: Option<Uuid>,
+    paragraphs: Vec<TextParagraph>,
+) -> Option<Uuid> {
+    if paragraphs.is_empty() {
+        return None;
+    }
+
+    let text_target = slide.elements.iter_mut().find_map(|e| match e {
+        SlideElement::Text(text) if Some(text.id) == target => Some(text),
+        _ => None,
+    });
+    if let Some(text) = text_target {
+        // An empty box takes the pasted text as is
+        if text.paragraphs.iter().all(|p| p.full_text().is_empty()) {
+            text.paragraphs.clear();
+        }
+        text.paragraphs.extend(paragraphs);
+        return Some(text.id);
+    }
+
+    let mut text = TextElement::new(Rect::new(0.0, 0.0, 400.0, 50.0), "");
+    text.paragraphs = paragraphs;
+    let size = overflow::fit_box_to_text(&text).size;
+    let existing: Vec<Rect> = slide.elements.iter().map(|e| *e.bounds()).collect();
+    text.bounds = arrange::place_new(&existing, size, slide_size);
+    let id = text.id;
+    slide.add_element(SlideElement::Text(text));
+    Some(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::shape::{ShapeElement, ShapeType};
+    use crate::model::style::FontStyle;
+    use crate::model::text::TextRun;
+
+    /// A paragraph of bold, plain and italic runs as it is read back from
+    /// the clipboard, with the fonts of its runs.
+    fn copied_paragraphs() -> (Vec<TextParagraph>, Vec<FontStyle>) {
+        let bold = FontStyle { bold: true, ..FontStyle::default() };
+        let italic = FontStyle { italic: true, ..FontStyle::default() };
+        let paragraphs = vec![TextParagraph::new(vec![
+            TextRun::new("bold ", bold.clone()),
+            TextRun::plain("plain "),
+            TextRun::new("italic", italic.clone()),
+        ])];
+        // As copy_elements and read_paragraphs pass them as TEXT_MIME
+        let json = serde_json::to_vec(&paragraphs).unwrap();
+        let paragraphs = serde_json::from_slice(&json).unwrap();
+        (paragraphs, vec![bold, FontStyle::default(), italic])
+    }
+
+    fn run_fonts(slide: &Slide, id: Uuid) -> Vec<FontStyle> {
+        let text = slide.elements.iter().find_map(|e| match e {
+            SlideElement::Text(text) if text.id == id => Some(text),
+            _ => None,
+        });
+        let text = text.expect("the text box pasted into");
+        text.paragraphs.iter().flat_map(|p| &p.runs).map(|r| r.font.clone()).collect()
+    }
+
+    #[test]
+    fn text_pasted_into_a_text_box_keeps_bold_and_italic_runs() {
+        let mut slide = Slide::new();
+        let text = TextElement::new(Rect::new(10.0, 10.0, 300.0, 50.0), "");
+        let target = text.id;
+        slide.add_element(SlideElement::Text(text));
+        let (paragraphs, fonts) = copied_paragraphs();
+
+        let size = Size::new(960.0, 540.0);
+        let pasted = paste_paragraphs(&mut slide, &size, Some(target), paragraphs);
+
+        assert_eq!(pasted, Some(target));
+        assert_eq!(slide.elements.len(), 1);
+        assert_eq!(run_fonts(&slide, target), fonts);
+    }
+
+    #[test]
+    fn text_pasted_into_a_new_text_box_keeps_bold_and_italic_runs() {
+        let mut slide = Slide::new();
+        let (paragraphs, fonts) = copied_paragraphs();
+
+        let size = Size::new(960.0, 540.0);
+        let pasted = paste_paragraphs(&mut slide, &size, None, paragraphs);
+
+        let id = pasted.expect("a new text box");
+        assert_eq!(slide.elements.len(), 1);
+        assert_eq!(run_fonts(&slide, id), fonts);
+    }
+
+    #[test]
+    fn copied_text_boxes_paste_into_another_selected_text_box() {
+        let bounds = Rect::new(10.0, 10.0, 300.0, 50.0);
+        let copied = TextElement::new(bounds, "copied");
+        let other = TextElement::new(bounds, "selected");
+        let elements = vec![SlideElement::Text(copied.clone())];
+
+        assert!(pastes_into_text(&elements, Some(other.id)));
+        // Pasting over the copied box itself duplicates it
+        assert!(!pastes_into_text(&elements, Some(copied.id)));
+        assert!(!pastes_into_text(&elements, None));
+        let shape = ShapeElement::new(bounds, ShapeType::Rectangle);
+        let mixed = vec![SlideElement::Text(copied), SlideElement::Shape(shape)];
+        assert!(!pastes_into_text(&mixed, Some(other.id)));
+    }
+}
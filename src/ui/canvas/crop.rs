@@ -0,0 +1,126 @@
+use crate::model::geometry::Rect;
+use crate::model::image::{ImageCrop, ImageElement};
+use crate::render::image_render;
+use crate::ui::canvas::interaction::MIN_ELEMENT_SIZE;
+use crate::ui::canvas::selection::HandlePosition;
+
+/// Opacity of the cropped-away parts of the picture while cropping
+const CROPPED_ALPHA: f64 = 0.35;
+
+/// Area the whole picture of a stretched image covers on the slide, of
+/// which the bounds show the uncropped part.
+pub fn uncropped_bounds(image: &ImageElement) -> Rect {
+    let crop = image.crop.mirrored(image.flip_h, image.flip_v);
+    let (visible_w, visible_h) = crop.visible();
+    let width = image.bounds.size.width / visible_w.max(0.01);
+    let height = image.bounds.size.height / visible_h.max(0.01);
+    Rect::new(
+        image.bounds.origin.x - crop.left * width,
+        image.bounds.origin.y - crop.top * height,
+        width,
+        height,
+    )
+}
+
+/// Bounds and crop of a stretched image after dragging crop `handle` by
+/// `dx`, `dy`: the edge moves over the picture, which stays in place. An
+/// edge stops at the edge of the picture and short of the opposite one.
+pub fn crop_edges(
+    orig_bounds: &Rect,
+    orig_crop: &ImageCrop,
+    flips: (bool, bool),
+    handle: HandlePosition,
+    dx: f64,
+    dy: f64,
+) -> (Rect, ImageCrop) {
+    use HandlePosition::*;
+
+    // Worked out as seen on the slide, where a mirrored picture shows the
+    // crop of its other side
+    let mut crop = orig_crop.mirrored(flips.0, flips.1);
+    let (visible_w, visible_h) = crop.visible();
+    let per_x = orig_bounds.size.width / visible_w.max(0.01);
+    let per_y = orig_bounds.size.height / visible_h.max(0.01);
+    let mut bounds = *orig_bounds;
+    let (w, h) = (orig_bounds.size.width, orig_bounds.size.height);
+
+    if matches!(handle, TopLeft | MiddleLeft | BottomLeft) {
+        let dx = dx.clamp(-crop.left * per_x, (w - MIN_ELEMENT_SIZE).max(0.0));
+        bounds.origin.x += dx;
+        bounds.size.width -= dx;
+        crop.left += dx / per_x;
+    }
+    if matches!(handle, TopRight | MiddleRight | BottomRight) {
+        let dx = dx.clamp((MIN_ELEMENT_SIZE - w).min(0.0), crop.right * per_x);
+        bounds.size.width += dx;
+        crop.right -= dx / per_x;
+    }
+    if matches!(handle, TopLeft | TopCenter | TopRight) {
+        let dy = dy.clamp(-crop.top * per_y, (h - MIN_ELEMENT_SIZE).max(0.0));
+        bounds.origin.y += dy;
+        bounds.size.height -= dy;
+        crop.top += dy / per_y;
+    }
+    if matches!(handle, BottomLeft | BottomCenter | BottomRight) {
+        let dy = dy.clamp((MIN_ELEMENT_SIZE - h).min(0.0), crop.bottom * per_y);
+        bounds.size.height += dy;
+        crop.bottom -= dy / per_y;
+    }
+
+    (bounds, crop.mirrored(flips.0, flips.1))
+}
+
+/// An image being cropped: the whole picture faded around the kept part,
+/// and dark handles on the bounds in place of the resize handles.
+pub fn render_crop_frame(cr: &cairo::Context, image: &ImageElement, scale: f64) {
+    cr.save().expect("cairo save");
+
+    // Both pictures turn about the center of the bounds
+    if image.rotation != 0.0 {
+        let center = image.bounds.center();
+        cr.translate(center.x, center.y);
+        cr.rotate(image.rotation.to_radians());
+        cr.translate(-center.x, -center.y);
+    }
+
+    let mut whole = image.clone();
+    whole.bounds = uncropped_bounds(image);
+    whole.crop = ImageCrop::default();
+    whole.rotation = 0.0;
+    cr.push_group();
+    image_render::render_image(cr, &whole);
+    let _ = cr.pop_group_to_source();
+    let _ = cr.paint_with_alpha(CROPPED_ALPHA);
+
+    let mut kept = image.clone();
+    kept.rotation = 0.0;
+    image_render::render_image(cr, &kept);
+
+    cr.restore().expect("cairo restore");
+
+    let bounds = &image.bounds;
+    cr.set_source_rgba(0.0, 0.0, 0.0, 0.8);
+    cr.set_line_width(1.5 / scale);
+    cr.rectangle(
+        bounds.origin.x,
+        bounds.origin.y,
+        bounds.size.width,
+        bounds.size.height,
+    );
+    let _ = cr.stroke();
+
+    for pos in HandlePosition::all() {
+        let handle = pos.rect_for_bounds(bounds, scale);
+        cr.rectangle(
+            handle.origin.x,
+            handle.origin.y,
+            handle.size.width,
+            handle.size.height,
+        );
+        cr.set_source_rgb(0.1, 0.1, 0.1);
+        let _ = cr.fill_preserve();
+        cr.set_source_rgb(1.0, 1.0, 1.0);
+        cr.set_line_width(1.0 / scale);
+        let _ = cr.stroke();
+    }
+}
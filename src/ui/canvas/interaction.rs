@@ -1,16 +1,57 @@
-use crate::model::geometry::{Point, Rect};
+use uuid::Uuid;
+
+use crate::model::geometry::{Point, Rect, Size};
+use crate::model::image::ImageCrop;
+use crate::model::shape::ShapeType;
+use crate::ui::canvas::crop;
 use crate::ui::canvas::selection::HandlePosition;
 use crate::ui::canvas::tool::Tool;
 
-#[derive(Debug, Clone, Copy)]
+/// Smallest width and height an element can be resized to
+pub const MIN_ELEMENT_SIZE: f64 = 20.0;
+
+/// Degrees a rotation snaps to while Shift is held
+const ROTATION_SNAP_STEP: f64 = 15.0;
+/// Degrees a line being drawn snaps to while Shift is held
+const LINE_SNAP_STEP: f64 = 45.0;
+
+#[derive(Debug, Clone)]
 pub enum DragOperation {
-    Move { start_x: f64, start_y: f64, orig_bounds: Rect },
-    Resize { handle: HandlePosition, orig_bounds: Rect },
+    /// Moves the first-selected element; `others` are the further selected
+    /// elements with their bounds at the start, moved by the same delta
+    Move {
+        start_x: f64,
+        start_y: f64,
+        orig_bounds: Rect,
+        others: Vec<(Uuid, Rect)>,
+    },
+    /// `keep_aspect` resizes in proportion also without Shift, as for images
+    Resize {
+        handle: HandlePosition,
+        orig_bounds: Rect,
+        min_size: Size,
+        keep_aspect: bool,
+    },
     Create { tool: Tool, start: Point },
+    /// Rubber band from empty space; `extend` adds to the selection
+    Select { start: Point, extend: bool },
+    /// Turns the first-selected element about the center of its bounds by
+    /// the angle the pointer sweeps around it, starting at `start`
+    Rotate { orig_bounds: Rect, start: Point, orig_rotation: f64 },
+    /// Moves an edge of the first-selected image over its picture; `flips`
+    /// are its horizontal and vertical flips
+    Crop {
+        handle: HandlePosition,
+        orig_bounds: Rect,
+        orig_crop: ImageCrop,
+        flips: (bool, bool),
+    },
 }
 
 impl DragOperation {
-    pub fn apply(&self, dx: f64, dy: f64) -> Rect {
+    /// Bounds after dragging by `dx`, `dy`. `shift` keeps the aspect ratio
+    /// of a resized element.
+    pub fn apply(&self, dx: f64, dy: f64, shift: bool) -> Rect {
         match self {
             DragOperation::Move { orig_bounds, .. } => Rect::new(
                 orig_bounds.origin.x + dx,
@@ -18,14 +59,75 @@ impl DragOperation {
                 orig_bounds.size.width,
                 orig_bounds.size.height,
             ),
-            DragOperation::Resize { handle, orig_bounds } => {
-                resize_bounds(orig_bounds, *handle, dx, dy)
+            DragOperation::Resize { handle, orig_bounds, min_size, .. } => {
+                let bounds = resize_bounds(orig_bounds, *handle, dx, dy, *min_size);
+                if self.keeps_aspect(shift) {
+                    keep_aspect(orig_bounds, *handle, bounds.size, *min_size)
+                } else {
+                    bounds
+                }
             }
-            DragOperation::Create { start, .. } => {
+            DragOperation::Create { start, .. } | DragOperation::Select { start, .. } => {
                 normalize_rect(start.x, start.y, start.x + dx, start.y + dy)
             }
+            // Rotation turns about the center, the bounds stay
+            DragOperation::Rotate { orig_bounds, .. } => *orig_bounds,
+            DragOperation::Crop { handle, orig_bounds, orig_crop, flips } => {
+                crop::crop_edges(orig_bounds, orig_crop, *flips, *handle, dx, dy).0
+            }
         }
     }
+
+    /// Whether a resize keeps the aspect ratio, always or while `shift` is held.
+    pub fn keeps_aspect(&self, shift: bool) -> bool {
+        matches!(self, DragOperation::Resize { keep_aspect, .. } if *keep_aspect || shift)
+    }
+
+    /// Rotation in degrees clockwise, 0..360, after dragging a rotate
+    /// operation by `dx`, `dy`. `snap` rounds it to 15° steps.
+    pub fn rotation(&self, dx: f64, dy: f64, snap: bool) -> Option<f64> {
+        let DragOperation::Rotate { orig_bounds, start, orig_rotation } = self else {
+            return None;
+        };
+        let center = orig_bounds.center();
+        let angle = |x: f64, y: f64| (y - center.y).atan2(x - center.x).to_degrees();
+        let turned = angle(start.x + dx, start.y + dy) - angle(start.x, start.y);
+        let rotation = orig_rotation + turned;
+        let rotation = if snap {
+            (rotation / ROTATION_SNAP_STEP).round() * ROTATION_SNAP_STEP
+        } else {
+            rotation
+        };
+        Some(rotation.rem_euclid(360.0))
+    }
+
+    /// Bounds and crop of the image after dragging a crop operation by `dx`,
+    /// `dy`.
+    pub fn crop(&self, dx: f64, dy: f64) -> Option<(Rect, ImageCrop)> {
+        let DragOperation::Crop { handle, orig_bounds, orig_crop, flips } = self else {
+            return None;
+        };
+        Some(crop::crop_edges(orig_bounds, orig_crop, *flips, *handle, dx, dy))
+    }
+}
+
+/// End point of a shape drawn from `start` toward `end` while Shift is
+/// held: lines turn to 45° steps, other shapes become as wide as high.
+pub fn constrain_create(tool: Tool, start: Point, end: Point) -> Point {
+    let (dx, dy) = (end.x - start.x, end.y - start.y);
+    match tool {
+        Tool::Shape(ShapeType::Line) => {
+            let step = LINE_SNAP_STEP.to_radians();
+            let angle = (dy.atan2(dx) / step).round() * step;
+            let length = dx.hypot(dy);
+            Point::new(start.x + length * angle.cos(), start.y + length * angle.sin())
+        }
+        Tool::Shape(_) => {
+            let side = dx.abs().max(dy.abs());
+            Point::new(start.x + side.copysign(dx), start.y + side.copysign(dy))
+        }
+        Tool::Pointer | Tool::Text | Tool::Image => end,
+    }
 }
 
 /// Create a normalized rect from two corners (handles negative width/height from dragging up/left)
@@ -37,7 +139,7 @@ pub fn normalize_rect(x1: f64, y1: f64, x2: f64, y2: f64) -> Rect {
     Rect::new(x, y, w, h)
 }
 
-fn resize_bounds(orig: &Rect, handle: HandlePosition, dx: f64, dy: f64) -> Rect {
+fn resize_bounds(orig: &Rect, handle: HandlePosition, dx: f64, dy: f64, min_size: Size) -> Rect {
     let mut x = orig.origin.x;
     let mut y = orig.origin.y;
     let mut w = orig.size.width;
@@ -80,18 +182,65 @@ fn resize_bounds(orig: &Rect, handle: HandlePosition, dx: f64, dy: f64) -> Rect
         }
     }
 
-    // Enforce minimum size
-    let min_size = 20.0;
-    if w < min_size {
-        w = min_size;
+    // Enforce minimum size, keeping the edge opposite the handle in place
+    if w < min_size.width {
+        w = min_size.width;
+        if matches!(
+            handle,
+            HandlePosition::TopLeft | HandlePosition::MiddleLeft | HandlePosition::BottomLeft
+        ) {
+            x = orig.right() - w;
+        }
     }
-    if h < min_size {
-        h = min_size;
+    if h < min_size.height {
+        h = min_size.height;
+        if matches!(
+            handle,
+            HandlePosition::TopLeft | HandlePosition::TopCenter | HandlePosition::TopRight
+        ) {
+            y = orig.bottom() - h;
+        }
     }
 
     Rect::new(x, y, w, h)
 }
 
+/// Scale `orig` to the proportions it had, from the `size` a resize gave
+/// it. Corner handles follow the dimension that changed more and keep the
+/// opposite corner in place; edge handles keep the center of the other axis.
+fn keep_aspect(orig: &Rect, handle: HandlePosition, size: Size, min_size: Size) -> Rect {
+    let scale_x = size.width / orig.size.width;
+    let scale_y = size.height / orig.size.height;
+    let scale = match handle {
+        HandlePosition::MiddleLeft | HandlePosition::MiddleRight => scale_x,
+        HandlePosition::TopCenter | HandlePosition::BottomCenter => scale_y,
+        _ if (scale_x - 1.0).abs() >= (scale_y - 1.0).abs() => scale_x,
+        _ => scale_y,
+    };
+    let scale = scale
+        .max(min_size.width / orig.size.width)
+        .max(min_size.height / orig.size.height);
+    let w = orig.size.width * scale;
+    let h = orig.size.height * scale;
+    let center = orig.center();
+
+    let x = match handle {
+        HandlePosition::TopLeft | HandlePosition::MiddleLeft | HandlePosition::BottomLeft => {
+            orig.right() - w
+        }
+        HandlePosition::TopCenter | HandlePosition::BottomCenter => center.x - w / 2.0,
+        _ => orig.origin.x,
+    };
+    let y = match handle {
+        HandlePosition::TopLeft | HandlePosition::TopCenter | HandlePosition::TopRight => {
+            orig.bottom() - h
+        }
+        HandlePosition::MiddleLeft | HandlePosition::MiddleRight => center.y - h / 2.0,
+        _ => orig.origin.y,
+    };
+    Rect::new(x, y, w, h)
+}
+
 pub fn widget_to_slide_coords(
     widget_x: f64,
     widget_y: f64,
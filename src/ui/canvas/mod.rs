@@ -1,3 +1,10 @@
+pub mod arrange;
+pub mod aspect;
+pub mod clipboard;
+pub mod crop;
 pub mod interaction;
+pub mod overflow;
 pub mod selection;
+pub mod snap;
 pub mod tool;
+pub mod zoom;
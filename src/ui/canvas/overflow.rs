@@ -0,0 +1,86 @@
+use crate::model::geometry::{Point, Rect};
+use crate::model::text::TextElement;
+use crate::render::text_render;
+
+const BADGE_SIZE: f64 = 12.0;
+
+/// Line height relative to the font size, roughly what pango lays out
+const LINE_HEIGHT_FACTOR: f64 = 1.2;
+
+/// Whether the laid-out text is taller than its box and therefore clipped,
+/// even after auto-fit shrank it.
+pub fn text_overflows(text: &TextElement) -> bool {
+    text_render::measure_text(&text_render::fitted_text(text)).height
+        > text.bounds.size.height + 0.5
+}
+
+/// Hit area of the overflow badge in the bottom-right corner of a text box.
+pub fn badge_rect(bounds: &Rect) -> Rect {
+    Rect::new(
+        bounds.right() - BADGE_SIZE,
+        bounds.bottom() - BADGE_SIZE,
+        BADGE_SIZE,
+        BADGE_SIZE,
+    )
+}
+
+pub fn hit_test_badge(point: Point, bounds: &Rect) -> bool {
+    badge_rect(bounds).contains(point)
+}
+
+/// Bounds grown (or shrunk) so the box height fits the laid-out text.
+pub fn fit_box_to_text(text: &TextElement) -> Rect {
+    let mut bounds = text.bounds;
+    bounds.size.height = text_render::measure_text(text).height.ceil();
+    bounds
+}
+
+/// Smallest box height that still fits one line of the largest font used.
+pub fn min_box_height(text: &TextElement) -> f64 {
+    let largest_font = text
+        .paragraphs
+        .iter()
+        .flat_map(|p| &p.runs)
+        .map(|r| r.font.size)
+        .fold(0.0, f64::max);
+    largest_font * LINE_HEIGHT_FACTOR + text.inset.top + text.inset.bottom
+}
+
+pub fn render_overflow_badge(cr: &cairo::Context, bounds: &Rect) {
+    let badge = badge_rect(bounds);
+
+    // Red triangle tucked into the corner
+    cr.set_source_rgba(0.88, 0.11, 0.14, 0.9);
+    cr.move_to(badge.right(), badge.origin.y);
+    cr.line_to(badge.right(), badge.bottom());
+    cr.line_to(badge.origin.x, badge.bottom());
+    cr.close_path();
+    let _ = cr.fill();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::geometry::{Insets, Size};
+    use crate::ui::canvas::interaction::{DragOperation, MIN_ELEMENT_SIZE};
+    use crate::ui::canvas::selection::HandlePosition;
+
+    #[test]
+    fn text_box_cannot_shrink_below_one_line() {
+        let mut text = TextElement::new(Rect::new(0.0, 0.0, 300.0, 100.0), "Title");
+        text.paragraphs[0].runs[0].font.size = 40.0;
+        text.inset = Insets::new(0.0, 5.0, 0.0, 5.0);
+        let min_height = min_box_height(&text);
+        assert!((min_height - 58.0).abs() < 1e-9);
+
+        let resize = DragOperation::Resize {
+            handle: HandlePosition::BottomRight,
+            orig_bounds: text.bounds,
+            min_size: Size::new(MIN_ELEMENT_SIZE, min_height),
+            keep_aspect: false,
+        };
+        let bounds = resize.apply(-200.0, -90.0, false);
+        assert_eq!(bounds.size.width, 100.0);
+        assert_eq!(bounds.size.height, min_height);
+    }
+}
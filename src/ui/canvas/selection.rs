@@ -4,6 +4,9 @@ use crate::model::geometry::{Point, Rect};
 
 const HANDLE_SIZE: f64 = 8.0;
 
+/// Distance of the rotation handle above the top edge, in screen pixels
+const ROTATION_HANDLE_DISTANCE: f64 = 24.0;
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum HandlePosition {
     TopLeft,
@@ -30,8 +33,11 @@ impl HandlePosition {
         ]
     }
 
-    pub fn rect_for_bounds(&self, bounds: &Rect) -> Rect {
-        let half = HANDLE_SIZE / 2.0;
+    /// Handle rect in slide coordinates. Handles keep the same on-screen size
+    /// at every zoom, so `scale` is the current slide-to-widget scale.
+    pub fn rect_for_bounds(&self, bounds: &Rect, scale: f64) -> Rect {
+        let size = HANDLE_SIZE / scale;
+        let half = size / 2.0;
         let (cx, cy) = match self {
             HandlePosition::TopLeft => (bounds.origin.x, bounds.origin.y),
             HandlePosition::TopCenter => (bounds.center().x, bounds.origin.y),
@@ -42,30 +48,84 @@ impl HandlePosition {
             HandlePosition::BottomCenter => (bounds.center().x, bounds.bottom()),
             HandlePosition::BottomRight => (bounds.right(), bounds.bottom()),
         };
-        Rect::new(cx - half, cy - half, HANDLE_SIZE, HANDLE_SIZE)
+        Rect::new(cx - half, cy - half, size, size)
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct Selection {
+    /// Element that was selected first; handles and properties act on it
     pub element_id: Option<Uuid>,
+    /// Further elements added with Shift+click, in selection order
+    pub additional_ids: Vec<Uuid>,
 }
 
 impl Selection {
     pub fn new() -> Self {
-        Self { element_id: None }
+        Self {
+            element_id: None,
+            additional_ids: Vec::new(),
+        }
     }
 
     pub fn select(&mut self, id: Uuid) {
         self.element_id = Some(id);
+        self.additional_ids.clear();
+    }
+
+    /// Add `id` to the selection, or remove it when it is already selected.
+    pub fn toggle(&mut self, id: Uuid) {
+        if self.element_id == Some(id) {
+            self.element_id = if self.additional_ids.is_empty() {
+                None
+            } else {
+                Some(self.additional_ids.remove(0))
+            };
+        } else if let Some(pos) = self.additional_ids.iter().position(|i| *i == id) {
+            self.additional_ids.remove(pos);
+        } else if self.element_id.is_none() {
+            self.element_id = Some(id);
+        } else {
+            self.additional_ids.push(id);
+        }
+    }
+
+    /// Add `id` to the selection, keeping it if it is already selected.
+    pub fn add(&mut self, id: Uuid) {
+        if self.element_id.is_none() {
+            self.element_id = Some(id);
+        } else if !self.is_selected(id) {
+            self.additional_ids.push(id);
+        }
+    }
+
+    /// Make the selected element `id` the first-selected one, keeping the
+    /// rest of the selection.
+    pub fn focus(&mut self, id: Uuid) {
+        let Some(pos) = self.additional_ids.iter().position(|i| *i == id) else {
+            return;
+        };
+        self.additional_ids.remove(pos);
+        if let Some(previous) = self.element_id.replace(id) {
+            self.additional_ids.insert(0, previous);
+        }
     }
 
     pub fn deselect(&mut self) {
         self.element_id = None;
+        self.additional_ids.clear();
     }
 
     pub fn is_selected(&self, id: Uuid) -> bool {
-        self.element_id == Some(id)
+        self.element_id == Some(id) || self.additional_ids.contains(&id)
+    }
+
+    /// All selected ids, first-selected first.
+    pub fn ids(&self) -> Vec<Uuid> {
+        self.element_id
+            .into_iter()
+            .chain(self.additional_ids.iter().copied())
+            .collect()
     }
 
     pub fn has_selection(&self) -> bool {
@@ -79,21 +139,70 @@ impl Default for Selection {
     }
 }
 
-pub fn render_selection_handles(cr: &cairo::Context, bounds: &Rect) {
-    // Bounding box
+/// Bounding box without handles, used for the additionally selected elements.
+pub fn render_selection_outline(cr: &cairo::Context, bounds: &Rect, scale: f64) {
+    cr.set_source_rgba(0.2, 0.52, 0.89, 0.8);
+    cr.set_line_width(1.5 / scale);
+    cr.rectangle(
+        bounds.origin.x,
+        bounds.origin.y,
+        bounds.size.width,
+        bounds.size.height,
+    );
+    let _ = cr.stroke();
+}
+
+/// Dashed box around all elements of a multiple selection.
+pub fn render_group_outline(cr: &cairo::Context, bounds: &Rect, scale: f64) {
     cr.set_source_rgba(0.2, 0.52, 0.89, 0.8);
-    cr.set_line_width(1.5);
+    cr.set_line_width(1.0 / scale);
+    cr.set_dash(&[4.0 / scale, 3.0 / scale], 0.0);
+    cr.rectangle(
+        bounds.origin.x,
+        bounds.origin.y,
+        bounds.size.width,
+        bounds.size.height,
+    );
+    let _ = cr.stroke();
+    cr.set_dash(&[], 0.0);
+}
+
+/// The area being dragged out to select the elements it touches.
+pub fn render_rubber_band(cr: &cairo::Context, bounds: &Rect, scale: f64) {
     cr.rectangle(
         bounds.origin.x,
         bounds.origin.y,
         bounds.size.width,
         bounds.size.height,
     );
+    cr.set_source_rgba(0.2, 0.52, 0.89, 0.15);
+    let _ = cr.fill_preserve();
+    cr.set_source_rgba(0.2, 0.52, 0.89, 0.8);
+    cr.set_line_width(1.0 / scale);
+    let _ = cr.stroke();
+}
+
+pub fn render_selection_handles(cr: &cairo::Context, bounds: &Rect, scale: f64) {
+    let line_width = 1.5 / scale;
+
+    render_selection_outline(cr, bounds, scale);
+
+    // Rotation handle on a stem above the top edge
+    let knob = rotation_handle_center(bounds, scale);
+    cr.set_source_rgba(0.2, 0.52, 0.89, 0.8);
+    cr.set_line_width(line_width);
+    cr.move_to(knob.x, bounds.origin.y);
+    cr.line_to(knob.x, knob.y);
+    let _ = cr.stroke();
+    cr.arc(knob.x, knob.y, HANDLE_SIZE / 2.0 / scale, 0.0, std::f64::consts::TAU);
+    cr.set_source_rgb(1.0, 1.0, 1.0);
+    let _ = cr.fill_preserve();
+    cr.set_source_rgba(0.2, 0.52, 0.89, 0.8);
     let _ = cr.stroke();
 
     // Handles
     for pos in HandlePosition::all() {
-        let handle = pos.rect_for_bounds(bounds);
+        let handle = pos.rect_for_bounds(bounds, scale);
 
         // White fill
         cr.set_source_rgb(1.0, 1.0, 1.0);
@@ -107,20 +216,35 @@ pub fn render_selection_handles(cr: &cairo::Context, bounds: &Rect) {
 
         // Blue border
         cr.set_source_rgba(0.2, 0.52, 0.89, 0.8);
-        cr.set_line_width(1.5);
+        cr.set_line_width(line_width);
         let _ = cr.stroke();
     }
 }
 
-pub fn hit_test_handle(point: Point, bounds: &Rect) -> Option<HandlePosition> {
+/// Center of the rotation handle, above the top-center resize handle.
+pub fn rotation_handle_center(bounds: &Rect, scale: f64) -> Point {
+    Point::new(
+        bounds.center().x,
+        bounds.origin.y - ROTATION_HANDLE_DISTANCE / scale,
+    )
+}
+
+pub fn hit_test_rotation_handle(point: Point, bounds: &Rect, scale: f64) -> bool {
+    let center = rotation_handle_center(bounds, scale);
+    let reach = (HANDLE_SIZE / 2.0 + 4.0) / scale;
+    (point.x - center.x).hypot(point.y - center.y) <= reach
+}
+
+pub fn hit_test_handle(point: Point, bounds: &Rect, scale: f64) -> Option<HandlePosition> {
+    let margin = 4.0 / scale;
     for pos in HandlePosition::all() {
-        let handle = pos.rect_for_bounds(bounds);
+        let handle = pos.rect_for_bounds(bounds, scale);
         // Expand hit area slightly for easier grabbing
         let expanded = Rect::new(
-            handle.origin.x - 4.0,
-            handle.origin.y - 4.0,
-            handle.size.width + 8.0,
-            handle.size.height + 8.0,
+            handle.origin.x - margin,
+            handle.origin.y - margin,
+            handle.size.width + 2.0 * margin,
+            handle.size.height + 2.0 * margin,
         );
         if expanded.contains(point) {
             return Some(*pos);
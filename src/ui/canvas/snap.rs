@@ -0,0 +1,320 @@
+use crate::model::geometry::{Point, Rect, Size};
+use crate::ui::canvas::selection::HandlePosition;
+use crate::ui::preferences;
+
+/// Length of the dashes and gaps of guides in screen pixels
+const GUIDE_DASH: f64 = 4.0;
+/// Smallest distance in screen pixels between drawn grid lines
+const MIN_GRID_PIXELS: f64 = 4.0;
+
+/// A line an edge snapped to, in slide coordinates
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Guide {
+    Vertical(f64),
+    Horizontal(f64),
+}
+
+impl Guide {
+    /// Whether an edge or the center of `bounds` lies on the guide.
+    pub fn touches(&self, bounds: &Rect) -> bool {
+        let on = |values: [f64; 3], line: f64| values.iter().any(|v| (v - line).abs() < 0.01);
+        match *self {
+            Guide::Vertical(x) => on([bounds.origin.x, bounds.center().x, bounds.right()], x),
+            Guide::Horizontal(y) => on([bounds.origin.y, bounds.center().y, bounds.bottom()], y),
+        }
+    }
+}
+
+/// Edges and centers of the slide and of the elements not being dragged,
+/// and the grid lines when the grid is shown.
+#[derive(Debug, Clone, Default)]
+pub struct SnapTargets {
+    xs: Vec<f64>,
+    ys: Vec<f64>,
+    /// Spacing of the grid, which catches what no edge or center snapped to
+    grid: Option<f64>,
+}
+
+impl SnapTargets {
+    pub fn new(slide_size: &Size, others: impl IntoIterator<Item = Rect>) -> Self {
+        let mut targets = Self {
+            xs: vec![0.0, slide_size.width / 2.0, slide_size.width],
+            ys: vec![0.0, slide_size.height / 2.0, slide_size.height],
+            grid: None,
+        };
+        for rect in others {
+            targets
+                .xs
+                .extend([rect.origin.x, rect.center().x, rect.right()]);
+            targets
+                .ys
+                .extend([rect.origin.y, rect.center().y, rect.bottom()]);
+        }
+        targets
+    }
+
+    /// Also snap to a grid of `spacing` points.
+    pub fn with_grid(mut self, spacing: f64) -> Self {
+        self.grid = Some(spacing).filter(|&spacing| spacing > 0.0);
+        self
+    }
+
+    /// The nearest grid line to `value`, if the grid is on.
+    fn grid_line(&self, value: f64) -> Option<f64> {
+        self.grid.map(|spacing| (value / spacing).round() * spacing)
+    }
+
+    /// Where a single x coordinate snaps to: the closest target within
+    /// `threshold`, with its guide, or else the nearest grid line.
+    fn snap_x(&self, x: f64, threshold: f64) -> Option<(f64, Option<Guide>)> {
+        match closest(&[x], &self.xs, threshold) {
+            Some((_, target)) => Some((target, Some(Guide::Vertical(target)))),
+            None => self.grid_line(x).map(|line| (line, None)),
+        }
+    }
+
+    /// Where a single y coordinate snaps to, as for `snap_x`.
+    fn snap_y(&self, y: f64, threshold: f64) -> Option<(f64, Option<Guide>)> {
+        match closest(&[y], &self.ys, threshold) {
+            Some((_, target)) => Some((target, Some(Guide::Horizontal(target)))),
+            None => self.grid_line(y).map(|line| (line, None)),
+        }
+    }
+}
+
+/// The (value, target) pair that is closest, if within `threshold`.
+fn closest(values: &[f64], targets: &[f64], threshold: f64) -> Option<(f64, f64)> {
+    values
+        .iter()
+        .flat_map(|&value| targets.iter().map(move |&target| (value, target)))
+        .filter(|(value, target)| (target - value).abs() <= threshold)
+        .min_by(|a, b| (a.1 - a.0).abs().total_cmp(&(b.1 - b.0).abs()))
+}
+
+/// Shift moved bounds so their nearest edge or center lines up with a target,
+/// or else their origin with the grid.
+pub fn snap_move(bounds: Rect, targets: &SnapTargets, scale: f64) -> (Rect, Vec<Guide>) {
+    let threshold = preferences::snap_distance() / scale;
+    let mut snapped = bounds;
+    let mut guides = Vec::new();
+
+    let xs = [bounds.origin.x, bounds.center().x, bounds.right()];
+    if let Some((value, target)) = closest(&xs, &targets.xs, threshold) {
+        snapped.origin.x += target - value;
+        guides.push(Guide::Vertical(target));
+    } else if let Some(line) = targets.grid_line(bounds.origin.x) {
+        snapped.origin.x = line;
+    }
+    let ys = [bounds.origin.y, bounds.center().y, bounds.bottom()];
+    if let Some((value, target)) = closest(&ys, &targets.ys, threshold) {
+        snapped.origin.y += target - value;
+        guides.push(Guide::Horizontal(target));
+    } else if let Some(line) = targets.grid_line(bounds.origin.y) {
+        snapped.origin.y = line;
+    }
+
+    (snapped, guides)
+}
+
+/// Snap a free corner, e.g. of an element being drawn, to nearby targets.
+pub fn snap_point(point: Point, targets: &SnapTargets, scale: f64) -> (Point, Vec<Guide>) {
+    let threshold = preferences::snap_distance() / scale;
+    let mut snapped = point;
+    let mut guides = Vec::new();
+
+    if let Some((x, guide)) = targets.snap_x(point.x, threshold) {
+        snapped.x = x;
+        guides.extend(guide);
+    }
+    if let Some((y, guide)) = targets.snap_y(point.y, threshold) {
+        snapped.y = y;
+        guides.extend(guide);
+    }
+
+    (snapped, guides)
+}
+
+/// Snap the edges the handle drags to nearby targets. The opposite edges stay
+/// in place and snaps that would shrink the bounds below `min_size` are skipped.
+pub fn snap_resize(
+    bounds: Rect,
+    handle: HandlePosition,
+    min_size: Size,
+    targets: &SnapTargets,
+    scale: f64,
+) -> (Rect, Vec<Guide>) {
+    let threshold = preferences::snap_distance() / scale;
+    let mut snapped = bounds;
+    let mut guides = Vec::new();
+
+    match handle {
+        HandlePosition::TopLeft | HandlePosition::MiddleLeft | HandlePosition::BottomLeft => {
+            if let Some((x, guide)) = targets.snap_x(bounds.origin.x, threshold) {
+                if bounds.right() - x >= min_size.width {
+                    snapped.origin.x = x;
+                    snapped.size.width = bounds.right() - x;
+                    guides.extend(guide);
+                }
+            }
+        }
+        HandlePosition::TopRight | HandlePosition::MiddleRight | HandlePosition::BottomRight => {
+            if let Some((x, guide)) = targets.snap_x(bounds.right(), threshold) {
+                if x - bounds.origin.x >= min_size.width {
+                    snapped.size.width = x - bounds.origin.x;
+                    guides.extend(guide);
+                }
+            }
+        }
+        HandlePosition::TopCenter | HandlePosition::BottomCenter => {}
+    }
+
+    match handle {
+        HandlePosition::TopLeft | HandlePosition::TopCenter | HandlePosition::TopRight => {
+            if let Some((y, guide)) = targets.snap_y(bounds.origin.y, threshold) {
+                if bounds.bottom() - y >= min_size.height {
+                    snapped.origin.y = y;
+                    snapped.size.height = bounds.bottom() - y;
+                    guides.extend(guide);
+                }
+            }
+        }
+        HandlePosition::BottomLeft | HandlePosition::BottomCenter | HandlePosition::BottomRight => {
+            if let Some((y, guide)) = targets.snap_y(bounds.bottom(), threshold) {
+                if y - bounds.origin.y >= min_size.height {
+                    snapped.size.height = y - bounds.origin.y;
+                    guides.extend(guide);
+                }
+            }
+        }
+        HandlePosition::MiddleLeft | HandlePosition::MiddleRight => {}
+    }
+
+    (snapped, guides)
+}
+
+/// Draw grid lines every `spacing` points across the whole slide.
+pub fn render_grid(cr: &cairo::Context, slide_size: &Size, spacing: f64, scale: f64) {
+    // Lines closer than a few pixels would only darken the slide
+    if spacing * scale < MIN_GRID_PIXELS {
+        return;
+    }
+
+    cr.save().expect("cairo save");
+    cr.set_source_rgba(0.5, 0.5, 0.5, 0.3);
+    cr.set_line_width(1.0 / scale);
+    let mut x = spacing;
+    while x < slide_size.width {
+        cr.move_to(x, 0.0);
+        cr.line_to(x, slide_size.height);
+        x += spacing;
+    }
+    let mut y = spacing;
+    while y < slide_size.height {
+        cr.move_to(0.0, y);
+        cr.line_to(slide_size.width, y);
+        y += spacing;
+    }
+    let _ = cr.stroke();
+    cr.restore().expect("cairo restore");
+}
+
+/// Draw the guides across the whole slide.
+pub fn render_guides(cr: &cairo::Context, guides: &[Guide], slide_size: &Size, scale: f64) {
+    if guides.is_empty() {
+        return;
+    }
+
+    cr.save().expect("cairo save");
+    cr.set_source_rgba(0.88, 0.11, 0.6, 0.9);
+    cr.set_line_width(1.0 / scale);
+    cr.set_dash(&[GUIDE_DASH / scale, GUIDE_DASH / scale], 0.0);
+    for guide in guides {
+        match *guide {
+            Guide::Vertical(x) => {
+                cr.move_to(x, 0.0);
+                cr.line_to(x, slide_size.height);
+            }
+            Guide::Horizontal(y) => {
+                cr.move_to(0.0, y);
+                cr.line_to(slide_size.width, y);
+            }
+        }
+    }
+    let _ = cr.stroke();
+    cr.restore().expect("cairo restore");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::shape::ShapeType;
+    use crate::ui::canvas::interaction::DragOperation;
+    use crate::ui::canvas::tool::Tool;
+
+    fn targets() -> SnapTargets {
+        let others = [Rect::new(100.0, 100.0, 300.0, 200.0), Rect::new(500.0, 400.0, 200.0, 150.0)];
+        SnapTargets::new(&Size::new(1280.0, 720.0), others)
+    }
+
+    #[test]
+    fn corner_handle_snaps_to_the_size_of_other_elements() {
+        // Right edge near the first element's, bottom near the second's
+        let bounds = Rect::new(100.0, 400.0, 302.0, 148.0);
+        let min_size = Size::new(20.0, 20.0);
+        let (snapped, guides) =
+            snap_resize(bounds, HandlePosition::BottomRight, min_size, &targets(), 1.0);
+
+        assert_eq!(snapped, Rect::new(100.0, 400.0, 300.0, 150.0));
+        assert_eq!(guides, vec![Guide::Vertical(400.0), Guide::Horizontal(550.0)]);
+    }
+
+    #[test]
+    fn corner_handle_keeps_the_opposite_corner() {
+        let bounds = Rect::new(398.0, 302.0, 200.0, 100.0);
+        let min_size = Size::new(20.0, 20.0);
+        let (snapped, _) = snap_resize(bounds, HandlePosition::TopLeft, min_size, &targets(), 1.0);
+
+        assert_eq!(snapped.origin, Point::new(400.0, 300.0));
+        assert_eq!((snapped.right(), snapped.bottom()), (bounds.right(), bounds.bottom()));
+    }
+
+    #[test]
+    fn resize_snap_does_not_shrink_below_the_minimum() {
+        let bounds = Rect::new(390.0, 500.0, 12.0, 100.0);
+        let min_size = Size::new(20.0, 20.0);
+        let (snapped, guides) =
+            snap_resize(bounds, HandlePosition::MiddleRight, min_size, &targets(), 1.0);
+
+        assert_eq!(snapped, bounds);
+        assert!(guides.is_empty());
+    }
+
+    #[test]
+    fn created_corner_snaps_flush_against_other_elements() {
+        let start = Point::new(200.0, 350.0);
+        let (end, guides) = snap_point(Point::new(397.0, 403.0), &targets(), 1.0);
+        assert_eq!(end, Point::new(400.0, 400.0));
+        assert_eq!(guides, vec![Guide::Vertical(400.0), Guide::Horizontal(400.0)]);
+
+        let create = DragOperation::Create { tool: Tool::Shape(ShapeType::Rectangle), start };
+        let bounds = create.apply(end.x - start.x, end.y - start.y, false);
+        assert_eq!(bounds, Rect::new(200.0, 350.0, 200.0, 50.0));
+    }
+
+    #[test]
+    fn created_corner_falls_back_to_the_grid() {
+        let targets = targets().with_grid(20.0);
+        let (end, guides) = snap_point(Point::new(1001.0, 653.0), &targets, 1.0);
+        assert_eq!(end, Point::new(1000.0, 660.0));
+        assert!(guides.is_empty());
+    }
+
+    #[test]
+    fn created_corner_snaps_within_fewer_points_when_zoomed_in() {
+        let corner = Point::new(397.0, 403.0);
+        let (end, guides) = snap_point(corner, &targets(), 4.0);
+        assert_eq!(end, corner);
+        assert!(guides.is_empty());
+    }
+}
+
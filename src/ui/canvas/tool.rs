@@ -1,5 +1,11 @@
+use crate::model::geometry::Size;
 use crate::model::shape::ShapeType;
 
+/// Size of a shape created by clicking instead of dragging
+const DEFAULT_SHAPE_SIZE: (f64, f64) = (200.0, 120.0);
+/// Size of a text box created by clicking, with room for a line of text
+const DEFAULT_TEXT_SIZE: (f64, f64) = (300.0, 60.0);
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Tool {
     Pointer,
@@ -8,6 +14,20 @@ pub enum Tool {
     Image,
 }
 
+impl Tool {
+    /// Size of the element a click creates without dragging out its
+    /// bounds. Lines run horizontally across the width.
+    pub fn default_size(&self) -> Option<Size> {
+        let (width, height) = match self {
+            Tool::Text => DEFAULT_TEXT_SIZE,
+            Tool::Shape(ShapeType::Line) => (DEFAULT_SHAPE_SIZE.0, 0.0),
+            Tool::Shape(_) => DEFAULT_SHAPE_SIZE,
+            Tool::Pointer | Tool::Image => return None,
+        };
+        Some(Size::new(width, height))
+    }
+}
+
 impl Default for Tool {
     fn default() -> Self {
         Tool::Pointer
@@ -0,0 +1,78 @@
+use crate::model::geometry::Size;
+
+/// Margin kept around the slide in the fitting modes
+const FIT_PADDING: f64 = 0.9;
+
+/// Space around a zoomed slide that is larger than the view, in pixels
+const ZOOM_MARGIN: f64 = 24.0;
+
+/// Factor between neighbouring zoom levels of the zoom buttons
+pub const ZOOM_STEP: f64 = 1.25;
+
+/// Range of the zoom factor
+pub const MIN_ZOOM: f64 = 0.1;
+pub const MAX_ZOOM: f64 = 4.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ZoomMode {
+    /// Fit the whole slide into the view
+    #[default]
+    Fit,
+    /// Fit the slide width into the view
+    FitWidth,
+    /// Fixed zoom factor, 1.0 draws one slide point per pixel
+    Factor(f64),
+}
+
+impl ZoomMode {
+    /// Parse the target of the `win.zoom` action.
+    pub fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "fit" => Some(ZoomMode::Fit),
+            "fit-width" => Some(ZoomMode::FitWidth),
+            percent => percent
+                .parse::<u32>()
+                .ok()
+                .filter(|p| *p > 0)
+                .map(|p| ZoomMode::Factor(p as f64 / 100.0)),
+        }
+    }
+
+    /// Target of the `win.zoom` action selecting this mode.
+    pub fn id(&self) -> String {
+        match self {
+            ZoomMode::Fit => "fit".to_string(),
+            ZoomMode::FitWidth => "fit-width".to_string(),
+            ZoomMode::Factor(factor) => ((factor * 100.0).round() as u32).to_string(),
+        }
+    }
+
+    /// Size the canvas needs to show the whole slide in a view `width`
+    /// wide, so it can be scrolled. Zero where the slide fits any view.
+    pub fn content_size(&self, slide_size: &Size, width: f64) -> (f64, f64) {
+        match self {
+            ZoomMode::Fit => (0.0, 0.0),
+            ZoomMode::FitWidth => {
+                let scale = self.scale(slide_size, width, 0.0);
+                (0.0, slide_size.height * scale / FIT_PADDING)
+            }
+            ZoomMode::Factor(factor) => (
+                slide_size.width * factor + 2.0 * ZOOM_MARGIN,
+                slide_size.height * factor + 2.0 * ZOOM_MARGIN,
+            ),
+        }
+    }
+
+    /// Slide-to-widget scale for a view of the given size.
+    pub fn scale(&self, slide_size: &Size, width: f64, height: f64) -> f64 {
+        match self {
+            ZoomMode::Fit => {
+                let scale_x = width / slide_size.width;
+                let scale_y = height / slide_size.height;
+                scale_x.min(scale_y) * FIT_PADDING
+            }
+            ZoomMode::FitWidth => width / slide_size.width * FIT_PADDING,
+            ZoomMode::Factor(factor) => *factor,
+        }
+    }
+}
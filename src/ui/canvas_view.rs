@@ -5,26 +5,69 @@ use std::rc::Rc;
 
 use crate::model::document::Document;
 use crate::model::element::SlideElement;
-use crate::model::geometry::{Rect, Size};
-use crate::model::shape::ShapeElement;
-use crate::model::text::TextElement;
-use crate::render::engine;
-use crate::ui::canvas::interaction::{self, DragOperation};
+use crate::model::geometry::{Point, Rect, Size};
+use crate::model::shape::{ShapeElement, ShapeType};
+use crate::model::style::FontStyle;
+use crate::model::text::{TextAlignment, TextElement};
+use crate::model::image::ScaleMode;
+use crate::render::{engine, image_render, shape_render, text_render};
+use crate::ui::canvas::arrange::{self, MatchDimension};
+use crate::ui::canvas::aspect;
+use crate::ui::canvas::clipboard;
+use crate::ui::canvas::crop;
+use crate::ui::canvas::interaction::{self, DragOperation, MIN_ELEMENT_SIZE};
+use crate::ui::canvas::overflow;
 use crate::ui::canvas::selection::{self, Selection};
+use crate::ui::canvas::snap::{self, Guide, SnapTargets};
 use crate::ui::canvas::tool::Tool;
+use crate::ui::canvas::zoom::{ZoomMode, MAX_ZOOM, MIN_ZOOM, ZOOM_STEP};
+use crate::ui::preferences;
+use crate::ui::shortcuts;
+
+/// Tag giving the in-place text editor the element's font
+const EDITOR_FONT_TAG: &str = "font";
+
+type ZoomCallback = RefCell<Option<Box<dyn Fn(ZoomMode)>>>;
+type NotifyCallback = Rc<RefCell<Option<Box<dyn Fn()>>>>;
 
 mod imp {
     use super::*;
 
     pub struct CanvasView {
+        /// Pans the drawing area when zoomed beyond the view
+        pub scrolled_window: gtk::ScrolledWindow,
+        /// Holds the drawing area and, while editing text, the text view
+        pub overlay: gtk::Overlay,
         pub drawing_area: gtk::DrawingArea,
         pub document: RefCell<Option<Rc<RefCell<Document>>>>,
         pub current_slide_index: Cell<usize>,
         pub selection: Rc<RefCell<Selection>>,
         pub drag_op: Rc<RefCell<Option<DragOperation>>>,
+        pub create_preview: Rc<Cell<Option<Point>>>,
         pub current_tool: Rc<Cell<Tool>>,
+        pub zoom_mode: Rc<Cell<ZoomMode>>,
+        /// Slide point to bring back under the given view position once
+        /// the drawing area is resized for a new zoom
+        pub zoom_anchor: Cell<Option<(Point, f64, f64)>>,
+        /// Last pointer position over the drawing area
+        pub pointer: Cell<Option<(f64, f64)>>,
+        pub aspect_snap: Rc<Cell<bool>>,
+        pub aspect_badge: Rc<Cell<Option<(u32, u32)>>>,
+        pub object_snap: Rc<Cell<bool>>,
+        /// Draw the grid behind the elements and snap to it
+        pub show_grid: Rc<Cell<bool>>,
+        pub snap_guides: Rc<RefCell<Vec<Guide>>>,
         pub on_selection_changed: Rc<RefCell<Option<Box<dyn Fn(Option<uuid::Uuid>)>>>>,
         pub on_tool_changed: Rc<RefCell<Option<Box<dyn Fn(Tool)>>>>,
+        /// Called while a drag moves, resizes, rotates or crops the selection
+        pub on_geometry_changed: NotifyCallback,
+        /// Called once an edit on the canvas is complete
+        pub on_edited: NotifyCallback,
+        pub on_zoom_changed: ZoomCallback,
+        /// Text element being edited in place, and the view editing it
+        pub text_edit: RefCell<Option<(uuid::Uuid, gtk::TextView)>>,
+        /// Image whose handles crop it instead of resizing it
+        pub crop_image: Rc<Cell<Option<uuid::Uuid>>>,
     }
 
     impl std::fmt::Debug for CanvasView {
@@ -36,14 +79,30 @@ mod imp {
     impl Default for CanvasView {
         fn default() -> Self {
             Self {
+                scrolled_window: gtk::ScrolledWindow::new(),
+                overlay: gtk::Overlay::new(),
                 drawing_area: gtk::DrawingArea::new(),
                 document: RefCell::new(None),
                 current_slide_index: Cell::new(0),
                 selection: Rc::new(RefCell::new(Selection::new())),
                 drag_op: Rc::new(RefCell::new(None)),
+                create_preview: Rc::new(Cell::new(None)),
                 current_tool: Rc::new(Cell::new(Tool::Pointer)),
+                zoom_mode: Rc::new(Cell::new(ZoomMode::default())),
+                zoom_anchor: Cell::new(None),
+                pointer: Cell::new(None),
+                aspect_snap: Rc::new(Cell::new(true)),
+                aspect_badge: Rc::new(Cell::new(None)),
+                object_snap: Rc::new(Cell::new(true)),
+                show_grid: Rc::new(Cell::new(false)),
+                snap_guides: Rc::new(RefCell::new(Vec::new())),
                 on_selection_changed: Rc::new(RefCell::new(None)),
                 on_tool_changed: Rc::new(RefCell::new(None)),
+                on_geometry_changed: Rc::new(RefCell::new(None)),
+                on_edited: Rc::new(RefCell::new(None)),
+                on_zoom_changed: RefCell::new(None),
+                text_edit: RefCell::new(None),
+                crop_image: Rc::new(Cell::new(None)),
             }
         }
     }
@@ -64,14 +123,16 @@ mod imp {
             self.parent_constructed();
 
             let obj = self.obj();
-            self.drawing_area.set_parent(&*obj);
+            self.scrolled_window.set_parent(&*obj);
+            self.scrolled_window.set_child(Some(&self.overlay));
+            self.overlay.set_child(Some(&self.drawing_area));
             self.drawing_area.set_hexpand(true);
             self.drawing_area.set_vexpand(true);
             self.drawing_area.set_focusable(true);
         }
 
         fn dispose(&self) {
-            self.drawing_area.unparent();
+            self.scrolled_window.unparent();
         }
     }
 
@@ -95,7 +156,12 @@ impl CanvasView {
         let slide_index = imp.current_slide_index.clone();
         let selection = imp.selection.clone();
         let drag_op_for_draw = imp.drag_op.clone();
-        let current_tool_for_draw = imp.current_tool.clone();
+        let create_preview_for_draw = imp.create_preview.clone();
+        let zoom_for_draw = imp.zoom_mode.clone();
+        let aspect_badge_for_draw = imp.aspect_badge.clone();
+        let snap_guides_for_draw = imp.snap_guides.clone();
+        let show_grid_for_draw = imp.show_grid.clone();
+        let crop_for_draw = imp.crop_image.clone();
 
         imp.drawing_area
             .set_draw_func(move |_area, cr, width, height| {
@@ -111,8 +177,12 @@ impl CanvasView {
 
                 draw_canvas_background(cr, width as f64, height as f64);
 
-                let (scale, offset_x, offset_y) =
-                    compute_slide_transform(slide_size, width as f64, height as f64);
+                let (scale, offset_x, offset_y) = compute_slide_transform(
+                    slide_size,
+                    width as f64,
+                    height as f64,
+                    zoom_for_draw.get(),
+                );
 
                 cr.save().expect("cairo save");
                 cr.translate(offset_x, offset_y);
@@ -128,24 +198,92 @@ impl CanvasView {
                 cr.rectangle(-0.5, -0.5, slide_size.width + 1.0, slide_size.height + 1.0);
                 let _ = cr.stroke();
 
-                engine::render_slide(cr, slide, slide_size);
+                engine::render_background(cr, &slide.background, slide_size);
+                if show_grid_for_draw.get() {
+                    snap::render_grid(cr, slide_size, preferences::grid_spacing(), scale);
+                }
+                engine::render_elements(cr, slide);
+
+                // Flag text boxes whose content is clipped
+                for element in &slide.elements {
+                    if let SlideElement::Text(text) = element {
+                        if text.visible && overflow::text_overflows(text) {
+                            overflow::render_overflow_badge(cr, &text.bounds);
+                        }
+                    }
+                }
 
-                // Draw selection handles
+                // Draw selection handles; a multiple selection also gets a
+                // box around all of it
                 let sel = selection.borrow();
+                let mut group: Option<Rect> = None;
+                for element in &slide.elements {
+                    if sel.additional_ids.contains(&element.id()) {
+                        selection::render_selection_outline(cr, element.bounds(), scale);
+                    }
+                    if sel.is_selected(element.id()) {
+                        let bounds = element.bounds();
+                        group = Some(group.map_or(*bounds, |g| g.union(bounds)));
+                    }
+                }
+                if let Some(group) = group.filter(|_| !sel.additional_ids.is_empty()) {
+                    selection::render_group_outline(cr, &group, scale);
+                }
                 if let Some(sel_id) = sel.element_id {
                     for element in &slide.elements {
                         if element.id() == sel_id {
-                            selection::render_selection_handles(cr, element.bounds());
+                            match element {
+                                SlideElement::Image(image)
+                                    if crop_for_draw.get() == Some(sel_id) =>
+                                {
+                                    crop::render_crop_frame(cr, image, scale);
+                                }
+                                _ => {
+                                    selection::render_selection_handles(cr, element.bounds(), scale)
+                                }
+                            }
+                            if let Some(ratio) = aspect_badge_for_draw.get() {
+                                aspect::render_ratio_badge(cr, element.bounds(), ratio, scale);
+                            }
                             break;
                         }
                     }
                 }
 
-                let _ = (&drag_op_for_draw, &current_tool_for_draw);
+                snap::render_guides(cr, &snap_guides_for_draw.borrow(), slide_size, scale);
+
+                // Preview of the element being created, or the rubber band
+                match &*drag_op_for_draw.borrow() {
+                    Some(DragOperation::Create { tool, start }) => {
+                        if let Some(end) = create_preview_for_draw.get() {
+                            draw_create_preview(cr, *tool, *start, end);
+                        }
+                    }
+                    Some(DragOperation::Select { start, .. }) => {
+                        if let Some(end) = create_preview_for_draw.get() {
+                            let band = interaction::normalize_rect(start.x, start.y, end.x, end.y);
+                            selection::render_rubber_band(cr, &band, scale);
+                        }
+                    }
+                    _ => {}
+                }
 
                 cr.restore().expect("cairo restore");
             });
 
+        // Keep the in-place text editor over its element, and the zoomed
+        // slide under the pointer
+        let canvas = self.downgrade();
+        imp.drawing_area.connect_resize(move |_, _, _| {
+            if let Some(canvas) = canvas.upgrade() {
+                canvas.update_content_size();
+                canvas.scroll_to_zoom_anchor();
+                canvas.place_text_editor();
+            }
+        });
+
+        self.setup_zoom_handler();
+
         // Set up click handler
         self.setup_click_handler(doc.clone());
         self.setup_drag_handler(doc.clone());
@@ -162,9 +300,18 @@ impl CanvasView {
         let slide_index = imp.current_slide_index.clone();
         let drawing_area = imp.drawing_area.clone();
         let on_changed = imp.on_selection_changed.clone();
+        let on_edited = imp.on_edited.clone();
         let current_tool = imp.current_tool.clone();
+        let zoom_mode = imp.zoom_mode.clone();
+        let canvas = self.downgrade();
+
+        gesture.connect_pressed(move |gesture, n_press, x, y| {
+            let Some(canvas) = canvas.upgrade() else {
+                return;
+            };
+            // Clicking the canvas ends in-place text editing
+            canvas.finish_text_edit();
 
-        gesture.connect_pressed(move |_gesture, _n_press, x, y| {
             let tool = current_tool.get();
 
             // For creation tools, clicking is handled by drag handler
@@ -172,29 +319,96 @@ impl CanvasView {
                 return;
             }
 
-            let doc = doc.borrow();
+            let doc_ref = doc.borrow();
             let idx = slide_index.get();
-            if idx >= doc.slides.len() {
+            if idx >= doc_ref.slides.len() {
                 return;
             }
 
-            let slide = &doc.slides[idx];
-            let slide_size = &doc.slide_size;
+            let slide = &doc_ref.slides[idx];
+            let slide_size = &doc_ref.slide_size;
             let width = drawing_area.width() as f64;
             let height = drawing_area.height() as f64;
-            let (scale, offset_x, offset_y) = compute_slide_transform(slide_size, width, height);
+            let (scale, offset_x, offset_y) =
+                compute_slide_transform(slide_size, width, height, zoom_mode.get());
 
             let slide_point = interaction::widget_to_slide_coords(x, y, scale, offset_x, offset_y);
 
-            let mut sel = selection.borrow_mut();
+            // Clicking an overflow badge fits the text box to its content
+            let badge_hit = slide.elements.iter().rev().find_map(|element| match element {
+                SlideElement::Text(text)
+                    if text.visible
+                        && overflow::hit_test_badge(slide_point, &text.bounds)
+                        && overflow::text_overflows(text) =>
+                {
+                    Some((text.id, overflow::fit_box_to_text(text)))
+                }
+                _ => None,
+            });
+            if let Some((id, fitted)) = badge_hit {
+                drop(doc_ref);
+                if let Some(element) = doc.borrow_mut().slides[idx]
+                    .elements
+                    .iter_mut()
+                    .find(|e| e.id() == id)
+                {
+                    *element.bounds_mut() = fitted;
+                }
+                selection.borrow_mut().select(id);
+                if let Some(cb) = on_edited.borrow().as_ref() {
+                    cb();
+                }
+                if let Some(cb) = on_changed.borrow().as_ref() {
+                    cb(Some(id));
+                }
+                drawing_area.queue_draw();
+                return;
+            }
+
+            // Presses on the handles of the selected element are left to the
+            // drag handler, even where they stick out of the element
+            let on_handle = selection
+                .borrow()
+                .element_id
+                .and_then(|id| slide.elements.iter().find(|e| e.id() == id))
+                .is_some_and(|element| {
+                    let bounds = element.bounds();
+                    selection::hit_test_rotation_handle(slide_point, bounds, scale)
+                        || selection::hit_test_handle(slide_point, bounds, scale).is_some()
+                });
+            if on_handle {
+                return;
+            }
+            canvas.finish_crop();
 
+            let mut sel = selection.borrow_mut();
+            let extend = gesture
+                .current_event_state()
+                .contains(gdk::ModifierType::SHIFT_MASK);
+
+            // Double-clicking a text box edits its text in place, and an
+            // image starts cropping it
+            let mut edit = None;
+            let mut crop = false;
             if let Some((_idx, element)) = slide.find_element_at(slide_point) {
-                sel.select(element.id());
-                let id = Some(element.id());
+                if n_press == 2 && !extend && matches!(element, SlideElement::Text(_)) {
+                    edit = Some(element.id());
+                }
+                crop = n_press == 2 && !extend && matches!(element, SlideElement::Image(_));
+                if extend {
+                    sel.toggle(element.id());
+                } else if sel.is_selected(element.id()) {
+                    // Keep the group so it can be dragged together
+                    sel.focus(element.id());
+                } else {
+                    sel.select(element.id());
+                }
+                let id = sel.element_id;
                 if let Some(cb) = on_changed.borrow().as_ref() {
                     cb(id);
                 }
-            } else {
+            } else if !extend {
+                // Shift+click on empty space keeps the selection
                 sel.deselect();
                 if let Some(cb) = on_changed.borrow().as_ref() {
                     cb(None);
@@ -202,6 +416,14 @@ impl CanvasView {
             }
 
             drawing_area.queue_draw();
+            drop(sel);
+            drop(doc_ref);
+            if let Some(id) = edit {
+                canvas.edit_text(id, false);
+            }
+            if crop {
+                canvas.start_crop();
+            }
         });
 
         imp.drawing_area.add_controller(gesture);
@@ -216,6 +438,7 @@ impl CanvasView {
         let slide_index = imp.current_slide_index.clone();
         let drawing_area = imp.drawing_area.clone();
         let current_tool = imp.current_tool.clone();
+        let zoom_mode = imp.zoom_mode.clone();
         let doc_for_drag = doc.clone();
         let doc_for_update = doc.clone();
         let doc_for_end = doc;
@@ -225,8 +448,12 @@ impl CanvasView {
         let slide_index_start = slide_index.clone();
         let drawing_area_start = drawing_area.clone();
         let current_tool_start = current_tool.clone();
+        let zoom_start = zoom_mode.clone();
+        let object_snap_start = imp.object_snap.clone();
+        let show_grid_start = imp.show_grid.clone();
+        let crop_start = imp.crop_image.clone();
 
-        gesture.connect_drag_begin(move |_gesture, x, y| {
+        gesture.connect_drag_begin(move |gesture, x, y| {
             let doc = doc_for_drag.borrow();
             let idx = slide_index_start.get();
             if idx >= doc.slides.len() {
@@ -237,18 +464,27 @@ impl CanvasView {
             let slide_size = &doc.slide_size;
             let width = drawing_area_start.width() as f64;
             let height = drawing_area_start.height() as f64;
-            let (scale, offset_x, offset_y) = compute_slide_transform(slide_size, width, height);
+            let (scale, offset_x, offset_y) =
+                compute_slide_transform(slide_size, width, height, zoom_start.get());
 
             let slide_point = interaction::widget_to_slide_coords(x, y, scale, offset_x, offset_y);
 
             let tool = current_tool_start.get();
 
-            // Creation tools: start a create drag
+            // Creation tools: start a create drag, from a snapped corner
             if !matches!(tool, Tool::Pointer) {
-                *drag_op_start.borrow_mut() = Some(DragOperation::Create {
-                    tool,
-                    start: slide_point,
-                });
+                let targets = drag_snap_targets(
+                    object_snap_start.get(),
+                    show_grid_start.get(),
+                    gesture,
+                    slide_size,
+                    slide.elements.iter().map(|e| *e.bounds()),
+                );
+                let start = match targets {
+                    Some(targets) => snap::snap_point(slide_point, &targets, scale).0,
+                    None => slide_point,
+                };
+                *drag_op_start.borrow_mut() = Some(DragOperation::Create { tool, start });
                 return;
             }
 
@@ -257,51 +493,104 @@ impl CanvasView {
             if let Some(sel_id) = sel.element_id {
                 for element in &slide.elements {
                     if element.id() == sel_id {
+                        if element.is_locked() {
+                            break;
+                        }
+                        // While cropping, the handles move the edges over the
+                        // picture and there is no rotation handle
+                        if crop_start.get() == Some(sel_id) {
+                            let handle =
+                                selection::hit_test_handle(slide_point, element.bounds(), scale);
+                            if let (SlideElement::Image(image), Some(handle)) = (element, handle) {
+                                *drag_op_start.borrow_mut() = Some(DragOperation::Crop {
+                                    handle,
+                                    orig_bounds: image.bounds,
+                                    orig_crop: image.crop,
+                                    flips: (image.flip_h, image.flip_v),
+                                });
+                                return;
+                            }
+                        } else if selection::hit_test_rotation_handle(
+                            slide_point,
+                            element.bounds(),
+                            scale,
+                        ) {
+                            *drag_op_start.borrow_mut() = Some(DragOperation::Rotate {
+                                orig_bounds: *element.bounds(),
+                                start: slide_point,
+                                orig_rotation: element.rotation(),
+                            });
+                            return;
+                        }
                         if let Some(handle) =
-                            selection::hit_test_handle(slide_point, element.bounds())
+                            selection::hit_test_handle(slide_point, element.bounds(), scale)
                         {
+                            // Text boxes keep room for at least one line
+                            let min_height = match element {
+                                SlideElement::Text(text) => {
+                                    overflow::min_box_height(text).max(MIN_ELEMENT_SIZE)
+                                }
+                                _ => MIN_ELEMENT_SIZE,
+                            };
                             *drag_op_start.borrow_mut() = Some(DragOperation::Resize {
                                 handle,
                                 orig_bounds: *element.bounds(),
+                                min_size: Size::new(MIN_ELEMENT_SIZE, min_height),
+                                keep_aspect: matches!(element, SlideElement::Image(_)),
                             });
                             return;
                         }
 
                         if element.bounds().contains(slide_point) {
+                            let others = slide
+                                .elements
+                                .iter()
+                                .filter(|e| sel.additional_ids.contains(&e.id()) && !e.is_locked())
+                                .map(|e| (e.id(), *e.bounds()))
+                                .collect();
                             *drag_op_start.borrow_mut() = Some(DragOperation::Move {
                                 start_x: slide_point.x,
                                 start_y: slide_point.y,
                                 orig_bounds: *element.bounds(),
+                                others,
                             });
                             return;
                         }
                     }
                 }
             }
+
+            // Dragging on empty space selects the elements the band touches
+            if slide.find_element_at(slide_point).is_none() {
+                let extend = gesture
+                    .current_event_state()
+                    .contains(gdk::ModifierType::SHIFT_MASK);
+                *drag_op_start.borrow_mut() = Some(DragOperation::Select {
+                    start: slide_point,
+                    extend,
+                });
+            }
         });
 
         let selection_update = selection.clone();
         let drag_op_update = drag_op.clone();
+        let create_preview_update = imp.create_preview.clone();
         let slide_index_update = slide_index.clone();
         let drawing_area_update = drawing_area.clone();
-
-        gesture.connect_drag_update(move |_gesture, offset_x, offset_y| {
+        let zoom_update = zoom_mode.clone();
+        let aspect_snap_update = imp.aspect_snap.clone();
+        let aspect_badge_update = imp.aspect_badge.clone();
+        let object_snap_update = imp.object_snap.clone();
+        let show_grid_update = imp.show_grid.clone();
+        let snap_guides_update = imp.snap_guides.clone();
+        let on_geometry_update = imp.on_geometry_changed.clone();
+
+        gesture.connect_drag_update(move |gesture, offset_x, offset_y| {
             let op = drag_op_update.borrow();
             if op.is_none() {
                 return;
             }
 
-            let is_create = matches!(op.as_ref(), Some(DragOperation::Create { .. }));
-
-            if is_create {
-                // For creation, just redraw to show preview
-                drop(op);
-                // We update the drag offset in a different way for create:
-                // store the offset so draw_func can compute the preview rect
-                drawing_area_update.queue_draw();
-                return;
-            }
-
             let mut doc = doc_for_update.borrow_mut();
             let idx = slide_index_update.get();
             if idx >= doc.slides.len() {
@@ -311,40 +600,284 @@ impl CanvasView {
             let slide_size = doc.slide_size;
             let width = drawing_area_update.width() as f64;
             let height = drawing_area_update.height() as f64;
-            let (scale, _, _) = compute_slide_transform(&slide_size, width, height);
+            let (scale, _, _) =
+                compute_slide_transform(&slide_size, width, height, zoom_update.get());
 
             let dx = offset_x / scale;
             let dy = offset_y / scale;
 
+            if let Some(DragOperation::Create { tool, start }) = op.as_ref() {
+                // For creation, store the drag end point so draw_func can render the preview
+                let end = Point::new(start.x + dx, start.y + dy);
+                // Shift draws squares, circles and lines in 45° steps, which
+                // snapping to other edges would undo
+                let constrain = gesture
+                    .current_event_state()
+                    .contains(gdk::ModifierType::SHIFT_MASK);
+                if constrain && matches!(tool, Tool::Shape(_)) {
+                    let end = interaction::constrain_create(*tool, *start, end);
+                    create_preview_update.set(Some(end));
+                    snap_guides_update.borrow_mut().clear();
+                    drawing_area_update.queue_draw();
+                    return;
+                }
+                let targets = drag_snap_targets(
+                    object_snap_update.get(),
+                    show_grid_update.get(),
+                    gesture,
+                    &slide_size,
+                    doc.slides[idx].elements.iter().map(|e| *e.bounds()),
+                );
+                let (end, guides) = match targets {
+                    Some(targets) => snap::snap_point(end, &targets, scale),
+                    None => (end, Vec::new()),
+                };
+                create_preview_update.set(Some(end));
+                *snap_guides_update.borrow_mut() = guides;
+                drawing_area_update.queue_draw();
+                return;
+            }
+
+            if let Some(DragOperation::Select { start, .. }) = op.as_ref() {
+                create_preview_update.set(Some(Point::new(start.x + dx, start.y + dy)));
+                drawing_area_update.queue_draw();
+                return;
+            }
+
+            // Shift snaps the rotation to 15° steps
+            if let Some(op @ DragOperation::Rotate { .. }) = op.as_ref() {
+                let snap = gesture
+                    .current_event_state()
+                    .contains(gdk::ModifierType::SHIFT_MASK);
+                let sel_id = selection_update.borrow().element_id;
+                if let (Some(rotation), Some(sel_id)) = (op.rotation(dx, dy, snap), sel_id) {
+                    if let Some(element) =
+                        doc.slides[idx].elements.iter_mut().find(|e| e.id() == sel_id)
+                    {
+                        element.set_rotation(rotation);
+                    }
+                }
+                drop(doc);
+                if let Some(cb) = on_geometry_update.borrow().as_ref() {
+                    cb();
+                }
+                drawing_area_update.queue_draw();
+                return;
+            }
+
+            if let Some(op @ DragOperation::Crop { .. }) = op.as_ref() {
+                let sel_id = selection_update.borrow().element_id;
+                if let (Some((bounds, crop)), Some(sel_id)) = (op.crop(dx, dy), sel_id) {
+                    if let Some(SlideElement::Image(image)) =
+                        doc.slides[idx].elements.iter_mut().find(|e| e.id() == sel_id)
+                    {
+                        image.bounds = bounds;
+                        image.crop = crop;
+                    }
+                }
+                drop(doc);
+                if let Some(cb) = on_geometry_update.borrow().as_ref() {
+                    cb();
+                }
+                drawing_area_update.queue_draw();
+                return;
+            }
+
             let sel = selection_update.borrow();
             if let Some(sel_id) = sel.element_id {
                 if let Some(op) = op.as_ref() {
-                    let new_bounds = op.apply(dx, dy);
+                    // Shift keeps the proportions of a resized element
+                    let shift = gesture
+                        .current_event_state()
+                        .contains(gdk::ModifierType::SHIFT_MASK);
+                    let new_bounds = op.apply(dx, dy, shift);
+                    let keeps_aspect = op.keeps_aspect(shift);
 
                     let slide = &mut doc.slides[idx];
+
+                    // Line up with the slide and the unselected elements
+                    let targets = drag_snap_targets(
+                        object_snap_update.get(),
+                        show_grid_update.get(),
+                        gesture,
+                        &slide_size,
+                        slide
+                            .elements
+                            .iter()
+                            .filter(|e| !sel.is_selected(e.id()))
+                            .map(|e| *e.bounds()),
+                    );
+                    let (new_bounds, mut guides) = match (op, &targets) {
+                        (DragOperation::Move { .. }, Some(targets)) => {
+                            snap::snap_move(new_bounds, targets, scale)
+                        }
+                        // Snapping an edge would change the kept proportions
+                        (DragOperation::Resize { handle, min_size, .. }, Some(targets))
+                            if !keeps_aspect =>
+                        {
+                            snap::snap_resize(new_bounds, *handle, *min_size, targets, scale)
+                        }
+                        _ => (new_bounds, Vec::new()),
+                    };
+
                     for element in &mut slide.elements {
                         if element.id() == sel_id {
-                            *element.bounds_mut() = new_bounds;
+                            // Shapes snap to common aspect ratios while resized
+                            // freely; images keep their own
+                            let snaps = aspect_snap_update.get()
+                                && !keeps_aspect
+                                && match element {
+                                    SlideElement::Image(_) => false,
+                                    SlideElement::Shape(s) => s.shape_type != ShapeType::Line,
+                                    SlideElement::Text(_) => false,
+                                };
+                            match op {
+                                DragOperation::Resize { handle, .. } if snaps => {
+                                    let (snapped, ratio) = aspect::snap_bounds(new_bounds, *handle);
+                                    guides.retain(|guide| guide.touches(&snapped));
+                                    *element.bounds_mut() = snapped;
+                                    aspect_badge_update.set(ratio);
+                                }
+                                _ => *element.bounds_mut() = new_bounds,
+                            }
                             break;
                         }
                     }
+                    *snap_guides_update.borrow_mut() = guides;
+
+                    // The rest of the selection follows the snapped element
+                    if let DragOperation::Move { orig_bounds, others, .. } = op {
+                        let moved = slide
+                            .elements
+                            .iter()
+                            .find(|e| e.id() == sel_id)
+                            .map(|e| e.bounds().origin);
+                        if let Some(moved) = moved {
+                            let delta_x = moved.x - orig_bounds.origin.x;
+                            let delta_y = moved.y - orig_bounds.origin.y;
+                            for (id, start) in others {
+                                if let Some(element) =
+                                    slide.elements.iter_mut().find(|e| e.id() == *id)
+                                {
+                                    let bounds = element.bounds_mut();
+                                    bounds.origin.x = start.origin.x + delta_x;
+                                    bounds.origin.y = start.origin.y + delta_y;
+                                }
+                            }
+                        }
+                    }
                 }
             }
 
+            // The properties panel reads the new bounds
+            drop(doc);
+            if let Some(cb) = on_geometry_update.borrow().as_ref() {
+                cb();
+            }
             drawing_area_update.queue_draw();
         });
 
         let drag_op_end = drag_op.clone();
+        let create_preview_end = imp.create_preview.clone();
+        let aspect_badge_end = imp.aspect_badge.clone();
+        let snap_guides_end = imp.snap_guides.clone();
         let selection_end = selection;
         let slide_index_end = slide_index;
         let drawing_area_end = drawing_area.clone();
         let current_tool_end = current_tool.clone();
+        let zoom_end = zoom_mode;
         let on_changed_end = imp.on_selection_changed.clone();
+        let on_edited_end = imp.on_edited.clone();
         let on_tool_changed_end = imp.on_tool_changed.clone();
+        let canvas_end = self.downgrade();
 
         gesture.connect_drag_end(move |gesture, offset_x, offset_y| {
             let op = drag_op_end.borrow().clone();
             *drag_op_end.borrow_mut() = None;
+            let preview_end = create_preview_end.take();
+            let had_guides = !std::mem::take(&mut *snap_guides_end.borrow_mut()).is_empty();
+            if aspect_badge_end.take().is_some() || had_guides {
+                drawing_area_end.queue_draw();
+            }
+
+            if let Some(DragOperation::Select { start, extend }) = &op {
+                let Some(end) = preview_end else {
+                    drawing_area_end.queue_draw();
+                    return;
+                };
+                let band = interaction::normalize_rect(start.x, start.y, end.x, end.y);
+                let hits: Vec<uuid::Uuid> = {
+                    let doc = doc_for_end.borrow();
+                    doc.slides
+                        .get(slide_index_end.get())
+                        .map(|slide| {
+                            slide
+                                .elements
+                                .iter()
+                                .filter(|e| e.is_visible() && e.bounds().intersects(&band))
+                                .map(|e| e.id())
+                                .collect()
+                        })
+                        .unwrap_or_default()
+                };
+                let selected = {
+                    let mut sel = selection_end.borrow_mut();
+                    if !extend {
+                        sel.deselect();
+                    }
+                    for id in hits {
+                        sel.add(id);
+                    }
+                    sel.element_id
+                };
+                if let Some(cb) = on_changed_end.borrow().as_ref() {
+                    cb(selected);
+                }
+                drawing_area_end.queue_draw();
+                return;
+            }
+
+            // A finished move, resize, rotation or crop is one undo step
+            if let Some(
+                op @ (DragOperation::Move { .. }
+                | DragOperation::Resize { .. }
+                | DragOperation::Rotate { .. }
+                | DragOperation::Crop { .. }),
+            ) = &op
+            {
+                let sel_id = selection_end.borrow().element_id;
+                let moved = sel_id.is_some_and(|id| {
+                    let doc = doc_for_end.borrow();
+                    doc.slides
+                        .get(slide_index_end.get())
+                        .and_then(|slide| slide.elements.iter().find(|e| e.id() == id))
+                        .is_some_and(|e| match op {
+                            DragOperation::Rotate { orig_rotation, .. } => {
+                                e.rotation() != *orig_rotation
+                            }
+                            DragOperation::Move { orig_bounds, .. }
+                            | DragOperation::Resize { orig_bounds, .. } => {
+                                e.bounds() != orig_bounds
+                            }
+                            DragOperation::Crop { orig_bounds, orig_crop, .. } => match e {
+                                SlideElement::Image(image) => {
+                                    image.bounds != *orig_bounds || image.crop != *orig_crop
+                                }
+                                _ => false,
+                            },
+                            _ => false,
+                        })
+                });
+                if moved {
+                    if let Some(cb) = on_edited_end.borrow().as_ref() {
+                        cb();
+                    }
+                    if let Some(cb) = on_changed_end.borrow().as_ref() {
+                        cb(sel_id);
+                    }
+                }
+                return;
+            }
 
             if let Some(DragOperation::Create { tool, start }) = op {
                 let slide_size;
@@ -354,26 +887,40 @@ impl CanvasView {
                     slide_size = doc.slide_size;
                     let width = drawing_area_end.width() as f64;
                     let height = drawing_area_end.height() as f64;
-                    let transform = compute_slide_transform(&slide_size, width, height);
+                    let transform =
+                        compute_slide_transform(&slide_size, width, height, zoom_end.get());
                     scale = transform.0;
                 }
 
                 let dx = offset_x / scale;
                 let dy = offset_y / scale;
 
-                // Require minimum drag distance to create element
-                if dx.abs() < 5.0 && dy.abs() < 5.0 {
-                    return;
-                }
-
-                let bounds = interaction::normalize_rect(
-                    start.x,
-                    start.y,
-                    start.x + dx,
-                    start.y + dy,
-                );
-
-                let element = create_element_for_tool(tool, bounds);
+                // A click without dragging creates an element of the default
+                // size centered on it. The preview holds the snapped corner
+                // of a drag.
+                let (start, end) = if dx.abs() < 5.0 && dy.abs() < 5.0 {
+                    let Some(size) = tool.default_size() else {
+                        drawing_area_end.queue_draw();
+                        return;
+                    };
+                    let (half_w, half_h) = (size.width / 2.0, size.height / 2.0);
+                    (
+                        Point::new(start.x - half_w, start.y - half_h),
+                        Point::new(start.x + half_w, start.y + half_h),
+                    )
+                } else {
+                    let end = Point::new(start.x + dx, start.y + dy);
+                    // Shift may have been pressed since the last update
+                    let constrain = gesture
+                        .current_event_state()
+                        .contains(gdk::ModifierType::SHIFT_MASK);
+                    if constrain && matches!(tool, Tool::Shape(_)) {
+                        (start, interaction::constrain_create(tool, start, end))
+                    } else {
+                        (start, preview_end.unwrap_or(end))
+                    }
+                };
+                let element = create_element_for_tool(tool, start, end);
                 if let Some(element) = element {
                     let element_id = element.id();
                     {
@@ -386,6 +933,9 @@ impl CanvasView {
 
                     // Select the newly created element
                     selection_end.borrow_mut().select(element_id);
+                    if let Some(cb) = on_edited_end.borrow().as_ref() {
+                        cb();
+                    }
                     if let Some(cb) = on_changed_end.borrow().as_ref() {
                         cb(Some(element_id));
                     }
@@ -395,6 +945,14 @@ impl CanvasView {
                     if let Some(cb) = on_tool_changed_end.borrow().as_ref() {
                         cb(Tool::Pointer);
                     }
+
+                    // A new text box is typed into right away, replacing
+                    // its placeholder
+                    if tool == Tool::Text {
+                        if let Some(canvas) = canvas_end.upgrade() {
+                            canvas.edit_text(element_id, true);
+                        }
+                    }
                 }
 
                 drawing_area_end.queue_draw();
@@ -415,18 +973,49 @@ impl CanvasView {
         let slide_index = imp.current_slide_index.clone();
         let drawing_area = imp.drawing_area.clone();
         let on_changed = imp.on_selection_changed.clone();
+        let on_edited = imp.on_edited.clone();
         let current_tool = imp.current_tool.clone();
         let on_tool_changed = imp.on_tool_changed.clone();
+        let crop_image = imp.crop_image.clone();
 
-        key_controller.connect_key_pressed(move |_, keyval, _, _| {
+        key_controller.connect_key_pressed(move |_, keyval, _, state| {
+            // Escape and Enter end cropping, keeping the image selected
+            if matches!(keyval, gdk::Key::Escape | gdk::Key::Return | gdk::Key::KP_Enter)
+                && crop_image.take().is_some()
+            {
+                drawing_area.queue_draw();
+                return glib::Propagation::Stop;
+            }
+            // The clipboard keys only act on elements while the canvas has
+            // focus, so text fields keep their own copy and paste. Ctrl+Delete
+            // deletes the slide rather than the selected elements.
+            if state.contains(gdk::ModifierType::CONTROL_MASK) {
+                let action = shortcuts::CANVAS_CONTROL_KEYS
+                    .iter()
+                    .find(|(key, _)| *key == keyval)
+                    .map(|(_, action)| *action);
+                if let Some(action) = action {
+                    let _ = drawing_area.activate_action(action, None);
+                    return glib::Propagation::Stop;
+                }
+            }
             if keyval == gdk::Key::Delete || keyval == gdk::Key::BackSpace {
-                let mut sel = selection.borrow_mut();
-                if let Some(sel_id) = sel.element_id {
-                    let mut doc = doc.borrow_mut();
-                    let idx = slide_index.get();
-                    if idx < doc.slides.len() {
-                        doc.slides[idx].remove_element(sel_id);
-                        sel.deselect();
+                let ids = selection.borrow().ids();
+                if !ids.is_empty() {
+                    // Locked elements stay, as they do when dragged
+                    let removed = {
+                        let mut doc = doc.borrow_mut();
+                        doc.slides.get_mut(slide_index.get()).is_some_and(|slide| {
+                            let before = slide.elements.len();
+                            slide.elements.retain(|e| !ids.contains(&e.id()) || e.is_locked());
+                            slide.elements.len() < before
+                        })
+                    };
+                    if removed {
+                        selection.borrow_mut().deselect();
+                        if let Some(cb) = on_edited.borrow().as_ref() {
+                            cb();
+                        }
                         if let Some(cb) = on_changed.borrow().as_ref() {
                             cb(None);
                         }
@@ -435,6 +1024,49 @@ impl CanvasView {
                 }
                 return glib::Propagation::Stop;
             }
+            // Arrow keys nudge the selection, further with Shift
+            let nudge = match keyval {
+                gdk::Key::Left => Some((-1.0, 0.0)),
+                gdk::Key::Right => Some((1.0, 0.0)),
+                gdk::Key::Up => Some((0.0, -1.0)),
+                gdk::Key::Down => Some((0.0, 1.0)),
+                _ => None,
+            };
+            if let Some((dx, dy)) = nudge {
+                let Some(sel_id) = selection.borrow().element_id else {
+                    return glib::Propagation::Proceed;
+                };
+                let step = preferences::nudge_step(state.contains(gdk::ModifierType::SHIFT_MASK));
+                let ids = selection.borrow().ids();
+                let moved = {
+                    let mut doc = doc.borrow_mut();
+                    let Some(slide) = doc.slides.get_mut(slide_index.get()) else {
+                        return glib::Propagation::Stop;
+                    };
+                    let mut moved = false;
+                    for element in slide
+                        .elements
+                        .iter_mut()
+                        .filter(|e| ids.contains(&e.id()) && !e.is_locked())
+                    {
+                        let bounds = element.bounds_mut();
+                        bounds.origin.x += dx * step;
+                        bounds.origin.y += dy * step;
+                        moved = true;
+                    }
+                    moved
+                };
+                if moved {
+                    if let Some(cb) = on_edited.borrow().as_ref() {
+                        cb();
+                    }
+                    if let Some(cb) = on_changed.borrow().as_ref() {
+                        cb(Some(sel_id));
+                    }
+                    drawing_area.queue_draw();
+                }
+                return glib::Propagation::Stop;
+            }
             if keyval == gdk::Key::Escape {
                 // If a creation tool is active, switch back to pointer
                 let tool = current_tool.get();
@@ -468,6 +1100,18 @@ impl CanvasView {
         *self.imp().on_tool_changed.borrow_mut() = Some(Box::new(callback));
     }
 
+    pub fn connect_geometry_changed<F: Fn() + 'static>(&self, callback: F) {
+        *self.imp().on_geometry_changed.borrow_mut() = Some(Box::new(callback));
+    }
+
+    pub fn connect_edited<F: Fn() + 'static>(&self, callback: F) {
+        *self.imp().on_edited.borrow_mut() = Some(Box::new(callback));
+    }
+
+    pub fn connect_zoom_changed<F: Fn(ZoomMode) + 'static>(&self, callback: F) {
+        *self.imp().on_zoom_changed.borrow_mut() = Some(Box::new(callback));
+    }
+
     pub fn set_current_tool(&self, tool: Tool) {
         self.imp().current_tool.set(tool);
     }
@@ -477,17 +1121,418 @@ impl CanvasView {
     }
 
     pub fn set_current_slide(&self, index: usize) {
+        self.finish_text_edit();
+        self.finish_crop();
         let imp = self.imp();
         imp.current_slide_index.set(index);
         imp.selection.borrow_mut().deselect();
+        if let Some(cb) = imp.on_selection_changed.borrow().as_ref() {
+            cb(None);
+        }
         self.queue_draw();
     }
 
+    /// Select a single element on the current slide, e.g. from the objects
+    /// panel.
+    pub fn select_element(&self, id: uuid::Uuid) {
+        self.finish_crop();
+        let imp = self.imp();
+        imp.selection.borrow_mut().select(id);
+        if let Some(cb) = imp.on_selection_changed.borrow().as_ref() {
+            cb(Some(id));
+        }
+        self.queue_draw();
+    }
+
+    /// Move the selected element one step up (`forward`) or down the
+    /// stacking order.
+    pub fn restack_selected(&self, forward: bool) {
+        let imp = self.imp();
+        let Some(doc) = self.document() else { return };
+        let Some(id) = imp.selection.borrow().element_id else {
+            return;
+        };
+
+        let moved = {
+            let mut doc = doc.borrow_mut();
+            let Some(slide) = doc.slides.get_mut(imp.current_slide_index.get()) else {
+                return;
+            };
+            if forward {
+                slide.bring_forward(id)
+            } else {
+                slide.send_backward(id)
+            }
+        };
+
+        if moved {
+            self.notify_edited();
+            if let Some(cb) = imp.on_selection_changed.borrow().as_ref() {
+                cb(Some(id));
+            }
+            self.queue_draw();
+        }
+    }
+
+    /// Edit the text of text element `id` on the current slide in a text view
+    /// laid over its box. The text is written back when the view loses focus
+    /// or Escape is pressed. With `select_all`, typing replaces the text.
+    pub fn edit_text(&self, id: uuid::Uuid, select_all: bool) {
+        self.finish_text_edit();
+        let imp = self.imp();
+        let Some(doc) = self.document() else { return };
+        let (content, alignment) = {
+            let doc = doc.borrow();
+            let text = doc
+                .slides
+                .get(imp.current_slide_index.get())
+                .and_then(|slide| slide.elements.iter().find(|e| e.id() == id));
+            let Some(SlideElement::Text(text)) = text else {
+                return;
+            };
+            (text.plain_text(), text.alignment)
+        };
+
+        let view = gtk::TextView::new();
+        view.set_wrap_mode(gtk::WrapMode::WordChar);
+        view.set_halign(gtk::Align::Start);
+        view.set_valign(gtk::Align::Start);
+        view.set_justification(match alignment {
+            TextAlignment::Left => gtk::Justification::Left,
+            TextAlignment::Center => gtk::Justification::Center,
+            TextAlignment::Right => gtk::Justification::Right,
+        });
+        let buffer = view.buffer();
+        buffer.set_text(&content);
+        buffer.create_tag(Some(EDITOR_FONT_TAG), &[]);
+        // Typed text does not pick up tags, so the font is applied throughout
+        buffer.connect_changed(|buffer| {
+            let (start, end) = buffer.bounds();
+            buffer.apply_tag_by_name(EDITOR_FONT_TAG, &start, &end);
+        });
+        if select_all {
+            let (start, end) = buffer.bounds();
+            buffer.select_range(&start, &end);
+        }
+
+        let focus = gtk::EventControllerFocus::new();
+        let canvas = self.downgrade();
+        focus.connect_leave(move |_| {
+            if let Some(canvas) = canvas.upgrade() {
+                canvas.finish_text_edit();
+            }
+        });
+        view.add_controller(focus);
+
+        let keys = gtk::EventControllerKey::new();
+        let canvas = self.downgrade();
+        keys.connect_key_pressed(move |_, keyval, _, _| {
+            if keyval != gdk::Key::Escape {
+                return glib::Propagation::Proceed;
+            }
+            if let Some(canvas) = canvas.upgrade() {
+                canvas.finish_text_edit();
+                canvas.imp().drawing_area.grab_focus();
+            }
+            glib::Propagation::Stop
+        });
+        view.add_controller(keys);
+
+        imp.overlay.add_overlay(&view);
+        *imp.text_edit.borrow_mut() = Some((id, view.clone()));
+        self.place_text_editor();
+        view.grab_focus();
+    }
+
+    /// Write the in-place edited text back and close the editor.
+    pub fn finish_text_edit(&self) {
+        let imp = self.imp();
+        // Taken first: removing the view moves the focus, which calls back
+        let Some((id, view)) = imp.text_edit.borrow_mut().take() else {
+            return;
+        };
+        let buffer = view.buffer();
+        let (start, end) = buffer.bounds();
+        let edited = buffer.text(&start, &end, false);
+        imp.overlay.remove_overlay(&view);
+
+        let Some(doc) = self.document() else { return };
+        let changed = {
+            let mut doc = doc.borrow_mut();
+            let text = doc
+                .slides
+                .get_mut(imp.current_slide_index.get())
+                .and_then(|slide| slide.elements.iter_mut().find(|e| e.id() == id));
+            match text {
+                Some(SlideElement::Text(text)) if text.plain_text() != edited.as_str() => {
+                    text.set_plain_text(&edited);
+                    true
+                }
+                _ => false,
+            }
+        };
+
+        if changed {
+            let selected = imp.selection.borrow().element_id;
+            self.notify_edited();
+            if let Some(cb) = imp.on_selection_changed.borrow().as_ref() {
+                cb(selected);
+            }
+        }
+        self.queue_draw();
+    }
+
+    /// Let the handles of the selected image crop it until Enter or Escape
+    /// is pressed or the canvas is clicked. A fitted or filled picture is
+    /// first stretched over the part of it that shows, which looks the same.
+    pub fn start_crop(&self) {
+        let imp = self.imp();
+        let Some(doc) = self.document() else { return };
+        let Some(id) = imp.selection.borrow().element_id else {
+            return;
+        };
+        let stretched = {
+            let mut doc = doc.borrow_mut();
+            let image = doc
+                .slides
+                .get_mut(imp.current_slide_index.get())
+                .and_then(|slide| slide.elements.iter_mut().find(|e| e.id() == id));
+            let Some(SlideElement::Image(image)) = image else {
+                return;
+            };
+            if image.locked {
+                return;
+            }
+            if image.scale_mode == ScaleMode::Stretch {
+                false
+            } else {
+                let Some(natural) = image_render::natural_size(image) else {
+                    return;
+                };
+                image.stretch_in_place(natural);
+                true
+            }
+        };
+
+        imp.crop_image.set(Some(id));
+        if stretched {
+            self.notify_edited();
+            if let Some(cb) = imp.on_selection_changed.borrow().as_ref() {
+                cb(Some(id));
+            }
+        }
+        self.queue_draw();
+    }
+
+    /// Leave the crop mode of `start_crop`.
+    pub fn finish_crop(&self) {
+        if self.imp().crop_image.take().is_some() {
+            self.queue_draw();
+        }
+    }
+
+    /// Line the in-place text editor up with its element at the current
+    /// zoom, with the font scaled to match.
+    fn place_text_editor(&self) {
+        let imp = self.imp();
+        let Some((id, view)) = imp.text_edit.borrow().clone() else {
+            return;
+        };
+        let Some(doc) = self.document() else { return };
+        let (scale, offset_x, offset_y) = self.slide_transform();
+        let doc = doc.borrow();
+        let text = doc
+            .slides
+            .get(imp.current_slide_index.get())
+            .and_then(|slide| slide.elements.iter().find(|e| e.id() == id));
+        let Some(SlideElement::Text(text)) = text else {
+            return;
+        };
+
+        let bounds = &text.bounds;
+        view.set_margin_start((offset_x + bounds.origin.x * scale).max(0.0) as i32);
+        view.set_margin_top((offset_y + bounds.origin.y * scale).max(0.0) as i32);
+        view.set_size_request(
+            (bounds.size.width * scale) as i32,
+            (bounds.size.height * scale) as i32,
+        );
+        view.set_left_margin((text.inset.left * scale) as i32);
+        view.set_right_margin((text.inset.right * scale) as i32);
+        view.set_top_margin((text.inset.top * scale) as i32);
+        view.set_bottom_margin((text.inset.bottom * scale) as i32);
+
+        let font = text
+            .paragraphs
+            .iter()
+            .flat_map(|p| p.runs.first())
+            .next()
+            .map(|run| run.font.clone())
+            .unwrap_or_default();
+        let (line_spacing, space_before, space_after) =
+            text.paragraphs.first().map_or((1.0, 0.0, 0.0), |p| {
+                (p.line_spacing, p.space_before, p.space_after)
+            });
+        let font = FontStyle {
+            size: font.size * scale,
+            ..font
+        };
+        let buffer = view.buffer();
+        if let Some(tag) = buffer.tag_table().lookup(EDITOR_FONT_TAG) {
+            tag.set_font_desc(Some(&text_render::build_font_description(&font)));
+            tag.set_underline(if font.underline {
+                pango::Underline::Single
+            } else {
+                pango::Underline::None
+            });
+            tag.set_strikethrough(font.strikethrough);
+            tag.set_line_height(line_spacing as f32);
+            tag.set_pixels_above_lines((space_before * scale) as i32);
+            tag.set_pixels_below_lines((space_after * scale) as i32);
+        }
+        let (start, end) = buffer.bounds();
+        buffer.apply_tag_by_name(EDITOR_FONT_TAG, &start, &end);
+    }
+
+    /// Put the selected elements on the clipboard, in stacking order.
+    pub fn copy_selection(&self) {
+        let imp = self.imp();
+        let Some(doc) = self.document() else { return };
+        let ids = imp.selection.borrow().ids();
+        let copied: Vec<SlideElement> = {
+            let doc = doc.borrow();
+            let Some(slide) = doc.slides.get(imp.current_slide_index.get()) else {
+                return;
+            };
+            slide
+                .elements
+                .iter()
+                .filter(|e| ids.contains(&e.id()))
+                .cloned()
+                .collect()
+        };
+        if !copied.is_empty() {
+            clipboard::copy_elements(&self.clipboard(), &copied);
+        }
+    }
+
+    /// Copy the selected elements and remove those that are not locked from
+    /// the slide.
+    pub fn cut_selection(&self) {
+        let imp = self.imp();
+        let Some(doc) = self.document() else { return };
+        let ids = imp.selection.borrow().ids();
+        if ids.is_empty() {
+            return;
+        }
+
+        self.copy_selection();
+        {
+            let mut doc = doc.borrow_mut();
+            let Some(slide) = doc.slides.get_mut(imp.current_slide_index.get()) else {
+                return;
+            };
+            slide.elements.retain(|e| !ids.contains(&e.id()) || e.is_locked());
+        }
+
+        imp.selection.borrow_mut().deselect();
+        self.notify_edited();
+        if let Some(cb) = imp.on_selection_changed.borrow().as_ref() {
+            cb(None);
+        }
+        self.queue_draw();
+    }
+
+    /// Paste elements copied in Lumina onto the current slide and select
+    /// them. Copied text boxes go into the selected text box as styled text
+    /// instead, and other text into the selected text box or a new one.
+    pub fn paste_clipboard(&self) {
+        let imp = self.imp();
+        let Some(doc) = self.document() else { return };
+        let clipboard = self.clipboard();
+        let canvas = self.clone();
+        let target = imp.selection.borrow().element_id;
+
+        if clipboard::has_elements(&clipboard) {
+            clipboard::read_elements(&clipboard, move |elements| {
+                let imp = canvas.imp();
+                let text_target = {
+                    let doc = doc.borrow();
+                    let slide = doc.slides.get(imp.current_slide_index.get());
+                    target.filter(|id| {
+                        slide.is_some_and(|slide| {
+                            slide
+                                .elements
+                                .iter()
+                                .any(|e| e.id() == *id && matches!(e, SlideElement::Text(_)))
+                        })
+                    })
+                };
+                if clipboard::pastes_into_text(&elements, text_target) {
+                    canvas.paste_text(text_target);
+                    return;
+                }
+
+                let ids = {
+                    let mut doc = doc.borrow_mut();
+                    let Some(slide) = doc.slides.get_mut(imp.current_slide_index.get()) else {
+                        return;
+                    };
+                    clipboard::paste_elements(slide, elements)
+                };
+                let Some(&first) = ids.first() else { return };
+                {
+                    let mut sel = imp.selection.borrow_mut();
+                    sel.deselect();
+                    for id in ids {
+                        sel.add(id);
+                    }
+                }
+                canvas.notify_edited();
+                if let Some(cb) = imp.on_selection_changed.borrow().as_ref() {
+                    cb(Some(first));
+                }
+                canvas.queue_draw();
+            });
+            return;
+        }
+
+        self.paste_text(target);
+    }
+
+    /// Paste the text on the clipboard into the text element `target`, or
+    /// into a new text box, and select it.
+    fn paste_text(&self, target: Option<uuid::Uuid>) {
+        let Some(doc) = self.document() else { return };
+        let canvas = self.clone();
+        clipboard::read_paragraphs(&self.clipboard(), move |paragraphs| {
+            let imp = canvas.imp();
+            let pasted = {
+                let mut doc = doc.borrow_mut();
+                let slide_size = doc.slide_size;
+                let Some(slide) = doc.slides.get_mut(imp.current_slide_index.get()) else {
+                    return;
+                };
+                clipboard::paste_paragraphs(slide, &slide_size, target, paragraphs)
+            };
+            if let Some(id) = pasted {
+                imp.selection.borrow_mut().select(id);
+                canvas.notify_edited();
+                if let Some(cb) = imp.on_selection_changed.borrow().as_ref() {
+                    cb(Some(id));
+                }
+                canvas.queue_draw();
+            }
+        });
+    }
+
     pub fn current_slide_index(&self) -> usize {
         self.imp().current_slide_index.get()
     }
 
+    /// Redraw the slide, e.g. after an edit. The slide size may have
+    /// changed too, so the scrollable area is updated as well.
     pub fn queue_draw(&self) {
+        self.update_content_size();
         self.imp().drawing_area.queue_draw();
     }
 
@@ -499,12 +1544,284 @@ impl CanvasView {
         if let Some(doc) = doc_ref.as_ref() {
             let doc = doc.borrow();
             let slide_size = &doc.slide_size;
-            compute_slide_transform(slide_size, width, height)
+            compute_slide_transform(slide_size, width, height, self.imp().zoom_mode.get())
         } else {
             (1.0, 0.0, 0.0)
         }
     }
 
+    /// Switch the zoom mode. It is kept across slide and document changes.
+    pub fn set_zoom_mode(&self, mode: ZoomMode) {
+        self.imp().zoom_mode.set(mode);
+        self.place_text_editor();
+        self.queue_draw();
+        if let Some(cb) = self.imp().on_zoom_changed.borrow().as_ref() {
+            cb(mode);
+        }
+    }
+
+    pub fn zoom_mode(&self) -> ZoomMode {
+        self.imp().zoom_mode.get()
+    }
+
+    /// Zoom to a fixed factor, keeping the middle of the view in place.
+    pub fn set_zoom(&self, factor: f64) {
+        self.zoom_around(factor, None);
+    }
+
+    pub fn zoom_in(&self) {
+        self.set_zoom(self.slide_transform().0 * ZOOM_STEP);
+    }
+
+    pub fn zoom_out(&self) {
+        self.set_zoom(self.slide_transform().0 / ZOOM_STEP);
+    }
+
+    pub fn zoom_to_fit(&self) {
+        self.set_zoom_mode(ZoomMode::Fit);
+    }
+
+    /// Zoom to `factor`, keeping the slide point at `anchor` in the view,
+    /// or at the middle of the view when None, where it is.
+    fn zoom_around(&self, factor: f64, anchor: Option<(f64, f64)>) {
+        let imp = self.imp();
+        let factor = factor.clamp(MIN_ZOOM, MAX_ZOOM);
+        let mode = ZoomMode::Factor(factor);
+        if imp.zoom_mode.get() == mode {
+            return;
+        }
+        let Some(doc) = self.document() else { return };
+        let slide_size = doc.borrow().slide_size;
+
+        let hadj = imp.scrolled_window.hadjustment();
+        let vadj = imp.scrolled_window.vadjustment();
+        let (x, y) = anchor.unwrap_or((hadj.page_size() / 2.0, vadj.page_size() / 2.0));
+
+        // Only a view that scrolls has to follow the anchor; the drawing
+        // area is resized then, which scrolls it into place
+        let (width, height) = mode.content_size(&slide_size, hadj.page_size());
+        if width > hadj.page_size() || height > vadj.page_size() {
+            let (scale, offset_x, offset_y) = self.slide_transform();
+            let point = interaction::widget_to_slide_coords(
+                x + hadj.value(),
+                y + vadj.value(),
+                scale,
+                offset_x,
+                offset_y,
+            );
+            imp.zoom_anchor.set(Some((point, x, y)));
+        }
+        self.set_zoom_mode(mode);
+    }
+
+    /// Ask for the room the slide needs at the current zoom, so the view
+    /// scrolls when it is larger.
+    fn update_content_size(&self) {
+        let imp = self.imp();
+        let Some(doc) = self.document() else { return };
+        let slide_size = doc.borrow().slide_size;
+        let width = imp.scrolled_window.width() as f64;
+        let (content_width, content_height) =
+            imp.zoom_mode.get().content_size(&slide_size, width);
+        imp.drawing_area.set_content_width(content_width.ceil() as i32);
+        imp.drawing_area.set_content_height(content_height.ceil() as i32);
+    }
+
+    fn scroll_to_zoom_anchor(&self) {
+        let imp = self.imp();
+        let Some((point, x, y)) = imp.zoom_anchor.take() else {
+            return;
+        };
+        let (scale, offset_x, offset_y) = self.slide_transform();
+        imp.scrolled_window
+            .hadjustment()
+            .set_value(offset_x + point.x * scale - x);
+        imp.scrolled_window
+            .vadjustment()
+            .set_value(offset_y + point.y * scale - y);
+    }
+
+    /// Ctrl+scroll zooms around the pointer; plain scrolling pans.
+    fn setup_zoom_handler(&self) {
+        let imp = self.imp();
+
+        let motion = gtk::EventControllerMotion::new();
+        let canvas = self.downgrade();
+        motion.connect_motion(move |_, x, y| {
+            if let Some(canvas) = canvas.upgrade() {
+                canvas.imp().pointer.set(Some((x, y)));
+            }
+        });
+        let canvas = self.downgrade();
+        motion.connect_leave(move |_| {
+            if let Some(canvas) = canvas.upgrade() {
+                canvas.imp().pointer.set(None);
+            }
+        });
+        imp.drawing_area.add_controller(motion);
+
+        let scroll = gtk::EventControllerScroll::new(gtk::EventControllerScrollFlags::VERTICAL);
+        let canvas = self.downgrade();
+        scroll.connect_scroll(move |scroll, _dx, dy| {
+            let Some(canvas) = canvas.upgrade() else {
+                return glib::Propagation::Proceed;
+            };
+            if !scroll
+                .current_event_state()
+                .contains(gdk::ModifierType::CONTROL_MASK)
+            {
+                return glib::Propagation::Proceed;
+            }
+            // The pointer is over the drawing area, the anchor is in the view
+            let imp = canvas.imp();
+            let anchor = imp.pointer.get().map(|(x, y)| {
+                (
+                    x - imp.scrolled_window.hadjustment().value(),
+                    y - imp.scrolled_window.vadjustment().value(),
+                )
+            });
+            let factor = canvas.slide_transform().0 * ZOOM_STEP.powf(-dy);
+            canvas.zoom_around(factor, anchor);
+            glib::Propagation::Stop
+        });
+        imp.drawing_area.add_controller(scroll);
+    }
+
+    /// Enable or disable snapping to common aspect ratios while resizing.
+    pub fn set_aspect_snap(&self, enabled: bool) {
+        self.imp().aspect_snap.set(enabled);
+    }
+
+    pub fn set_object_snap(&self, enabled: bool) {
+        self.imp().object_snap.set(enabled);
+    }
+
+    /// Show or hide the grid, which elements snap to while it is shown.
+    pub fn set_show_grid(&self, enabled: bool) {
+        self.imp().show_grid.set(enabled);
+        self.imp().drawing_area.queue_draw();
+    }
+
+    /// Resize the selected elements to the first-selected one's width and/or
+    /// height. Does nothing unless at least two elements are selected.
+    pub fn match_selected_sizes(&self, dimension: MatchDimension) {
+        let imp = self.imp();
+        let Some(doc) = self.document() else { return };
+        let ids = imp.selection.borrow().ids();
+        if ids.len() < 2 {
+            return;
+        }
+
+        {
+            let mut doc = doc.borrow_mut();
+            let Some(slide) = doc.slides.get_mut(imp.current_slide_index.get()) else {
+                return;
+            };
+            let (found, mut bounds): (Vec<uuid::Uuid>, Vec<Rect>) = ids
+                .iter()
+                .filter_map(|id| slide.elements.iter().find(|e| e.id() == *id))
+                .map(|e| (e.id(), *e.bounds()))
+                .unzip();
+            arrange::match_sizes(&mut bounds, dimension);
+            for (id, matched) in found.iter().zip(bounds) {
+                if let Some(element) = slide.elements.iter_mut().find(|e| e.id() == *id) {
+                    *element.bounds_mut() = matched;
+                }
+            }
+        }
+
+        self.notify_edited();
+        if let Some(cb) = imp.on_selection_changed.borrow().as_ref() {
+            cb(ids.first().copied());
+        }
+        self.queue_draw();
+    }
+
+    /// Give the selected elements their default fill, stroke, font or filters.
+    pub fn reset_selected_style(&self) {
+        let imp = self.imp();
+        let Some(doc) = self.document() else { return };
+        let ids = imp.selection.borrow().ids();
+        if ids.is_empty() {
+            return;
+        }
+
+        {
+            let mut doc = doc.borrow_mut();
+            let Some(slide) = doc.slides.get_mut(imp.current_slide_index.get()) else {
+                return;
+            };
+            for element in slide.elements.iter_mut().filter(|e| ids.contains(&e.id())) {
+                element.reset_style();
+            }
+        }
+
+        self.notify_edited();
+        if let Some(cb) = imp.on_selection_changed.borrow().as_ref() {
+            cb(ids.first().copied());
+        }
+        self.queue_draw();
+    }
+
+    /// Hide the selected elements, or show them again when all of them are
+    /// already hidden.
+    pub fn toggle_selected_visibility(&self) {
+        let imp = self.imp();
+        let Some(doc) = self.document() else { return };
+        let ids = imp.selection.borrow().ids();
+        if ids.is_empty() {
+            return;
+        }
+
+        {
+            let mut doc = doc.borrow_mut();
+            let Some(slide) = doc.slides.get_mut(imp.current_slide_index.get()) else {
+                return;
+            };
+            let selected = || slide.elements.iter().filter(|e| ids.contains(&e.id()));
+            let visible = !selected().any(|e| e.is_visible());
+            for element in slide.elements.iter_mut().filter(|e| ids.contains(&e.id())) {
+                element.set_visible(visible);
+            }
+        }
+
+        self.notify_edited();
+        if let Some(cb) = imp.on_selection_changed.borrow().as_ref() {
+            cb(ids.first().copied());
+        }
+        self.queue_draw();
+    }
+
+    /// Make every hidden element on the current slide visible again.
+    pub fn show_hidden_elements(&self) {
+        let imp = self.imp();
+        let Some(doc) = self.document() else { return };
+
+        {
+            let mut doc = doc.borrow_mut();
+            let Some(slide) = doc.slides.get_mut(imp.current_slide_index.get()) else {
+                return;
+            };
+            for element in &mut slide.elements {
+                element.set_visible(true);
+            }
+        }
+
+        let selected = imp.selection.borrow().element_id;
+        self.notify_edited();
+        if let Some(cb) = imp.on_selection_changed.borrow().as_ref() {
+            cb(selected);
+        }
+        self.queue_draw();
+    }
+
+    /// Report a completed edit, which the window records for undo.
+    pub fn notify_edited(&self) {
+        if let Some(cb) = self.imp().on_edited.borrow().as_ref() {
+            cb();
+        }
+    }
+
     pub fn selection(&self) -> Rc<RefCell<Selection>> {
         self.imp().selection.clone()
     }
@@ -518,13 +1835,16 @@ impl CanvasView {
     }
 }
 
-fn create_element_for_tool(tool: Tool, bounds: Rect) -> Option<SlideElement> {
+fn create_element_for_tool(tool: Tool, start: Point, end: Point) -> Option<SlideElement> {
+    let bounds = interaction::normalize_rect(start.x, start.y, end.x, end.y);
     match tool {
         Tool::Pointer => None,
         Tool::Text => {
             let text = TextElement::new(bounds, "Text");
             Some(SlideElement::Text(text))
         }
+        // Lines keep the drag direction instead of always sloping down-right
+        Tool::Shape(ShapeType::Line) => Some(SlideElement::Shape(ShapeElement::line(start, end))),
         Tool::Shape(shape_type) => {
             let shape = ShapeElement::new(bounds, shape_type);
             Some(SlideElement::Shape(shape))
@@ -533,11 +1853,67 @@ fn create_element_for_tool(tool: Tool, bounds: Rect) -> Option<SlideElement> {
     }
 }
 
-fn compute_slide_transform(slide_size: &Size, width: f64, height: f64) -> (f64, f64, f64) {
-    let padding = 0.9;
-    let scale_x = width / slide_size.width;
-    let scale_y = height / slide_size.height;
-    let scale = scale_x.min(scale_y) * padding;
+/// Draw the element being created with the style it will get once the drag ends.
+fn draw_create_preview(cr: &cairo::Context, tool: Tool, start: Point, end: Point) {
+    match tool {
+        Tool::Shape(_) => {
+            let Some(SlideElement::Shape(shape)) = create_element_for_tool(tool, start, end) else {
+                return;
+            };
+            cr.push_group();
+            shape_render::render_shape(cr, &shape);
+            let _ = cr.pop_group_to_source();
+            let _ = cr.paint_with_alpha(0.6);
+        }
+        Tool::Text => {
+            let rect = interaction::normalize_rect(start.x, start.y, end.x, end.y);
+            cr.save().expect("cairo save");
+            cr.set_source_rgba(0.2, 0.52, 0.89, 0.8);
+            cr.set_line_width(1.0);
+            cr.set_dash(&[4.0, 4.0], 0.0);
+            cr.rectangle(rect.origin.x, rect.origin.y, rect.size.width, rect.size.height);
+            let _ = cr.stroke();
+            cr.restore().expect("cairo restore");
+        }
+        Tool::Pointer | Tool::Image => {}
+    }
+}
+
+/// What a drag snaps to: the slide and `others` with object snapping on,
+/// and the grid while it is shown. `None` when neither is on or Alt is held
+/// to place freely.
+fn drag_snap_targets(
+    object_snap: bool,
+    show_grid: bool,
+    gesture: &gtk::GestureDrag,
+    slide_size: &Size,
+    others: impl IntoIterator<Item = Rect>,
+) -> Option<SnapTargets> {
+    let free = gesture
+        .current_event_state()
+        .contains(gdk::ModifierType::ALT_MASK);
+    if free || !(object_snap || show_grid) {
+        return None;
+    }
+    let targets = if object_snap {
+        SnapTargets::new(slide_size, others)
+    } else {
+        SnapTargets::default()
+    };
+    Some(if show_grid {
+        targets.with_grid(preferences::grid_spacing())
+    } else {
+        targets
+    })
+}
+
+fn compute_slide_transform(
+    slide_size: &Size,
+    width: f64,
+    height: f64,
+    zoom: ZoomMode,
+) -> (f64, f64, f64) {
+    let scale = zoom.scale(slide_size, width, height);
 
     let offset_x = (width - slide_size.width * scale) / 2.0;
     let offset_y = (height - slide_size.height * scale) / 2.0;
@@ -1,5 +1,10 @@
 pub mod canvas;
 pub mod canvas_view;
+pub mod objects_panel;
+pub mod preferences;
 pub mod properties_panel;
+pub mod shortcuts;
 pub mod slide_panel;
+pub mod slide_sorter;
+pub mod slideshow;
 pub mod window;
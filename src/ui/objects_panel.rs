@@ -0,0 +1,363 @@
+use gettextrs::gettext;
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use uuid::Uuid;
+
+use crate::model::document::Document;
+use crate::model::element::SlideElement;
+use crate::model::shape::ShapeType;
+
+/// Characters of content used to label an unnamed text box
+const LABEL_CHARS: usize = 24;
+
+/// What a row shows. Kept to skip rebuilding the list when nothing changed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjectRow {
+    pub id: Uuid,
+    pub icon: &'static str,
+    pub label: String,
+    pub visible: bool,
+    pub locked: bool,
+}
+
+type SelectedCallback = Rc<RefCell<Option<Box<dyn Fn(Uuid)>>>>;
+type ChangedCallback = Rc<RefCell<Option<Box<dyn Fn()>>>>;
+type ElementChange = Box<dyn FnOnce(&mut SlideElement)>;
+
+mod imp {
+    use super::*;
+
+    pub struct ObjectsPanel {
+        pub container: gtk::Box,
+        pub list_box: gtk::ListBox,
+        pub document: RefCell<Option<Rc<RefCell<Document>>>>,
+        pub slide_index: Rc<Cell<usize>>,
+        /// Rows in list order, topmost element first
+        pub rows: Rc<RefCell<Vec<ObjectRow>>>,
+        pub updating: Rc<Cell<bool>>,
+        pub on_element_selected: SelectedCallback,
+        pub on_elements_changed: ChangedCallback,
+    }
+
+    impl std::fmt::Debug for ObjectsPanel {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("ObjectsPanel").finish()
+        }
+    }
+
+    impl Default for ObjectsPanel {
+        fn default() -> Self {
+            let list_box = gtk::ListBox::new();
+            list_box.set_selection_mode(gtk::SelectionMode::Single);
+            list_box.add_css_class("navigation-sidebar");
+
+            let scrolled_window = gtk::ScrolledWindow::builder()
+                .hscrollbar_policy(gtk::PolicyType::Never)
+                .vscrollbar_policy(gtk::PolicyType::Automatic)
+                .min_content_height(160)
+                .child(&list_box)
+                .build();
+
+            // Stacking order buttons act on the canvas selection
+            let forward_btn = gtk::Button::from_icon_name("go-up-symbolic");
+            forward_btn.set_tooltip_text(Some(&gettext("Bring Forward")));
+            forward_btn.set_action_name(Some("win.bring-forward"));
+            forward_btn.add_css_class("flat");
+            let backward_btn = gtk::Button::from_icon_name("go-down-symbolic");
+            backward_btn.set_tooltip_text(Some(&gettext("Send Backward")));
+            backward_btn.set_action_name(Some("win.send-backward"));
+            backward_btn.add_css_class("flat");
+
+            let toolbar = gtk::Box::new(gtk::Orientation::Horizontal, 4);
+            toolbar.set_halign(gtk::Align::End);
+            toolbar.append(&forward_btn);
+            toolbar.append(&backward_btn);
+
+            let container = gtk::Box::new(gtk::Orientation::Vertical, 4);
+            container.append(&scrolled_window);
+            container.append(&toolbar);
+
+            Self {
+                container,
+                list_box,
+                document: RefCell::new(None),
+                slide_index: Rc::new(Cell::new(0)),
+                rows: Rc::new(RefCell::new(Vec::new())),
+                updating: Rc::new(Cell::new(false)),
+                on_element_selected: Rc::new(RefCell::new(None)),
+                on_elements_changed: Rc::new(RefCell::new(None)),
+            }
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ObjectsPanel {
+        const NAME: &'static str = "LuminaObjectsPanel";
+        type Type = super::ObjectsPanel;
+        type ParentType = gtk::Widget;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.set_layout_manager_type::<gtk::BinLayout>();
+        }
+    }
+
+    impl ObjectImpl for ObjectsPanel {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+            self.container.set_parent(&*obj);
+
+            let rows = self.rows.clone();
+            let updating = self.updating.clone();
+            let on_selected = self.on_element_selected.clone();
+            self.list_box.connect_row_selected(move |_, row| {
+                if updating.get() {
+                    return;
+                }
+                let Some(row) = row else { return };
+                let id = rows.borrow().get(row.index() as usize).map(|r| r.id);
+                if let (Some(id), Some(cb)) = (id, on_selected.borrow().as_ref()) {
+                    cb(id);
+                }
+            });
+        }
+
+        fn dispose(&self) {
+            self.container.unparent();
+        }
+    }
+
+    impl WidgetImpl for ObjectsPanel {}
+}
+
+glib::wrapper! {
+    pub struct ObjectsPanel(ObjectSubclass<imp::ObjectsPanel>)
+        @extends gtk::Widget;
+}
+
+impl ObjectsPanel {
+    pub fn new() -> Self {
+        glib::Object::builder().build()
+    }
+
+    pub fn set_document(&self, doc: Rc<RefCell<Document>>) {
+        *self.imp().document.borrow_mut() = Some(doc);
+    }
+
+    pub fn connect_element_selected<F: Fn(Uuid) + 'static>(&self, callback: F) {
+        *self.imp().on_element_selected.borrow_mut() = Some(Box::new(callback));
+    }
+
+    /// Called after an element was renamed, hidden, shown, locked or unlocked.
+    pub fn connect_elements_changed<F: Fn() + 'static>(&self, callback: F) {
+        *self.imp().on_elements_changed.borrow_mut() = Some(Box::new(callback));
+    }
+
+    /// Show the elements of slide `slide_index` and highlight `selected`.
+    pub fn sync(&self, slide_index: usize, selected: Option<Uuid>) {
+        let imp = self.imp();
+        imp.slide_index.set(slide_index);
+
+        let rows = self.collect_rows();
+        if rows != *imp.rows.borrow() {
+            self.rebuild(rows);
+        }
+
+        let position = selected.and_then(|id| imp.rows.borrow().iter().position(|r| r.id == id));
+        imp.updating.set(true);
+        match position.and_then(|pos| imp.list_box.row_at_index(pos as i32)) {
+            Some(row) => imp.list_box.select_row(Some(&row)),
+            None => imp.list_box.unselect_all(),
+        }
+        imp.updating.set(false);
+    }
+
+    fn collect_rows(&self) -> Vec<ObjectRow> {
+        let doc_ref = self.imp().document.borrow();
+        let Some(doc) = doc_ref.as_ref() else {
+            return Vec::new();
+        };
+        let doc = doc.borrow();
+        let Some(slide) = doc.slides.get(self.imp().slide_index.get()) else {
+            return Vec::new();
+        };
+
+        slide
+            .elements
+            .iter()
+            .rev()
+            .map(|element| ObjectRow {
+                id: element.id(),
+                icon: element_icon(element),
+                label: element_label(element),
+                visible: element.is_visible(),
+                locked: element.is_locked(),
+            })
+            .collect()
+    }
+
+    fn rebuild(&self, rows: Vec<ObjectRow>) {
+        let imp = self.imp();
+        imp.updating.set(true);
+        imp.list_box.remove_all();
+        for row in &rows {
+            imp.list_box.append(&self.build_row(row));
+        }
+        imp.updating.set(false);
+        *imp.rows.borrow_mut() = rows;
+    }
+
+    fn build_row(&self, row: &ObjectRow) -> gtk::Box {
+        let row_box = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+
+        let icon = gtk::Image::from_icon_name(row.icon);
+        if !row.visible {
+            row_box.set_opacity(0.5);
+        }
+        row_box.append(&icon);
+
+        // Editing the label renames the element; an empty name restores the default
+        let name_label = gtk::EditableLabel::new(&row.label);
+        name_label.set_hexpand(true);
+        let generated = row.label.clone();
+        let modify = self.modifier(row.id);
+        name_label.connect_editing_notify(move |label| {
+            if label.is_editing() {
+                return;
+            }
+            let text = label.text().trim().to_string();
+            if text == generated {
+                return;
+            }
+            modify(Box::new(move |element| element.set_name(text)));
+        });
+        row_box.append(&name_label);
+
+        let visibility_btn = gtk::Button::from_icon_name(if row.visible {
+            "view-reveal-symbolic"
+        } else {
+            "view-conceal-symbolic"
+        });
+        visibility_btn.set_tooltip_text(Some(&if row.visible {
+            gettext("Hide")
+        } else {
+            gettext("Show")
+        }));
+        visibility_btn.add_css_class("flat");
+        let modify = self.modifier(row.id);
+        let visible = row.visible;
+        visibility_btn.connect_clicked(move |_| {
+            modify(Box::new(move |element| element.set_visible(!visible)));
+        });
+        row_box.append(&visibility_btn);
+
+        let lock_btn = gtk::Button::from_icon_name(if row.locked {
+            "changes-prevent-symbolic"
+        } else {
+            "changes-allow-symbolic"
+        });
+        lock_btn.set_tooltip_text(Some(&if row.locked {
+            gettext("Unlock")
+        } else {
+            gettext("Lock")
+        }));
+        lock_btn.add_css_class("flat");
+        let modify = self.modifier(row.id);
+        let locked = row.locked;
+        lock_btn.connect_clicked(move |_| {
+            modify(Box::new(move |element| element.set_locked(!locked)));
+        });
+        row_box.append(&lock_btn);
+
+        row_box
+    }
+
+    /// A function applying a change to element `id` on the shown slide and
+    /// notifying listeners.
+    fn modifier(&self, id: Uuid) -> impl Fn(ElementChange) {
+        let imp = self.imp();
+        let doc = imp.document.borrow().clone();
+        let slide_index = imp.slide_index.clone();
+        let on_changed = imp.on_elements_changed.clone();
+
+        move |change| {
+            let Some(doc) = doc.as_ref() else { return };
+            {
+                let mut doc = doc.borrow_mut();
+                let Some(element) = doc
+                    .slides
+                    .get_mut(slide_index.get())
+                    .and_then(|slide| slide.elements.iter_mut().find(|e| e.id() == id))
+                else {
+                    return;
+                };
+                change(element);
+            }
+            if let Some(cb) = on_changed.borrow().as_ref() {
+                cb();
+            }
+        }
+    }
+}
+
+fn element_icon(element: &SlideElement) -> &'static str {
+    match element {
+        SlideElement::Text(_) => "insert-text-symbolic",
+        SlideElement::Image(_) => "insert-image-symbolic",
+        SlideElement::Shape(shape) => match shape.shape_type {
+            ShapeType::Rectangle => "checkbox-symbolic",
+            ShapeType::Ellipse => "color-select-symbolic",
+            ShapeType::Line => "format-text-strikethrough-symbolic",
+            ShapeType::Triangle => "pan-up-symbolic",
+            ShapeType::RoundedRectangle { .. } => "window-maximize-symbolic",
+            ShapeType::Star { .. } => "starred-symbolic",
+            ShapeType::Arrow => "go-next-symbolic",
+        },
+    }
+}
+
+/// The element's name, or a label derived from its type and content.
+fn element_label(element: &SlideElement) -> String {
+    if !element.name().is_empty() {
+        return element.name().to_string();
+    }
+    match element {
+        SlideElement::Text(text) => {
+            let content = text
+                .paragraphs
+                .iter()
+                .map(|p| p.full_text())
+                .find(|t| !t.trim().is_empty())
+                .unwrap_or_default();
+            let content = content.trim();
+            if content.is_empty() {
+                gettext("Text")
+            } else if content.chars().count() > LABEL_CHARS {
+                format!("{}…", content.chars().take(LABEL_CHARS).collect::<String>())
+            } else {
+                content.to_string()
+            }
+        }
+        SlideElement::Image(image) if !image.alt_text.is_empty() => image.alt_text.clone(),
+        _ => element_kind(element),
+    }
+}
+
+/// What kind of element this is, e.g. "Image" or "Star".
+pub fn element_kind(element: &SlideElement) -> String {
+    match element {
+        SlideElement::Text(_) => gettext("Text"),
+        SlideElement::Image(_) => gettext("Image"),
+        SlideElement::Shape(shape) => match shape.shape_type {
+            ShapeType::Rectangle => gettext("Rectangle"),
+            ShapeType::Ellipse => gettext("Ellipse"),
+            ShapeType::Line => gettext("Line"),
+            ShapeType::Triangle => gettext("Triangle"),
+            ShapeType::RoundedRectangle { .. } => gettext("Rounded Rectangle"),
+            ShapeType::Star { .. } => gettext("Star"),
+            ShapeType::Arrow => gettext("Arrow"),
+        },
+    }
+}
@@ -0,0 +1,122 @@
+use adw::prelude::*;
+use gettextrs::gettext;
+use std::cell::OnceCell;
+
+use crate::config;
+
+/// Distance in screen pixels within which an edge snaps to a target
+pub const DEFAULT_SNAP_DISTANCE: f64 = 6.0;
+/// Points between grid lines
+pub const DEFAULT_GRID_SPACING: f64 = 20.0;
+/// Points an arrow key moves the selection
+pub const DEFAULT_NUDGE_STEP: f64 = 1.0;
+/// Points an arrow key moves the selection with Shift held
+pub const DEFAULT_NUDGE_STEP_LARGE: f64 = 10.0;
+
+thread_local! {
+    static SETTINGS: OnceCell<Option<gio::Settings>> = const { OnceCell::new() };
+}
+
+/// The application settings, or `None` when the schema is not installed,
+/// e.g. when running from the build tree. The defaults apply then.
+pub fn settings() -> Option<gio::Settings> {
+    SETTINGS.with(|cell| {
+        cell.get_or_init(|| {
+            gio::SettingsSchemaSource::default()?.lookup(config::APP_ID, true)?;
+            Some(gio::Settings::new(config::APP_ID))
+        })
+        .clone()
+    })
+}
+
+fn double_setting(key: &str, default: f64) -> f64 {
+    settings().map_or(default, |settings| settings.double(key))
+}
+
+// Read on every use, so changes apply without a restart
+
+pub fn snap_distance() -> f64 {
+    double_setting("snap-distance", DEFAULT_SNAP_DISTANCE)
+}
+
+pub fn grid_spacing() -> f64 {
+    double_setting("grid-spacing", DEFAULT_GRID_SPACING)
+}
+
+/// Points an arrow key moves the selection, `large` with Shift held.
+pub fn nudge_step(large: bool) -> f64 {
+    if large {
+        double_setting("nudge-step-large", DEFAULT_NUDGE_STEP_LARGE)
+    } else {
+        double_setting("nudge-step", DEFAULT_NUDGE_STEP)
+    }
+}
+
+pub fn show_preferences_dialog(parent: &impl IsA<gtk::Widget>) {
+    let snapping = adw::PreferencesGroup::builder()
+        .title(gettext("Snapping"))
+        .build();
+    snapping.add(&setting_row(
+        &gettext("Snap Distance"),
+        &gettext("Screen pixels within which elements snap to guides"),
+        "snap-distance",
+        DEFAULT_SNAP_DISTANCE,
+        (1.0, 30.0, 1.0),
+    ));
+    snapping.add(&setting_row(
+        &gettext("Grid Spacing"),
+        &gettext("Points between grid lines"),
+        "grid-spacing",
+        DEFAULT_GRID_SPACING,
+        (2.0, 200.0, 1.0),
+    ));
+
+    let nudging = adw::PreferencesGroup::builder()
+        .title(gettext("Arrow Keys"))
+        .build();
+    nudging.add(&setting_row(
+        &gettext("Nudge Step"),
+        &gettext("Points an arrow key moves the selection"),
+        "nudge-step",
+        DEFAULT_NUDGE_STEP,
+        (0.1, 50.0, 0.5),
+    ));
+    nudging.add(&setting_row(
+        &gettext("Large Nudge Step"),
+        &gettext("Points moved with Shift held"),
+        "nudge-step-large",
+        DEFAULT_NUDGE_STEP_LARGE,
+        (1.0, 200.0, 1.0),
+    ));
+
+    let page = adw::PreferencesPage::new();
+    page.add(&snapping);
+    page.add(&nudging);
+
+    let dialog = adw::PreferencesDialog::new();
+    dialog.add(&page);
+    dialog.present(Some(parent));
+}
+
+/// A spin row bound to the double setting `key`. Without settings it only
+/// shows the default.
+fn setting_row(
+    title: &str,
+    subtitle: &str,
+    key: &str,
+    default: f64,
+    range: (f64, f64, f64),
+) -> adw::SpinRow {
+    let (min, max, step) = range;
+    let row = adw::SpinRow::with_range(min, max, step);
+    row.set_title(title);
+    row.set_subtitle(subtitle);
+    row.set_digits(1);
+    row.set_value(default);
+
+    match settings() {
+        Some(settings) => settings.bind(key, &row, "value").build(),
+        None => row.set_sensitive(false),
+    }
+    row
+}
@@ -1,13 +1,28 @@
 use gettextrs::gettext;
 use gtk::prelude::*;
 use gtk::subclass::prelude::*;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 use uuid::Uuid;
 
 use crate::model::document::Document;
 use crate::model::element::SlideElement;
-use crate::model::style::Color;
+use crate::model::geometry::Insets;
+use crate::model::image::{ImageCrop, ImageData, ImageFilters, ScaleMode};
+use crate::model::shape::{LineMarker, ShapeElement, ShapeType};
+use crate::model::slide::{Background, Gradient, GradientKind};
+use crate::model::style::{Color, FillStyle, FontStyle, LineCap, LineJoin, StrokeStyle};
+use crate::model::text::{
+    ListStyle, TextElement, TextOutline, TextParagraph, TextShadow, VerticalAlignment,
+    MAX_LIST_LEVEL,
+};
+
+use super::window::{image_file_filters, image_mime_type};
+
+/// Characters of a paragraph shown in the paragraph drop-down
+const PARAGRAPH_PREVIEW_CHARS: usize = 24;
+/// Largest position and size the position fields take, in points
+const MAX_COORDINATE: f64 = 10000.0;
 
 mod imp {
     use super::*;
@@ -18,6 +33,14 @@ mod imp {
         pub document: RefCell<Option<Rc<RefCell<Document>>>>,
         pub selected_id: RefCell<Option<Uuid>>,
         pub slide_index: RefCell<usize>,
+        /// Paragraph the text formatting controls apply to, `None` for all
+        pub paragraph_index: Cell<Option<usize>>,
+        /// Slide index and state of the element the rows show, to tell
+        /// whether a selection update changes anything
+        pub shown_element: RefCell<Option<(usize, SlideElement)>>,
+        /// X, Y, W, H and R fields of the selected element, refreshed in
+        /// place while it is dragged
+        pub position_spins: RefCell<Vec<gtk::SpinButton>>,
         pub on_property_changed: Rc<RefCell<Option<Box<dyn Fn()>>>>,
         pub updating: RefCell<bool>,
     }
@@ -48,6 +71,9 @@ mod imp {
                 document: RefCell::new(None),
                 selected_id: RefCell::new(None),
                 slide_index: RefCell::new(0),
+                paragraph_index: Cell::new(None),
+                shown_element: RefCell::new(None),
+                position_spins: RefCell::new(Vec::new()),
                 on_property_changed: Rc::new(RefCell::new(None)),
                 updating: RefCell::new(false),
             }
@@ -99,15 +125,101 @@ impl PropertiesPanel {
     }
 
     pub fn connect_property_changed<F: Fn() + 'static>(&self, callback: F) {
-        *self.imp().on_property_changed.borrow_mut() = Some(Box::new(callback));
+        let panel = self.downgrade();
+        *self.imp().on_property_changed.borrow_mut() = Some(Box::new(move || {
+            callback();
+            // The rows already show the edit. The element can only be read
+            // once the handler making it lets go of the document.
+            let panel = panel.clone();
+            glib::idle_add_local_once(move || {
+                if let Some(panel) = panel.upgrade() {
+                    panel.remember_shown_element();
+                }
+            });
+        }));
     }
 
+    /// Show the properties of `element_id`, or of the slide for `None`. The
+    /// rows are kept while they still show the selected element as it is,
+    /// so a field being typed into keeps the focus.
     pub fn update_for_selection(&self, element_id: Option<Uuid>) {
         let imp = self.imp();
+        if *imp.selected_id.borrow() != element_id {
+            imp.paragraph_index.set(None);
+        } else if element_id.is_some() && self.shows_current_element() {
+            return;
+        }
         *imp.selected_id.borrow_mut() = element_id;
         self.rebuild_ui();
     }
 
+    /// Whether the rows were built for the selected element on the current
+    /// slide and it has not changed since.
+    fn shows_current_element(&self) -> bool {
+        let imp = self.imp();
+        let shown = imp.shown_element.borrow();
+        let Some((shown_idx, shown)) = shown.as_ref() else {
+            return false;
+        };
+        let idx = *imp.slide_index.borrow();
+        let doc_ref = imp.document.borrow();
+        let Some(Ok(doc)) = doc_ref.as_ref().map(|doc| doc.try_borrow()) else {
+            return false;
+        };
+        *shown_idx == idx
+            && doc
+                .slides
+                .get(idx)
+                .and_then(|slide| slide.elements.iter().find(|e| e.id() == shown.id()))
+                == Some(shown)
+    }
+
+    /// Update the position and size fields to the selected element without
+    /// rebuilding the rows, e.g. while it is dragged on the canvas.
+    pub fn refresh_values(&self) {
+        let imp = self.imp();
+        let Some(sel_id) = *imp.selected_id.borrow() else {
+            return;
+        };
+        let values = {
+            let doc_ref = imp.document.borrow();
+            let Some(Ok(doc)) = doc_ref.as_ref().map(|doc| doc.try_borrow()) else {
+                return;
+            };
+            let element = doc
+                .slides
+                .get(*imp.slide_index.borrow())
+                .and_then(|slide| slide.elements.iter().find(|e| e.id() == sel_id));
+            let Some(element) = element else { return };
+            let bounds = element.bounds();
+            [
+                bounds.origin.x,
+                bounds.origin.y,
+                bounds.size.width,
+                bounds.size.height,
+                element.rotation(),
+            ]
+        };
+
+        *imp.updating.borrow_mut() = true;
+        for (spin, value) in imp.position_spins.borrow().iter().zip(values) {
+            spin.set_value(value);
+        }
+        *imp.updating.borrow_mut() = false;
+    }
+
+    /// Note the selected element as the rows show it now.
+    fn remember_shown_element(&self) {
+        let imp = self.imp();
+        let idx = *imp.slide_index.borrow();
+        let element = imp.selected_id.borrow().and_then(|id| {
+            let doc_ref = imp.document.borrow();
+            let doc = doc_ref.as_ref()?.try_borrow().ok()?;
+            doc.slides.get(idx)?.elements.iter().find(|e| e.id() == id).cloned()
+        });
+        *imp.shown_element.borrow_mut() = element.map(|element| (idx, element));
+    }
+
     fn rebuild_ui(&self) {
         let imp = self.imp();
         let content = &imp.content_box;
@@ -116,13 +228,12 @@ impl PropertiesPanel {
         while let Some(child) = content.first_child() {
             content.remove(&child);
         }
+        imp.position_spins.borrow_mut().clear();
+        self.remember_shown_element();
 
         let sel_id = *imp.selected_id.borrow();
         let Some(sel_id) = sel_id else {
-            let label = gtk::Label::new(Some(&gettext("No selection")));
-            label.add_css_class("dim-label");
-            label.set_margin_top(24);
-            content.append(&label);
+            self.build_slide_properties(content);
             return;
         };
 
@@ -145,17 +256,233 @@ impl PropertiesPanel {
         // Type-specific properties
         match element {
             SlideElement::Text(text) => {
-                self.build_text_properties(content, text);
+                self.build_text_properties(content, text, slide);
             }
             SlideElement::Shape(shape) => {
                 self.build_shape_properties(content, shape);
             }
-            SlideElement::Image(_) => {
-                let label = gtk::Label::new(Some(&gettext("Image")));
-                label.add_css_class("heading");
-                label.set_halign(gtk::Align::Start);
-                content.append(&label);
+            SlideElement::Image(image) => {
+                self.build_image_properties(content, image);
+            }
+        }
+
+        let visibility_label = if element.is_visible() {
+            gettext("Hide")
+        } else {
+            gettext("Show")
+        };
+        let visibility_btn = gtk::Button::with_label(&visibility_label);
+        visibility_btn.set_action_name(Some("win.toggle-visibility"));
+        visibility_btn.set_margin_top(12);
+        content.append(&visibility_btn);
+
+        let reset_btn = gtk::Button::with_label(&gettext("Reset Style"));
+        reset_btn.set_action_name(Some("win.reset-style"));
+        content.append(&reset_btn);
+    }
+
+    /// Properties of the current slide, shown while no element is selected.
+    fn build_slide_properties(&self, content: &gtk::Box) {
+        let imp = self.imp();
+        let doc_rc = imp.document.borrow().clone();
+        let slide_idx = *imp.slide_index.borrow();
+        let background = doc_rc
+            .as_ref()
+            .and_then(|doc| doc.borrow().slides.get(slide_idx).map(|s| s.background.clone()));
+        let Some(background) = background else {
+            let label = gtk::Label::new(Some(&gettext("No selection")));
+            label.add_css_class("dim-label");
+            label.set_margin_top(24);
+            content.append(&label);
+            return;
+        };
+
+        let section_label = gtk::Label::new(Some(&gettext("Slide")));
+        section_label.add_css_class("heading");
+        section_label.set_halign(gtk::Align::Start);
+        content.append(&section_label);
+
+        self.build_background_kind_row(content, &background);
+        match &background {
+            Background::Solid(color) => {
+                let panel = self.downgrade();
+                self.build_color_button_row(content, &gettext("Color"), color, move |color| {
+                    let Some(panel) = panel.upgrade() else { return };
+                    panel.update_slide_background(|bg| *bg = Background::Solid(color));
+                });
+            }
+            Background::Gradient(gradient) => {
+                self.build_background_gradient_rows(content, gradient);
+            }
+            Background::Image(_) => {
+                let choose_btn = gtk::Button::with_label(&gettext("Choose Picture…"));
+                let panel = self.downgrade();
+                choose_btn.connect_clicked(move |_| {
+                    if let Some(panel) = panel.upgrade() {
+                        panel.choose_background_picture();
+                    }
+                });
+                content.append(&choose_btn);
+            }
+        }
+
+        let apply_all_btn = gtk::Button::with_label(&gettext("Apply to All Slides"));
+        apply_all_btn.set_tooltip_text(Some(&gettext(
+            "Give every slide the background of this slide",
+        )));
+        let on_changed = imp.on_property_changed.clone();
+        apply_all_btn.connect_clicked(move |_| {
+            let Some(doc_rc) = doc_rc.as_ref() else { return };
+            {
+                let mut doc = doc_rc.borrow_mut();
+                let Some(background) = doc.slides.get(slide_idx).map(|s| s.background.clone())
+                else {
+                    return;
+                };
+                for slide in &mut doc.slides {
+                    slide.background = background.clone();
+                }
+            }
+            if let Some(cb) = on_changed.borrow().as_ref() {
+                cb();
+            }
+        });
+        content.append(&apply_all_btn);
+    }
+
+    /// Drop-down switching the slide background between a color, a gradient
+    /// and a picture, keeping the color of the current background.
+    fn build_background_kind_row(&self, content: &gtk::Box, background: &Background) {
+        let row = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+        let label = gtk::Label::new(Some(&gettext("Background")));
+        label.add_css_class("dim-label");
+        label.set_width_chars(6);
+        label.set_halign(gtk::Align::Start);
+
+        let kinds = [gettext("Color"), gettext("Gradient"), gettext("Picture")];
+        let kind_refs: Vec<&str> = kinds.iter().map(String::as_str).collect();
+        let dropdown = gtk::DropDown::from_strings(&kind_refs);
+        dropdown.set_selected(match background {
+            Background::Solid(_) => 0,
+            Background::Gradient(_) => 1,
+            Background::Image(_) => 2,
+        });
+        dropdown.set_hexpand(true);
+
+        let color = match background {
+            Background::Solid(color) => color.clone(),
+            Background::Gradient(gradient) => gradient.start.clone(),
+            Background::Image(_) => Color::white(),
+        };
+        let panel = self.downgrade();
+        dropdown.connect_selected_notify(move |dropdown| {
+            let Some(panel) = panel.upgrade() else { return };
+            match dropdown.selected() {
+                0 => panel.update_slide_background(|bg| *bg = Background::Solid(color.clone())),
+                1 => {
+                    let end = color.mix(&Color::white(), 0.5);
+                    let gradient = Gradient::linear(color.clone(), end, 90.0);
+                    panel.update_slide_background(|bg| *bg = Background::Gradient(gradient));
+                }
+                // Rebuilt once the picture is chosen or the dialog cancelled
+                _ => return panel.choose_background_picture(),
+            }
+            // Rebuilding removes this drop-down, so wait until its signal is done
+            glib::idle_add_local_once(move || panel.rebuild_ui());
+        });
+
+        row.append(&label);
+        row.append(&dropdown);
+        content.append(&row);
+    }
+
+    /// Start and end color of a gradient slide background, and the angle of
+    /// a linear one.
+    fn build_background_gradient_rows(&self, content: &gtk::Box, gradient: &Gradient) {
+        let panel = self.downgrade();
+        self.build_color_button_row(content, &gettext("Start"), &gradient.start, move |color| {
+            let Some(panel) = panel.upgrade() else { return };
+            panel.update_slide_background(|bg| {
+                if let Background::Gradient(gradient) = bg {
+                    gradient.start = color;
+                }
+            });
+        });
+        let panel = self.downgrade();
+        self.build_color_button_row(content, &gettext("End"), &gradient.end, move |color| {
+            let Some(panel) = panel.upgrade() else { return };
+            panel.update_slide_background(|bg| {
+                if let Background::Gradient(gradient) = bg {
+                    gradient.end = color;
+                }
+            });
+        });
+        if gradient.kind != GradientKind::Linear {
+            return;
+        }
+
+        let row = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+        let label = gtk::Label::new(Some(&gettext("Angle")));
+        label.add_css_class("dim-label");
+        label.set_width_chars(6);
+        label.set_halign(gtk::Align::Start);
+
+        let angle_spin = gtk::SpinButton::with_range(0.0, 360.0, 15.0);
+        angle_spin.set_value(gradient.angle);
+        angle_spin.set_wrap(true);
+        angle_spin.set_hexpand(true);
+        angle_spin.set_tooltip_text(Some(&gettext("Gradient angle in degrees")));
+
+        let panel = self.downgrade();
+        angle_spin.connect_value_changed(move |spin| {
+            let Some(panel) = panel.upgrade() else { return };
+            panel.update_slide_background(|bg| {
+                if let Background::Gradient(gradient) = bg {
+                    gradient.angle = spin.value();
+                }
+            });
+        });
+
+        row.append(&label);
+        row.append(&angle_spin);
+        content.append(&row);
+    }
+
+    /// Let the user pick a picture file for the slide background.
+    fn choose_background_picture(&self) {
+        let dialog = gtk::FileDialog::builder()
+            .title(gettext("Choose Background Picture"))
+            .filters(&image_file_filters())
+            .build();
+        let window = self.root().and_downcast::<gtk::Window>();
+
+        let panel = self.downgrade();
+        dialog.open(window.as_ref(), gio::Cancellable::NONE, move |result| {
+            let Some(panel) = panel.upgrade() else { return };
+            let picture = result.ok().and_then(|file| file.path()).and_then(|path| {
+                let data = std::fs::read(&path).ok()?;
+                let mime = image_mime_type(&path).to_string();
+                Some(ImageData::Embedded { data: data.into(), mime })
+            });
+            if let Some(picture) = picture {
+                panel.update_slide_background(|bg| *bg = Background::Image(picture));
             }
+            // Also resets the drop-down after a cancelled switch to a picture
+            panel.rebuild_ui();
+        });
+    }
+
+    /// Change the background of the current slide and report the change.
+    fn update_slide_background<F: FnOnce(&mut Background)>(&self, apply: F) {
+        let imp = self.imp();
+        let Some(doc_rc) = imp.document.borrow().clone() else { return };
+        {
+            let mut doc = doc_rc.borrow_mut();
+            let Some(slide) = doc.slides.get_mut(*imp.slide_index.borrow()) else { return };
+            apply(&mut slide.background);
+        }
+        if let Some(cb) = imp.on_property_changed.borrow().as_ref() {
+            cb();
         }
     }
 
@@ -172,20 +499,21 @@ impl PropertiesPanel {
         grid.set_row_spacing(6);
         grid.set_column_spacing(8);
 
-        let fields: Vec<(&str, f64)> = vec![
-            ("X", bounds.origin.x),
-            ("Y", bounds.origin.y),
-            ("W", bounds.size.width),
-            ("H", bounds.size.height),
+        // Elements may be dragged past the top and left edge of the slide
+        let fields: Vec<(&str, f64, f64)> = vec![
+            ("X", bounds.origin.x, -MAX_COORDINATE),
+            ("Y", bounds.origin.y, -MAX_COORDINATE),
+            ("W", bounds.size.width, 0.0),
+            ("H", bounds.size.height, 0.0),
         ];
 
-        for (row, (label_text, value)) in fields.iter().enumerate() {
+        for (row, (label_text, value, min)) in fields.iter().enumerate() {
             let label = gtk::Label::new(Some(label_text));
             label.set_halign(gtk::Align::End);
             label.add_css_class("dim-label");
             label.set_width_chars(2);
 
-            let spin = gtk::SpinButton::with_range(0.0, 10000.0, 1.0);
+            let spin = gtk::SpinButton::with_range(*min, MAX_COORDINATE, 1.0);
             spin.set_value(*value);
             spin.set_digits(1);
             spin.set_hexpand(true);
@@ -226,8 +554,52 @@ impl PropertiesPanel {
 
             grid.attach(&label, 0, row as i32, 1, 1);
             grid.attach(&spin, 1, row as i32, 1, 1);
+            imp.position_spins.borrow_mut().push(spin);
         }
 
+        // Rotation in degrees clockwise, about the center
+        let label = gtk::Label::new(Some("R"));
+        label.set_halign(gtk::Align::End);
+        label.add_css_class("dim-label");
+        label.set_width_chars(2);
+
+        let rotation_spin = gtk::SpinButton::with_range(0.0, 360.0, 1.0);
+        rotation_spin.set_value(element.rotation());
+        rotation_spin.set_digits(1);
+        rotation_spin.set_wrap(true);
+        rotation_spin.set_hexpand(true);
+        rotation_spin.set_tooltip_text(Some(&gettext("Rotation in degrees")));
+
+        let doc_rc = imp.document.borrow().clone();
+        let sel_id = *imp.selected_id.borrow();
+        let slide_idx = *imp.slide_index.borrow();
+        let on_changed = imp.on_property_changed.clone();
+        let updating = imp.updating.clone();
+
+        rotation_spin.connect_value_changed(move |spin| {
+            if *updating.borrow() {
+                return;
+            }
+            let Some(doc_rc) = doc_rc.as_ref() else { return };
+            let Some(sel_id) = sel_id else { return };
+            let mut doc = doc_rc.borrow_mut();
+            if slide_idx >= doc.slides.len() {
+                return;
+            }
+            let slide = &mut doc.slides[slide_idx];
+            if let Some(element) = slide.elements.iter_mut().find(|e| e.id() == sel_id) {
+                element.set_rotation(spin.value().rem_euclid(360.0));
+                if let Some(cb) = on_changed.borrow().as_ref() {
+                    cb();
+                }
+            }
+        });
+
+        let row = fields.len() as i32;
+        grid.attach(&label, 0, row, 1, 1);
+        grid.attach(&rotation_spin, 1, row, 1, 1);
+        imp.position_spins.borrow_mut().push(rotation_spin);
+
         content.append(&grid);
 
         let sep = gtk::Separator::new(gtk::Orientation::Horizontal);
@@ -240,6 +612,7 @@ impl PropertiesPanel {
         &self,
         content: &gtk::Box,
         text: &crate::model::text::TextElement,
+        slide: &crate::model::slide::Slide,
     ) {
         let imp = self.imp();
 
@@ -248,15 +621,22 @@ impl PropertiesPanel {
         section_label.set_halign(gtk::Align::Start);
         content.append(&section_label);
 
-        // Get font info from first run of first paragraph
-        let (font_family, font_size, bold, italic, text_color) =
-            if let Some(para) = text.paragraphs.first() {
+        // Formatting applies to one paragraph or to all of them
+        let paragraph = imp
+            .paragraph_index
+            .get()
+            .filter(|&i| i < text.paragraphs.len());
+        if text.paragraphs.len() > 1 {
+            self.build_paragraph_row(content, text, paragraph);
+        }
+
+        // Get font info from first run of the first targeted paragraph
+        let (font_family, font_size, text_color) =
+            if let Some(para) = text.paragraphs_at(paragraph).first() {
                 if let Some(run) = para.runs.first() {
                     (
                         run.font.family.clone(),
                         run.font.size,
-                        run.font.bold,
-                        run.font.italic,
                         run.font.color.clone(),
                     )
                 } else {
@@ -294,7 +674,7 @@ impl PropertiesPanel {
             if let Some(SlideElement::Text(text)) =
                 slide.elements.iter_mut().find(|e| e.id() == sel_id)
             {
-                for para in &mut text.paragraphs {
+                for para in text.paragraphs_at_mut(paragraph) {
                     for run in &mut para.runs {
                         run.font.family = family.clone();
                     }
@@ -342,7 +722,7 @@ impl PropertiesPanel {
             if let Some(SlideElement::Text(text)) =
                 slide.elements.iter_mut().find(|e| e.id() == sel_id)
             {
-                for para in &mut text.paragraphs {
+                for para in text.paragraphs_at_mut(paragraph) {
                     for run in &mut para.runs {
                         run.font.size = size;
                     }
@@ -357,7 +737,7 @@ impl PropertiesPanel {
         size_row.append(&size_spin);
         content.append(&size_row);
 
-        // Bold / Italic toggles
+        // Bold / Italic / Underline / Strikethrough toggles
         let style_row = gtk::Box::new(gtk::Orientation::Horizontal, 4);
         let style_label = gtk::Label::new(Some(&gettext("Style")));
         style_label.add_css_class("dim-label");
@@ -365,9 +745,133 @@ impl PropertiesPanel {
         style_label.set_halign(gtk::Align::Start);
         style_row.append(&style_label);
 
-        let bold_btn = gtk::ToggleButton::new();
-        bold_btn.set_icon_name("format-text-bold-symbolic");
-        bold_btn.set_active(bold);
+        let bold_btn = self.build_font_toggle(
+            text,
+            paragraph,
+            "format-text-bold-symbolic",
+            |font| font.bold,
+            |font, on| font.bold = on,
+        );
+        let italic_btn = self.build_font_toggle(
+            text,
+            paragraph,
+            "format-text-italic-symbolic",
+            |font| font.italic,
+            |font, on| font.italic = on,
+        );
+        let underline_btn = self.build_font_toggle(
+            text,
+            paragraph,
+            "format-text-underline-symbolic",
+            |font| font.underline,
+            |font, on| font.underline = on,
+        );
+        let strikethrough_btn = self.build_font_toggle(
+            text,
+            paragraph,
+            "format-text-strikethrough-symbolic",
+            |font| font.strikethrough,
+            |font, on| font.strikethrough = on,
+        );
+
+        style_row.append(&bold_btn);
+        style_row.append(&italic_btn);
+        style_row.append(&underline_btn);
+        style_row.append(&strikethrough_btn);
+        content.append(&style_row);
+
+        // Bulleted / numbered list and the nesting level of its items
+        let list_row = gtk::Box::new(gtk::Orientation::Horizontal, 4);
+        let list_label = gtk::Label::new(Some(&gettext("List")));
+        list_label.add_css_class("dim-label");
+        list_label.set_width_chars(5);
+        list_label.set_halign(gtk::Align::Start);
+        list_row.append(&list_label);
+
+        let bullet_btn = self.build_list_toggle(
+            text,
+            paragraph,
+            "view-list-bullet-symbolic",
+            &gettext("Bulleted List"),
+            ListStyle::Bullet,
+        );
+        let numbered_btn = self.build_list_toggle(
+            text,
+            paragraph,
+            "view-list-ordered-symbolic",
+            &gettext("Numbered List"),
+            ListStyle::Numbered,
+        );
+        // Switching one list style on switches the other off
+        for (btn, other) in [(&bullet_btn, &numbered_btn), (&numbered_btn, &bullet_btn)] {
+            let other = other.clone();
+            btn.connect_toggled(move |btn| {
+                if btn.is_active() {
+                    other.set_active(false);
+                }
+            });
+        }
+        let outdent_btn = self.build_list_level_button(
+            paragraph,
+            "format-indent-less-symbolic",
+            &gettext("Decrease List Level"),
+            -1,
+        );
+        let indent_btn = self.build_list_level_button(
+            paragraph,
+            "format-indent-more-symbolic",
+            &gettext("Increase List Level"),
+            1,
+        );
+
+        list_row.append(&bullet_btn);
+        list_row.append(&numbered_btn);
+        list_row.append(&outdent_btn);
+        list_row.append(&indent_btn);
+        content.append(&list_row);
+
+        self.build_paragraph_spin_row(
+            content,
+            text,
+            paragraph,
+            &gettext("Lines"),
+            &gettext("Line spacing as a multiple of the line height"),
+            (0.5, 5.0, 0.1),
+            |para| para.line_spacing,
+            |para, value| para.line_spacing = value,
+        );
+        self.build_paragraph_spin_row(
+            content,
+            text,
+            paragraph,
+            &gettext("Before"),
+            &gettext("Space above the paragraph in points"),
+            (0.0, 200.0, 1.0),
+            |para| para.space_before,
+            |para, value| para.space_before = value,
+        );
+        self.build_paragraph_spin_row(
+            content,
+            text,
+            paragraph,
+            &gettext("After"),
+            &gettext("Space below the paragraph in points"),
+            (0.0, 200.0, 1.0),
+            |para| para.space_after,
+            |para, value| para.space_after = value,
+        );
+
+        // Padding between the box edges and the text, same on all sides
+        let padding_row = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+        let padding_label = gtk::Label::new(Some(&gettext("Padding")));
+        padding_label.add_css_class("dim-label");
+        padding_label.set_width_chars(5);
+        padding_label.set_halign(gtk::Align::Start);
+
+        let padding_spin = gtk::SpinButton::with_range(0.0, 200.0, 1.0);
+        padding_spin.set_value(text.inset.left);
+        padding_spin.set_digits(1);
+        padding_spin.set_hexpand(true);
 
         let doc_rc = imp.document.borrow().clone();
         let sel_id = *imp.selected_id.borrow();
@@ -375,13 +879,13 @@ impl PropertiesPanel {
         let on_changed = imp.on_property_changed.clone();
         let updating = imp.updating.clone();
 
-        bold_btn.connect_toggled(move |btn| {
+        padding_spin.connect_value_changed(move |spin| {
             if *updating.borrow() {
                 return;
             }
             let Some(doc_rc) = doc_rc.as_ref() else { return };
             let Some(sel_id) = sel_id else { return };
-            let is_bold = btn.is_active();
+            let padding = spin.value();
             let mut doc = doc_rc.borrow_mut();
             if slide_idx >= doc.slides.len() {
                 return;
@@ -390,20 +894,138 @@ impl PropertiesPanel {
             if let Some(SlideElement::Text(text)) =
                 slide.elements.iter_mut().find(|e| e.id() == sel_id)
             {
-                for para in &mut text.paragraphs {
-                    for run in &mut para.runs {
-                        run.font.bold = is_bold;
-                    }
+                text.inset = Insets::uniform(padding);
+                if let Some(cb) = on_changed.borrow().as_ref() {
+                    cb();
                 }
+            }
+        });
+
+        padding_row.append(&padding_label);
+        padding_row.append(&padding_spin);
+        content.append(&padding_row);
+
+        // Where the text sits in a box taller than the text
+        let valign_row = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+        let valign_label = gtk::Label::new(Some(&gettext("Vertical")));
+        valign_label.add_css_class("dim-label");
+        valign_label.set_width_chars(5);
+        valign_label.set_halign(gtk::Align::Start);
+
+        let alignments = [
+            VerticalAlignment::Top,
+            VerticalAlignment::Middle,
+            VerticalAlignment::Bottom,
+        ];
+        let valign_names = [gettext("Top"), gettext("Middle"), gettext("Bottom")];
+        let valign_refs: Vec<&str> = valign_names.iter().map(String::as_str).collect();
+        let valign_dropdown = gtk::DropDown::from_strings(&valign_refs);
+        valign_dropdown.set_hexpand(true);
+        valign_dropdown.set_tooltip_text(Some(&gettext("Vertical alignment in the box")));
+        let selected = alignments
+            .iter()
+            .position(|a| *a == text.vertical_alignment)
+            .unwrap_or(0);
+        valign_dropdown.set_selected(selected as u32);
+
+        let doc_rc = imp.document.borrow().clone();
+        let sel_id = *imp.selected_id.borrow();
+        let slide_idx = *imp.slide_index.borrow();
+        let on_changed = imp.on_property_changed.clone();
+        let updating = imp.updating.clone();
+
+        valign_dropdown.connect_selected_notify(move |dropdown| {
+            if *updating.borrow() {
+                return;
+            }
+            let Some(doc_rc) = doc_rc.as_ref() else { return };
+            let Some(sel_id) = sel_id else { return };
+            let Some(&alignment) = alignments.get(dropdown.selected() as usize) else {
+                return;
+            };
+            let mut doc = doc_rc.borrow_mut();
+            if slide_idx >= doc.slides.len() {
+                return;
+            }
+            let slide = &mut doc.slides[slide_idx];
+            if let Some(SlideElement::Text(text)) =
+                slide.elements.iter_mut().find(|e| e.id() == sel_id)
+            {
+                text.vertical_alignment = alignment;
                 if let Some(cb) = on_changed.borrow().as_ref() {
                     cb();
                 }
             }
         });
 
-        let italic_btn = gtk::ToggleButton::new();
-        italic_btn.set_icon_name("format-text-italic-symbolic");
-        italic_btn.set_active(italic);
+        valign_row.append(&valign_label);
+        valign_row.append(&valign_dropdown);
+        content.append(&valign_row);
+
+        // Shrink the text rather than let it overflow the box
+        let auto_fit_check = gtk::CheckButton::with_label(&gettext("Shrink text to fit"));
+        auto_fit_check.set_active(text.auto_fit);
+        auto_fit_check.set_tooltip_text(Some(&gettext(
+            "Draw the text smaller while it is too long for the box",
+        )));
+
+        let doc_rc = imp.document.borrow().clone();
+        let sel_id = *imp.selected_id.borrow();
+        let slide_idx = *imp.slide_index.borrow();
+        let on_changed = imp.on_property_changed.clone();
+        let updating = imp.updating.clone();
+
+        auto_fit_check.connect_toggled(move |check| {
+            if *updating.borrow() {
+                return;
+            }
+            let Some(doc_rc) = doc_rc.as_ref() else { return };
+            let Some(sel_id) = sel_id else { return };
+            let mut doc = doc_rc.borrow_mut();
+            if slide_idx >= doc.slides.len() {
+                return;
+            }
+            let slide = &mut doc.slides[slide_idx];
+            if let Some(SlideElement::Text(text)) =
+                slide.elements.iter_mut().find(|e| e.id() == sel_id)
+            {
+                text.auto_fit = check.is_active();
+                if let Some(cb) = on_changed.borrow().as_ref() {
+                    cb();
+                }
+            }
+        });
+        content.append(&auto_fit_check);
+
+        // Line on the slide to run the text along, numbered in stacking order
+        let line_ids: Vec<Uuid> = slide
+            .elements
+            .iter()
+            .filter_map(|e| match e {
+                SlideElement::Shape(shape) if shape.shape_type == ShapeType::Line => Some(shape.id),
+                _ => None,
+            })
+            .collect();
+
+        let path_row = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+        let path_label = gtk::Label::new(Some(&gettext("Path")));
+        path_label.add_css_class("dim-label");
+        path_label.set_width_chars(5);
+        path_label.set_halign(gtk::Align::Start);
+
+        let mut path_names = vec![gettext("None")];
+        path_names.extend(
+            (1..=line_ids.len()).map(|n| gettext("Line {}").replace("{}", &n.to_string())),
+        );
+        let path_refs: Vec<&str> = path_names.iter().map(String::as_str).collect();
+        let path_dropdown = gtk::DropDown::from_strings(&path_refs);
+        path_dropdown.set_hexpand(true);
+        path_dropdown.set_tooltip_text(Some(&gettext("Follow the direction of a line")));
+        let selected = text
+            .path_line
+            .and_then(|id| line_ids.iter().position(|l| *l == id))
+            .map_or(0, |pos| pos + 1);
+        path_dropdown.set_selected(selected as u32);
 
         let doc_rc = imp.document.borrow().clone();
         let sel_id = *imp.selected_id.borrow();
@@ -411,13 +1033,42 @@ impl PropertiesPanel {
         let on_changed = imp.on_property_changed.clone();
         let updating = imp.updating.clone();
 
-        italic_btn.connect_toggled(move |btn| {
+        path_dropdown.connect_selected_notify(move |dropdown| {
             if *updating.borrow() {
                 return;
             }
             let Some(doc_rc) = doc_rc.as_ref() else { return };
             let Some(sel_id) = sel_id else { return };
-            let is_italic = btn.is_active();
+            let path_line = (dropdown.selected() as usize)
+                .checked_sub(1)
+                .and_then(|i| line_ids.get(i).copied());
+            let mut doc = doc_rc.borrow_mut();
+            if slide_idx >= doc.slides.len() {
+                return;
+            }
+            let slide = &mut doc.slides[slide_idx];
+            if let Some(SlideElement::Text(text)) =
+                slide.elements.iter_mut().find(|e| e.id() == sel_id)
+            {
+                text.path_line = path_line;
+                if let Some(cb) = on_changed.borrow().as_ref() {
+                    cb();
+                }
+            }
+        });
+
+        path_row.append(&path_label);
+        path_row.append(&path_dropdown);
+        content.append(&path_row);
+
+        // Text color
+        let doc_rc = imp.document.borrow().clone();
+        let sel_id = *imp.selected_id.borrow();
+        let slide_idx = *imp.slide_index.borrow();
+        let on_changed = imp.on_property_changed.clone();
+        self.build_color_button_row(content, &gettext("Color"), &text_color, move |color| {
+            let Some(doc_rc) = doc_rc.as_ref() else { return };
+            let Some(sel_id) = sel_id else { return };
             let mut doc = doc_rc.borrow_mut();
             if slide_idx >= doc.slides.len() {
                 return;
@@ -426,9 +1077,9 @@ impl PropertiesPanel {
             if let Some(SlideElement::Text(text)) =
                 slide.elements.iter_mut().find(|e| e.id() == sel_id)
             {
-                for para in &mut text.paragraphs {
+                for para in text.paragraphs_at_mut(paragraph) {
                     for run in &mut para.runs {
-                        run.font.italic = is_italic;
+                        run.font.color = color.clone();
                     }
                 }
                 if let Some(cb) = on_changed.borrow().as_ref() {
@@ -437,21 +1088,529 @@ impl PropertiesPanel {
             }
         });
 
-        style_row.append(&bold_btn);
-        style_row.append(&italic_btn);
-        content.append(&style_row);
+        // Black or white text, whichever reads best on what is behind the box
+        let auto_color_btn = gtk::Button::with_label(&gettext("Auto Text Color"));
+        auto_color_btn.set_tooltip_text(Some(&gettext(
+            "Use black or white text, whichever contrasts more with the fill behind it",
+        )));
 
-        // Text color
-        self.build_color_row(content, &gettext("Color"), &text_color, move |color| {
-            // Color change callback - will be wired separately
-            color
+        let doc_rc = imp.document.borrow().clone();
+        let sel_id = *imp.selected_id.borrow();
+        let slide_idx = *imp.slide_index.borrow();
+        let on_changed = imp.on_property_changed.clone();
+
+        auto_color_btn.connect_clicked(move |_| {
+            let Some(doc_rc) = doc_rc.as_ref() else { return };
+            let Some(sel_id) = sel_id else { return };
+            let mut doc = doc_rc.borrow_mut();
+            if slide_idx >= doc.slides.len() {
+                return;
+            }
+            let slide = &mut doc.slides[slide_idx];
+            let own_fill = slide.elements.iter().find_map(|e| match e {
+                SlideElement::Text(text) if text.id == sel_id => Some(text.fill.clone()),
+                _ => None,
+            });
+            let Some(own_fill) = own_fill else { return };
+            let backdrop = match own_fill {
+                Some(fill) => Some(fill.average_color()),
+                None => slide.backdrop_color(sel_id),
+            };
+            let Some(backdrop) = backdrop else { return };
+            let text_color = backdrop.contrasting_text_color();
+            if let Some(SlideElement::Text(text)) =
+                slide.elements.iter_mut().find(|e| e.id() == sel_id)
+            {
+                for para in text.paragraphs_at_mut(paragraph) {
+                    for run in &mut para.runs {
+                        run.font.color = text_color.clone();
+                    }
+                }
+                if let Some(cb) = on_changed.borrow().as_ref() {
+                    cb();
+                }
+            }
         });
+        content.append(&auto_color_btn);
+
+        // Glyph outline and shadow, off by default
+        self.build_text_effect_row(
+            content,
+            &gettext("Outline"),
+            &gettext("Outline width"),
+            text.outline.as_ref().map(|o| (o.color.clone(), o.width)),
+            (Color::black(), 1.5),
+            |text, effect| {
+                text.outline = effect.map(|(color, width)| TextOutline { color, width });
+            },
+        );
+        self.build_text_effect_row(
+            content,
+            &gettext("Shadow"),
+            &gettext("Shadow offset"),
+            text.shadow.as_ref().map(|s| (s.color.clone(), s.offset_x)),
+            (Color::new(0.0, 0.0, 0.0, 0.5), 2.0),
+            |text, effect| {
+                text.shadow = effect.map(|(color, offset)| TextShadow {
+                    color,
+                    offset_x: offset,
+                    offset_y: offset,
+                });
+            },
+        );
     }
 
-    fn build_shape_properties(
+    /// Drop-down choosing whether the text formatting controls apply to all
+    /// paragraphs or to a single one. Choosing rebuilds the panel so the
+    /// controls show the values of the new target.
+    fn build_paragraph_row(
         &self,
         content: &gtk::Box,
-        shape: &crate::model::shape::ShapeElement,
+        text: &TextElement,
+        paragraph: Option<usize>,
+    ) {
+        let row = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+        let label = gtk::Label::new(Some(&gettext("Apply to")));
+        label.add_css_class("dim-label");
+        label.set_width_chars(5);
+        label.set_halign(gtk::Align::Start);
+
+        let mut names = vec![gettext("All Paragraphs")];
+        names.extend(text.paragraphs.iter().enumerate().map(|(i, para)| {
+            let full_text = para.full_text();
+            let mut preview: String = full_text.chars().take(PARAGRAPH_PREVIEW_CHARS).collect();
+            if preview.len() < full_text.len() {
+                preview.push('…');
+            }
+            format!("{}: {}", i + 1, preview)
+        }));
+        let name_refs: Vec<&str> = names.iter().map(String::as_str).collect();
+        let dropdown = gtk::DropDown::from_strings(&name_refs);
+        dropdown.set_hexpand(true);
+        dropdown.set_tooltip_text(Some(&gettext("Paragraphs the text formatting applies to")));
+        dropdown.set_selected(paragraph.map_or(0, |i| i + 1) as u32);
+
+        let panel = self.downgrade();
+        dropdown.connect_selected_notify(move |dropdown| {
+            let Some(panel) = panel.upgrade() else { return };
+            let paragraph = (dropdown.selected() as usize).checked_sub(1);
+            panel.imp().paragraph_index.set(paragraph);
+            // Rebuilding removes this drop-down, so wait until its signal is done
+            glib::idle_add_local_once(move || panel.rebuild_ui());
+        });
+
+        row.append(&label);
+        row.append(&dropdown);
+        content.append(&row);
+    }
+
+    /// A toggle for an on/off font property, applied to the runs of the
+    /// targeted paragraphs of the selected text element.
+    fn build_font_toggle(
+        &self,
+        text: &TextElement,
+        paragraph: Option<usize>,
+        icon_name: &str,
+        get: impl Fn(&FontStyle) -> bool,
+        set: impl Fn(&mut FontStyle, bool) + 'static,
+    ) -> gtk::ToggleButton {
+        let imp = self.imp();
+        let button = gtk::ToggleButton::new();
+        button.set_icon_name(icon_name);
+        set_toggle_state(&button, text.uniform_font_value(paragraph, get));
+
+        let doc_rc = imp.document.borrow().clone();
+        let sel_id = *imp.selected_id.borrow();
+        let slide_idx = *imp.slide_index.borrow();
+        let on_changed = imp.on_property_changed.clone();
+        let updating = imp.updating.clone();
+
+        button.connect_toggled(move |btn| {
+            if *updating.borrow() {
+                return;
+            }
+            let Some(doc_rc) = doc_rc.as_ref() else { return };
+            let Some(sel_id) = sel_id else { return };
+            btn.remove_css_class("mixed-state");
+            btn.set_tooltip_text(None);
+            let active = btn.is_active();
+            let mut doc = doc_rc.borrow_mut();
+            if slide_idx >= doc.slides.len() {
+                return;
+            }
+            let slide = &mut doc.slides[slide_idx];
+            if let Some(SlideElement::Text(text)) =
+                slide.elements.iter_mut().find(|e| e.id() == sel_id)
+            {
+                for para in text.paragraphs_at_mut(paragraph) {
+                    for run in &mut para.runs {
+                        set(&mut run.font, active);
+                    }
+                }
+                if let Some(cb) = on_changed.borrow().as_ref() {
+                    cb();
+                }
+            }
+        });
+
+        button
+    }
+
+    /// A toggle turning the targeted paragraphs of the selected text element
+    /// into list items of `list`, or back into plain paragraphs.
+    fn build_list_toggle(
+        &self,
+        text: &TextElement,
+        paragraph: Option<usize>,
+        icon_name: &str,
+        tooltip: &str,
+        list: ListStyle,
+    ) -> gtk::ToggleButton {
+        let imp = self.imp();
+        let button = gtk::ToggleButton::new();
+        button.set_icon_name(icon_name);
+        button.set_tooltip_text(Some(tooltip));
+        let targeted = text.paragraphs_at(paragraph);
+        button.set_active(!targeted.is_empty() && targeted.iter().all(|p| p.list == list));
+
+        let doc_rc = imp.document.borrow().clone();
+        let sel_id = *imp.selected_id.borrow();
+        let slide_idx = *imp.slide_index.borrow();
+        let on_changed = imp.on_property_changed.clone();
+        let updating = imp.updating.clone();
+
+        button.connect_toggled(move |btn| {
+            if *updating.borrow() {
+                return;
+            }
+            let Some(doc_rc) = doc_rc.as_ref() else { return };
+            let Some(sel_id) = sel_id else { return };
+            let active = btn.is_active();
+            let mut doc = doc_rc.borrow_mut();
+            if slide_idx >= doc.slides.len() {
+                return;
+            }
+            let slide = &mut doc.slides[slide_idx];
+            if let Some(SlideElement::Text(text)) =
+                slide.elements.iter_mut().find(|e| e.id() == sel_id)
+            {
+                for para in text.paragraphs_at_mut(paragraph) {
+                    if active {
+                        para.list = list;
+                    } else if para.list == list {
+                        // Items already switched to the other style stay
+                        para.list = ListStyle::None;
+                    }
+                }
+                if let Some(cb) = on_changed.borrow().as_ref() {
+                    cb();
+                }
+            }
+        });
+
+        button
+    }
+
+    /// A button moving the targeted list items of the selected text element
+    /// `delta` levels deeper, or shallower when negative.
+    fn build_list_level_button(
+        &self,
+        paragraph: Option<usize>,
+        icon_name: &str,
+        tooltip: &str,
+        delta: i32,
+    ) -> gtk::Button {
+        let imp = self.imp();
+        let button = gtk::Button::from_icon_name(icon_name);
+        button.set_tooltip_text(Some(tooltip));
+
+        let doc_rc = imp.document.borrow().clone();
+        let sel_id = *imp.selected_id.borrow();
+        let slide_idx = *imp.slide_index.borrow();
+        let on_changed = imp.on_property_changed.clone();
+
+        button.connect_clicked(move |_| {
+            let Some(doc_rc) = doc_rc.as_ref() else { return };
+            let Some(sel_id) = sel_id else { return };
+            let mut doc = doc_rc.borrow_mut();
+            if slide_idx >= doc.slides.len() {
+                return;
+            }
+            let slide = &mut doc.slides[slide_idx];
+            if let Some(SlideElement::Text(text)) =
+                slide.elements.iter_mut().find(|e| e.id() == sel_id)
+            {
+                for para in text.paragraphs_at_mut(paragraph) {
+                    if para.list != ListStyle::None {
+                        para.level = para
+                            .level
+                            .saturating_add_signed(delta)
+                            .min(MAX_LIST_LEVEL);
+                    }
+                }
+                if let Some(cb) = on_changed.borrow().as_ref() {
+                    cb();
+                }
+            }
+        });
+
+        button
+    }
+
+    /// A spin button for a spacing value of the targeted paragraphs of the
+    /// selected text element, showing the value of the first one. `range`
+    /// is the minimum, maximum and step.
+    #[allow(clippy::too_many_arguments)]
+    fn build_paragraph_spin_row(
+        &self,
+        content: &gtk::Box,
+        text: &TextElement,
+        paragraph: Option<usize>,
+        label_text: &str,
+        tooltip: &str,
+        range: (f64, f64, f64),
+        get: impl Fn(&TextParagraph) -> f64,
+        set: impl Fn(&mut TextParagraph, f64) + 'static,
+    ) {
+        let imp = self.imp();
+        let row = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+        let label = gtk::Label::new(Some(label_text));
+        label.add_css_class("dim-label");
+        label.set_width_chars(5);
+        label.set_halign(gtk::Align::Start);
+
+        let (min, max, step) = range;
+        let spin = gtk::SpinButton::with_range(min, max, step);
+        spin.set_digits(1);
+        spin.set_hexpand(true);
+        spin.set_tooltip_text(Some(tooltip));
+        if let Some(para) = text.paragraphs_at(paragraph).first() {
+            spin.set_value(get(para));
+        }
+
+        let doc_rc = imp.document.borrow().clone();
+        let sel_id = *imp.selected_id.borrow();
+        let slide_idx = *imp.slide_index.borrow();
+        let on_changed = imp.on_property_changed.clone();
+        let updating = imp.updating.clone();
+
+        spin.connect_value_changed(move |spin| {
+            if *updating.borrow() {
+                return;
+            }
+            let Some(doc_rc) = doc_rc.as_ref() else { return };
+            let Some(sel_id) = sel_id else { return };
+            let value = spin.value();
+            let mut doc = doc_rc.borrow_mut();
+            if slide_idx >= doc.slides.len() {
+                return;
+            }
+            let slide = &mut doc.slides[slide_idx];
+            if let Some(SlideElement::Text(text)) =
+                slide.elements.iter_mut().find(|e| e.id() == sel_id)
+            {
+                for para in text.paragraphs_at_mut(paragraph) {
+                    set(para, value);
+                }
+                if let Some(cb) = on_changed.borrow().as_ref() {
+                    cb();
+                }
+            }
+        });
+
+        row.append(&label);
+        row.append(&spin);
+        content.append(&row);
+    }
+
+    /// A check button switching a text effect on or off, with its color and
+    /// size. `apply` receives `None` when the effect is switched off.
+    fn build_text_effect_row<F: Fn(&mut TextElement, Option<(Color, f64)>) + 'static>(
+        &self,
+        content: &gtk::Box,
+        label_text: &str,
+        amount_tooltip: &str,
+        effect: Option<(Color, f64)>,
+        default: (Color, f64),
+        apply: F,
+    ) {
+        let imp = self.imp();
+        let row = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+
+        let enabled = effect.is_some();
+        let (color, amount) = effect.unwrap_or(default);
+
+        let check = gtk::CheckButton::with_label(label_text);
+        check.set_active(enabled);
+        check.set_hexpand(true);
+
+        let color_btn = color_dialog_button(&color);
+
+        let amount_spin = gtk::SpinButton::with_range(0.5, 20.0, 0.5);
+        amount_spin.set_digits(1);
+        amount_spin.set_value(amount);
+        amount_spin.set_tooltip_text(Some(amount_tooltip));
+
+        // Each control updates its part of the state and writes the whole
+        // effect back, so the widgets need no references to each other
+        let state = Rc::new(RefCell::new((enabled, color, amount)));
+
+        let doc_rc = imp.document.borrow().clone();
+        let sel_id = *imp.selected_id.borrow();
+        let slide_idx = *imp.slide_index.borrow();
+        let on_changed = imp.on_property_changed.clone();
+        let updating = imp.updating.clone();
+        let commit_state = state.clone();
+        let commit = Rc::new(move || {
+            if *updating.borrow() {
+                return;
+            }
+            let Some(doc_rc) = doc_rc.as_ref() else { return };
+            let Some(sel_id) = sel_id else { return };
+            let (enabled, color, amount) = commit_state.borrow().clone();
+            let mut doc = doc_rc.borrow_mut();
+            if slide_idx >= doc.slides.len() {
+                return;
+            }
+            let slide = &mut doc.slides[slide_idx];
+            if let Some(SlideElement::Text(text)) =
+                slide.elements.iter_mut().find(|e| e.id() == sel_id)
+            {
+                apply(text, enabled.then_some((color, amount)));
+                if let Some(cb) = on_changed.borrow().as_ref() {
+                    cb();
+                }
+            }
+        });
+
+        let check_state = state.clone();
+        let check_commit = commit.clone();
+        check.connect_toggled(move |check| {
+            check_state.borrow_mut().0 = check.is_active();
+            check_commit();
+        });
+
+        let color_state = state.clone();
+        let color_commit = commit.clone();
+        color_btn.connect_rgba_notify(move |btn| {
+            color_state.borrow_mut().1 = rgba_to_color(&btn.rgba());
+            if color_state.borrow().0 {
+                color_commit();
+            }
+        });
+
+        amount_spin.connect_value_changed(move |spin| {
+            state.borrow_mut().2 = spin.value();
+            if state.borrow().0 {
+                commit();
+            }
+        });
+
+        row.append(&check);
+        row.append(&color_btn);
+        row.append(&amount_spin);
+        content.append(&row);
+    }
+
+    /// A check button turning the shape fill into a gradient from the fill
+    /// color, with the end color and the angle.
+    fn build_gradient_row(&self, content: &gtk::Box, fill: &FillStyle) {
+        let imp = self.imp();
+        let row = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+
+        let enabled = fill.gradient.is_some();
+        let (end, angle) = match &fill.gradient {
+            Some(gradient) => (gradient.end.clone(), gradient.angle),
+            None => (fill.color.mix(&Color::white(), 0.5), 90.0),
+        };
+
+        let check = gtk::CheckButton::with_label(&gettext("Gradient"));
+        check.set_active(enabled);
+        check.set_hexpand(true);
+
+        let color_btn = color_dialog_button(&end);
+        color_btn.set_tooltip_text(Some(&gettext("Gradient end color")));
+
+        let angle_spin = gtk::SpinButton::with_range(0.0, 360.0, 15.0);
+        angle_spin.set_value(angle);
+        angle_spin.set_wrap(true);
+        angle_spin.set_tooltip_text(Some(&gettext("Gradient angle in degrees")));
+
+        // Same pattern as the text effects: each control updates its part of
+        // the state and writes the whole gradient back
+        let state = Rc::new(RefCell::new((enabled, end, angle)));
+
+        let doc_rc = imp.document.borrow().clone();
+        let sel_id = *imp.selected_id.borrow();
+        let slide_idx = *imp.slide_index.borrow();
+        let on_changed = imp.on_property_changed.clone();
+        let updating = imp.updating.clone();
+        let commit_state = state.clone();
+        let commit = Rc::new(move || {
+            if *updating.borrow() {
+                return;
+            }
+            let Some(doc_rc) = doc_rc.as_ref() else { return };
+            let Some(sel_id) = sel_id else { return };
+            let (enabled, end, angle) = commit_state.borrow().clone();
+            let mut doc = doc_rc.borrow_mut();
+            if slide_idx >= doc.slides.len() {
+                return;
+            }
+            let slide = &mut doc.slides[slide_idx];
+            if let Some(SlideElement::Shape(shape)) =
+                slide.elements.iter_mut().find(|e| e.id() == sel_id)
+            {
+                let Some(fill) = &mut shape.fill else { return };
+                fill.gradient = match (enabled, fill.gradient.take()) {
+                    (false, _) => None,
+                    // Keep the kind of an imported radial gradient
+                    (true, Some(gradient)) => Some(Gradient {
+                        end,
+                        angle,
+                        ..gradient
+                    }),
+                    (true, None) => Some(Gradient::linear(fill.color.clone(), end, angle)),
+                };
+                if let Some(cb) = on_changed.borrow().as_ref() {
+                    cb();
+                }
+            }
+        });
+
+        let check_state = state.clone();
+        let check_commit = commit.clone();
+        check.connect_toggled(move |check| {
+            check_state.borrow_mut().0 = check.is_active();
+            check_commit();
+        });
+
+        let color_state = state.clone();
+        let color_commit = commit.clone();
+        color_btn.connect_rgba_notify(move |btn| {
+            color_state.borrow_mut().1 = rgba_to_color(&btn.rgba());
+            if color_state.borrow().0 {
+                color_commit();
+            }
+        });
+
+        angle_spin.connect_value_changed(move |spin| {
+            state.borrow_mut().2 = spin.value();
+            if state.borrow().0 {
+                commit();
+            }
+        });
+
+        row.append(&check);
+        row.append(&color_btn);
+        row.append(&angle_spin);
+        content.append(&row);
+    }
+
+    fn build_shape_properties(
+        &self,
+        content: &gtk::Box,
+        shape: &crate::model::shape::ShapeElement,
     ) {
         let imp = self.imp();
 
@@ -460,6 +1619,64 @@ impl PropertiesPanel {
         section_label.set_halign(gtk::Align::Start);
         content.append(&section_label);
 
+        // Corner radius of rectangles; a rectangle with rounded corners is a
+        // rounded rectangle, and one without is a plain rectangle again
+        let radius = match shape.shape_type {
+            ShapeType::Rectangle => Some(0.0),
+            ShapeType::RoundedRectangle { radius } => Some(radius),
+            _ => None,
+        };
+        if let Some(radius) = radius {
+            let radius_row = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+            let radius_label = gtk::Label::new(Some(&gettext("Radius")));
+            radius_label.add_css_class("dim-label");
+            radius_label.set_width_chars(6);
+            radius_label.set_halign(gtk::Align::Start);
+
+            let max_radius = shape.bounds.size.width.min(shape.bounds.size.height) / 2.0;
+            let radius_spin = gtk::SpinButton::with_range(0.0, max_radius.max(1.0), 1.0);
+            radius_spin.set_value(radius);
+            radius_spin.set_digits(1);
+            radius_spin.set_hexpand(true);
+            radius_spin.set_tooltip_text(Some(&gettext("Corner radius")));
+
+            let doc_rc = imp.document.borrow().clone();
+            let sel_id = *imp.selected_id.borrow();
+            let slide_idx = *imp.slide_index.borrow();
+            let on_changed = imp.on_property_changed.clone();
+            let updating = imp.updating.clone();
+
+            radius_spin.connect_value_changed(move |spin| {
+                if *updating.borrow() {
+                    return;
+                }
+                let Some(doc_rc) = doc_rc.as_ref() else { return };
+                let Some(sel_id) = sel_id else { return };
+                let mut doc = doc_rc.borrow_mut();
+                if slide_idx >= doc.slides.len() {
+                    return;
+                }
+                let slide = &mut doc.slides[slide_idx];
+                if let Some(SlideElement::Shape(shape)) =
+                    slide.elements.iter_mut().find(|e| e.id() == sel_id)
+                {
+                    let radius = spin.value();
+                    shape.shape_type = if radius > 0.0 {
+                        ShapeType::RoundedRectangle { radius }
+                    } else {
+                        ShapeType::Rectangle
+                    };
+                    if let Some(cb) = on_changed.borrow().as_ref() {
+                        cb();
+                    }
+                }
+            });
+
+            radius_row.append(&radius_label);
+            radius_row.append(&radius_spin);
+            content.append(&radius_row);
+        }
+
         // Fill color
         if let Some(fill) = &shape.fill {
             let doc_rc = imp.document.borrow().clone();
@@ -478,7 +1695,11 @@ impl PropertiesPanel {
                 if let Some(SlideElement::Shape(shape)) =
                     slide.elements.iter_mut().find(|e| e.id() == sel_id)
                 {
+                    // The fill color doubles as the start of a gradient
                     if let Some(fill) = &mut shape.fill {
+                        if let Some(gradient) = &mut fill.gradient {
+                            gradient.start = color.clone();
+                        }
                         fill.color = color;
                     }
                     if let Some(cb) = on_changed.borrow().as_ref() {
@@ -486,6 +1707,7 @@ impl PropertiesPanel {
                     }
                 }
             });
+            self.build_gradient_row(content, fill);
         }
 
         // Stroke color & width
@@ -559,29 +1781,439 @@ impl PropertiesPanel {
             width_row.append(&width_label);
             width_row.append(&width_spin);
             content.append(&width_row);
+
+            // Caps only show on open lines, joins only on corners
+            if shape.shape_type == ShapeType::Line {
+                const CAPS: [LineCap; 3] = [LineCap::Butt, LineCap::Round, LineCap::Square];
+                self.build_stroke_choice_row(
+                    content,
+                    &gettext("Ends"),
+                    &[gettext("Flat"), gettext("Round"), gettext("Square")],
+                    CAPS.iter().position(|c| *c == stroke.line_cap).unwrap_or(0),
+                    |stroke, i| stroke.line_cap = CAPS[i],
+                );
+
+                // Arrowheads and dots on the end points
+                const MARKERS: [LineMarker; 3] =
+                    [LineMarker::None, LineMarker::Arrow, LineMarker::Circle];
+                let marker_names = [gettext("None"), gettext("Arrow"), gettext("Circle")];
+                let position = |marker| MARKERS.iter().position(|m| *m == marker).unwrap_or(0);
+                self.build_shape_choice_row(
+                    content,
+                    &gettext("Start"),
+                    &marker_names,
+                    position(shape.start_marker),
+                    |shape, i| shape.start_marker = MARKERS[i],
+                );
+                self.build_shape_choice_row(
+                    content,
+                    &gettext("End"),
+                    &marker_names,
+                    position(shape.end_marker),
+                    |shape, i| shape.end_marker = MARKERS[i],
+                );
+            } else if shape.shape_type != ShapeType::Ellipse {
+                const JOINS: [LineJoin; 3] = [LineJoin::Miter, LineJoin::Round, LineJoin::Bevel];
+                self.build_stroke_choice_row(
+                    content,
+                    &gettext("Corners"),
+                    &[gettext("Sharp"), gettext("Round"), gettext("Beveled")],
+                    JOINS.iter().position(|j| *j == stroke.line_join).unwrap_or(0),
+                    |stroke, i| stroke.line_join = JOINS[i],
+                );
+            }
         }
+
+        self.build_opacity_slider(content, shape.opacity, 6);
+    }
+
+    /// Slider row for the opacity of the selected shape or image, with a
+    /// label `label_chars` wide to line up with the rows of its section.
+    fn build_opacity_slider(&self, content: &gtk::Box, value: f64, label_chars: i32) {
+        let imp = self.imp();
+
+        let row = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+        let label = gtk::Label::new(Some(&gettext("Opacity")));
+        label.add_css_class("dim-label");
+        label.set_width_chars(label_chars);
+        label.set_xalign(0.0);
+
+        let scale = gtk::Scale::with_range(gtk::Orientation::Horizontal, 0.0, 1.0, 0.05);
+        scale.set_value(value);
+        scale.set_hexpand(true);
+
+        let doc_rc = imp.document.borrow().clone();
+        let sel_id = *imp.selected_id.borrow();
+        let slide_idx = *imp.slide_index.borrow();
+        let on_changed = imp.on_property_changed.clone();
+        let updating = imp.updating.clone();
+
+        scale.connect_value_changed(move |scale| {
+            if *updating.borrow() {
+                return;
+            }
+            let Some(doc_rc) = doc_rc.as_ref() else { return };
+            let Some(sel_id) = sel_id else { return };
+            let mut doc = doc_rc.borrow_mut();
+            if slide_idx >= doc.slides.len() {
+                return;
+            }
+            let slide = &mut doc.slides[slide_idx];
+            let opacity = match slide.elements.iter_mut().find(|e| e.id() == sel_id) {
+                Some(SlideElement::Shape(shape)) => &mut shape.opacity,
+                Some(SlideElement::Image(image)) => &mut image.opacity,
+                _ => return,
+            };
+            *opacity = scale.value();
+            if let Some(cb) = on_changed.borrow().as_ref() {
+                cb();
+            }
+        });
+
+        row.append(&label);
+        row.append(&scale);
+        content.append(&row);
     }
 
-    fn build_color_row<F: Fn(Color) -> Color + 'static>(
+    /// Dropdown row choosing one of `names` for the stroke; `apply` gets the
+    /// chosen index.
+    fn build_stroke_choice_row<F: Fn(&mut StrokeStyle, usize) + 'static>(
         &self,
         content: &gtk::Box,
         label_text: &str,
-        color: &Color,
-        _transform: F,
+        names: &[String],
+        selected: usize,
+        apply: F,
+    ) {
+        self.build_shape_choice_row(content, label_text, names, selected, move |shape, i| {
+            if let Some(stroke) = &mut shape.stroke {
+                apply(stroke, i);
+            }
+        });
+    }
+
+    /// Dropdown row choosing one of `names`; `apply` gets the selected shape
+    /// and the chosen index.
+    fn build_shape_choice_row<F: Fn(&mut ShapeElement, usize) + 'static>(
+        &self,
+        content: &gtk::Box,
+        label_text: &str,
+        names: &[String],
+        selected: usize,
+        apply: F,
     ) {
+        let imp = self.imp();
+
         let row = gtk::Box::new(gtk::Orientation::Horizontal, 6);
         let label = gtk::Label::new(Some(label_text));
         label.add_css_class("dim-label");
-        label.set_width_chars(5);
+        label.set_width_chars(6);
         label.set_halign(gtk::Align::Start);
 
-        let rgba = gdk::RGBA::new(color.r as f32, color.g as f32, color.b as f32, color.a as f32);
-        let color_dialog = gtk::ColorDialog::new();
-        let color_btn = gtk::ColorDialogButton::new(Some(color_dialog));
-        color_btn.set_rgba(&rgba);
+        let name_refs: Vec<&str> = names.iter().map(String::as_str).collect();
+        let dropdown = gtk::DropDown::from_strings(&name_refs);
+        dropdown.set_selected(selected as u32);
+        dropdown.set_hexpand(true);
+
+        let doc_rc = imp.document.borrow().clone();
+        let sel_id = *imp.selected_id.borrow();
+        let slide_idx = *imp.slide_index.borrow();
+        let on_changed = imp.on_property_changed.clone();
+        let updating = imp.updating.clone();
+
+        dropdown.connect_selected_notify(move |dropdown| {
+            if *updating.borrow() {
+                return;
+            }
+            let Some(doc_rc) = doc_rc.as_ref() else { return };
+            let Some(sel_id) = sel_id else { return };
+            let mut doc = doc_rc.borrow_mut();
+            if slide_idx >= doc.slides.len() {
+                return;
+            }
+            let slide = &mut doc.slides[slide_idx];
+            if let Some(SlideElement::Shape(shape)) =
+                slide.elements.iter_mut().find(|e| e.id() == sel_id)
+            {
+                apply(shape, dropdown.selected() as usize);
+                if let Some(cb) = on_changed.borrow().as_ref() {
+                    cb();
+                }
+            }
+        });
 
         row.append(&label);
-        row.append(&color_btn);
+        row.append(&dropdown);
+        content.append(&row);
+    }
+
+    fn build_image_properties(
+        &self,
+        content: &gtk::Box,
+        image: &crate::model::image::ImageElement,
+    ) {
+        let imp = self.imp();
+
+        let section_label = gtk::Label::new(Some(&gettext("Image")));
+        section_label.add_css_class("heading");
+        section_label.set_halign(gtk::Align::Start);
+        content.append(&section_label);
+
+        // How the picture fills its bounds
+        let scale_row = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+        let scale_label = gtk::Label::new(Some(&gettext("Scale")));
+        scale_label.add_css_class("dim-label");
+        scale_label.set_width_chars(9);
+        scale_label.set_xalign(0.0);
+
+        const SCALE_MODES: [ScaleMode; 3] = [ScaleMode::Fit, ScaleMode::Fill, ScaleMode::Stretch];
+        let scale_names = [gettext("Fit"), gettext("Crop to Fill"), gettext("Stretch")];
+        let scale_refs: Vec<&str> = scale_names.iter().map(String::as_str).collect();
+        let scale_dropdown = gtk::DropDown::from_strings(&scale_refs);
+        scale_dropdown.set_hexpand(true);
+        let selected = SCALE_MODES
+            .iter()
+            .position(|mode| *mode == image.scale_mode)
+            .unwrap_or(0);
+        scale_dropdown.set_selected(selected as u32);
+
+        let doc_rc = imp.document.borrow().clone();
+        let sel_id = *imp.selected_id.borrow();
+        let slide_idx = *imp.slide_index.borrow();
+        let on_changed = imp.on_property_changed.clone();
+        let updating = imp.updating.clone();
+
+        scale_dropdown.connect_selected_notify(move |dropdown| {
+            if *updating.borrow() {
+                return;
+            }
+            let Some(doc_rc) = doc_rc.as_ref() else { return };
+            let Some(sel_id) = sel_id else { return };
+            let Some(mode) = SCALE_MODES.get(dropdown.selected() as usize) else {
+                return;
+            };
+            let mut doc = doc_rc.borrow_mut();
+            if slide_idx >= doc.slides.len() {
+                return;
+            }
+            let slide = &mut doc.slides[slide_idx];
+            if let Some(SlideElement::Image(image)) =
+                slide.elements.iter_mut().find(|e| e.id() == sel_id)
+            {
+                image.scale_mode = *mode;
+                if let Some(cb) = on_changed.borrow().as_ref() {
+                    cb();
+                }
+            }
+        });
+
+        scale_row.append(&scale_label);
+        scale_row.append(&scale_dropdown);
+        content.append(&scale_row);
+
+        let replace_btn = gtk::Button::with_label(&gettext("Replace Image…"));
+        let panel = self.downgrade();
+        replace_btn.connect_clicked(move |_| {
+            if let Some(panel) = panel.upgrade() {
+                panel.choose_replacement_image();
+            }
+        });
+        content.append(&replace_btn);
+
+        // The handles crop the picture on the canvas until Enter is pressed
+        let crop_btn = gtk::Button::with_label(&gettext("Crop"));
+        crop_btn.set_action_name(Some("win.crop-image"));
+        content.append(&crop_btn);
+
+        // Description for screen readers
+        let alt_row = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+        let alt_label = gtk::Label::new(Some(&gettext("Alt Text")));
+        alt_label.add_css_class("dim-label");
+        alt_label.set_width_chars(9);
+        alt_label.set_xalign(0.0);
+
+        let alt_entry = gtk::Entry::new();
+        alt_entry.set_text(&image.alt_text);
+        alt_entry.set_placeholder_text(Some(&gettext("Describe the image")));
+        alt_entry.set_hexpand(true);
+
+        let doc_rc = imp.document.borrow().clone();
+        let sel_id = *imp.selected_id.borrow();
+        let slide_idx = *imp.slide_index.borrow();
+        let on_changed = imp.on_property_changed.clone();
+        let updating = imp.updating.clone();
+
+        alt_entry.connect_changed(move |entry| {
+            if *updating.borrow() {
+                return;
+            }
+            let Some(doc_rc) = doc_rc.as_ref() else { return };
+            let Some(sel_id) = sel_id else { return };
+            let mut doc = doc_rc.borrow_mut();
+            if slide_idx >= doc.slides.len() {
+                return;
+            }
+            let slide = &mut doc.slides[slide_idx];
+            if let Some(SlideElement::Image(image)) =
+                slide.elements.iter_mut().find(|e| e.id() == sel_id)
+            {
+                image.alt_text = entry.text().to_string();
+                if let Some(cb) = on_changed.borrow().as_ref() {
+                    cb();
+                }
+            }
+        });
+
+        alt_row.append(&alt_label);
+        alt_row.append(&alt_entry);
+        content.append(&alt_row);
+
+        let filters = image.filters;
+
+        // Grayscale
+        let gray_check = gtk::CheckButton::with_label(&gettext("Grayscale"));
+        gray_check.set_active(filters.grayscale);
+
+        let doc_rc = imp.document.borrow().clone();
+        let sel_id = *imp.selected_id.borrow();
+        let slide_idx = *imp.slide_index.borrow();
+        let on_changed = imp.on_property_changed.clone();
+        let updating = imp.updating.clone();
+
+        gray_check.connect_toggled(move |check| {
+            if *updating.borrow() {
+                return;
+            }
+            let Some(doc_rc) = doc_rc.as_ref() else { return };
+            let Some(sel_id) = sel_id else { return };
+            let mut doc = doc_rc.borrow_mut();
+            if slide_idx >= doc.slides.len() {
+                return;
+            }
+            let slide = &mut doc.slides[slide_idx];
+            if let Some(SlideElement::Image(image)) =
+                slide.elements.iter_mut().find(|e| e.id() == sel_id)
+            {
+                image.filters.grayscale = check.is_active();
+                if let Some(cb) = on_changed.borrow().as_ref() {
+                    cb();
+                }
+            }
+        });
+        content.append(&gray_check);
+
+        self.build_filter_slider(
+            content,
+            &gettext("Brightness"),
+            (-1.0, 1.0),
+            filters.brightness,
+            |filters, val| filters.brightness = val,
+        );
+        self.build_filter_slider(
+            content,
+            &gettext("Contrast"),
+            (0.0, 2.0),
+            filters.contrast,
+            |filters, val| filters.contrast = val,
+        );
+        self.build_filter_slider(
+            content,
+            &gettext("Saturation"),
+            (0.0, 2.0),
+            filters.saturation,
+            |filters, val| filters.saturation = val,
+        );
+        self.build_opacity_slider(content, image.opacity, 9);
+    }
+
+    /// Swap the picture of the selected image for one picked from a file,
+    /// keeping its bounds, filters and description. The crop of the old
+    /// picture is dropped.
+    fn choose_replacement_image(&self) {
+        let dialog = gtk::FileDialog::builder()
+            .title(gettext("Replace Image"))
+            .filters(&image_file_filters())
+            .build();
+        let window = self.root().and_downcast::<gtk::Window>();
+
+        let panel = self.downgrade();
+        dialog.open(window.as_ref(), gio::Cancellable::NONE, move |result| {
+            let Some(panel) = panel.upgrade() else { return };
+            let Some(path) = result.ok().and_then(|file| file.path()) else { return };
+            let Ok(data) = std::fs::read(&path) else { return };
+            let mime = image_mime_type(&path).to_string();
+
+            let imp = panel.imp();
+            let Some(doc_rc) = imp.document.borrow().clone() else { return };
+            let Some(sel_id) = *imp.selected_id.borrow() else { return };
+            {
+                let mut doc = doc_rc.borrow_mut();
+                let Some(slide) = doc.slides.get_mut(*imp.slide_index.borrow()) else { return };
+                let Some(SlideElement::Image(image)) =
+                    slide.elements.iter_mut().find(|e| e.id() == sel_id)
+                else {
+                    return;
+                };
+                image.image_data = ImageData::Embedded { data: data.into(), mime };
+                image.crop = ImageCrop::default();
+                image.name_metafile(&path.to_string_lossy());
+            }
+            if let Some(cb) = imp.on_property_changed.borrow().as_ref() {
+                cb();
+            }
+            panel.rebuild_ui();
+        });
+    }
+
+    fn build_filter_slider<F: Fn(&mut ImageFilters, f64) + 'static>(
+        &self,
+        content: &gtk::Box,
+        label_text: &str,
+        range: (f64, f64),
+        value: f64,
+        apply: F,
+    ) {
+        let imp = self.imp();
+
+        let row = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+        let label = gtk::Label::new(Some(label_text));
+        label.add_css_class("dim-label");
+        label.set_width_chars(9);
+        label.set_xalign(0.0);
+
+        let scale = gtk::Scale::with_range(gtk::Orientation::Horizontal, range.0, range.1, 0.05);
+        scale.set_value(value);
+        scale.set_hexpand(true);
+
+        let doc_rc = imp.document.borrow().clone();
+        let sel_id = *imp.selected_id.borrow();
+        let slide_idx = *imp.slide_index.borrow();
+        let on_changed = imp.on_property_changed.clone();
+        let updating = imp.updating.clone();
+
+        scale.connect_value_changed(move |scale| {
+            if *updating.borrow() {
+                return;
+            }
+            let Some(doc_rc) = doc_rc.as_ref() else { return };
+            let Some(sel_id) = sel_id else { return };
+            let mut doc = doc_rc.borrow_mut();
+            if slide_idx >= doc.slides.len() {
+                return;
+            }
+            let slide = &mut doc.slides[slide_idx];
+            if let Some(SlideElement::Image(image)) =
+                slide.elements.iter_mut().find(|e| e.id() == sel_id)
+            {
+                apply(&mut image.filters, scale.value());
+                if let Some(cb) = on_changed.borrow().as_ref() {
+                    cb();
+                }
+            }
+        });
+
+        row.append(&label);
+        row.append(&scale);
         content.append(&row);
     }
 
@@ -598,30 +2230,101 @@ impl PropertiesPanel {
         label.set_width_chars(6);
         label.set_halign(gtk::Align::Start);
 
-        let rgba = gdk::RGBA::new(color.r as f32, color.g as f32, color.b as f32, color.a as f32);
-        let color_dialog = gtk::ColorDialog::new();
-        let color_btn = gtk::ColorDialogButton::new(Some(color_dialog));
-        color_btn.set_rgba(&rgba);
+        let color_btn = color_dialog_button(color);
         color_btn.set_hexpand(true);
+        let hex_entry = hex_entry_for(&color_btn);
 
         let on_color_set = Rc::new(on_color_set);
         color_btn.connect_rgba_notify(move |btn| {
-            let rgba = btn.rgba();
-            let color = Color::new(
-                rgba.red() as f64,
-                rgba.green() as f64,
-                rgba.blue() as f64,
-                rgba.alpha() as f64,
-            );
-            on_color_set(color);
+            on_color_set(rgba_to_color(&btn.rgba()));
         });
 
         row.append(&label);
         row.append(&color_btn);
+        row.append(&hex_entry);
         content.append(&row);
     }
 }
 
-fn default_font_info() -> (String, f64, bool, bool, Color) {
-    ("Sans".to_string(), 24.0, false, false, Color::black())
+/// Color button showing `color`, whose dialog lets the alpha be picked too.
+fn color_dialog_button(color: &Color) -> gtk::ColorDialogButton {
+    let dialog = gtk::ColorDialog::new();
+    dialog.set_with_alpha(true);
+    let button = gtk::ColorDialogButton::new(Some(dialog));
+    button.set_rgba(&color_to_rgba(color));
+    button
+}
+
+fn color_to_rgba(color: &Color) -> gdk::RGBA {
+    gdk::RGBA::new(color.r as f32, color.g as f32, color.b as f32, color.a as f32)
+}
+
+fn rgba_to_color(rgba: &gdk::RGBA) -> Color {
+    Color::new(
+        rgba.red() as f64,
+        rgba.green() as f64,
+        rgba.blue() as f64,
+        rgba.alpha() as f64,
+    )
+}
+
+/// Entry with the hex code of the color of `button`, following it. A code
+/// typed in sets the color of the button when the entry is activated or
+/// left; one that does not parse is put back.
+fn hex_entry_for(button: &gtk::ColorDialogButton) -> gtk::Entry {
+    let entry = gtk::Entry::new();
+    entry.set_width_chars(9);
+    entry.set_max_width_chars(9);
+    entry.set_tooltip_text(Some(&gettext("Hex color code, #rrggbb or #rrggbbaa with alpha")));
+    entry.set_text(&rgba_to_color(&button.rgba()).to_hex());
+
+    button.connect_rgba_notify({
+        let entry = entry.downgrade();
+        move |button| {
+            if let Some(entry) = entry.upgrade() {
+                entry.set_text(&rgba_to_color(&button.rgba()).to_hex());
+            }
+        }
+    });
+
+    let apply = {
+        let button = button.downgrade();
+        move |entry: &gtk::Entry| {
+            let Some(button) = button.upgrade() else { return };
+            let current = rgba_to_color(&button.rgba());
+            match Color::from_hex(entry.text().trim()) {
+                Some(color) if color.to_hex() != current.to_hex() => {
+                    button.set_rgba(&color_to_rgba(&color));
+                }
+                _ => entry.set_text(&current.to_hex()),
+            }
+        }
+    };
+    entry.connect_activate(apply.clone());
+    let focus = gtk::EventControllerFocus::new();
+    focus.connect_leave({
+        let entry = entry.downgrade();
+        move |_| {
+            if let Some(entry) = entry.upgrade() {
+                apply(&entry);
+            }
+        }
+    });
+    entry.add_controller(focus);
+
+    entry
+}
+
+fn default_font_info() -> (String, f64, Color) {
+    ("Sans".to_string(), 24.0, Color::black())
+}
+
+/// Show a toggle as on, off, or mixed when the runs disagree. A mixed toggle
+/// is off, so the first click applies the style to all runs.
+fn set_toggle_state(button: &gtk::ToggleButton, state: Option<bool>) {
+    button.set_active(state == Some(true));
+    if state.is_none() {
+        button.add_css_class("mixed-state");
+        button.set_tooltip_text(Some(&gettext("Mixed")));
+    }
 }
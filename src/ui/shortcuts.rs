@@ -0,0 +1,165 @@
+use gettextrs::gettext;
+
+/// Accelerators of the application and window actions, set up by the
+/// application and listed in the shortcuts window
+pub const ACTION_ACCELS: &[(&str, &[&str])] = &[
+    ("win.new-presentation", &["<Control>n"]),
+    ("app.new-window", &["<Control><Shift>n"]),
+    ("win.open", &["<Control>o"]),
+    ("win.save", &["<Control>s"]),
+    ("win.save-as", &["<Control><Shift>s"]),
+    ("win.undo", &["<Control>z"]),
+    ("win.redo", &["<Control><Shift>z", "<Control>y"]),
+    ("win.export-pdf", &["<Control><Shift>e"]),
+    ("win.copy-slide-image", &["<Control><Shift>c"]),
+    ("win.toggle-visibility", &["<Control><Shift>h"]),
+    ("win.delete-slide", &["<Control>Delete"]),
+    ("win.start-slideshow", &["F5"]),
+    ("win.zoom::fit", &["<Control>0"]),
+    ("win.zoom::100", &["<Control>1"]),
+    ("win.zoom-in", &["<Control>plus", "<Control>equal"]),
+    ("win.zoom-out", &["<Control>minus"]),
+    ("win.toggle-grid", &["<Control>apostrophe"]),
+    ("win.show-help-overlay", &["<Control>question"]),
+    ("app.preferences", &["<Control>comma"]),
+    ("app.quit", &["<Control>q"]),
+];
+
+/// Keys the canvas turns into window actions while it has the focus and
+/// Control is held, so text fields keep their own copy and paste
+pub const CANVAS_CONTROL_KEYS: &[(gdk::Key, &str)] = &[
+    (gdk::Key::c, "win.copy"),
+    (gdk::Key::x, "win.cut"),
+    (gdk::Key::v, "win.paste"),
+    (gdk::Key::Delete, "win.delete-slide"),
+];
+
+/// Where the keys of a shortcut come from
+enum Keys {
+    /// The accelerators of an action in `ACTION_ACCELS`
+    Action(&'static str),
+    /// The Control key of an action in `CANVAS_CONTROL_KEYS`
+    Control(&'static str),
+    /// Keys a widget such as the canvas or the slideshow handles itself,
+    /// as accelerators
+    Widget(&'static str),
+}
+
+impl Keys {
+    /// The keys as a space-separated list of accelerators.
+    fn accelerator(&self) -> String {
+        match self {
+            Keys::Action(action) => ACTION_ACCELS
+                .iter()
+                .find(|(name, _)| name == action)
+                .map(|(_, accels)| accels.join(" "))
+                .unwrap_or_default(),
+            Keys::Control(action) => CANVAS_CONTROL_KEYS
+                .iter()
+                .find(|(_, name)| name == action)
+                .map(|(key, _)| {
+                    gtk::accelerator_name(*key, gdk::ModifierType::CONTROL_MASK).to_string()
+                })
+                .unwrap_or_default(),
+            Keys::Widget(keys) => keys.to_string(),
+        }
+    }
+}
+
+/// Window listing the keyboard shortcuts, shown by the
+/// `win.show-help-overlay` action of the window it is set on.
+pub fn shortcuts_window() -> gtk::ShortcutsWindow {
+    let groups = [
+        (
+            gettext("Presentation"),
+            vec![
+                (gettext("New presentation"), Keys::Action("win.new-presentation")),
+                (gettext("New window"), Keys::Action("app.new-window")),
+                (gettext("Open"), Keys::Action("win.open")),
+                (gettext("Save"), Keys::Action("win.save")),
+                (gettext("Save as"), Keys::Action("win.save-as")),
+                (gettext("Export as PDF"), Keys::Action("win.export-pdf")),
+                (gettext("Copy slide as image"), Keys::Action("win.copy-slide-image")),
+                (gettext("Delete slide"), Keys::Action("win.delete-slide")),
+                (gettext("Start slideshow"), Keys::Action("win.start-slideshow")),
+            ],
+        ),
+        (
+            gettext("Editing"),
+            vec![
+                (gettext("Undo"), Keys::Action("win.undo")),
+                (gettext("Redo"), Keys::Action("win.redo")),
+                (gettext("Copy"), Keys::Control("win.copy")),
+                (gettext("Cut"), Keys::Control("win.cut")),
+                (gettext("Paste"), Keys::Control("win.paste")),
+                (gettext("Delete element"), Keys::Widget("Delete BackSpace")),
+                (gettext("Move element"), Keys::Widget("Left Right Up Down")),
+                (
+                    gettext("Move element further"),
+                    Keys::Widget("<Shift>Left <Shift>Right <Shift>Up <Shift>Down"),
+                ),
+                (gettext("Hide or show selection"), Keys::Action("win.toggle-visibility")),
+                (gettext("Deselect / Reset tool"), Keys::Widget("Escape")),
+            ],
+        ),
+        (
+            gettext("View"),
+            vec![
+                (gettext("Zoom to fit"), Keys::Action("win.zoom::fit")),
+                (gettext("Actual size"), Keys::Action("win.zoom::100")),
+                (gettext("Zoom in"), Keys::Action("win.zoom-in")),
+                (gettext("Zoom out"), Keys::Action("win.zoom-out")),
+                (gettext("Show grid"), Keys::Action("win.toggle-grid")),
+            ],
+        ),
+        (
+            gettext("Slideshow"),
+            vec![
+                (gettext("Next slide"), Keys::Widget("Right space Page_Down")),
+                (gettext("Previous slide"), Keys::Widget("Left BackSpace Page_Up")),
+                (gettext("First and last slide"), Keys::Widget("Home End")),
+                (gettext("End slideshow"), Keys::Widget("Escape")),
+            ],
+        ),
+        (
+            gettext("General"),
+            vec![
+                (gettext("Keyboard shortcuts"), Keys::Action("win.show-help-overlay")),
+                (gettext("Preferences"), Keys::Action("app.preferences")),
+                (gettext("Quit"), Keys::Action("app.quit")),
+            ],
+        ),
+    ];
+
+    // Sections and groups can only be added through a builder with GTK 4.12
+    let mut ui = String::from(
+        "<interface><object class=\"GtkShortcutsWindow\" id=\"window\">\
+         <property name=\"modal\">true</property>\
+         <child><object class=\"GtkShortcutsSection\">\
+         <property name=\"section-name\">shortcuts</property>\
+         <property name=\"max-height\">12</property>",
+    );
+    for (title, shortcuts) in &groups {
+        ui.push_str(&format!(
+            "<child><object class=\"GtkShortcutsGroup\">\
+             <property name=\"title\">{}</property>",
+            glib::markup_escape_text(title)
+        ));
+        for (title, keys) in shortcuts {
+            ui.push_str(&format!(
+                "<child><object class=\"GtkShortcutsShortcut\">\
+                 <property name=\"title\">{}</property>\
+                 <property name=\"accelerator\">{}</property>\
+                 </object></child>",
+                glib::markup_escape_text(title),
+                glib::markup_escape_text(&keys.accelerator())
+            ));
+        }
+        ui.push_str("</object></child>");
+    }
+    ui.push_str("</object></child></object></interface>");
+
+    gtk::Builder::from_string(&ui)
+        .object("window")
+        .expect("shortcuts window")
+}
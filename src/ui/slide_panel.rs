@@ -1,3 +1,4 @@
+use gettextrs::gettext;
 use gtk::prelude::*;
 use gtk::subclass::prelude::*;
 use std::cell::{Cell, RefCell};
@@ -6,6 +7,8 @@ use std::rc::Rc;
 use crate::model::document::Document;
 use crate::render::engine;
 
+type SlideCallback = RefCell<Option<Box<dyn Fn(usize)>>>;
+
 mod imp {
     use super::*;
 
@@ -14,8 +17,13 @@ mod imp {
         pub list_box: gtk::Box,
         pub document: RefCell<Option<Rc<RefCell<Document>>>>,
         pub selected_index: Cell<usize>,
-        pub on_slide_selected: RefCell<Option<Box<dyn Fn(usize)>>>,
+        pub on_slide_selected: SlideCallback,
+        pub on_slides_changed: SlideCallback,
         pub thumbnails: RefCell<Vec<gtk::DrawingArea>>,
+        pub context_menu: gtk::PopoverMenu,
+        pub delete_action: gio::SimpleAction,
+        /// Slide the context menu was opened on
+        pub context_index: Cell<usize>,
     }
 
     impl std::fmt::Debug for SlidePanel {
@@ -38,13 +46,24 @@ mod imp {
                 .child(&list_box)
                 .build();
 
+            let menu = gio::Menu::new();
+            menu.append(Some(&gettext("Duplicate Slide")), Some("slide-panel.duplicate"));
+            menu.append(Some(&gettext("Delete Slide")), Some("slide-panel.delete"));
+            let context_menu = gtk::PopoverMenu::from_model(Some(&menu));
+            context_menu.set_has_arrow(false);
+            context_menu.set_halign(gtk::Align::Start);
+
             Self {
                 scrolled_window,
                 list_box,
                 document: RefCell::new(None),
                 selected_index: Cell::new(0),
                 on_slide_selected: RefCell::new(None),
+                on_slides_changed: RefCell::new(None),
                 thumbnails: RefCell::new(Vec::new()),
+                context_menu,
+                delete_action: gio::SimpleAction::new("delete", None),
+                context_index: Cell::new(0),
             }
         }
     }
@@ -65,10 +84,13 @@ mod imp {
             self.parent_constructed();
             let obj = self.obj();
             self.scrolled_window.set_parent(&*obj);
+            self.context_menu.set_parent(&*obj);
+            obj.setup_actions();
         }
 
         fn dispose(&self) {
             self.scrolled_window.unparent();
+            self.context_menu.unparent();
         }
     }
 
@@ -94,6 +116,105 @@ impl SlidePanel {
         *self.imp().on_slide_selected.borrow_mut() = Some(Box::new(callback));
     }
 
+    /// Called after slides were reordered, duplicated or deleted, with the
+    /// slide to show.
+    pub fn connect_slides_changed<F: Fn(usize) + 'static>(&self, callback: F) {
+        *self.imp().on_slides_changed.borrow_mut() = Some(Box::new(callback));
+    }
+
+    fn setup_actions(&self) {
+        let group = gio::SimpleActionGroup::new();
+
+        let duplicate = gio::SimpleAction::new("duplicate", None);
+        let panel = self.downgrade();
+        duplicate.connect_activate(move |_, _| {
+            if let Some(panel) = panel.upgrade() {
+                panel.duplicate_slide(panel.imp().context_index.get());
+            }
+        });
+        group.add_action(&duplicate);
+
+        let delete = &self.imp().delete_action;
+        let panel = self.downgrade();
+        delete.connect_activate(move |_, _| {
+            if let Some(panel) = panel.upgrade() {
+                panel.delete_slide(panel.imp().context_index.get());
+            }
+        });
+        group.add_action(delete);
+
+        self.insert_action_group("slide-panel", Some(&group));
+    }
+
+    /// Open the context menu of slide `index` at `(x, y)` on its thumbnail.
+    fn show_context_menu(&self, index: usize, thumbnail: &gtk::DrawingArea, x: f64, y: f64) {
+        let imp = self.imp();
+        let point = gtk::graphene::Point::new(x as f32, y as f32);
+        let Some(point) = thumbnail.compute_point(self, &point) else {
+            return;
+        };
+        imp.context_index.set(index);
+
+        // The last slide cannot be deleted
+        let slide_count = imp
+            .document
+            .borrow()
+            .as_ref()
+            .map_or(0, |doc| doc.borrow().slides.len());
+        imp.delete_action.set_enabled(slide_count > 1);
+
+        let rect = gdk::Rectangle::new(point.x() as i32, point.y() as i32, 1, 1);
+        imp.context_menu.set_pointing_to(Some(&rect));
+        imp.context_menu.popup();
+    }
+
+    fn duplicate_slide(&self, index: usize) {
+        let Some(doc) = self.imp().document.borrow().clone() else {
+            return;
+        };
+        let Some(copy) = doc.borrow_mut().duplicate_slide(index) else {
+            return;
+        };
+        self.slides_changed(copy);
+    }
+
+    /// Delete slide `index` and show the one that took its place. The last
+    /// slide is kept.
+    pub fn delete_slide(&self, index: usize) {
+        let Some(doc) = self.imp().document.borrow().clone() else {
+            return;
+        };
+        let remaining = {
+            let mut doc = doc.borrow_mut();
+            // The model refuses to remove the last slide
+            if doc.remove_slide(index).is_none() {
+                return;
+            }
+            doc.slides.len()
+        };
+        // Show the slide that took its place, or the new last one
+        self.slides_changed(index.min(remaining - 1));
+    }
+
+    fn move_slide(&self, from: usize, to: usize) {
+        let Some(doc) = self.imp().document.borrow().clone() else {
+            return;
+        };
+        if from == to {
+            return;
+        }
+        doc.borrow_mut().move_slide(from, to);
+        self.slides_changed(to);
+    }
+
+    fn slides_changed(&self, index: usize) {
+        self.rebuild_thumbnails();
+        self.set_selected_index(index);
+        if let Some(cb) = self.imp().on_slides_changed.borrow().as_ref() {
+            cb(index);
+        }
+    }
+
     pub fn set_selected_index(&self, index: usize) {
         let prev = self.imp().selected_index.get();
         self.imp().selected_index.set(index);
@@ -144,34 +265,7 @@ impl SlidePanel {
             let doc_clone = doc.clone();
             let slide_idx = i;
             drawing_area.set_draw_func(move |_area, cr, width, height| {
-                let doc = doc_clone.borrow();
-                if slide_idx >= doc.slides.len() {
-                    return;
-                }
-
-                let slide = &doc.slides[slide_idx];
-                let slide_size = &doc.slide_size;
-
-                // White background
-                cr.set_source_rgb(1.0, 1.0, 1.0);
-                cr.rectangle(0.0, 0.0, width as f64, height as f64);
-                let _ = cr.fill();
-
-                // Scale to fit
-                let scale_x = width as f64 / slide_size.width;
-                let scale_y = height as f64 / slide_size.height;
-                let scale = scale_x.min(scale_y);
-
-                cr.save().expect("save");
-                cr.scale(scale, scale);
-                engine::render_slide(cr, slide, slide_size);
-                cr.restore().expect("restore");
-
-                // Border
-                cr.set_source_rgba(0.0, 0.0, 0.0, 0.15);
-                cr.rectangle(0.0, 0.0, width as f64, height as f64);
-                cr.set_line_width(1.0);
-                let _ = cr.stroke();
+                draw_slide_thumbnail(cr, &doc_clone.borrow(), slide_idx, width, height);
             });
 
             // Click handler - attach to drawing_area so it receives events directly
@@ -187,6 +281,36 @@ impl SlidePanel {
             });
             drawing_area.add_controller(gesture);
 
+            // Right click opens the slide's context menu
+            let context_gesture = gtk::GestureClick::new();
+            context_gesture.set_button(gdk::BUTTON_SECONDARY);
+            let panel = self.clone();
+            let thumbnail = drawing_area.clone();
+            context_gesture.connect_pressed(move |_, _, x, y| {
+                panel.show_context_menu(idx, &thumbnail, x, y);
+            });
+            drawing_area.add_controller(context_gesture);
+
+            // Drag source: carries the slide index
+            let drag_source = gtk::DragSource::new();
+            drag_source.set_actions(gdk::DragAction::MOVE);
+            drag_source.connect_prepare(move |_, _, _| {
+                Some(gdk::ContentProvider::for_value(&(idx as u32).to_value()))
+            });
+            frame.add_controller(drag_source);
+
+            // Drop target: move the dragged slide to this position
+            let drop_target = gtk::DropTarget::new(u32::static_type(), gdk::DragAction::MOVE);
+            let panel = self.clone();
+            drop_target.connect_drop(move |_, value, _, _| {
+                let Ok(from) = value.get::<u32>() else {
+                    return false;
+                };
+                panel.move_slide(from as usize, idx);
+                true
+            });
+            frame.add_controller(drop_target);
+
             frame.append(&drawing_area);
             frame.append(&label);
             list_box.append(&frame);
@@ -209,6 +333,43 @@ impl SlidePanel {
     }
 }
 
+/// Render a slide scaled to fit a thumbnail of the given widget size.
+pub fn draw_slide_thumbnail(
+    cr: &cairo::Context,
+    doc: &Document,
+    slide_idx: usize,
+    width: i32,
+    height: i32,
+) {
+    if slide_idx >= doc.slides.len() {
+        return;
+    }
+
+    let slide = &doc.slides[slide_idx];
+    let slide_size = &doc.slide_size;
+
+    // White background
+    cr.set_source_rgb(1.0, 1.0, 1.0);
+    cr.rectangle(0.0, 0.0, width as f64, height as f64);
+    let _ = cr.fill();
+
+    // Scale to fit
+    let scale_x = width as f64 / slide_size.width;
+    let scale_y = height as f64 / slide_size.height;
+    let scale = scale_x.min(scale_y);
+
+    cr.save().expect("save");
+    cr.scale(scale, scale);
+    engine::render_slide(cr, slide, slide_size);
+    cr.restore().expect("restore");
+
+    // Border
+    cr.set_source_rgba(0.0, 0.0, 0.0, 0.15);
+    cr.rectangle(0.0, 0.0, width as f64, height as f64);
+    cr.set_line_width(1.0);
+    let _ = cr.stroke();
+}
+
 fn update_thumbnail_style(drawing_area: &gtk::DrawingArea, selected: bool) {
     if selected {
         drawing_area.add_css_class("selected-thumbnail");
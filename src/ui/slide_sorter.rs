@@ -0,0 +1,312 @@
+use gettextrs::gettext;
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::model::document::Document;
+use crate::ui::slide_panel;
+
+const THUMB_WIDTH: i32 = 280;
+
+mod imp {
+    use super::*;
+
+    pub struct SlideSorter {
+        pub container: gtk::Box,
+        pub flow_box: gtk::FlowBox,
+        pub duplicate_btn: gtk::Button,
+        pub delete_btn: gtk::Button,
+        pub document: RefCell<Option<Rc<RefCell<Document>>>>,
+        pub on_slide_activated: RefCell<Option<Box<dyn Fn(usize)>>>,
+        pub on_slides_changed: RefCell<Option<Box<dyn Fn()>>>,
+    }
+
+    impl std::fmt::Debug for SlideSorter {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("SlideSorter").finish()
+        }
+    }
+
+    impl Default for SlideSorter {
+        fn default() -> Self {
+            let flow_box = gtk::FlowBox::builder()
+                .selection_mode(gtk::SelectionMode::Multiple)
+                .homogeneous(true)
+                .activate_on_single_click(false)
+                .row_spacing(16)
+                .column_spacing(16)
+                .margin_start(24)
+                .margin_end(24)
+                .margin_top(24)
+                .margin_bottom(24)
+                .valign(gtk::Align::Start)
+                .build();
+
+            let scrolled_window = gtk::ScrolledWindow::builder()
+                .hscrollbar_policy(gtk::PolicyType::Never)
+                .vscrollbar_policy(gtk::PolicyType::Automatic)
+                .vexpand(true)
+                .child(&flow_box)
+                .build();
+
+            let duplicate_btn = gtk::Button::with_label(&gettext("Duplicate Slide"));
+            let delete_btn = gtk::Button::with_label(&gettext("Delete Slide"));
+            delete_btn.add_css_class("destructive-action");
+
+            let hint = gtk::Label::new(Some(&gettext(
+                "Drag slides to reorder them. Press Enter to edit the selected slide.",
+            )));
+            hint.add_css_class("dim-label");
+            hint.set_hexpand(true);
+            hint.set_xalign(0.0);
+
+            let toolbar = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+            toolbar.set_margin_start(24);
+            toolbar.set_margin_end(24);
+            toolbar.set_margin_top(12);
+            toolbar.append(&hint);
+            toolbar.append(&duplicate_btn);
+            toolbar.append(&delete_btn);
+
+            let container = gtk::Box::new(gtk::Orientation::Vertical, 0);
+            container.append(&toolbar);
+            container.append(&scrolled_window);
+
+            Self {
+                container,
+                flow_box,
+                duplicate_btn,
+                delete_btn,
+                document: RefCell::new(None),
+                on_slide_activated: RefCell::new(None),
+                on_slides_changed: RefCell::new(None),
+            }
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for SlideSorter {
+        const NAME: &'static str = "LuminaSlideSorter";
+        type Type = super::SlideSorter;
+        type ParentType = gtk::Widget;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.set_layout_manager_type::<gtk::BinLayout>();
+        }
+    }
+
+    impl ObjectImpl for SlideSorter {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+            self.container.set_parent(&*obj);
+            obj.setup_handlers();
+        }
+
+        fn dispose(&self) {
+            self.container.unparent();
+        }
+    }
+
+    impl WidgetImpl for SlideSorter {}
+}
+
+glib::wrapper! {
+    pub struct SlideSorter(ObjectSubclass<imp::SlideSorter>)
+        @extends gtk::Widget;
+}
+
+impl SlideSorter {
+    pub fn new() -> Self {
+        glib::Object::builder().build()
+    }
+
+    pub fn set_document(&self, doc: Rc<RefCell<Document>>) {
+        *self.imp().document.borrow_mut() = Some(doc);
+        self.rebuild();
+    }
+
+    /// Called when a slide is activated (Enter or double-click).
+    pub fn connect_slide_activated<F: Fn(usize) + 'static>(&self, callback: F) {
+        *self.imp().on_slide_activated.borrow_mut() = Some(Box::new(callback));
+    }
+
+    /// Called after slides were reordered, duplicated or deleted.
+    pub fn connect_slides_changed<F: Fn() + 'static>(&self, callback: F) {
+        *self.imp().on_slides_changed.borrow_mut() = Some(Box::new(callback));
+    }
+
+    fn setup_handlers(&self) {
+        let imp = self.imp();
+
+        let sorter = self.clone();
+        imp.flow_box.connect_child_activated(move |_, child| {
+            if let Some(cb) = sorter.imp().on_slide_activated.borrow().as_ref() {
+                cb(child.index() as usize);
+            }
+        });
+
+        let sorter = self.clone();
+        imp.duplicate_btn.connect_clicked(move |_| {
+            sorter.duplicate_selected();
+        });
+
+        let sorter = self.clone();
+        imp.delete_btn.connect_clicked(move |_| {
+            sorter.delete_selected();
+        });
+
+        let key_controller = gtk::EventControllerKey::new();
+        let sorter = self.clone();
+        key_controller.connect_key_pressed(move |_, keyval, _, _| {
+            if keyval == gdk::Key::Delete || keyval == gdk::Key::BackSpace {
+                sorter.delete_selected();
+                return glib::Propagation::Stop;
+            }
+            glib::Propagation::Proceed
+        });
+        imp.flow_box.add_controller(key_controller);
+    }
+
+    pub fn rebuild(&self) {
+        let imp = self.imp();
+        let flow_box = &imp.flow_box;
+
+        while let Some(child) = flow_box.first_child() {
+            flow_box.remove(&child);
+        }
+
+        let doc_ref = imp.document.borrow();
+        let Some(doc) = doc_ref.as_ref() else {
+            return;
+        };
+
+        let doc_borrowed = doc.borrow();
+        let slide_count = doc_borrowed.slides.len();
+        let slide_size = doc_borrowed.slide_size;
+        drop(doc_borrowed);
+
+        let thumb_height = (THUMB_WIDTH as f64 * slide_size.height / slide_size.width) as i32;
+
+        for i in 0..slide_count {
+            let frame = gtk::Box::new(gtk::Orientation::Vertical, 4);
+
+            let drawing_area = gtk::DrawingArea::new();
+            drawing_area.set_content_width(THUMB_WIDTH);
+            drawing_area.set_content_height(thumb_height);
+
+            let doc_clone = doc.clone();
+            drawing_area.set_draw_func(move |_area, cr, width, height| {
+                slide_panel::draw_slide_thumbnail(cr, &doc_clone.borrow(), i, width, height);
+            });
+
+            let label = gtk::Label::new(Some(&format!("{}", i + 1)));
+            label.add_css_class("caption");
+            label.set_opacity(0.6);
+
+            // Drag source: carries the slide index
+            let drag_source = gtk::DragSource::new();
+            drag_source.set_actions(gdk::DragAction::MOVE);
+            drag_source.connect_prepare(move |_, _, _| {
+                Some(gdk::ContentProvider::for_value(&(i as u32).to_value()))
+            });
+            frame.add_controller(drag_source);
+
+            // Drop target: move the dragged slide to this position
+            let drop_target = gtk::DropTarget::new(u32::static_type(), gdk::DragAction::MOVE);
+            let sorter = self.clone();
+            drop_target.connect_drop(move |_, value, _, _| {
+                let Ok(from) = value.get::<u32>() else {
+                    return false;
+                };
+                sorter.move_slide(from as usize, i);
+                true
+            });
+            frame.add_controller(drop_target);
+
+            frame.append(&drawing_area);
+            frame.append(&label);
+            flow_box.append(&frame);
+        }
+    }
+
+    /// Select a single slide and give it keyboard focus.
+    pub fn select_slide(&self, index: usize) {
+        let flow_box = &self.imp().flow_box;
+        flow_box.unselect_all();
+        if let Some(child) = flow_box.child_at_index(index as i32) {
+            flow_box.select_child(&child);
+            child.grab_focus();
+        }
+    }
+
+    /// Indices of the selected slides, in ascending order.
+    pub fn selected_indices(&self) -> Vec<usize> {
+        let mut indices: Vec<usize> = self
+            .imp()
+            .flow_box
+            .selected_children()
+            .iter()
+            .map(|child| child.index() as usize)
+            .collect();
+        indices.sort_unstable();
+        indices
+    }
+
+    fn move_slide(&self, from: usize, to: usize) {
+        let Some(doc) = self.imp().document.borrow().clone() else {
+            return;
+        };
+        doc.borrow_mut().move_slide(from, to);
+        self.rebuild();
+        self.select_slide(to);
+        self.notify_slides_changed();
+    }
+
+    fn duplicate_selected(&self) {
+        let Some(doc) = self.imp().document.borrow().clone() else {
+            return;
+        };
+        let indices = self.selected_indices();
+        if indices.is_empty() {
+            return;
+        }
+        {
+            let mut doc = doc.borrow_mut();
+            // Work back to front so earlier indices stay valid
+            for &idx in indices.iter().rev() {
+                doc.duplicate_slide(idx);
+            }
+        }
+        self.rebuild();
+        self.notify_slides_changed();
+    }
+
+    fn delete_selected(&self) {
+        let Some(doc) = self.imp().document.borrow().clone() else {
+            return;
+        };
+        let indices = self.selected_indices();
+        let Some(&first) = indices.first() else {
+            return;
+        };
+        let remaining = {
+            let mut doc = doc.borrow_mut();
+            // The model refuses to remove the last slide
+            for &idx in indices.iter().rev() {
+                doc.remove_slide(idx);
+            }
+            doc.slides.len()
+        };
+        self.rebuild();
+        self.select_slide(first.min(remaining - 1));
+        self.notify_slides_changed();
+    }
+
+    fn notify_slides_changed(&self) {
+        if let Some(cb) = self.imp().on_slides_changed.borrow().as_ref() {
+            cb();
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use gtk::prelude::*;
+use std::cell::Cell;
+use std::rc::Rc;
+
+use crate::model::document::Document;
+use crate::render::engine;
+
+/// Play `doc` in a borderless fullscreen window, starting at slide `start`.
+/// The slideshow shows the document as it was when it started, so editing
+/// in the meantime does not change it.
+pub fn start_slideshow(parent: &impl IsA<gtk::Window>, doc: Document, start: usize) {
+    let last = doc.slides.len().saturating_sub(1);
+    let doc = Rc::new(doc);
+    let current = Rc::new(Cell::new(start.min(last)));
+
+    let area = gtk::DrawingArea::new();
+    area.set_cursor_from_name(Some("none"));
+    area.set_draw_func({
+        let doc = doc.clone();
+        let current = current.clone();
+        move |_, cr, width, height| {
+            draw_slide(cr, &doc, current.get(), width, height);
+        }
+    });
+
+    let window = gtk::Window::builder()
+        .transient_for(parent)
+        .decorated(false)
+        .child(&area)
+        .build();
+
+    // Moves to a slide, staying on the first and last one
+    let show = {
+        let area = area.clone();
+        let current = current.clone();
+        move |index: usize| {
+            if index != current.get() {
+                current.set(index.min(last));
+                area.queue_draw();
+            }
+        }
+    };
+
+    let keys = gtk::EventControllerKey::new();
+    keys.connect_key_pressed({
+        let window = window.downgrade();
+        let current = current.clone();
+        let show = show.clone();
+        move |_, keyval, _, _| {
+            use gdk::Key;
+            let index = current.get();
+            match keyval {
+                Key::Right | Key::Down | Key::space | Key::Page_Down | Key::Return
+                | Key::KP_Enter | Key::n => show(index + 1),
+                Key::Left | Key::Up | Key::BackSpace | Key::Page_Up | Key::p => {
+                    show(index.saturating_sub(1))
+                }
+                Key::Home => show(0),
+                Key::End => show(last),
+                Key::Escape => {
+                    if let Some(window) = window.upgrade() {
+                        window.close();
+                    }
+                }
+                _ => return glib::Propagation::Proceed,
+            }
+            glib::Propagation::Stop
+        }
+    });
+    window.add_controller(keys);
+
+    // A click goes to the next slide, a right click back to the previous one
+    let click = gtk::GestureClick::new();
+    click.set_button(0);
+    click.connect_pressed(move |gesture, _, _, _| {
+        let index = current.get();
+        match gesture.current_button() {
+            gdk::BUTTON_PRIMARY => show(index + 1),
+            gdk::BUTTON_SECONDARY => show(index.saturating_sub(1)),
+            _ => {}
+        }
+    });
+    area.add_controller(click);
+
+    window.fullscreen();
+    window.present();
+}
+
+/// Render a slide as large as it fits on a black screen of the given size.
+fn draw_slide(cr: &cairo::Context, doc: &Document, slide_idx: usize, width: i32, height: i32) {
+    cr.set_source_rgb(0.0, 0.0, 0.0);
+    let _ = cr.paint();
+
+    let Some(slide) = doc.slides.get(slide_idx) else {
+        return;
+    };
+    let slide_size = &doc.slide_size;
+    let scale = (width as f64 / slide_size.width).min(height as f64 / slide_size.height);
+
+    cr.save().expect("save");
+    cr.translate(
+        (width as f64 - slide_size.width * scale) / 2.0,
+        (height as f64 - slide_size.height * scale) / 2.0,
+    );
+    cr.scale(scale, scale);
+    cr.rectangle(0.0, 0.0, slide_size.width, slide_size.height);
+    cr.clip();
+    // Slides without a background are white, as in the editor
+    cr.set_source_rgb(1.0, 1.0, 1.0);
+    let _ = cr.paint();
+    engine::render_slide(cr, slide, slide_size);
+    cr.restore().expect("restore");
+}
@@ -2,23 +2,40 @@ use adw::prelude::*;
 use adw::subclass::prelude::*;
 use gettextrs::gettext;
 use gtk::gio;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 
-use crate::format::odp;
-use crate::render::pdf_export;
+use crate::format::{autosave, odp, pptx};
+use crate::format::warning::ParseWarning;
+use crate::render::{fonts, pdf_export, raster, svg_export};
 use crate::templates;
-use crate::model::document::Document;
+use crate::model::document::{rescale_document, Document};
 use crate::model::element::SlideElement;
-use crate::model::geometry::Rect;
+use crate::model::geometry::{Rect, Size, SlideSizePreset, SLIDE_SIZE_PRESETS};
+use crate::model::history::{self, History};
 use crate::model::image::ImageElement;
-use crate::model::shape::{ShapeElement, ShapeType};
+use crate::model::shape::{ShapeElement, ShapeType, DEFAULT_CORNER_RADIUS, DEFAULT_STAR_POINTS};
 use crate::model::style::{Color, FillStyle, FontStyle, StrokeStyle};
 use crate::model::text::{TextAlignment, TextElement, TextParagraph, TextRun};
+use crate::ui::canvas::arrange::{self, MatchDimension};
 use crate::ui::canvas::tool::Tool;
+use crate::ui::canvas::zoom::ZoomMode;
 use crate::ui::canvas_view::CanvasView;
+use crate::ui::objects_panel::{self, ObjectsPanel};
 use crate::ui::properties_panel::PropertiesPanel;
-use crate::ui::slide_panel::SlidePanel;
+use crate::ui::shortcuts;
+use crate::ui::slide_panel::{self, SlidePanel};
+use crate::ui::slide_sorter::SlideSorter;
+use crate::ui::slideshow;
+
+/// Pixels per point when copying a slide to the clipboard
+const CLIPBOARD_IMAGE_SCALE: f64 = 2.0;
+/// Seconds between autosaves of a presentation with unsaved edits
+const AUTOSAVE_INTERVAL: u32 = 60;
+/// Most files listed in the Recent submenu
+const MAX_RECENT_FILES: usize = 10;
+/// Files the Recent submenu lists, by extension
+const RECENT_EXTENSIONS: [&str; 3] = ["odp", "fodp", "pptx"];
 
 mod imp {
     use super::*;
@@ -28,10 +45,22 @@ mod imp {
         pub canvas: CanvasView,
         pub slide_panel: SlidePanel,
         pub properties_panel: PropertiesPanel,
+        pub objects_panel: ObjectsPanel,
+        pub slide_sorter: SlideSorter,
+        pub toast_overlay: adw::ToastOverlay,
+        /// Current slide and the selected element's kind and bounds
+        pub status_label: gtk::Label,
         pub header: adw::HeaderBar,
         pub title_widget: RefCell<Option<adw::WindowTitle>>,
+        /// File name shown under the title
+        pub document_name: RefCell<String>,
         pub tool_buttons: RefCell<Vec<(Tool, gtk::ToggleButton)>>,
         pub file_path: Rc<RefCell<Option<std::path::PathBuf>>>,
+        pub history: RefCell<History>,
+        /// Edited since the last save
+        pub dirty: Cell<bool>,
+        /// The autosave holds the latest edits
+        pub autosaved: Cell<bool>,
     }
 
     impl std::fmt::Debug for LuminaWindow {
@@ -47,10 +76,18 @@ mod imp {
                 canvas: CanvasView::new(),
                 slide_panel: SlidePanel::new(),
                 properties_panel: PropertiesPanel::new(),
+                objects_panel: ObjectsPanel::new(),
+                slide_sorter: SlideSorter::new(),
+                toast_overlay: adw::ToastOverlay::new(),
+                status_label: gtk::Label::new(None),
                 header: adw::HeaderBar::new(),
                 title_widget: RefCell::new(None),
+                document_name: RefCell::new(String::new()),
                 tool_buttons: RefCell::new(Vec::new()),
                 file_path: Rc::new(RefCell::new(None)),
+                history: RefCell::new(History::new(&Document::new())),
+                dirty: Cell::new(false),
+                autosaved: Cell::new(false),
             }
         }
     }
@@ -71,7 +108,16 @@ mod imp {
     }
 
     impl WidgetImpl for LuminaWindow {}
-    impl WindowImpl for LuminaWindow {}
+    impl WindowImpl for LuminaWindow {
+        /// Edits that were not saved are saved or discarded first.
+        fn close_request(&self) -> glib::Propagation {
+            if !self.dirty.get() {
+                return self.parent_close_request();
+            }
+            self.obj().resolve_unsaved(|window| window.close());
+            glib::Propagation::Stop
+        }
+    }
     impl ApplicationWindowImpl for LuminaWindow {}
     impl AdwApplicationWindowImpl for LuminaWindow {}
 }
@@ -98,13 +144,14 @@ impl LuminaWindow {
         let imp = self.imp();
 
         // Create demo document
-        let doc = create_demo_document();
-        let doc = Rc::new(RefCell::new(doc));
+        let doc = imp.document.clone();
+        *doc.borrow_mut() = create_demo_document();
 
         // Header bar
-        let title = adw::WindowTitle::new("Lumina", &gettext("Untitled Presentation"));
+        let title = adw::WindowTitle::new("Lumina", "");
         imp.header.set_title_widget(Some(&title));
         *imp.title_widget.borrow_mut() = Some(title);
+        self.set_document_name(&gettext("Untitled Presentation"));
 
         // Add slide button in header
         let add_slide_btn = gtk::Button::from_icon_name("list-add-symbolic");
@@ -125,20 +172,93 @@ impl LuminaWindow {
 
         let menu = gio::Menu::new();
         let file_section = gio::Menu::new();
-        file_section.append(Some(&gettext("New...")), Some("win.new-presentation"));
+        file_section.append(Some(&gettext("New Presentation")), Some("win.new-presentation"));
+        file_section.append(Some(&gettext("New from Template…")), Some("win.new-from-template"));
+        file_section.append(Some(&gettext("New Window")), Some("app.new-window"));
         file_section.append(Some(&gettext("Open...")), Some("win.open"));
+        let recent_menu = gio::Menu::new();
+        fill_recent_menu(&recent_menu);
+        let recent_weak = recent_menu.downgrade();
+        gtk::RecentManager::default().connect_changed(move |_| {
+            if let Some(menu) = recent_weak.upgrade() {
+                fill_recent_menu(&menu);
+            }
+        });
+        file_section.append_submenu(Some(&gettext("Recent")), &recent_menu);
         file_section.append(Some(&gettext("Save")), Some("win.save"));
         file_section.append(Some(&gettext("Save As...")), Some("win.save-as"));
         menu.append_section(None, &file_section);
+        let view_menu = gio::Menu::new();
+        view_menu.append(Some(&gettext("Fit Slide")), Some("win.zoom::fit"));
+        view_menu.append(Some(&gettext("Fit Width")), Some("win.zoom::fit-width"));
+        view_menu.append(Some("50%"), Some("win.zoom::50"));
+        view_menu.append(Some("100%"), Some("win.zoom::100"));
+        view_menu.append(Some("200%"), Some("win.zoom::200"));
+        let assist_section = gio::Menu::new();
+        assist_section.append(Some(&gettext("Snap to Aspect Ratios")), Some("win.snap-aspect"));
+        assist_section.append(Some(&gettext("Snap to Objects")), Some("win.snap-objects"));
+        assist_section.append(Some(&gettext("Show Grid")), Some("win.toggle-grid"));
+        view_menu.append_section(None, &assist_section);
+        let arrange_menu = gio::Menu::new();
+        arrange_menu.append(Some(&gettext("Make Same Width")), Some("win.match-size::width"));
+        arrange_menu.append(Some(&gettext("Make Same Height")), Some("win.match-size::height"));
+        arrange_menu.append(Some(&gettext("Make Same Size")), Some("win.match-size::size"));
+        let order_section = gio::Menu::new();
+        order_section.append(Some(&gettext("Bring Forward")), Some("win.bring-forward"));
+        order_section.append(Some(&gettext("Send Backward")), Some("win.send-backward"));
+        arrange_menu.append_section(None, &order_section);
+        let visibility_section = gio::Menu::new();
+        visibility_section.append(Some(&gettext("Hide or Show Selection")), Some("win.toggle-visibility"));
+        visibility_section.append(Some(&gettext("Show Hidden Elements")), Some("win.show-hidden"));
+        arrange_menu.append_section(None, &visibility_section);
+        let view_section = gio::Menu::new();
+        view_section.append_submenu(Some(&gettext("View")), &view_menu);
+        view_section.append_submenu(Some(&gettext("Arrange")), &arrange_menu);
+        view_section.append(Some(&gettext("Start Slideshow")), Some("win.start-slideshow"));
+        view_section.append(Some(&gettext("Slide Size…")), Some("win.slide-size"));
+        view_section.append(
+            Some(&gettext("Document Properties…")),
+            Some("win.document-properties"),
+        );
+        menu.append_section(None, &view_section);
         let export_section = gio::Menu::new();
         export_section.append(Some(&gettext("Export as PDF...")), Some("win.export-pdf"));
+        export_section.append(Some(&gettext("Export as PPTX...")), Some("win.export-pptx"));
+        export_section.append(Some(&gettext("Export Slide as SVG...")), Some("win.export-svg"));
+        export_section.append(Some(&gettext("Copy Slide as Image")), Some("win.copy-slide-image"));
         menu.append_section(None, &export_section);
         let about_section = gio::Menu::new();
+        about_section.append(Some(&gettext("Preferences")), Some("app.preferences"));
+        about_section.append(Some(&gettext("Keyboard Shortcuts")), Some("win.show-help-overlay"));
         about_section.append(Some(&gettext("About Lumina")), Some("app.about"));
         menu.append_section(None, &about_section);
         menu_btn.set_menu_model(Some(&menu));
         imp.header.pack_end(&menu_btn);
 
+        let slideshow_btn = gtk::Button::from_icon_name("media-playback-start-symbolic");
+        slideshow_btn.set_tooltip_text(Some(&gettext("Start Slideshow")));
+        slideshow_btn.set_action_name(Some("win.start-slideshow"));
+        imp.header.pack_end(&slideshow_btn);
+
+        // Slide sorter toggle
+        let sorter_btn = gtk::ToggleButton::new();
+        sorter_btn.set_icon_name("view-grid-symbolic");
+        sorter_btn.set_tooltip_text(Some(&gettext("Slide Sorter")));
+        imp.header.pack_end(&sorter_btn);
+
+        // Zoom buttons
+        let zoom_box = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+        zoom_box.add_css_class("linked");
+        let zoom_out_btn = gtk::Button::from_icon_name("zoom-out-symbolic");
+        zoom_out_btn.set_tooltip_text(Some(&gettext("Zoom Out")));
+        zoom_out_btn.set_action_name(Some("win.zoom-out"));
+        zoom_box.append(&zoom_out_btn);
+        let zoom_in_btn = gtk::Button::from_icon_name("zoom-in-symbolic");
+        zoom_in_btn.set_tooltip_text(Some(&gettext("Zoom In")));
+        zoom_in_btn.set_action_name(Some("win.zoom-in"));
+        zoom_box.append(&zoom_in_btn);
+        imp.header.pack_end(&zoom_box);
+
         // Main layout
         let main_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
         main_box.append(&imp.header);
@@ -151,9 +271,20 @@ impl LuminaWindow {
         left_paned.set_shrink_end_child(false);
         left_paned.set_resize_start_child(false);
 
-        // Sidebar
+        // Sidebar: slide thumbnails above the collapsible objects list
+        let sidebar_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
+        imp.slide_panel.set_vexpand(true);
+        sidebar_box.append(&imp.slide_panel);
+        let objects_expander = gtk::Expander::new(Some(&gettext("Objects")));
+        objects_expander.set_expanded(true);
+        objects_expander.set_margin_start(8);
+        objects_expander.set_margin_end(8);
+        objects_expander.set_margin_bottom(8);
+        objects_expander.set_child(Some(&imp.objects_panel));
+        sidebar_box.append(&objects_expander);
+
         let sidebar_frame = gtk::Frame::new(None);
-        sidebar_frame.set_child(Some(&imp.slide_panel));
+        sidebar_frame.set_child(Some(&sidebar_box));
         sidebar_frame.set_width_request(180);
         left_paned.set_start_child(Some(&sidebar_frame));
 
@@ -176,13 +307,77 @@ impl LuminaWindow {
 
         left_paned.set_end_child(Some(&right_paned));
 
-        main_box.append(&left_paned);
+        // Editor and slide sorter share the area below the header
+        let view_stack = gtk::Stack::new();
+        view_stack.set_transition_type(gtk::StackTransitionType::Crossfade);
+        view_stack.add_named(&left_paned, Some("editor"));
+        imp.slide_sorter.set_vexpand(true);
+        view_stack.add_named(&imp.slide_sorter, Some("sorter"));
+
+        imp.toast_overlay.set_child(Some(&view_stack));
+        main_box.append(&imp.toast_overlay);
+
+        // Status bar
+        main_box.append(&gtk::Separator::new(gtk::Orientation::Horizontal));
+        imp.status_label.set_halign(gtk::Align::Start);
+        imp.status_label.set_margin_start(8);
+        imp.status_label.set_margin_end(8);
+        imp.status_label.set_margin_top(2);
+        imp.status_label.set_margin_bottom(2);
+        imp.status_label.add_css_class("caption");
+        imp.status_label.add_css_class("dim-label");
+        main_box.append(&imp.status_label);
         self.set_content(Some(&main_box));
+        self.set_help_overlay(Some(&shortcuts::shortcuts_window()));
 
         // Connect document
         imp.slide_panel.set_document(doc.clone());
         imp.canvas.set_document(doc.clone());
         imp.properties_panel.set_document(doc.clone());
+        imp.objects_panel.set_document(doc.clone());
+        imp.slide_sorter.set_document(doc.clone());
+
+        // Slide sorter view
+        let sorter = imp.slide_sorter.clone();
+        let canvas_for_sorter = imp.canvas.clone();
+        sorter_btn.connect_toggled(move |btn| {
+            if btn.is_active() {
+                sorter.rebuild();
+                view_stack.set_visible_child_name("sorter");
+                sorter.select_slide(canvas_for_sorter.current_slide_index());
+            } else {
+                view_stack.set_visible_child_name("editor");
+            }
+        });
+
+        let panel_for_sorter = imp.slide_panel.clone();
+        let canvas_for_sorter = imp.canvas.clone();
+        let props_for_sorter = imp.properties_panel.clone();
+        imp.slide_sorter.connect_slide_activated(move |index| {
+            panel_for_sorter.set_selected_index(index);
+            canvas_for_sorter.set_current_slide(index);
+            props_for_sorter.update_for_selection(None);
+            sorter_btn.set_active(false);
+        });
+
+        let doc_for_sorter = doc.clone();
+        let panel_for_sorter = imp.slide_panel.clone();
+        let canvas_for_sorter = imp.canvas.clone();
+        let props_for_sorter = imp.properties_panel.clone();
+        let window = self.downgrade();
+        imp.slide_sorter.connect_slides_changed(move || {
+            if let Some(window) = window.upgrade() {
+                window.record_edit();
+            }
+            let slide_count = doc_for_sorter.borrow().slides.len();
+            let index = canvas_for_sorter
+                .current_slide_index()
+                .min(slide_count.saturating_sub(1));
+            panel_for_sorter.rebuild_thumbnails();
+            panel_for_sorter.set_selected_index(index);
+            canvas_for_sorter.set_current_slide(index);
+            props_for_sorter.update_for_selection(None);
+        });
 
         // Slide selection
         let canvas = imp.canvas.clone();
@@ -190,20 +385,76 @@ impl LuminaWindow {
             canvas.set_current_slide(index);
         });
 
+        // Slides deleted, duplicated or reordered from the sidebar
+        let canvas_for_panel = imp.canvas.clone();
+        let props_for_panel = imp.properties_panel.clone();
+        let window = self.downgrade();
+        imp.slide_panel.connect_slides_changed(move |index| {
+            if let Some(window) = window.upgrade() {
+                window.record_edit();
+            }
+            canvas_for_panel.set_current_slide(index);
+            props_for_panel.update_for_selection(None);
+        });
+
         // Refresh thumbnails and properties panel when selection changes
         let panel_for_sel = imp.slide_panel.clone();
         let props_for_sel = imp.properties_panel.clone();
+        let objects_for_sel = imp.objects_panel.clone();
         let canvas_for_sel = imp.canvas.clone();
+        let window = self.downgrade();
         imp.canvas.connect_selection_changed(move |sel_id| {
+            if let Some(window) = window.upgrade() {
+                window.update_status();
+            }
             panel_for_sel.queue_draw_all();
             props_for_sel.set_slide_index(canvas_for_sel.current_slide_index());
             props_for_sel.update_for_selection(sel_id);
+            objects_for_sel.sync(canvas_for_sel.current_slide_index(), sel_id);
+        });
+
+        // Canvas edits become undo steps once complete, e.g. after a drag
+        let window = self.downgrade();
+        imp.canvas.connect_edited(move || {
+            if let Some(window) = window.upgrade() {
+                window.record_edit();
+            }
+        });
+
+        // Position fields follow an element while it is dragged
+        let props_for_geometry = imp.properties_panel.clone();
+        imp.canvas.connect_geometry_changed(move || props_for_geometry.refresh_values());
+
+        // Objects panel picks elements and changes their state
+        let canvas_for_objects = imp.canvas.clone();
+        imp.objects_panel.connect_element_selected(move |id| {
+            canvas_for_objects.select_element(id);
+        });
+        let canvas_for_objects = imp.canvas.clone();
+        let panel_for_objects = imp.slide_panel.clone();
+        let props_for_objects = imp.properties_panel.clone();
+        let objects_for_objects = imp.objects_panel.clone();
+        let window = self.downgrade();
+        imp.objects_panel.connect_elements_changed(move || {
+            if let Some(window) = window.upgrade() {
+                window.record_edit();
+            }
+            let sel_id = canvas_for_objects.selection().borrow().element_id;
+            canvas_for_objects.queue_draw();
+            panel_for_objects.queue_draw_all();
+            props_for_objects.update_for_selection(sel_id);
+            objects_for_objects.sync(canvas_for_objects.current_slide_index(), sel_id);
         });
 
         // When properties change, redraw canvas and thumbnails
         let canvas_for_props = imp.canvas.clone();
         let panel_for_props = imp.slide_panel.clone();
+        let window = self.downgrade();
         imp.properties_panel.connect_property_changed(move || {
+            if let Some(window) = window.upgrade() {
+                window.record_edit();
+                window.update_status();
+            }
             canvas_for_props.queue_draw();
             panel_for_props.queue_draw_all();
         });
@@ -212,12 +463,16 @@ impl LuminaWindow {
         let doc_clone = doc.clone();
         let panel_clone = imp.slide_panel.clone();
         let canvas_clone = imp.canvas.clone();
+        let window = self.downgrade();
         add_slide_btn.connect_clicked(move |_| {
             let new_idx = {
                 let mut doc = doc_clone.borrow_mut();
                 let current = canvas_clone.current_slide_index();
                 doc.insert_slide(current + 1)
             };
+            if let Some(window) = window.upgrade() {
+                window.record_edit();
+            }
             panel_clone.rebuild_thumbnails();
             panel_clone.set_selected_index(new_idx);
             canvas_clone.set_current_slide(new_idx);
@@ -235,6 +490,9 @@ impl LuminaWindow {
             .tool-active {
                 background: alpha(@accent_color, 0.2);
             }
+            .mixed-state {
+                box-shadow: inset 0 -3px alpha(@accent_color, 0.6);
+            }
             ",
         );
         gtk::style_context_add_provider_for_display(
@@ -244,154 +502,745 @@ impl LuminaWindow {
         );
 
         // File actions
-        self.setup_file_actions(doc);
+        self.setup_file_actions(doc.clone());
+        self.setup_view_actions();
+        self.setup_slide_size_action(doc.clone());
+        self.setup_document_properties_action(doc.clone());
+        self.setup_slideshow_action(doc.clone());
+        self.setup_font_actions(doc);
+        self.reset_history();
+        self.update_status();
+
+        let window = self.downgrade();
+        glib::timeout_add_seconds_local(AUTOSAVE_INTERVAL, move || {
+            let Some(window) = window.upgrade() else {
+                return glib::ControlFlow::Break;
+            };
+            window.autosave();
+            glib::ControlFlow::Continue
+        });
     }
 
-    fn setup_file_actions(&self, doc: Rc<RefCell<Document>>) {
+    fn setup_view_actions(&self) {
+        // Zoom presets; the state is the id of the current zoom, which the
+        // canvas reports back however it was changed
+        let zoom_action = gio::ActionEntry::builder("zoom")
+            .parameter_type(Some(&String::static_variant_type()))
+            .state("fit".to_variant())
+            .activate(|win: &LuminaWindow, _, param| {
+                let Some(id) = param.and_then(|p| p.get::<String>()) else {
+                    return;
+                };
+                let canvas = &win.imp().canvas;
+                match ZoomMode::from_id(&id) {
+                    Some(ZoomMode::Fit) => canvas.zoom_to_fit(),
+                    Some(ZoomMode::Factor(factor)) => canvas.set_zoom(factor),
+                    Some(mode) => canvas.set_zoom_mode(mode),
+                    None => {}
+                }
+            })
+            .build();
+
+        let zoom_in_action = gio::ActionEntry::builder("zoom-in")
+            .activate(|win: &LuminaWindow, _, _| {
+                win.imp().canvas.zoom_in();
+            })
+            .build();
+
+        let zoom_out_action = gio::ActionEntry::builder("zoom-out")
+            .activate(|win: &LuminaWindow, _, _| {
+                win.imp().canvas.zoom_out();
+            })
+            .build();
+
+        let window = self.downgrade();
+        self.imp().canvas.connect_zoom_changed(move |mode| {
+            let Some(window) = window.upgrade() else { return };
+            if let Some(action) = window.lookup_action("zoom").and_downcast::<gio::SimpleAction>() {
+                action.set_state(&mode.id().to_variant());
+            }
+        });
+
+        // Aspect ratio snapping while resizing
+        let snap_aspect_action = gio::ActionEntry::builder("snap-aspect")
+            .state(true.to_variant())
+            .activate(|win: &LuminaWindow, action, _| {
+                let enabled = !action.state().and_then(|s| s.get::<bool>()).unwrap_or(true);
+                action.set_state(&enabled.to_variant());
+                win.imp().canvas.set_aspect_snap(enabled);
+            })
+            .build();
+
+        // Edge and center snapping while moving and resizing
+        let snap_objects_action = gio::ActionEntry::builder("snap-objects")
+            .state(true.to_variant())
+            .activate(|win: &LuminaWindow, action, _| {
+                let enabled = !action.state().and_then(|s| s.get::<bool>()).unwrap_or(true);
+                action.set_state(&enabled.to_variant());
+                win.imp().canvas.set_object_snap(enabled);
+            })
+            .build();
+
+        // Grid behind the elements, snapped to while shown
+        let toggle_grid_action = gio::ActionEntry::builder("toggle-grid")
+            .state(false.to_variant())
+            .activate(|win: &LuminaWindow, action, _| {
+                let enabled = !action.state().and_then(|s| s.get::<bool>()).unwrap_or(false);
+                action.set_state(&enabled.to_variant());
+                win.imp().canvas.set_show_grid(enabled);
+            })
+            .build();
+
+        // Match the selected elements' size to the first-selected one
+        let match_size_action = gio::ActionEntry::builder("match-size")
+            .parameter_type(Some(&String::static_variant_type()))
+            .activate(|win: &LuminaWindow, _, param| {
+                let Some(id) = param.and_then(|p| p.get::<String>()) else {
+                    return;
+                };
+                if let Some(dimension) = MatchDimension::from_id(&id) {
+                    win.imp().canvas.match_selected_sizes(dimension);
+                }
+            })
+            .build();
+
+        let reset_style_action = gio::ActionEntry::builder("reset-style")
+            .activate(|win: &LuminaWindow, _, _| {
+                win.imp().canvas.reset_selected_style();
+            })
+            .build();
+
+        let delete_slide_action = gio::ActionEntry::builder("delete-slide")
+            .activate(|win: &LuminaWindow, _, _| {
+                let imp = win.imp();
+                imp.slide_panel.delete_slide(imp.canvas.current_slide_index());
+            })
+            .build();
+
+        let crop_image_action = gio::ActionEntry::builder("crop-image")
+            .activate(|win: &LuminaWindow, _, _| {
+                win.imp().canvas.start_crop();
+            })
+            .build();
+
+        let toggle_visibility_action = gio::ActionEntry::builder("toggle-visibility")
+            .activate(|win: &LuminaWindow, _, _| {
+                win.imp().canvas.toggle_selected_visibility();
+            })
+            .build();
+
+        let show_hidden_action = gio::ActionEntry::builder("show-hidden")
+            .activate(|win: &LuminaWindow, _, _| {
+                win.imp().canvas.show_hidden_elements();
+            })
+            .build();
+
+        let bring_forward_action = gio::ActionEntry::builder("bring-forward")
+            .activate(|win: &LuminaWindow, _, _| {
+                win.imp().canvas.restack_selected(true);
+            })
+            .build();
+
+        let send_backward_action = gio::ActionEntry::builder("send-backward")
+            .activate(|win: &LuminaWindow, _, _| {
+                win.imp().canvas.restack_selected(false);
+            })
+            .build();
+
+        self.add_action_entries([
+            zoom_action,
+            zoom_in_action,
+            zoom_out_action,
+            snap_aspect_action,
+            snap_objects_action,
+            toggle_grid_action,
+            match_size_action,
+            reset_style_action,
+            delete_slide_action,
+            crop_image_action,
+            toggle_visibility_action,
+            show_hidden_action,
+            bring_forward_action,
+            send_backward_action,
+        ]);
+    }
+
+    fn setup_slide_size_action(&self, doc: Rc<RefCell<Document>>) {
         let imp = self.imp();
 
-        // Save action
-        let save_action = gio::ActionEntry::builder("save")
+        let slide_size_action = gio::ActionEntry::builder("slide-size")
             .activate({
-                let doc = doc.clone();
-                let file_path = imp.file_path.clone();
+                let slide_panel = imp.slide_panel.clone();
+                let canvas = imp.canvas.clone();
+                let props = imp.properties_panel.clone();
                 move |win: &LuminaWindow, _, _| {
-                    let path = file_path.borrow().clone();
-                    if let Some(path) = path {
-                        let doc = doc.borrow();
-                        if let Err(e) = odp::writer::save_document(&doc, &path) {
-                            eprintln!("Save error: {}", e);
-                        }
-                    } else {
-                        // No file path yet, trigger Save As
-                        gio::prelude::ActionGroupExt::activate_action(win, "save-as", None);
-                    }
+                    show_slide_size_dialog(win, &doc, &slide_panel, &canvas, &props);
                 }
             })
             .build();
 
-        // Save As action
-        let save_as_action = gio::ActionEntry::builder("save-as")
+        self.add_action_entries([slide_size_action]);
+    }
+
+    fn setup_document_properties_action(&self, doc: Rc<RefCell<Document>>) {
+        let properties_action = gio::ActionEntry::builder("document-properties")
+            .activate(move |win: &LuminaWindow, _, _| {
+                show_document_properties_dialog(win, &doc);
+            })
+            .build();
+
+        self.add_action_entries([properties_action]);
+    }
+
+    fn setup_slideshow_action(&self, doc: Rc<RefCell<Document>>) {
+        let canvas = self.imp().canvas.clone();
+        let slideshow_action = gio::ActionEntry::builder("start-slideshow")
+            .activate(move |win: &LuminaWindow, _, _| {
+                let doc = doc.borrow().clone();
+                slideshow::start_slideshow(win, doc, canvas.current_slide_index());
+            })
+            .build();
+
+        self.add_action_entries([slideshow_action]);
+    }
+
+    fn setup_font_actions(&self, doc: Rc<RefCell<Document>>) {
+        let imp = self.imp();
+
+        let remap_action = gio::ActionEntry::builder("remap-fonts")
             .activate({
-                let doc = doc.clone();
-                let file_path = imp.file_path.clone();
-                let title_widget = imp.title_widget.clone();
+                let slide_panel = imp.slide_panel.clone();
+                let canvas = imp.canvas.clone();
+                let props = imp.properties_panel.clone();
                 move |win: &LuminaWindow, _, _| {
-                    let filter = gtk::FileFilter::new();
-                    filter.set_name(Some(&gettext("ODP Presentation")));
-                    filter.add_mime_type("application/vnd.oasis.opendocument.presentation");
-                    filter.add_pattern("*.odp");
+                    show_remap_fonts_dialog(win, &doc, &slide_panel, &canvas, &props);
+                }
+            })
+            .build();
 
-                    let filters = gio::ListStore::new::<gtk::FileFilter>();
-                    filters.append(&filter);
+        self.add_action_entries([remap_action]);
+    }
 
-                    let dialog = gtk::FileDialog::builder()
-                        .title(gettext("Save Presentation"))
-                        .filters(&filters)
-                        .initial_name("presentation.odp")
-                        .build();
+    /// Show a toast when the document uses fonts that are not installed, since
+    /// pango silently substitutes them and the layout shifts.
+    fn warn_missing_fonts(&self, doc: &Document) {
+        let missing = fonts::find_missing_fonts(&doc.font_families());
+        if missing.is_empty() {
+            return;
+        }
 
-                    let doc = doc.clone();
-                    let file_path = file_path.clone();
-                    let title_widget = title_widget.clone();
-                    dialog.save(Some(win), gio::Cancellable::NONE, move |result| {
-                        if let Ok(file) = result {
-                            if let Some(path) = file.path() {
-                                let doc = doc.borrow();
-                                if let Err(e) = odp::writer::save_document(&doc, &path) {
-                                    eprintln!("Save error: {}", e);
-                                    return;
-                                }
-                                let filename = path
-                                    .file_name()
-                                    .and_then(|n| n.to_str())
-                                    .unwrap_or("Untitled");
-                                if let Some(title) = title_widget.borrow().as_ref() {
-                                    title.set_subtitle(filename);
-                                }
-                                *file_path.borrow_mut() = Some(path);
-                            }
-                        }
-                    });
+        let list = missing
+            .iter()
+            .map(|font| {
+                gettext("{family} (shown as {substitute})")
+                    .replace("{family}", &font.family)
+                    .replace("{substitute}", &font.substitute)
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let toast = adw::Toast::builder()
+            .title(gettext("Missing fonts: {}").replace("{}", &list))
+            .button_label(gettext("Remap Fonts…"))
+            .action_name("win.remap-fonts")
+            .timeout(10)
+            .build();
+        self.imp().toast_overlay.add_toast(toast);
+    }
+
+    /// Replace this window's document with the presentation at `path`.
+    pub fn open_file(&self, path: &std::path::Path) {
+        let imp = self.imp();
+        let (loaded_doc, warnings) = match crate::format::load_document(path) {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                eprintln!("Open error: {}", e);
+                return;
+            }
+        };
+
+        *imp.document.borrow_mut() = loaded_doc;
+        let filename = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("Untitled");
+        self.set_document_name(filename);
+        // Only zipped ODP is saved back in place, PPTX and flat ODP are
+        // import only
+        let import_only = !path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("odp"));
+        *imp.file_path.borrow_mut() = (!import_only).then(|| path.to_path_buf());
+        imp.slide_panel.rebuild_thumbnails();
+        imp.canvas.set_current_slide(0);
+        imp.properties_panel.update_for_selection(None);
+        self.reset_history();
+        add_recent_file(path);
+        self.warn_missing_fonts(&imp.document.borrow());
+        self.warn_parse_errors(&warnings);
+        if !import_only {
+            if let Some(autosave) = autosave::newer_autosave(path) {
+                self.offer_recovery(path.to_path_buf(), autosave);
+            }
+        }
+    }
+
+    /// Ask whether to bring back the changes in `autosave` that were never
+    /// saved to `path`. Recovered changes can be undone back to the file.
+    fn offer_recovery(&self, path: std::path::PathBuf, autosave: std::path::PathBuf) {
+        let dialog = adw::AlertDialog::builder()
+            .heading(gettext("Recover Unsaved Changes?"))
+            .body(gettext(
+                "This presentation has changes from an earlier session that were not saved.",
+            ))
+            .build();
+        dialog.add_response("discard", &gettext("Discard"));
+        dialog.add_response("recover", &gettext("Recover"));
+        dialog.set_response_appearance("discard", adw::ResponseAppearance::Destructive);
+        dialog.set_response_appearance("recover", adw::ResponseAppearance::Suggested);
+        dialog.set_default_response(Some("recover"));
+        // Closing the dialog keeps the autosave, to be offered again
+        dialog.set_close_response("close");
+
+        let window = self.downgrade();
+        dialog.connect_response(None, move |_dialog, response| {
+            let Some(window) = window.upgrade() else {
+                return;
+            };
+            match response {
+                "discard" => {
+                    autosave::remove_autosave(&path);
+                    return;
                 }
+                "recover" => {}
+                _ => return,
+            }
+            let recovered = match crate::format::load_document(&autosave) {
+                Ok((recovered, _)) => recovered,
+                Err(e) => {
+                    eprintln!("Recovery error: {}", e);
+                    return;
+                }
+            };
+            let imp = window.imp();
+            *imp.document.borrow_mut() = recovered;
+            imp.slide_panel.rebuild_thumbnails();
+            imp.canvas.set_current_slide(0);
+            imp.properties_panel.update_for_selection(None);
+            window.record_edit();
+        });
+
+        dialog.present(Some(self));
+    }
+
+    /// Write the document with unsaved edits next to its file, to be
+    /// recovered after a crash. Presentations never saved are skipped.
+    fn autosave(&self) {
+        let imp = self.imp();
+        if !imp.dirty.get() || imp.autosaved.get() {
+            return;
+        }
+        let Some(path) = imp.file_path.borrow().clone() else {
+            return;
+        };
+        // Tried again on the next tick while an edit holds the document
+        let Ok(doc) = imp.document.try_borrow() else {
+            return;
+        };
+        match odp::writer::save_document(&doc, &autosave::autosave_path(&path)) {
+            Ok(()) => imp.autosaved.set(true),
+            Err(e) => eprintln!("Autosave error: {}", e),
+        }
+    }
+
+    /// Save to the file the presentation was opened from or last saved
+    /// to, asking for one when there is none. `then` runs once it is saved.
+    fn save(&self, then: impl FnOnce(&Self) + 'static) {
+        let path = self.imp().file_path.borrow().clone();
+        match path {
+            Some(path) => {
+                if self.save_to(&path) {
+                    then(self);
+                }
+            }
+            None => self.save_as(then),
+        }
+    }
+
+    /// Ask where to save the presentation and save it there. `then` runs
+    /// once it is saved.
+    fn save_as(&self, then: impl FnOnce(&Self) + 'static) {
+        let filter = gtk::FileFilter::new();
+        filter.set_name(Some(&gettext("ODP Presentation")));
+        filter.add_mime_type("application/vnd.oasis.opendocument.presentation");
+        filter.add_pattern("*.odp");
+
+        let filters = gio::ListStore::new::<gtk::FileFilter>();
+        filters.append(&filter);
+
+        let dialog = gtk::FileDialog::builder()
+            .title(gettext("Save Presentation"))
+            .filters(&filters)
+            .initial_name("presentation.odp")
+            .build();
+
+        let window = self.clone();
+        dialog.save(Some(self), gio::Cancellable::NONE, move |result| {
+            let Some(path) = result.ok().and_then(|file| file.path()) else {
+                return;
+            };
+            if !window.save_to(&path) {
+                return;
+            }
+            let filename = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("Untitled");
+            window.set_document_name(filename);
+            *window.imp().file_path.borrow_mut() = Some(path);
+            then(&window);
+        });
+    }
+
+    /// Write the presentation to `path` as ODP. Returns whether it was
+    /// saved; the saved file makes its autosave obsolete.
+    fn save_to(&self, path: &std::path::Path) -> bool {
+        let imp = self.imp();
+        if let Err(e) = odp::writer::save_document(&imp.document.borrow(), path) {
+            eprintln!("Save error: {}", e);
+            return false;
+        }
+        self.set_dirty(false);
+        autosave::remove_autosave(path);
+        add_recent_file(path);
+        true
+    }
+
+    /// Run `then` once edits that were not saved are saved or discarded,
+    /// after asking which. Nothing runs when the user cancels.
+    pub fn resolve_unsaved(&self, then: impl FnOnce(&Self) + 'static) {
+        if !self.imp().dirty.get() {
+            then(self);
+            return;
+        }
+
+        let dialog = adw::AlertDialog::builder()
+            .heading(gettext("Save Changes?"))
+            .body(gettext(
+                "The presentation has changes that are not saved. They will be lost unless you save them.",
+            ))
+            .build();
+        dialog.add_response("cancel", &gettext("Cancel"));
+        dialog.add_response("discard", &gettext("Discard"));
+        dialog.add_response("save", &gettext("Save"));
+        dialog.set_response_appearance("discard", adw::ResponseAppearance::Destructive);
+        dialog.set_response_appearance("save", adw::ResponseAppearance::Suggested);
+        dialog.set_default_response(Some("save"));
+        dialog.set_close_response("cancel");
+
+        let window = self.downgrade();
+        let then = Cell::new(Some(then));
+        dialog.connect_response(None, move |_dialog, response| {
+            let (Some(window), Some(then)) = (window.upgrade(), then.take()) else {
+                return;
+            };
+            match response {
+                "save" => window.save(then),
+                "discard" => {
+                    window.set_dirty(false);
+                    if let Some(path) = window.imp().file_path.borrow().as_ref() {
+                        autosave::remove_autosave(path);
+                    }
+                    then(&window);
+                }
+                _ => {}
+            }
+        });
+
+        dialog.present(Some(self));
+    }
+
+    fn set_document_name(&self, name: &str) {
+        *self.imp().document_name.borrow_mut() = name.to_string();
+        self.update_title();
+    }
+
+    fn set_dirty(&self, dirty: bool) {
+        self.imp().dirty.set(dirty);
+        self.update_title();
+    }
+
+    /// Show the document name under the title, marked while there are
+    /// edits that were not saved.
+    fn update_title(&self) {
+        let imp = self.imp();
+        let name = imp.document_name.borrow();
+        if let Some(title) = imp.title_widget.borrow().as_ref() {
+            if imp.dirty.get() {
+                title.set_subtitle(&format!("• {}", name));
+            } else {
+                title.set_subtitle(&name);
+            }
+        }
+    }
+
+    /// Replace this window's document with a new, unsaved one.
+    pub fn new_document(&self, doc: Document) {
+        let imp = self.imp();
+        *imp.document.borrow_mut() = doc;
+        *imp.file_path.borrow_mut() = None;
+        self.set_document_name(&gettext("Untitled Presentation"));
+        imp.slide_panel.rebuild_thumbnails();
+        imp.canvas.set_current_slide(0);
+        imp.properties_panel.update_for_selection(None);
+        self.reset_history();
+    }
+
+    /// Record the document as an undo step after a completed edit. Does
+    /// nothing when the document did not change.
+    fn record_edit(&self) {
+        let imp = self.imp();
+        // The properties panel reports edits while it still holds the
+        // document, so take the snapshot once it is done
+        let Ok(doc) = imp.document.try_borrow() else {
+            let window = self.downgrade();
+            glib::idle_add_local_once(move || {
+                if let Some(window) = window.upgrade() {
+                    window.record_edit();
+                }
+            });
+            return;
+        };
+        let pushed = imp.history.borrow_mut().push(&doc);
+        drop(doc);
+        if pushed {
+            self.set_dirty(true);
+            imp.autosaved.set(false);
+            self.update_history_actions();
+            self.update_slide_actions();
+        }
+    }
+
+    /// Start a new undo history, e.g. for a newly opened document.
+    fn reset_history(&self) {
+        let imp = self.imp();
+        imp.history.borrow_mut().reset(&imp.document.borrow());
+        self.set_dirty(false);
+        self.update_history_actions();
+        self.update_slide_actions();
+    }
+
+    fn update_history_actions(&self) {
+        let (can_undo, can_redo) = {
+            let history = self.imp().history.borrow();
+            (history.can_undo(), history.can_redo())
+        };
+        for (name, enabled) in [("undo", can_undo), ("redo", can_redo)] {
+            if let Some(action) = self.lookup_action(name).and_downcast::<gio::SimpleAction>() {
+                action.set_enabled(enabled);
+            }
+        }
+    }
+
+    /// Show the current slide and the selected element in the status bar.
+    fn update_status(&self) {
+        let imp = self.imp();
+        // As with `record_edit`, the properties panel may still hold the
+        // document
+        let Ok(doc) = imp.document.try_borrow() else {
+            let window = self.downgrade();
+            glib::idle_add_local_once(move || {
+                if let Some(window) = window.upgrade() {
+                    window.update_status();
+                }
+            });
+            return;
+        };
+        let index = imp.canvas.current_slide_index();
+        let mut status = gettext("Slide {current} of {count}")
+            .replace("{current}", &(index + 1).to_string())
+            .replace("{count}", &doc.slides.len().to_string());
+        let selected = imp.canvas.selection().borrow().element_id;
+        let element = selected.and_then(|id| {
+            doc.slides
+                .get(index)
+                .and_then(|slide| slide.elements.iter().find(|e| e.id() == id))
+        });
+        if let Some(element) = element {
+            let bounds = element.bounds();
+            status.push_str(" · ");
+            status.push_str(
+                &gettext("{kind} · {width}×{height} at ({x}, {y})")
+                    .replace("{kind}", &objects_panel::element_kind(element))
+                    .replace("{width}", &format!("{:.0}", bounds.size.width))
+                    .replace("{height}", &format!("{:.0}", bounds.size.height))
+                    .replace("{x}", &format!("{:.0}", bounds.origin.x))
+                    .replace("{y}", &format!("{:.0}", bounds.origin.y)),
+            );
+        }
+        imp.status_label.set_label(&status);
+    }
+
+    /// The last slide cannot be deleted.
+    fn update_slide_actions(&self) {
+        let slide_count = self.imp().document.borrow().slides.len();
+        let action = self.lookup_action("delete-slide").and_downcast::<gio::SimpleAction>();
+        if let Some(action) = action {
+            action.set_enabled(slide_count > 1);
+        }
+    }
+
+    /// Show an undone or redone state on the first slide it changed. The
+    /// selected element stays selected when it still exists there.
+    fn restore_document(&self, restored: Document) {
+        let imp = self.imp();
+        let changed = history::first_changed_slide(&imp.document.borrow(), &restored);
+        let index = changed
+            .unwrap_or_else(|| imp.canvas.current_slide_index())
+            .min(restored.slides.len().saturating_sub(1));
+        let selected = imp.canvas.selection().borrow().element_id.filter(|id| {
+            restored
+                .slides
+                .get(index)
+                .is_some_and(|slide| slide.elements.iter().any(|e| e.id() == *id))
+        });
+
+        *imp.document.borrow_mut() = restored;
+        self.set_dirty(true);
+        imp.autosaved.set(false);
+        imp.slide_panel.rebuild_thumbnails();
+        imp.slide_panel.set_selected_index(index);
+        imp.canvas.set_current_slide(index);
+        if let Some(id) = selected {
+            imp.canvas.select_element(id);
+        }
+        if imp.slide_sorter.is_mapped() {
+            imp.slide_sorter.rebuild();
+            imp.slide_sorter.select_slide(index);
+        }
+        self.update_history_actions();
+        self.update_slide_actions();
+    }
+
+    /// After a PDF export, name the fonts that went into the file as
+    /// substitutes, since the PDF looks different wherever it is opened.
+    fn report_pdf_fonts(&self, doc: &Document) {
+        let substituted = pdf_export::substituted_fonts(doc);
+        if substituted.is_empty() {
+            self.imp()
+                .toast_overlay
+                .add_toast(adw::Toast::new(&gettext("Exported as PDF with all fonts embedded")));
+            return;
+        }
+
+        let list = substituted
+            .iter()
+            .map(|font| {
+                gettext("{family} (embedded as {substitute})")
+                    .replace("{family}", &font.family)
+                    .replace("{substitute}", &font.substitute)
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let toast = adw::Toast::builder()
+            .title(gettext("Fonts substituted in the PDF: {}").replace("{}", &list))
+            .button_label(gettext("Remap Fonts…"))
+            .action_name("win.remap-fonts")
+            .timeout(10)
+            .build();
+        self.imp().toast_overlay.add_toast(toast);
+    }
+
+    /// Tell the user that a damaged file was only partly imported. The
+    /// details are logged by the readers.
+    fn warn_parse_errors(&self, warnings: &[ParseWarning]) {
+        let Some(first) = warnings.first() else {
+            return;
+        };
+
+        let toast = adw::Toast::builder()
+            .title(
+                gettext("The file is damaged, some content may be missing ({part}, line {line})")
+                    .replace("{part}", &first.part)
+                    .replace("{line}", &first.line.to_string()),
+            )
+            .timeout(10)
+            .build();
+        self.imp().toast_overlay.add_toast(toast);
+    }
+
+    fn setup_file_actions(&self, doc: Rc<RefCell<Document>>) {
+        let imp = self.imp();
+
+        let save_action = gio::ActionEntry::builder("save")
+            .activate(|win: &LuminaWindow, _, _| {
+                win.save(|_| {});
+            })
+            .build();
+
+        let save_as_action = gio::ActionEntry::builder("save-as")
+            .activate(|win: &LuminaWindow, _, _| {
+                win.save_as(|_| {});
             })
             .build();
 
         // Open action
         let open_action = gio::ActionEntry::builder("open")
+            .activate(|win: &LuminaWindow, _, _| {
+                win.resolve_unsaved(show_open_dialog);
+            })
+            .build();
+
+        // Open an entry of the Recent submenu, given by its URI
+        let open_recent_action = gio::ActionEntry::builder("open-recent")
+            .parameter_type(Some(&String::static_variant_type()))
+            .activate(|win: &LuminaWindow, _, param| {
+                let Some(uri) = param.and_then(|p| p.get::<String>()) else {
+                    return;
+                };
+                if let Some(path) = gio::File::for_uri(&uri).path() {
+                    win.resolve_unsaved(move |win| win.open_file(&path));
+                }
+            })
+            .build();
+
+        // Export PDF action
+        let export_pdf_action = gio::ActionEntry::builder("export-pdf")
             .activate({
                 let doc = doc.clone();
-                let file_path = imp.file_path.clone();
-                let title_widget = imp.title_widget.clone();
-                let slide_panel = imp.slide_panel.clone();
                 let canvas = imp.canvas.clone();
-                let props = imp.properties_panel.clone();
                 move |win: &LuminaWindow, _, _| {
-                    let odp_filter = gtk::FileFilter::new();
-                    odp_filter.set_name(Some(&gettext("ODP Presentation")));
-                    odp_filter.add_mime_type("application/vnd.oasis.opendocument.presentation");
-                    odp_filter.add_pattern("*.odp");
-
-                    let pptx_filter = gtk::FileFilter::new();
-                    pptx_filter.set_name(Some(&gettext("PowerPoint Presentation")));
-                    pptx_filter.add_mime_type("application/vnd.openxmlformats-officedocument.presentationml.presentation");
-                    pptx_filter.add_pattern("*.pptx");
+                    show_export_pdf_dialog(win, &doc, canvas.current_slide_index());
+                }
+            })
+            .build();
 
-                    let all_filter = gtk::FileFilter::new();
-                    all_filter.set_name(Some(&gettext("All Presentations")));
-                    all_filter.add_pattern("*.odp");
-                    all_filter.add_pattern("*.pptx");
+        // Export PPTX action
+        let export_pptx_action = gio::ActionEntry::builder("export-pptx")
+            .activate({
+                let doc = doc.clone();
+                move |win: &LuminaWindow, _, _| {
+                    let filter = gtk::FileFilter::new();
+                    filter.set_name(Some(&gettext("PowerPoint Presentation")));
+                    filter.add_mime_type("application/vnd.openxmlformats-officedocument.presentationml.presentation");
+                    filter.add_pattern("*.pptx");
 
                     let filters = gio::ListStore::new::<gtk::FileFilter>();
-                    filters.append(&all_filter);
-                    filters.append(&odp_filter);
-                    filters.append(&pptx_filter);
+                    filters.append(&filter);
 
                     let dialog = gtk::FileDialog::builder()
-                        .title(gettext("Open Presentation"))
+                        .title(gettext("Export as PPTX"))
                         .filters(&filters)
+                        .initial_name("presentation.pptx")
                         .build();
 
                     let doc = doc.clone();
-                    let file_path = file_path.clone();
-                    let title_widget = title_widget.clone();
-                    let slide_panel = slide_panel.clone();
-                    let canvas = canvas.clone();
-                    let props = props.clone();
-
-                    dialog.open(Some(win), gio::Cancellable::NONE, move |result| {
-                        if let Ok(file) = result {
-                            if let Some(path) = file.path() {
-                                let load_result = if path.extension().and_then(|e| e.to_str()) == Some("pptx") {
-                                    crate::format::pptx::reader::load_document(&path)
-                                } else {
-                                    odp::reader::load_document(&path)
-                                };
-                                let is_pptx = path.extension().and_then(|e| e.to_str()) == Some("pptx");
-                                match load_result {
-                                    Ok(loaded_doc) => {
-                                        *doc.borrow_mut() = loaded_doc;
-                                        let filename = path
-                                            .file_name()
-                                            .and_then(|n| n.to_str())
-                                            .unwrap_or("Untitled");
-                                        if let Some(title) = title_widget.borrow().as_ref() {
-                                            title.set_subtitle(filename);
-                                        }
-                                        // Don't set file_path for PPTX (import only)
-                                        if !is_pptx {
-                                            *file_path.borrow_mut() = Some(path);
-                                        } else {
-                                            *file_path.borrow_mut() = None;
-                                        }
-                                        slide_panel.rebuild_thumbnails();
-                                        canvas.set_current_slide(0);
-                                        props.update_for_selection(None);
-                                    }
-                                    Err(e) => {
-                                        eprintln!("Open error: {}", e);
-                                    }
-                                }
+                    dialog.save(Some(win), gio::Cancellable::NONE, move |result| {
+                        if let Some(path) = result.ok().and_then(|file| file.path()) {
+                            if let Err(e) = pptx::writer::save_document(&doc.borrow(), &path) {
+                                eprintln!("PPTX export error: {}", e);
                             }
                         }
                     });
@@ -399,34 +1248,34 @@ impl LuminaWindow {
             })
             .build();
 
-        // Export PDF action
-        let export_pdf_action = gio::ActionEntry::builder("export-pdf")
+        // Export the current slide as SVG
+        let export_svg_action = gio::ActionEntry::builder("export-svg")
             .activate({
                 let doc = doc.clone();
+                let canvas = imp.canvas.clone();
                 move |win: &LuminaWindow, _, _| {
                     let filter = gtk::FileFilter::new();
-                    filter.set_name(Some(&gettext("PDF Document")));
-                    filter.add_mime_type("application/pdf");
-                    filter.add_pattern("*.pdf");
+                    filter.set_name(Some(&gettext("SVG Image")));
+                    filter.add_mime_type("image/svg+xml");
+                    filter.add_pattern("*.svg");
 
                     let filters = gio::ListStore::new::<gtk::FileFilter>();
                     filters.append(&filter);
 
+                    let slide_index = canvas.current_slide_index();
                     let dialog = gtk::FileDialog::builder()
-                        .title(gettext("Export as PDF"))
+                        .title(gettext("Export Slide as SVG"))
                         .filters(&filters)
-                        .initial_name("presentation.pdf")
+                        .initial_name(format!("slide-{}.svg", slide_index + 1))
                         .build();
 
                     let doc = doc.clone();
-
                     dialog.save(Some(win), gio::Cancellable::NONE, move |result| {
-                        if let Ok(file) = result {
-                            if let Some(path) = file.path() {
-                                let doc = doc.borrow();
-                                if let Err(e) = pdf_export::export_pdf(&doc, &path) {
-                                    eprintln!("PDF export error: {}", e);
-                                }
+                        if let Some(path) = result.ok().and_then(|file| file.path()) {
+                            if let Err(e) =
+                                svg_export::export_svg(&doc.borrow(), slide_index, &path)
+                            {
+                                eprintln!("SVG export error: {}", e);
                             }
                         }
                     });
@@ -434,32 +1283,104 @@ impl LuminaWindow {
             })
             .build();
 
-        // New presentation action
-        let new_action = gio::ActionEntry::builder("new-presentation")
+        // Put the current slide on the clipboard as a bitmap
+        let copy_slide_image_action = gio::ActionEntry::builder("copy-slide-image")
             .activate({
-                let doc = doc;
-                let file_path = imp.file_path.clone();
-                let title_widget = imp.title_widget.clone();
-                let slide_panel = imp.slide_panel.clone();
+                let doc = doc.clone();
                 let canvas = imp.canvas.clone();
-                let props = imp.properties_panel.clone();
                 move |win: &LuminaWindow, _, _| {
-                    let all_templates = templates::built_in_templates();
-                    show_template_dialog(
-                        win,
-                        &all_templates,
-                        &doc,
-                        &file_path,
-                        &title_widget,
-                        &slide_panel,
-                        &canvas,
-                        &props,
-                    );
+                    let png = {
+                        let doc = doc.borrow();
+                        let Some(slide) = doc.slides.get(canvas.current_slide_index()) else {
+                            return;
+                        };
+                        raster::render_slide_png(slide, &doc.slide_size, CLIPBOARD_IMAGE_SCALE)
+                    };
+                    let texture = png.map_err(|e| e.to_string()).and_then(|png| {
+                        gdk::Texture::from_bytes(&glib::Bytes::from_owned(png))
+                            .map_err(|e| e.to_string())
+                    });
+                    match texture {
+                        Ok(texture) => {
+                            win.clipboard().set_texture(&texture);
+                            win.imp()
+                                .toast_overlay
+                                .add_toast(adw::Toast::new(&gettext("Slide copied as image")));
+                        }
+                        Err(e) => eprintln!("Slide image error: {}", e),
+                    }
                 }
             })
             .build();
 
-        self.add_action_entries([save_action, save_as_action, open_action, export_pdf_action, new_action]);
+        // Undo and redo restore whole-document snapshots
+        let undo_action = gio::ActionEntry::builder("undo")
+            .activate(|win: &LuminaWindow, _, _| {
+                let restored = win.imp().history.borrow_mut().undo();
+                if let Some(restored) = restored {
+                    win.restore_document(restored);
+                }
+            })
+            .build();
+
+        let redo_action = gio::ActionEntry::builder("redo")
+            .activate(|win: &LuminaWindow, _, _| {
+                let restored = win.imp().history.borrow_mut().redo();
+                if let Some(restored) = restored {
+                    win.restore_document(restored);
+                }
+            })
+            .build();
+
+        // Element clipboard. Deliberately without accelerators: the canvas
+        // forwards Ctrl+C, Ctrl+X and Ctrl+V so text fields keep theirs.
+        let copy_action = gio::ActionEntry::builder("copy")
+            .activate(|win: &LuminaWindow, _, _| {
+                win.imp().canvas.copy_selection();
+            })
+            .build();
+
+        let cut_action = gio::ActionEntry::builder("cut")
+            .activate(|win: &LuminaWindow, _, _| {
+                win.imp().canvas.cut_selection();
+            })
+            .build();
+
+        let paste_action = gio::ActionEntry::builder("paste")
+            .activate(|win: &LuminaWindow, _, _| {
+                win.imp().canvas.paste_clipboard();
+            })
+            .build();
+
+        let new_action = gio::ActionEntry::builder("new-presentation")
+            .activate(|win: &LuminaWindow, _, _| {
+                win.resolve_unsaved(|win| win.new_document(Document::new()));
+            })
+            .build();
+
+        let new_from_template_action = gio::ActionEntry::builder("new-from-template")
+            .activate(|win: &LuminaWindow, _, _| {
+                win.resolve_unsaved(show_template_dialog);
+            })
+            .build();
+
+        self.add_action_entries([
+            save_action,
+            save_as_action,
+            open_action,
+            open_recent_action,
+            export_pdf_action,
+            export_pptx_action,
+            export_svg_action,
+            copy_slide_image_action,
+            undo_action,
+            redo_action,
+            copy_action,
+            cut_action,
+            paste_action,
+            new_action,
+            new_from_template_action,
+        ]);
     }
 
     fn setup_tool_buttons(&self, doc: Rc<RefCell<Document>>) {
@@ -478,6 +1399,26 @@ impl LuminaWindow {
                 "color-select-symbolic",
                 gettext("Ellipse"),
             ),
+            (
+                Tool::Shape(ShapeType::RoundedRectangle {
+                    radius: DEFAULT_CORNER_RADIUS,
+                }),
+                "window-maximize-symbolic",
+                gettext("Rounded Rectangle"),
+            ),
+            (
+                Tool::Shape(ShapeType::Triangle),
+                "pan-up-symbolic",
+                gettext("Triangle"),
+            ),
+            (
+                Tool::Shape(ShapeType::Star {
+                    points: DEFAULT_STAR_POINTS,
+                }),
+                "starred-symbolic",
+                gettext("Star"),
+            ),
+            (Tool::Shape(ShapeType::Arrow), "go-next-symbolic", gettext("Arrow")),
             (
                 Tool::Shape(ShapeType::Line),
                 "format-text-strikethrough-symbolic",
@@ -550,19 +1491,9 @@ impl LuminaWindow {
         doc: &Rc<RefCell<Document>>,
         buttons: &Rc<RefCell<Vec<(Tool, gtk::ToggleButton)>>>,
     ) {
-        let filter = gtk::FileFilter::new();
-        filter.set_name(Some(&gettext("Images")));
-        filter.add_mime_type("image/png");
-        filter.add_mime_type("image/jpeg");
-        filter.add_mime_type("image/svg+xml");
-        filter.add_mime_type("image/webp");
-
-        let filters = gio::ListStore::new::<gtk::FileFilter>();
-        filters.append(&filter);
-
         let dialog = gtk::FileDialog::builder()
             .title(gettext("Insert Image"))
-            .filters(&filters)
+            .filters(&image_file_filters())
             .build();
 
         let canvas = canvas.clone();
@@ -587,32 +1518,27 @@ impl LuminaWindow {
             if let Ok(file) = result {
                 if let Some(path) = file.path() {
                     if let Ok(data) = std::fs::read(&path) {
-                        let mime = match path
-                            .extension()
-                            .and_then(|e| e.to_str())
-                            .unwrap_or("")
-                        {
-                            "png" => "image/png",
-                            "jpg" | "jpeg" => "image/jpeg",
-                            "svg" => "image/svg+xml",
-                            "webp" => "image/webp",
-                            _ => "image/png",
+                        let mime = image_mime_type(&path);
+
+                        let idx = canvas.current_slide_index();
+                        let mut doc = doc.borrow_mut();
+                        let slide_size = doc.slide_size;
+                        let Some(slide) = doc.slides.get_mut(idx) else {
+                            return;
                         };
 
-                        let bounds = Rect::new(100.0, 100.0, 400.0, 300.0);
+                        // Cascade so repeated inserts don't hide each other
+                        let existing: Vec<Rect> =
+                            slide.elements.iter().map(|e| *e.bounds()).collect();
+                        let bounds =
+                            arrange::place_new(&existing, Size::new(400.0, 300.0), &slide_size);
                         let element = ImageElement::new(bounds, data, mime.to_string());
                         let element_id = element.id;
+                        slide.add_element(SlideElement::Image(element));
+                        drop(doc);
 
-                        let idx = canvas.current_slide_index();
-                        {
-                            let mut doc = doc.borrow_mut();
-                            if idx < doc.slides.len() {
-                                doc.slides[idx].add_element(SlideElement::Image(element));
-                            }
-                        }
-
-                        canvas.selection().borrow_mut().select(element_id);
-                        canvas.queue_draw();
+                        canvas.notify_edited();
+                        canvas.select_element(element_id);
                     }
                 }
             }
@@ -620,58 +1546,500 @@ impl LuminaWindow {
     }
 }
 
-#[allow(clippy::too_many_arguments)]
-fn show_template_dialog(
+/// Let the user pick a presentation to open in `win`.
+fn show_open_dialog(win: &LuminaWindow) {
+    let odp_filter = gtk::FileFilter::new();
+    odp_filter.set_name(Some(&gettext("ODP Presentation")));
+    odp_filter.add_mime_type("application/vnd.oasis.opendocument.presentation");
+    odp_filter.add_pattern("*.odp");
+
+    let fodp_filter = gtk::FileFilter::new();
+    fodp_filter.set_name(Some(&gettext("Flat ODP Presentation")));
+    fodp_filter.add_mime_type("application/vnd.oasis.opendocument.presentation-flat-xml");
+    fodp_filter.add_pattern("*.fodp");
+
+    let pptx_filter = gtk::FileFilter::new();
+    pptx_filter.set_name(Some(&gettext("PowerPoint Presentation")));
+    pptx_filter.add_mime_type("application/vnd.openxmlformats-officedocument.presentationml.presentation");
+    pptx_filter.add_pattern("*.pptx");
+
+    let all_filter = gtk::FileFilter::new();
+    all_filter.set_name(Some(&gettext("All Presentations")));
+    all_filter.add_pattern("*.odp");
+    all_filter.add_pattern("*.fodp");
+    all_filter.add_pattern("*.pptx");
+
+    let filters = gio::ListStore::new::<gtk::FileFilter>();
+    filters.append(&all_filter);
+    filters.append(&odp_filter);
+    filters.append(&fodp_filter);
+    filters.append(&pptx_filter);
+
+    let dialog = gtk::FileDialog::builder()
+        .title(gettext("Open Presentation"))
+        .filters(&filters)
+        .build();
+
+    let window = win.clone();
+    dialog.open(Some(win), gio::Cancellable::NONE, move |result| {
+        if let Some(path) = result.ok().and_then(|file| file.path()) {
+            window.open_file(&path);
+        }
+    });
+}
+
+/// Remember a presentation that was opened or saved, for the Recent
+/// submenu of every window.
+fn add_recent_file(path: &std::path::Path) {
+    gtk::RecentManager::default().add_item(&gio::File::for_path(path).uri());
+}
+
+/// List the presentations used last that still exist, newest first.
+fn fill_recent_menu(menu: &gio::Menu) {
+    menu.remove_all();
+    let mut items: Vec<gtk::RecentInfo> = gtk::RecentManager::default()
+        .items()
+        .into_iter()
+        .filter(|info| {
+            let uri = info.uri();
+            let extension = std::path::Path::new(uri.as_str())
+                .extension()
+                .and_then(|e| e.to_str())
+                .map(str::to_ascii_lowercase);
+            extension.is_some_and(|e| RECENT_EXTENSIONS.contains(&e.as_str()))
+                && info.is_local()
+                && info.exists()
+        })
+        .collect();
+    items.sort_by_key(|info| std::cmp::Reverse(info.modified().to_unix()));
+
+    for info in items.iter().take(MAX_RECENT_FILES) {
+        // Underscores would be taken as mnemonics
+        let label = info.display_name().replace('_', "__");
+        let item = gio::MenuItem::new(Some(&label), None);
+        item.set_action_and_target_value(
+            Some("win.open-recent"),
+            Some(&info.uri().to_string().to_variant()),
+        );
+        menu.append_item(&item);
+    }
+    if items.is_empty() {
+        menu.append(Some(&gettext("No Recent Presentations")), None);
+    }
+}
+
+/// File filters of the dialogs picking an image
+pub fn image_file_filters() -> gio::ListStore {
+    let filter = gtk::FileFilter::new();
+    filter.set_name(Some(&gettext("Images")));
+    filter.add_mime_type("image/png");
+    filter.add_mime_type("image/jpeg");
+    filter.add_mime_type("image/svg+xml");
+    filter.add_mime_type("image/webp");
+
+    let filters = gio::ListStore::new::<gtk::FileFilter>();
+    filters.append(&filter);
+    filters
+}
+
+/// Mime type of an image file from its extension, PNG when unknown
+pub fn image_mime_type(path: &std::path::Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()).unwrap_or("") {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "svg" => "image/svg+xml",
+        "webp" => "image/webp",
+        _ => "image/png",
+    }
+}
+
+/// Width in pixels of the template previews
+const TEMPLATE_PREVIEW_WIDTH: i32 = 160;
+
+/// Let the user start a new presentation from one of the built-in or user
+/// templates, each shown with a preview of its first slide.
+fn show_template_dialog(win: &LuminaWindow) {
+    let dialog = adw::Dialog::builder()
+        .title(gettext("New from Template"))
+        .content_width(600)
+        .build();
+
+    let flow_box = gtk::FlowBox::new();
+    flow_box.set_selection_mode(gtk::SelectionMode::None);
+    flow_box.set_activate_on_single_click(true);
+    flow_box.set_homogeneous(true);
+    flow_box.set_max_children_per_line(3);
+    flow_box.set_row_spacing(12);
+    flow_box.set_column_spacing(12);
+    flow_box.set_margin_top(12);
+    flow_box.set_margin_bottom(12);
+    flow_box.set_margin_start(12);
+    flow_box.set_margin_end(12);
+
+    let documents: Vec<Document> = templates::built_in_templates()
+        .into_iter()
+        .chain(templates::user_templates())
+        .map(|template| {
+            let doc = templates::create_document_from_template(&template);
+            flow_box.append(&build_template_card(&template, &doc));
+            doc
+        })
+        .collect();
+
+    let window = win.downgrade();
+    let dialog_weak = dialog.downgrade();
+    flow_box.connect_child_activated(move |_, child| {
+        let Some(doc) = usize::try_from(child.index()).ok().and_then(|i| documents.get(i)) else {
+            return;
+        };
+        if let Some(window) = window.upgrade() {
+            window.new_document(doc.clone());
+        }
+        if let Some(dialog) = dialog_weak.upgrade() {
+            dialog.close();
+        }
+    });
+
+    // User templates can make the list long
+    let scrolled = gtk::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .propagate_natural_height(true)
+        .max_content_height(520)
+        .child(&flow_box)
+        .build();
+
+    let toolbar_view = adw::ToolbarView::new();
+    toolbar_view.add_top_bar(&adw::HeaderBar::new());
+    toolbar_view.set_content(Some(&scrolled));
+    dialog.set_child(Some(&toolbar_view));
+    dialog.present(Some(win));
+}
+
+/// Preview of the first slide of a template with its name and description.
+fn build_template_card(template: &templates::TemplateDefinition, doc: &Document) -> gtk::Box {
+    let card = gtk::Box::new(gtk::Orientation::Vertical, 4);
+
+    let drawing_area = gtk::DrawingArea::new();
+    drawing_area.set_content_width(TEMPLATE_PREVIEW_WIDTH);
+    drawing_area.set_content_height(
+        (TEMPLATE_PREVIEW_WIDTH as f64 * doc.slide_size.height / doc.slide_size.width) as i32,
+    );
+    drawing_area.add_css_class("card");
+    let preview = doc.clone();
+    drawing_area.set_draw_func(move |_area, cr, width, height| {
+        slide_panel::draw_slide_thumbnail(cr, &preview, 0, width, height);
+    });
+
+    let name = gtk::Label::new(Some(&template.name));
+    name.add_css_class("heading");
+
+    let description = gtk::Label::new(Some(&template.description));
+    description.add_css_class("caption");
+    description.add_css_class("dim-label");
+    description.set_wrap(true);
+    description.set_justify(gtk::Justification::Center);
+    description.set_max_width_chars(20);
+
+    card.append(&drawing_area);
+    card.append(&name);
+    card.append(&description);
+    card
+}
+
+/// Let the user change the slide size, optionally scaling the content along.
+fn show_slide_size_dialog(
     win: &LuminaWindow,
-    all_templates: &[templates::TemplateDefinition],
     doc: &Rc<RefCell<Document>>,
-    file_path: &Rc<RefCell<Option<std::path::PathBuf>>>,
-    title_widget: &RefCell<Option<adw::WindowTitle>>,
     slide_panel: &SlidePanel,
     canvas: &CanvasView,
     props: &PropertiesPanel,
 ) {
+    let current = doc.borrow().slide_size;
+    let names: Vec<String> = SLIDE_SIZE_PRESETS
+        .iter()
+        .map(|preset| gettext(preset.name))
+        .collect();
+    let name_refs: Vec<&str> = names.iter().map(String::as_str).collect();
+
+    let size_dropdown = gtk::DropDown::from_strings(&name_refs);
+    size_dropdown.set_hexpand(true);
+    if let Some(pos) = SlideSizePreset::find(&current)
+        .and_then(|preset| SLIDE_SIZE_PRESETS.iter().position(|p| p == preset))
+    {
+        size_dropdown.set_selected(pos as u32);
+    }
+
+    let scale_check = gtk::CheckButton::with_label(&gettext("Scale content to fit"));
+    scale_check.set_active(true);
+
+    let content = gtk::Box::new(gtk::Orientation::Vertical, 12);
+    content.append(&size_dropdown);
+    content.append(&scale_check);
+
+    let dialog = adw::AlertDialog::builder()
+        .heading(gettext("Slide Size"))
+        .body(gettext(
+            "Scaling keeps the layout intact. Otherwise elements keep their positions and may end up outside the slide.",
+        ))
+        .extra_child(&content)
+        .build();
+    dialog.add_response("cancel", &gettext("Cancel"));
+    dialog.add_response("apply", &gettext("Apply"));
+    dialog.set_response_appearance("apply", adw::ResponseAppearance::Suggested);
+    dialog.set_default_response(Some("apply"));
+    dialog.set_close_response("cancel");
+
+    let doc = doc.clone();
+    let slide_panel = slide_panel.clone();
+    let canvas = canvas.clone();
+    let props = props.clone();
+    let window = win.downgrade();
+
+    dialog.connect_response(Some("apply"), move |_dialog, _response| {
+        let Some(preset) = SLIDE_SIZE_PRESETS.get(size_dropdown.selected() as usize) else {
+            return;
+        };
+        rescale_document(&mut doc.borrow_mut(), preset.size(), scale_check.is_active());
+        slide_panel.rebuild_thumbnails();
+        canvas.queue_draw();
+        props.update_for_selection(None);
+        if let Some(window) = window.upgrade() {
+            window.record_edit();
+        }
+    });
+
+    dialog.present(Some(win));
+}
+
+/// Let the user pick the slides to export as PDF, all of them, the current
+/// one or a range, and then the file to export them to.
+fn show_export_pdf_dialog(win: &LuminaWindow, doc: &Rc<RefCell<Document>>, current: usize) {
+    let count = doc.borrow().slides.len();
+
+    let all_check = gtk::CheckButton::with_label(&gettext("All slides"));
+    all_check.set_active(true);
+    let current_check = gtk::CheckButton::with_label(&gettext("Current slide"));
+    current_check.set_group(Some(&all_check));
+    let range_check = gtk::CheckButton::with_label(&gettext("Slides"));
+    range_check.set_group(Some(&all_check));
+    let range_entry = gtk::Entry::new();
+    range_entry.set_placeholder_text(Some(&gettext("e.g. 1-3, 5")));
+    range_entry.set_hexpand(true);
+    range_entry.set_activates_default(true);
+    range_check
+        .bind_property("active", &range_entry, "sensitive")
+        .sync_create()
+        .build();
+
+    let range_box = gtk::Box::new(gtk::Orientation::Horizontal, 12);
+    range_box.append(&range_check);
+    range_box.append(&range_entry);
+    let notes_check =
+        gtk::CheckButton::with_label(&gettext("Add a page with the speaker notes after every slide"));
+    notes_check.set_margin_top(6);
+
+    let content = gtk::Box::new(gtk::Orientation::Vertical, 6);
+    content.append(&all_check);
+    content.append(&current_check);
+    content.append(&range_box);
+    content.append(&notes_check);
+
+    let dialog = adw::AlertDialog::builder()
+        .heading(gettext("Export as PDF"))
+        .extra_child(&content)
+        .build();
+    dialog.add_response("cancel", &gettext("Cancel"));
+    dialog.add_response("export", &gettext("Export"));
+    dialog.set_response_appearance("export", adw::ResponseAppearance::Suggested);
+    dialog.set_default_response(Some("export"));
+    dialog.set_close_response("cancel");
+
+    // The slides to export, none while the range is not valid
+    let chosen_slides = {
+        let range_check = range_check.clone();
+        let current_check = current_check.clone();
+        let range_entry = range_entry.clone();
+        move || {
+            if range_check.is_active() {
+                pdf_export::parse_slide_range(&range_entry.text(), count).unwrap_or_default()
+            } else if current_check.is_active() {
+                vec![current.min(count.saturating_sub(1))]
+            } else {
+                (0..count).collect()
+            }
+        }
+    };
+    let update_export = {
+        let dialog = dialog.clone();
+        let chosen_slides = chosen_slides.clone();
+        move || dialog.set_response_enabled("export", !chosen_slides().is_empty())
+    };
+    range_check.connect_toggled({
+        let update_export = update_export.clone();
+        move |_| update_export()
+    });
+    range_entry.connect_changed(move |_| update_export());
+
+    let doc = doc.clone();
+    let window = win.downgrade();
+    dialog.connect_response(Some("export"), move |_dialog, _response| {
+        let Some(window) = window.upgrade() else {
+            return;
+        };
+        let slides = chosen_slides();
+        let notes = notes_check.is_active();
+
+        let filter = gtk::FileFilter::new();
+        filter.set_name(Some(&gettext("PDF Document")));
+        filter.add_mime_type("application/pdf");
+        filter.add_pattern("*.pdf");
+
+        let filters = gio::ListStore::new::<gtk::FileFilter>();
+        filters.append(&filter);
+
+        let file_dialog = gtk::FileDialog::builder()
+            .title(gettext("Export as PDF"))
+            .filters(&filters)
+            .initial_name("presentation.pdf")
+            .build();
+
+        let doc = doc.clone();
+        let parent = window.clone();
+        file_dialog.save(Some(&parent), gio::Cancellable::NONE, move |result| {
+            if let Some(path) = result.ok().and_then(|file| file.path()) {
+                let doc = doc.borrow();
+                match pdf_export::export_pdf(&doc, &slides, notes, &path) {
+                    Ok(()) => window.report_pdf_fonts(&doc),
+                    Err(e) => eprintln!("PDF export error: {}", e),
+                }
+            }
+        });
+    });
+
+    dialog.present(Some(win));
+}
+
+/// Let the user edit the title and author saved with the document.
+fn show_document_properties_dialog(win: &LuminaWindow, doc: &Rc<RefCell<Document>>) {
+    let (title, author) = {
+        let doc = doc.borrow();
+        (doc.title.clone(), doc.metadata.author.clone())
+    };
+
+    let grid = gtk::Grid::builder()
+        .row_spacing(6)
+        .column_spacing(12)
+        .build();
+    let title_entry = gtk::Entry::new();
+    title_entry.set_text(&title);
+    title_entry.set_hexpand(true);
+    title_entry.set_activates_default(true);
+    let author_entry = gtk::Entry::new();
+    author_entry.set_text(&author);
+    author_entry.set_hexpand(true);
+    author_entry.set_activates_default(true);
+    let rows = [(gettext("Title"), &title_entry), (gettext("Author"), &author_entry)];
+    for (row, (label, entry)) in rows.into_iter().enumerate() {
+        let label = gtk::Label::new(Some(&label));
+        label.set_xalign(0.0);
+        grid.attach(&label, 0, row as i32, 1, 1);
+        grid.attach(entry, 1, row as i32, 1, 1);
+    }
+
     let dialog = adw::AlertDialog::builder()
-        .heading(gettext("New Presentation"))
-        .body(gettext("Choose a template:"))
+        .heading(gettext("Document Properties"))
+        .extra_child(&grid)
         .build();
+    dialog.add_response("cancel", &gettext("Cancel"));
+    dialog.add_response("apply", &gettext("Apply"));
+    dialog.set_response_appearance("apply", adw::ResponseAppearance::Suggested);
+    dialog.set_default_response(Some("apply"));
+    dialog.set_close_response("cancel");
 
-    for (i, template) in all_templates.iter().enumerate() {
-        dialog.add_response(&format!("tmpl_{}", i), &template.name);
+    let doc = doc.clone();
+    let window = win.downgrade();
+    dialog.connect_response(Some("apply"), move |_dialog, _response| {
+        {
+            let mut doc = doc.borrow_mut();
+            doc.title = title_entry.text().trim().to_string();
+            doc.metadata.author = author_entry.text().trim().to_string();
+        }
+        if let Some(window) = window.upgrade() {
+            window.record_edit();
+        }
+    });
+
+    dialog.present(Some(win));
+}
+
+/// Let the user pick an installed replacement for every missing font family.
+fn show_remap_fonts_dialog(
+    win: &LuminaWindow,
+    doc: &Rc<RefCell<Document>>,
+    slide_panel: &SlidePanel,
+    canvas: &CanvasView,
+    props: &PropertiesPanel,
+) {
+    let missing = fonts::find_missing_fonts(&doc.borrow().font_families());
+    if missing.is_empty() {
+        return;
     }
+
+    let installed = fonts::installed_families();
+    let installed_refs: Vec<&str> = installed.iter().map(String::as_str).collect();
+
+    let grid = gtk::Grid::builder()
+        .row_spacing(6)
+        .column_spacing(12)
+        .build();
+    let mut choices: Vec<(String, gtk::DropDown)> = Vec::new();
+    for (row, font) in missing.iter().enumerate() {
+        let label = gtk::Label::new(Some(&font.family));
+        label.set_xalign(0.0);
+        grid.attach(&label, 0, row as i32, 1, 1);
+
+        let dropdown = gtk::DropDown::from_strings(&installed_refs);
+        dropdown.set_enable_search(true);
+        dropdown.set_hexpand(true);
+        if let Some(pos) = installed.iter().position(|name| *name == font.substitute) {
+            dropdown.set_selected(pos as u32);
+        }
+        grid.attach(&dropdown, 1, row as i32, 1, 1);
+        choices.push((font.family.clone(), dropdown));
+    }
+
+    let dialog = adw::AlertDialog::builder()
+        .heading(gettext("Remap Fonts"))
+        .body(gettext("Choose an installed font to use instead of each missing font."))
+        .extra_child(&grid)
+        .build();
     dialog.add_response("cancel", &gettext("Cancel"));
-    dialog.set_default_response(Some("tmpl_0"));
+    dialog.add_response("remap", &gettext("Remap"));
+    dialog.set_response_appearance("remap", adw::ResponseAppearance::Suggested);
+    dialog.set_default_response(Some("remap"));
     dialog.set_close_response("cancel");
 
     let doc = doc.clone();
-    let file_path = file_path.clone();
-    let title_widget = title_widget.clone();
     let slide_panel = slide_panel.clone();
     let canvas = canvas.clone();
     let props = props.clone();
-    let template_data: Vec<String> = all_templates
-        .iter()
-        .map(|t| serde_json::to_string(t).unwrap_or_default())
-        .collect();
-
-    dialog.connect_response(None, move |_dialog, response| {
-        if response.starts_with("tmpl_") {
-            if let Ok(idx) = response[5..].parse::<usize>() {
-                if let Some(json) = template_data.get(idx) {
-                    if let Ok(template) = serde_json::from_str::<templates::TemplateDefinition>(json) {
-                        let new_doc = templates::create_document_from_template(&template);
-                        *doc.borrow_mut() = new_doc;
-                        *file_path.borrow_mut() = None;
-                        if let Some(title) = title_widget.borrow().as_ref() {
-                            title.set_subtitle(&gettext("Untitled Presentation"));
-                        }
-                        slide_panel.rebuild_thumbnails();
-                        canvas.set_current_slide(0);
-                        props.update_for_selection(None);
-                    }
+    let window = win.downgrade();
+
+    dialog.connect_response(Some("remap"), move |_dialog, _response| {
+        {
+            let mut doc = doc.borrow_mut();
+            for (family, dropdown) in &choices {
+                if let Some(replacement) = installed.get(dropdown.selected() as usize) {
+                    doc.replace_font_family(family, replacement);
                 }
             }
         }
+        slide_panel.queue_draw_all();
+        canvas.queue_draw();
+        props.update_for_selection(None);
+        if let Some(window) = window.upgrade() {
+            window.record_edit();
+        }
     });
 
     dialog.present(Some(win));
@@ -692,6 +2060,8 @@ fn create_demo_document() -> Document {
                 size: 48.0,
                 bold: true,
                 italic: false,
+                underline: false,
+                strikethrough: false,
                 color: Color::from_hex("#1c1c1c").unwrap(),
             },
         )])];
@@ -706,6 +2076,8 @@ fn create_demo_document() -> Document {
                 size: 20.0,
                 bold: false,
                 italic: true,
+                underline: false,
+                strikethrough: false,
                 color: Color::from_hex("#555555").unwrap(),
             },
         )])];
@@ -726,6 +2098,8 @@ fn create_demo_document() -> Document {
                 size: 36.0,
                 bold: true,
                 italic: false,
+                underline: false,
+                strikethrough: false,
                 color: Color::from_hex("#1c1c1c").unwrap(),
             },
         )])];
@@ -760,6 +2134,8 @@ fn create_demo_document() -> Document {
                 size: 16.0,
                 bold: false,
                 italic: false,
+                underline: false,
+                strikethrough: false,
                 color: Color::from_hex("#555555").unwrap(),
             },
         )])];